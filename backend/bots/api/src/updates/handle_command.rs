@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use types::{BotMessage, Chat, MessageIndex, SlashCommand, UserId};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Args {
+    pub chat: Chat,
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub initiator: UserId,
+    pub command: SlashCommand,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub bot_name: String,
+    pub bot_display_name: Option<String>,
+    pub messages: Vec<BotMessage>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use types::BotChatEvent;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Args {
+    pub events: Vec<BotChatEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+}
@@ -1 +1,3 @@
+pub mod handle_command;
 pub mod handle_direct_message;
+pub mod handle_events;
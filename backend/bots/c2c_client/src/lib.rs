@@ -4,4 +4,6 @@ use canister_client::generate_c2c_call;
 // Queries
 
 // Updates
+generate_c2c_call!(handle_command);
 generate_c2c_call!(handle_direct_message);
+generate_c2c_call!(handle_events);
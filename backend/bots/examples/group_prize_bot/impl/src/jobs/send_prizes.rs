@@ -182,6 +182,9 @@ async fn send_prize_message_to_group(
         end_date,
         caption: None,
         diamond_only: false,
+        quiz_message_index: None,
+        draw: false,
+        gate: None,
     });
 
     let c2c_args = group_canister::send_message_v2::Args {
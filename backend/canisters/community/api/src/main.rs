@@ -4,13 +4,17 @@ use candid_gen::generate_candid_method;
 fn main() {
     generate_candid_method!(community, channel_summary_updates, query);
     generate_candid_method!(community, channel_summary, query);
+    generate_candid_method!(community, channel_message_seen_by, query);
+    generate_candid_method!(community, channel_quiz_leaderboard, query);
     generate_candid_method!(community, deleted_message, query);
     generate_candid_method!(community, events_by_index, query);
     generate_candid_method!(community, events_window, query);
     generate_candid_method!(community, events, query);
     generate_candid_method!(community, explore_channels, query);
+    generate_candid_method!(community, installed_bots, query);
     generate_candid_method!(community, invite_code, query);
     generate_candid_method!(community, local_user_index, query);
+    generate_candid_method!(community, message_edit_history, query);
     generate_candid_method!(community, messages_by_message_index, query);
     generate_candid_method!(community, search_channel, query);
     generate_candid_method!(community, selected_channel_initial, query);
@@ -25,31 +29,43 @@ fn main() {
     generate_candid_method!(community, video_call_participants, query);
 
     generate_candid_method!(community, accept_p2p_swap, update);
+    generate_candid_method!(community, add_bot, update);
     generate_candid_method!(community, add_members_to_channel, update);
     generate_candid_method!(community, add_reaction, update);
+    generate_candid_method!(community, assign_channel_custom_role, update);
+    generate_candid_method!(community, assign_custom_role, update);
     generate_candid_method!(community, block_user, update);
     generate_candid_method!(community, cancel_p2p_swap, update);
     generate_candid_method!(community, change_channel_role, update);
     generate_candid_method!(community, change_role, update);
     generate_candid_method!(community, claim_prize, update);
     generate_candid_method!(community, create_channel, update);
+    generate_candid_method!(community, create_channel_category, update);
+    generate_candid_method!(community, create_channel_custom_role, update);
+    generate_candid_method!(community, create_custom_role, update);
     generate_candid_method!(community, create_user_group, update);
     generate_candid_method!(community, decline_invitation, update);
     generate_candid_method!(community, delete_channel, update);
+    generate_candid_method!(community, delete_channel_categories, update);
+    generate_candid_method!(community, delete_channel_custom_role, update);
+    generate_candid_method!(community, delete_custom_role, update);
     generate_candid_method!(community, delete_messages, update);
     generate_candid_method!(community, delete_user_groups, update);
     generate_candid_method!(community, disable_invite_code, update);
     generate_candid_method!(community, edit_message, update);
     generate_candid_method!(community, enable_invite_code, update);
     generate_candid_method!(community, end_video_call, update);
+    generate_candid_method!(community, execute_bot_command, update);
     generate_candid_method!(community, follow_thread, update);
     generate_candid_method!(community, import_group, update);
     generate_candid_method!(community, join_video_call, update);
     generate_candid_method!(community, leave_channel, update);
+    generate_candid_method!(community, mute_channel_member, update);
     generate_candid_method!(community, pin_message, update);
     generate_candid_method!(community, register_poll_vote, update);
     generate_candid_method!(community, register_proposal_vote_v2, update);
     generate_candid_method!(community, register_proposal_vote, update);
+    generate_candid_method!(community, remove_bot, update);
     generate_candid_method!(community, remove_member_from_channel, update);
     generate_candid_method!(community, remove_member, update);
     generate_candid_method!(community, remove_reaction, update);
@@ -65,6 +81,9 @@ fn main() {
     generate_candid_method!(community, unfollow_thread, update);
     generate_candid_method!(community, unpin_message, update);
     generate_candid_method!(community, update_channel, update);
+    generate_candid_method!(community, update_channel_category, update);
+    generate_candid_method!(community, update_channel_custom_role, update);
+    generate_candid_method!(community, update_custom_role, update);
     generate_candid_method!(community, update_community, update);
     generate_candid_method!(community, update_user_group, update);
 
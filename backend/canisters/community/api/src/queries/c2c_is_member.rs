@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use types::UserId;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub user_id: UserId,
+}
+
+pub type Response = bool;
@@ -0,0 +1,27 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{ChannelId, MessageIndex, UserId};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channel_id: ChannelId,
+    pub message_index: MessageIndex,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    UserNotInCommunity,
+    UserNotInChannel,
+    ChannelNotFound,
+    ReadReceiptsDisabled,
+    MessageNotFound,
+    MessageTooOld,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub seen_by_count: u32,
+    // Only populated for channels which are small enough to track who has read each message
+    pub seen_by: Option<Vec<UserId>>,
+}
@@ -0,0 +1,23 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{ChannelId, QuizLeaderboardEntry};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channel_id: ChannelId,
+    pub max_results: u32,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    UserNotInCommunity,
+    UserNotInChannel,
+    ChannelNotFound,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub leaderboard: Vec<QuizLeaderboardEntry>,
+    pub caller: Option<QuizLeaderboardEntry>,
+}
@@ -8,6 +8,9 @@ pub struct Args {
     pub search_term: Option<String>,
     pub page_index: u32,
     pub page_size: u8,
+    // If set, only channels within this category are returned
+    #[serde(default)]
+    pub category_id: Option<u32>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
@@ -2,8 +2,11 @@ pub mod c2c_can_issue_access_token_for_channel;
 pub mod c2c_events;
 pub mod c2c_events_by_index;
 pub mod c2c_events_window;
+pub mod c2c_is_member;
 pub mod c2c_summary;
 pub mod c2c_summary_updates;
+pub mod channel_message_seen_by;
+pub mod channel_quiz_leaderboard;
 pub mod channel_summary;
 pub mod channel_summary_updates;
 pub mod deleted_message;
@@ -11,8 +14,10 @@ pub mod events;
 pub mod events_by_index;
 pub mod events_window;
 pub mod explore_channels;
+pub mod installed_bots;
 pub mod invite_code;
 pub mod local_user_index;
+pub mod message_edit_history;
 pub mod messages_by_message_index;
 pub mod search_channel;
 pub mod selected_channel_initial;
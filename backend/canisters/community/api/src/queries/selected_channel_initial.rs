@@ -1,6 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
-use types::{ChannelId, EventIndex, GroupMember, MessageIndex, TimestampMillis, UserId, VersionedRules};
+use types::{ChannelId, CustomRole, EventIndex, GroupMember, MessageIndex, TimestampMillis, UserId, VersionedRules};
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Args {
@@ -25,4 +25,5 @@ pub struct SuccessResult {
     pub invited_users: Vec<UserId>,
     pub pinned_messages: Vec<MessageIndex>,
     pub chat_rules: VersionedRules,
+    pub custom_roles: Vec<CustomRole>,
 }
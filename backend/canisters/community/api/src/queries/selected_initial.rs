@@ -1,6 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
-use types::{CommunityMember, EventIndex, TimestampMillis, UserGroupDetails, UserId, VersionedRules};
+use types::{CommunityCustomRole, CommunityMember, EventIndex, TimestampMillis, UserGroupDetails, UserId, VersionedRules};
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Args {
@@ -23,4 +23,5 @@ pub struct SuccessResult {
     pub invited_users: Vec<UserId>,
     pub chat_rules: VersionedRules,
     pub user_groups: Vec<UserGroupDetails>,
+    pub custom_roles: Vec<CommunityCustomRole>,
 }
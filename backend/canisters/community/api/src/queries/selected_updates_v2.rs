@@ -1,6 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
-use types::{CommunityMember, TimestampMillis, UserGroupDetails, UserId, VersionedRules};
+use types::{CommunityCustomRole, CommunityMember, TimestampMillis, UserGroupDetails, UserId, VersionedRules};
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Args {
@@ -28,6 +28,7 @@ pub struct SuccessResult {
     pub chat_rules: Option<VersionedRules>,
     pub user_groups: Vec<UserGroupDetails>,
     pub user_groups_deleted: Vec<u32>,
+    pub custom_roles: Option<Vec<CommunityCustomRole>>,
 }
 
 impl SuccessResult {
@@ -38,5 +39,6 @@ impl SuccessResult {
             || !self.blocked_users_removed.is_empty()
             || self.invited_users.is_some()
             || self.chat_rules.is_some()
+            || self.custom_roles.is_some()
     }
 }
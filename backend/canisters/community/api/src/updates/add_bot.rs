@@ -0,0 +1,24 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{ChannelId, UserId};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channel_id: ChannelId,
+    pub bot_id: UserId,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    CommunityFrozen,
+    UserNotInCommunity,
+    UserSuspended,
+    ChannelNotFound,
+    UserNotInChannel,
+    NotAuthorized,
+    BotNotFound,
+    BotBlocked,
+    UserLimitReached(u32),
+    InternalError(String),
+}
@@ -0,0 +1,26 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{ChannelId, UserId};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channel_id: ChannelId,
+    pub role_id: u32,
+    pub users_to_add: Vec<UserId>,
+    pub users_to_remove: Vec<UserId>,
+    // The members of these user groups who are also members of the channel are given the role
+    pub user_group_ids: Vec<u32>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    CommunityFrozen,
+    UserNotInCommunity,
+    UserSuspended,
+    ChannelNotFound,
+    UserNotInChannel,
+    NotAuthorized,
+    RoleNotFound,
+    UserGroupNotFound(u32),
+}
@@ -0,0 +1,23 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::UserId;
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub role_id: u32,
+    pub users_to_add: Vec<UserId>,
+    pub users_to_remove: Vec<UserId>,
+    // The members of these user groups are also given the role
+    pub user_group_ids: Vec<u32>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    CommunityFrozen,
+    UserNotInCommunity,
+    UserSuspended,
+    NotAuthorized,
+    RoleNotFound,
+    UserGroupNotFound(u32),
+}
@@ -0,0 +1,18 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{BotEventType, ChannelId};
+
+// Called by a bot which is installed in the channel. An empty list of event types cancels the subscription.
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channel_id: ChannelId,
+    pub event_types: Vec<BotEventType>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    ChannelNotFound,
+    BotNotInstalled,
+    CommunityFrozen,
+}
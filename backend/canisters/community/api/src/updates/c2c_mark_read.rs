@@ -0,0 +1,28 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{ChannelId, MessageIndex};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channels: Vec<ChannelReadUpTo>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct ChannelReadUpTo {
+    pub channel_id: ChannelId,
+    pub read_up_to: MessageIndex,
+    // The position previously reported, if any, so that channels which only track counts don't double count
+    pub previous: Option<MessageIndex>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    UserNotInCommunity,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    // The channels whose read receipts are disabled, so that the user's canister can stop reporting reads to them
+    pub read_receipts_disabled: Vec<ChannelId>,
+}
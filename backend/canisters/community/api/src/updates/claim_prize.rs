@@ -1,6 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
-use types::{ChannelId, CompletedCryptoTransaction, FailedCryptoTransaction, MessageId};
+use types::{ChannelId, CompletedCryptoTransaction, FailedCryptoTransaction, GateCheckFailedReason, MessageId};
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Args {
@@ -11,6 +11,8 @@ pub struct Args {
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub enum Response {
     Success,
+    // Returned for prize draws, whose winners are picked once the prize ends
+    EnteredDraw,
     MessageNotFound,
     UserNotInCommunity,
     UserNotInChannel,
@@ -20,6 +22,9 @@ pub enum Response {
     AlreadyClaimed,
     PrizeFullyClaimed,
     PrizeEnded,
+    NotEligible,
+    GateCheckFailed(GateCheckFailedReason),
+    InternalError(String),
     TransferFailed(String, FailedCryptoTransaction),
     FailedAfterTransfer(String, CompletedCryptoTransaction),
 }
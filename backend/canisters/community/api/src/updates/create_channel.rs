@@ -17,6 +17,10 @@ pub struct Args {
     pub permissions_v2: Option<GroupPermissions>,
     pub events_ttl: Option<Milliseconds>,
     pub gate: Option<AccessGate>,
+    // If set, the channel is added to this category and inherits the category's default permissions and gate
+    // unless they are specified explicitly
+    #[serde(default)]
+    pub category_id: Option<u32>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
@@ -31,6 +35,7 @@ pub enum Response {
     AvatarTooBig(FieldTooLongResult),
     AccessGateInvalid,
     MaxChannelsCreated(u32),
+    ChannelCategoryNotFound,
     NameTaken,
     UserSuspended,
     NotAuthorized,
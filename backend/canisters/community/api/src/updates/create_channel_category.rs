@@ -0,0 +1,31 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{AccessGate, ChannelId, FieldTooLongResult, FieldTooShortResult, GroupPermissions};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub name: String,
+    pub channel_ids: Vec<ChannelId>,
+    pub collapsed_by_default: bool,
+    pub default_permissions: Option<GroupPermissions>,
+    pub default_gate: Option<AccessGate>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    AccessGateInvalid,
+    ChannelNotFound,
+    MaxChannelCategoriesCreated(u32),
+    NotAuthorized,
+    CommunityFrozen,
+    UserSuspended,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub category_id: u32,
+}
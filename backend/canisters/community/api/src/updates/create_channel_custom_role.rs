@@ -0,0 +1,32 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{ChannelId, FieldTooLongResult, FieldTooShortResult, GroupPermission, MessagePermission};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channel_id: ChannelId,
+    pub name: String,
+    pub permissions: Vec<GroupPermission>,
+    pub message_permissions: Vec<MessagePermission>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    CommunityFrozen,
+    UserNotInCommunity,
+    UserSuspended,
+    ChannelNotFound,
+    UserNotInChannel,
+    NotAuthorized,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+    TooManyRoles(u32),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub role_id: u32,
+}
@@ -0,0 +1,28 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{CommunityPermission, FieldTooLongResult, FieldTooShortResult};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub name: String,
+    pub permissions: Vec<CommunityPermission>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    CommunityFrozen,
+    UserNotInCommunity,
+    UserSuspended,
+    NotAuthorized,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+    TooManyRoles(u32),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub role_id: u32,
+}
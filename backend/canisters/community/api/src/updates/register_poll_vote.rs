@@ -8,6 +8,8 @@ pub struct Args {
     pub thread_root_message_index: Option<MessageIndex>,
     pub message_index: MessageIndex,
     pub poll_option: u32,
+    // For ranked-choice polls, the user's preferences in order, starting with `poll_option`
+    pub ranking: Option<Vec<u32>>,
     pub operation: VoteOperation,
 }
 
@@ -23,4 +25,5 @@ pub enum Response {
     PollNotFound,
     PollEnded,
     OptionIndexOutOfRange,
+    InvalidRanking,
 }
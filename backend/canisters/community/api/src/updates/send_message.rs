@@ -38,6 +38,7 @@ pub enum Response {
     CommunityFrozen,
     RulesNotAccepted,
     CommunityRulesNotAccepted,
+    RateLimited(TimestampMillis),
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
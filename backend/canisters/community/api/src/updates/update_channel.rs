@@ -1,8 +1,8 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use types::{
-    AccessGate, ChannelId, Document, FieldTooLongResult, FieldTooShortResult, Milliseconds, OptionUpdate,
-    OptionalGroupPermissions, UpdatedRules, Version,
+    AccessGate, ChannelId, ChannelUserGroupPermissions, Document, FieldTooLongResult, FieldTooShortResult, GateReverification,
+    Milliseconds, OptionUpdate, OptionalGroupPermissions, SlowMode, UpdatedRules, Version,
 };
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
@@ -15,6 +15,14 @@ pub struct Args {
     pub permissions_v2: Option<OptionalGroupPermissions>,
     pub events_ttl: OptionUpdate<Milliseconds>,
     pub gate: OptionUpdate<AccessGate>,
+    #[serde(default)]
+    pub gate_reverification: OptionUpdate<GateReverification>,
+    #[serde(default)]
+    pub slow_mode: OptionUpdate<SlowMode>,
+    #[serde(default)]
+    pub read_receipts_enabled: Option<bool>,
+    #[serde(default)]
+    pub user_group_permissions: Option<ChannelUserGroupPermissions>,
     pub public: Option<bool>,
 }
 
@@ -31,6 +39,8 @@ pub enum Response {
     DescriptionTooLong(FieldTooLongResult),
     AvatarTooBig(FieldTooLongResult),
     AccessGateInvalid,
+    SlowModeInvalid,
+    UserGroupNotFound(u32),
     NameTaken,
     RulesTooLong(FieldTooLongResult),
     RulesTooShort(FieldTooShortResult),
@@ -0,0 +1,30 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{AccessGate, ChannelId, FieldTooLongResult, FieldTooShortResult, GroupPermissions, OptionUpdate};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub category_id: u32,
+    pub name: Option<String>,
+    // If set, replaces the channels in the category, in the order in which they should be displayed
+    pub channel_ids: Option<Vec<ChannelId>>,
+    pub collapsed_by_default: Option<bool>,
+    pub default_permissions: OptionUpdate<GroupPermissions>,
+    pub default_gate: OptionUpdate<AccessGate>,
+    // The new (zero based) position of the category within the community's list of categories
+    pub position: Option<u32>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    ChannelCategoryNotFound,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    AccessGateInvalid,
+    ChannelNotFound,
+    NotAuthorized,
+    CommunityFrozen,
+    UserSuspended,
+}
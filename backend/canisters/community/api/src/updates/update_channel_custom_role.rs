@@ -0,0 +1,28 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{ChannelId, FieldTooLongResult, FieldTooShortResult, GroupPermission, MessagePermission};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub channel_id: ChannelId,
+    pub role_id: u32,
+    pub name: Option<String>,
+    pub permissions: Option<Vec<GroupPermission>>,
+    pub message_permissions: Option<Vec<MessagePermission>>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    CommunityFrozen,
+    UserNotInCommunity,
+    UserSuspended,
+    ChannelNotFound,
+    UserNotInChannel,
+    NotAuthorized,
+    RoleNotFound,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+}
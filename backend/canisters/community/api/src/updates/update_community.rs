@@ -1,8 +1,8 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use types::{
-    AccessGate, Document, FieldTooLongResult, FieldTooShortResult, OptionUpdate, OptionalCommunityPermissions, UpdatedRules,
-    Version,
+    AccessGate, Document, FieldTooLongResult, FieldTooShortResult, GateReverification, OptionUpdate,
+    OptionalCommunityPermissions, UpdatedRules, Version,
 };
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
@@ -14,6 +14,8 @@ pub struct Args {
     pub banner: OptionUpdate<Document>,
     pub permissions: Option<OptionalCommunityPermissions>,
     pub gate: OptionUpdate<AccessGate>,
+    #[serde(default)]
+    pub gate_reverification: OptionUpdate<GateReverification>,
     pub public: Option<bool>,
     pub primary_language: Option<String>,
 }
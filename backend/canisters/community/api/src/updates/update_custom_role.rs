@@ -0,0 +1,24 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{CommunityPermission, FieldTooLongResult, FieldTooShortResult};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub role_id: u32,
+    pub name: Option<String>,
+    pub permissions: Option<Vec<CommunityPermission>>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    CommunityFrozen,
+    UserNotInCommunity,
+    UserSuspended,
+    NotAuthorized,
+    RoleNotFound,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+}
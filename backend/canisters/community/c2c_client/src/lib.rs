@@ -6,10 +6,12 @@ generate_c2c_call!(c2c_can_issue_access_token_for_channel);
 generate_c2c_call!(c2c_events);
 generate_c2c_call!(c2c_events_by_index);
 generate_c2c_call!(c2c_events_window);
+generate_c2c_call!(c2c_is_member);
 generate_c2c_call!(c2c_summary);
 generate_c2c_call!(c2c_summary_updates);
 
 // Updates
+generate_c2c_call!(c2c_bot_subscribe_to_events);
 generate_c2c_call!(c2c_create_proposals_channel);
 generate_c2c_call!(c2c_delete_community);
 generate_c2c_call!(c2c_freeze_community);
@@ -19,6 +21,7 @@ generate_c2c_call!(c2c_invite_users_to_channel);
 generate_c2c_call!(c2c_join_channel);
 generate_c2c_call!(c2c_join_community);
 generate_c2c_call!(c2c_leave_community);
+generate_c2c_call!(c2c_mark_read);
 generate_c2c_call!(c2c_send_message);
 generate_c2c_call!(c2c_set_user_suspended);
 generate_c2c_call!(c2c_tip_message);
@@ -1,4 +1,4 @@
-use crate::{Data, RuntimeState};
+use crate::{jobs, Data, RuntimeState};
 use chat_events::{ChatEventInternal, Reader};
 use fire_and_forget_handler::FireAndForgetHandler;
 use group_index_canister::c2c_mark_community_active;
@@ -9,6 +9,9 @@ use utils::time::{DAY_IN_MS, HOUR_IN_MS};
 
 // If needed, notify the group index canister that there has been activity in this community
 pub(crate) fn handle_activity_notification(state: &mut RuntimeState) {
+    // Any activity may have produced events for bots, so start pushing them if it isn't running already
+    jobs::push_bot_events::start_job_if_required(state);
+
     let now = state.env.now();
 
     if let Some(mark_active_duration) = state.data.activity_notification_state.notify_if_required(now) {
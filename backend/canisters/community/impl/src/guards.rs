@@ -24,6 +24,14 @@ pub fn caller_is_user_index_or_local_user_index() -> Result<(), String> {
     }
 }
 
+pub fn caller_is_group_index() -> Result<(), String> {
+    if read_state(|state| state.is_caller_group_index()) {
+        Ok(())
+    } else {
+        Err("Caller is not the group_index".to_string())
+    }
+}
+
 pub fn caller_is_group_index_or_local_group_index() -> Result<(), String> {
     if read_state(|state| state.is_caller_group_index() || state.is_caller_local_group_index()) {
         Ok(())
@@ -12,7 +12,7 @@ use std::cell::Cell;
 use std::collections::HashMap;
 use std::time::Duration;
 use tracing::{info, trace};
-use types::{ChannelId, ChannelLatestMessageIndex, Chat, ChatId, Empty, UserId, UsersBlocked};
+use types::{ChannelId, ChannelLatestMessageIndex, Chat, ChatId, Empty, Timestamped, UserId, UsersBlocked};
 use utils::consts::OPENCHAT_BOT_USER_ID;
 
 const PAGE_SIZE: u32 = 19 * 102 * 1024; // Roughly 1.9MB (1.9 * 1024 * 1024)
@@ -138,6 +138,7 @@ pub(crate) fn finalize_group_import(group_id: ChatId) {
                 id: channel_id,
                 chat,
                 date_imported: None, // This is only set once everything is complete
+                user_group_permissions: Timestamped::default(),
             });
 
             state.data.timer_jobs.enqueue_job(
@@ -149,6 +150,9 @@ pub(crate) fn finalize_group_import(group_id: ChatId) {
                 now,
                 now,
             );
+
+            crate::jobs::migrate_events_to_stable_memory::start_job_if_required(state);
+            crate::jobs::populate_search_index::start_job_if_required(state);
         }
     });
 
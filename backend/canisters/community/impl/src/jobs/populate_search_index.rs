@@ -0,0 +1,45 @@
+use crate::{mutate_state, RuntimeState};
+use ic_cdk_timers::TimerId;
+use std::cell::Cell;
+use std::time::Duration;
+use tracing::{info, trace};
+
+const BATCH_SIZE: usize = 100;
+const MAX_INSTRUCTIONS_PER_EXECUTION: u64 = 5_000_000_000;
+
+thread_local! {
+    static TIMER_ID: Cell<Option<TimerId>> = Cell::default();
+}
+
+pub(crate) fn start_job_if_required(state: &RuntimeState) -> bool {
+    if TIMER_ID.get().is_none() && state.data.channels.iter().any(|c| !c.chat.events.is_search_index_populated()) {
+        let timer_id = ic_cdk_timers::set_timer_interval(Duration::ZERO, run);
+        TIMER_ID.set(Some(timer_id));
+        trace!("'populate_search_index' job started");
+        true
+    } else {
+        false
+    }
+}
+
+fn run() {
+    if mutate_state(populate_search_index) {
+        if let Some(timer_id) = TIMER_ID.take() {
+            ic_cdk_timers::clear_timer(timer_id);
+            info!("'populate_search_index' job completed");
+        }
+    }
+}
+
+// Keeps indexing batches of events until either every chat's search index has been populated (in which case it
+// returns true) or the instruction limit for this execution is approached
+fn populate_search_index(state: &mut RuntimeState) -> bool {
+    for channel in state.data.channels.iter_mut() {
+        while !channel.chat.events.populate_search_index(BATCH_SIZE) {
+            if ic_cdk::api::instruction_counter() >= MAX_INSTRUCTIONS_PER_EXECUTION {
+                return false;
+            }
+        }
+    }
+    true
+}
@@ -0,0 +1,87 @@
+use crate::{mutate_state, read_state, RuntimeState};
+use ic_cdk_timers::TimerId;
+use std::cell::Cell;
+use std::time::Duration;
+use tracing::{error, trace};
+use types::{BotChatEvent, CanisterId, Chat};
+use utils::canister::should_retry_failed_c2c_call;
+
+// Events are collected up over this interval so that they can be pushed to bots in batches
+const BATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+thread_local! {
+    static TIMER_ID: Cell<Option<TimerId>> = Cell::default();
+}
+
+pub(crate) fn start_job_if_required(state: &RuntimeState) -> bool {
+    if TIMER_ID.get().is_none()
+        && (state.data.channels.iter().any(|c| c.chat.bots.has_pending_events()) || !state.data.bot_event_sync_queue.is_empty())
+    {
+        let timer_id = ic_cdk_timers::set_timer(BATCH_INTERVAL, run);
+        TIMER_ID.set(Some(timer_id));
+        true
+    } else {
+        false
+    }
+}
+
+fn run() {
+    trace!("'push_bot_events' job running");
+    TIMER_ID.set(None);
+
+    if let Some(batch) = mutate_state(next_batch) {
+        ic_cdk::spawn(process_batch(batch));
+    } else {
+        read_state(start_job_if_required);
+    }
+}
+
+fn next_batch(state: &mut RuntimeState) -> Option<Vec<(CanisterId, Vec<BotChatEvent>)>> {
+    let community_id = state.env.canister_id().into();
+
+    for channel in state.data.channels.iter_mut() {
+        let chat = Chat::Channel(community_id, channel.id);
+
+        for pending in channel.chat.bots.take_pending_events() {
+            state.data.bot_event_sync_queue.push(
+                pending.bot_id.into(),
+                BotChatEvent {
+                    chat,
+                    timestamp: pending.timestamp,
+                    event: pending.event,
+                },
+            );
+        }
+    }
+
+    state.data.bot_event_sync_queue.try_start_batch(state.env.now())
+}
+
+async fn process_batch(batch: Vec<(CanisterId, Vec<BotChatEvent>)>) {
+    let futures: Vec<_> = batch
+        .into_iter()
+        .map(|(canister_id, events)| push_events(canister_id, events))
+        .collect();
+
+    futures::future::join_all(futures).await;
+
+    mutate_state(|state| {
+        state.data.bot_event_sync_queue.mark_batch_completed();
+        start_job_if_required(state);
+    });
+}
+
+async fn push_events(bot_id: CanisterId, events: Vec<BotChatEvent>) {
+    let args = bot_api::handle_events::Args { events };
+    let result = bot_c2c_client::handle_events(bot_id, &args).await;
+
+    mutate_state(|state| match result {
+        Err((code, msg)) if should_retry_failed_c2c_call(code, &msg) => {
+            let now = state.env.now();
+            if !state.data.bot_event_sync_queue.mark_failed(bot_id, args.events, now) {
+                error!(%bot_id, "Failed to push events to bot, max attempts reached");
+            }
+        }
+        _ => state.data.bot_event_sync_queue.clear_failed_attempts(&bot_id),
+    });
+}
@@ -1,8 +1,11 @@
 use crate::memory::{get_instruction_counts_data_memory, get_instruction_counts_index_memory};
-use crate::model::channels::Channels;
+use crate::model::channel_categories::ChannelCategories;
+use crate::model::channels::{Channel, Channels};
+use crate::model::events::CommunityEventInternal;
 use crate::model::groups_being_imported::{GroupBeingImportedSummary, GroupsBeingImported};
-use crate::model::members::CommunityMembers;
-use crate::timer_job_types::{RemoveExpiredEventsJob, TimerJob};
+use crate::model::members::{CommunityMembers, CustomRoleChanges};
+use crate::timer_job_types::{RemoveExpiredEventsJob, ReverifyChannelGateJob, ReverifyCommunityGateJob, TimerJob};
+use crate::updates::c2c_join_channel::join_channel_unchecked;
 use activity_notification_state::ActivityNotificationState;
 use candid::Principal;
 use canister_state_macros::canister_state;
@@ -11,8 +14,12 @@ use chat_events::ChatMetricsInternal;
 use event_store_producer::{EventStoreClient, EventStoreClientBuilder, EventStoreClientInfo};
 use event_store_producer_cdk_runtime::CdkRuntime;
 use fire_and_forget_handler::FireAndForgetHandler;
-use group_chat_core::AccessRulesInternal;
-use group_community_common::{PaymentReceipts, PaymentRecipient, PendingPayment, PendingPaymentReason, PendingPaymentsQueue};
+use group_chat_core::{
+    apply_gate_reverification_results, next_members_to_reverify, AccessRulesInternal, AddResult, GateReverificationOutcome,
+};
+use group_community_common::{
+    BotEventSyncQueue, PaymentReceipts, PaymentRecipient, PendingPayment, PendingPaymentReason, PendingPaymentsQueue,
+};
 use instruction_counts_log::{InstructionCountEntry, InstructionCountFunctionId, InstructionCountsLog};
 use model::{events::CommunityEvents, invited_users::InvitedUsers, members::CommunityMemberInternal};
 use msgpack::serialize_then_unwrap;
@@ -21,11 +28,13 @@ use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::ops::Deref;
 use std::time::Duration;
 use types::{
-    AccessGate, BuildVersion, CanisterId, ChatMetrics, CommunityCanisterCommunitySummary, CommunityMembership,
-    CommunityPermissions, CommunityRole, Cryptocurrency, Cycles, Document, Empty, FrozenGroupInfo, Milliseconds, Notification,
+    AccessGate, BuildVersion, CanisterId, ChannelCategory, ChannelId, ChatMetrics, CommunityCanisterCommunitySummary,
+    CommunityMembership, CommunityPermissions, CommunityRole, Cryptocurrency, CustomRoleAssigned, CustomRoleUnassigned, Cycles,
+    Document, Empty, FrozenGroupInfo, GateReverification, MembersLapsed, MembersRemovedByGate, Milliseconds, Notification,
     PaymentGate, Rules, TimestampMillis, Timestamped, UserId,
 };
 use types::{CommunityId, SNS_FEE_SHARE_PERCENT};
@@ -160,6 +169,7 @@ impl RuntimeState {
                     .as_ref()
                     .map_or(false, |version| version.value >= self.data.rules.text.version),
                 display_name: m.display_name().value.clone(),
+                lapsed: m.lapsed.value,
             };
 
             // Return all the channels that the user is a member of
@@ -188,6 +198,8 @@ impl RuntimeState {
             member.map(|m| m.last_updated()).unwrap_or_default(),
             self.data.events.latest_event_timestamp(),
             self.data.members.user_groups_last_updated(),
+            self.data.channel_categories.last_updated(),
+            self.data.gate_reverification.timestamp,
         ]
         .into_iter()
         .chain(channels.iter().map(|c| c.last_updated))
@@ -208,10 +220,12 @@ impl RuntimeState {
             permissions: data.permissions.clone(),
             frozen: data.frozen.value.clone(),
             gate: data.gate.value.clone(),
+            gate_reverification: data.gate_reverification.value,
             primary_language: data.primary_language.clone(),
             channels,
             membership,
             user_groups: data.members.iter_user_groups().map(|u| u.into()).collect(),
+            channel_categories: data.channel_categories_summaries(member),
             metrics: data.cached_chat_metrics.value.clone(),
         }
     }
@@ -271,6 +285,29 @@ impl RuntimeState {
     }
 }
 
+// Users can only be joined to gated channels once they have passed the gate, which can't be checked synchronously, so
+// users are never joined to gated channels this way
+fn join_users_to_channel(
+    channel: &mut Channel,
+    members: &mut CommunityMembers,
+    user_ids: impl IntoIterator<Item = UserId>,
+    now: TimestampMillis,
+) -> bool {
+    if channel.chat.gate.value.is_some() {
+        return false;
+    }
+
+    let mut updated = false;
+    for user_id in user_ids {
+        if let Some(member) = members.get_by_user_id_mut(&user_id) {
+            if !member.suspended.value && matches!(join_channel_unchecked(channel, member, true, now), AddResult::Success(_)) {
+                updated = true;
+            }
+        }
+    }
+    updated
+}
+
 fn init_instruction_counts_log() -> InstructionCountsLog {
     InstructionCountsLog::init(get_instruction_counts_index_memory(), get_instruction_counts_data_memory())
 }
@@ -285,6 +322,8 @@ struct Data {
     banner: Option<Document>,
     permissions: CommunityPermissions,
     gate: Timestamped<Option<AccessGate>>,
+    #[serde(default)]
+    gate_reverification: Timestamped<Option<GateReverification>>,
     primary_language: String,
     user_index_canister_id: CanisterId,
     local_user_index_canister_id: CanisterId,
@@ -297,6 +336,8 @@ struct Data {
     date_created: TimestampMillis,
     members: CommunityMembers,
     channels: Channels,
+    #[serde(default)]
+    channel_categories: ChannelCategories,
     events: CommunityEvents,
     invited_users: InvitedUsers,
     invite_code: Option<u64>,
@@ -318,6 +359,8 @@ struct Data {
     #[serde(with = "serde_bytes")]
     ic_root_key: Vec<u8>,
     event_store_client: EventStoreClient<CdkRuntime>,
+    #[serde(default)]
+    bot_event_sync_queue: BotEventSyncQueue,
 }
 
 impl Data {
@@ -373,6 +416,7 @@ impl Data {
             banner,
             permissions,
             gate: Timestamped::new(gate, now),
+            gate_reverification: Timestamped::default(),
             primary_language,
             user_index_canister_id,
             local_user_index_canister_id,
@@ -385,6 +429,7 @@ impl Data {
             date_created: now,
             members,
             channels,
+            channel_categories: ChannelCategories::default(),
             events,
             invited_users: InvitedUsers::default(),
             invite_code: None,
@@ -406,6 +451,7 @@ impl Data {
             event_store_client: EventStoreClientBuilder::new(local_group_index_canister_id, CdkRuntime::default())
                 .with_flush_delay(Duration::from_millis(5 * MINUTE_IN_MS))
                 .build(),
+            bot_event_sync_queue: BotEventSyncQueue::default(),
         }
     }
 
@@ -450,12 +496,61 @@ impl Data {
         );
     }
 
+    // Adds all members of the user groups which the channel's membership is synced from to the channel, unless the
+    // channel is gated. Members are never removed from channels by syncing, so leaving a user group doesn't remove
+    // access to a channel's history.
+    pub fn sync_channel_members_from_user_groups(&mut self, channel_id: ChannelId, now: TimestampMillis) -> bool {
+        let Some(channel) = self.channels.get_mut(&channel_id) else {
+            return false;
+        };
+
+        let user_ids: HashSet<UserId> = channel
+            .user_group_permissions
+            .sync_membership
+            .iter()
+            .filter_map(|id| self.members.get_user_group(*id))
+            .flat_map(|g| g.members.iter().copied())
+            .collect();
+
+        join_users_to_channel(channel, &mut self.members, user_ids, now)
+    }
+
+    // Adds users who have just been added to a user group to each channel whose membership is synced from it
+    pub fn add_users_to_channels_synced_from_user_group(
+        &mut self,
+        user_group_id: u32,
+        user_ids: &[UserId],
+        now: TimestampMillis,
+    ) -> bool {
+        let mut updated = false;
+        for channel in self
+            .channels
+            .iter_mut()
+            .filter(|c| c.user_group_permissions.sync_membership.contains(&user_group_id))
+        {
+            updated |= join_users_to_channel(channel, &mut self.members, user_ids.iter().copied(), now);
+        }
+        updated
+    }
+
+    // Channels are only included if they are public, if the member has joined them or if the member
+    // can see them via one of their user groups
+    pub fn channel_categories_summaries(&self, member: Option<&CommunityMemberInternal>) -> Vec<ChannelCategory> {
+        self.channel_categories.summaries(|channel_id| {
+            member.map_or(false, |m| m.channels.contains(&channel_id))
+                || self.channels.get(&channel_id).map_or(false, |c| {
+                    c.chat.is_public.value || member.map_or(false, |m| c.can_view_via_user_groups(m.user_id, &self.members))
+                })
+        })
+    }
+
     pub fn details_last_updated(&self) -> TimestampMillis {
         [
             self.invited_users.last_updated(),
             self.events.latest_event_timestamp(),
             self.members.user_groups_last_updated(),
             self.members.display_names_last_updated(),
+            self.members.custom_roles().last_updated(),
         ]
         .into_iter()
         .max()
@@ -466,10 +561,152 @@ impl Data {
         self.gate
             .value
             .as_ref()
-            .map(|g| matches!(g, AccessGate::Payment(_)))
+            .map(|g| g.contains_payment_gate())
             .unwrap_or_default()
     }
 
+    pub fn schedule_gate_reverification(&mut self, now: TimestampMillis) {
+        self.timer_jobs
+            .cancel_jobs(|j| matches!(j, TimerJob::ReverifyCommunityGate(_)));
+
+        if self.gate.value.is_some() {
+            if let Some(gate_reverification) = self.gate_reverification.value {
+                self.timer_jobs.enqueue_job(
+                    TimerJob::ReverifyCommunityGate(ReverifyCommunityGateJob { after: None }),
+                    now + gate_reverification.interval,
+                    now,
+                );
+            }
+        }
+    }
+
+    pub fn schedule_channel_gate_reverification(&mut self, channel_id: ChannelId, now: TimestampMillis) {
+        self.timer_jobs
+            .cancel_jobs(|j| matches!(j, TimerJob::ReverifyChannelGate(c) if c.channel_id == channel_id));
+
+        if let Some(channel) = self.channels.get(&channel_id) {
+            if channel.chat.gate.value.is_some() {
+                if let Some(gate_reverification) = channel.chat.gate_reverification.value {
+                    self.timer_jobs.enqueue_job(
+                        TimerJob::ReverifyChannelGate(ReverifyChannelGateJob { channel_id, after: None }),
+                        now + gate_reverification.interval,
+                        now,
+                    );
+                }
+            }
+        }
+    }
+
+    // Queues the next batch of community members to be re-verified straight away
+    pub fn continue_gate_reverification(&mut self, after: UserId, now: TimestampMillis) {
+        self.timer_jobs
+            .cancel_jobs(|j| matches!(j, TimerJob::ReverifyCommunityGate(_)));
+        self.timer_jobs.enqueue_job(
+            TimerJob::ReverifyCommunityGate(ReverifyCommunityGateJob { after: Some(after) }),
+            now,
+            now,
+        );
+    }
+
+    // Queues the next batch of channel members to be re-verified straight away
+    pub fn continue_channel_gate_reverification(&mut self, channel_id: ChannelId, after: UserId, now: TimestampMillis) {
+        self.timer_jobs
+            .cancel_jobs(|j| matches!(j, TimerJob::ReverifyChannelGate(c) if c.channel_id == channel_id));
+        self.timer_jobs.enqueue_job(
+            TimerJob::ReverifyChannelGate(ReverifyChannelGateJob {
+                channel_id,
+                after: Some(after),
+            }),
+            now,
+            now,
+        );
+    }
+
+    // Returns the community gate to be re-verified along with the next batch of members who must pass it, starting
+    // after the cursor, or `None` if the gate is not due to be re-verified
+    pub fn gate_to_reverify(&self, after: Option<UserId>, max: usize) -> Option<(AccessGate, Vec<UserId>)> {
+        self.gate_reverification.value?;
+
+        let gate = self.gate.value.clone().filter(|g| g.can_be_reverified())?;
+        let members = next_members_to_reverify(
+            self.members
+                .iter()
+                .filter(|m| m.requires_gate_reverification())
+                .map(|m| m.user_id),
+            after,
+            max,
+        );
+
+        Some((gate, members))
+    }
+
+    // Clears the lapsed state of members who passed the community gate. Members who failed the gate are either marked
+    // as lapsed or, if there is no grace period or their grace period has passed, removed from the community and all
+    // of its channels. Returns the members who were removed.
+    pub fn apply_gate_reverification_results(
+        &mut self,
+        passed: Vec<UserId>,
+        failed: Vec<UserId>,
+        now: TimestampMillis,
+    ) -> Vec<UserId> {
+        let Some(gate_reverification) = self.gate_reverification.value else {
+            return Vec::new();
+        };
+
+        let GateReverificationOutcome { lapsed, removed } =
+            apply_gate_reverification_results(&mut self.members, gate_reverification, passed, failed, now);
+
+        for user_id in removed.iter() {
+            self.members.remove(user_id, now);
+            self.channels.leave_all_channels(*user_id, now);
+        }
+
+        if !lapsed.is_empty() {
+            self.events.push_event(
+                CommunityEventInternal::MembersLapsed(Box::new(MembersLapsed {
+                    user_ids: lapsed,
+                    grace_period_ends: now + gate_reverification.grace_period.unwrap_or_default(),
+                })),
+                now,
+            );
+        }
+
+        if !removed.is_empty() {
+            self.events.push_event(
+                CommunityEventInternal::MembersRemovedByGate(Box::new(MembersRemovedByGate {
+                    user_ids: removed.clone(),
+                })),
+                now,
+            );
+        }
+
+        removed
+    }
+
+    pub fn push_custom_role_events(&mut self, changes: CustomRoleChanges, changed_by: UserId, now: TimestampMillis) {
+        if !changes.users_added.is_empty() {
+            self.events.push_event(
+                CommunityEventInternal::CustomRoleAssigned(Box::new(CustomRoleAssigned {
+                    role_id: changes.role_id,
+                    user_ids: changes.users_added,
+                    assigned_by: changed_by,
+                })),
+                now,
+            );
+        }
+
+        if !changes.users_removed.is_empty() {
+            self.events.push_event(
+                CommunityEventInternal::CustomRoleUnassigned(Box::new(CustomRoleUnassigned {
+                    role_id: changes.role_id,
+                    user_ids: changes.users_removed,
+                    unassigned_by: changed_by,
+                })),
+                now,
+            );
+        }
+    }
+
     pub fn handle_event_expiry(&mut self, expiry: TimestampMillis, now: TimestampMillis) {
         if self.next_event_expiry.map_or(true, |ex| expiry < ex) {
             self.next_event_expiry = Some(expiry);
@@ -0,0 +1,372 @@
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use types::{AccessGate, ChannelCategory, ChannelId, GroupPermissions, OptionUpdate, TimestampMillis};
+
+pub const MAX_CHANNEL_CATEGORIES: u32 = 50;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ChannelCategories {
+    // Ordered as they should be displayed
+    categories: Vec<ChannelCategoryInternal>,
+    last_updated: TimestampMillis,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChannelCategoryInternal {
+    pub id: u32,
+    pub name: String,
+    pub channels: Vec<ChannelId>,
+    pub collapsed_by_default: bool,
+    pub default_permissions: Option<GroupPermissions>,
+    pub default_gate: Option<AccessGate>,
+    // The channels which took their permissions (or gate) from the category's defaults when they were created and
+    // which haven't had them changed since. These channels are updated whenever the category's defaults change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels_inheriting_permissions: Vec<ChannelId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels_inheriting_gate: Vec<ChannelId>,
+}
+
+pub struct ChannelCategoryUpdate {
+    pub name: Option<String>,
+    pub channels: Option<Vec<ChannelId>>,
+    pub collapsed_by_default: Option<bool>,
+    pub default_permissions: OptionUpdate<GroupPermissions>,
+    pub default_gate: OptionUpdate<AccessGate>,
+    pub position: Option<u32>,
+}
+
+pub enum CreateChannelCategoryResult {
+    Success(u32),
+    NameTaken,
+    MaxCategoriesCreated,
+}
+
+pub enum UpdateChannelCategoryResult {
+    Success,
+    NotFound,
+    NameTaken,
+}
+
+impl ChannelCategories {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create<R: RngCore>(
+        &mut self,
+        name: String,
+        channels: Vec<ChannelId>,
+        collapsed_by_default: bool,
+        default_permissions: Option<GroupPermissions>,
+        default_gate: Option<AccessGate>,
+        rng: &mut R,
+        now: TimestampMillis,
+    ) -> CreateChannelCategoryResult {
+        if self.categories.len() >= MAX_CHANNEL_CATEGORIES as usize {
+            return CreateChannelCategoryResult::MaxCategoriesCreated;
+        }
+        if self.is_name_taken(&name, None) {
+            return CreateChannelCategoryResult::NameTaken;
+        }
+
+        let id = self.generate_id(rng);
+        let channels = dedup(channels);
+        self.remove_channels_from_all(&channels);
+
+        self.categories.push(ChannelCategoryInternal {
+            id,
+            name,
+            channels,
+            collapsed_by_default,
+            default_permissions,
+            default_gate,
+            channels_inheriting_permissions: Vec::new(),
+            channels_inheriting_gate: Vec::new(),
+        });
+        self.last_updated = now;
+
+        CreateChannelCategoryResult::Success(id)
+    }
+
+    pub fn update(&mut self, id: u32, update: ChannelCategoryUpdate, now: TimestampMillis) -> UpdateChannelCategoryResult {
+        let Some(index) = self.categories.iter().position(|c| c.id == id) else {
+            return UpdateChannelCategoryResult::NotFound;
+        };
+        if update.name.as_ref().map_or(false, |n| self.is_name_taken(n, Some(id))) {
+            return UpdateChannelCategoryResult::NameTaken;
+        }
+
+        if let Some(channels) = update.channels {
+            let channels = dedup(channels);
+            self.remove_channels_from_all(&channels);
+            let category = &mut self.categories[index];
+            category.channels_inheriting_permissions.retain(|c| channels.contains(c));
+            category.channels_inheriting_gate.retain(|c| channels.contains(c));
+            category.channels = channels;
+        }
+
+        let category = &mut self.categories[index];
+        if let Some(name) = update.name {
+            category.name = name;
+        }
+        if let Some(collapsed_by_default) = update.collapsed_by_default {
+            category.collapsed_by_default = collapsed_by_default;
+        }
+        // Once a default is removed, the channels which inherited it keep their current settings
+        if matches!(update.default_permissions, OptionUpdate::SetToNone) {
+            category.channels_inheriting_permissions.clear();
+        }
+        if matches!(update.default_gate, OptionUpdate::SetToNone) {
+            category.channels_inheriting_gate.clear();
+        }
+        category.default_permissions = update.default_permissions.apply_to(category.default_permissions.take());
+        category.default_gate = update.default_gate.apply_to(category.default_gate.take());
+
+        if let Some(position) = update.position {
+            let category = self.categories.remove(index);
+            let position = (position as usize).min(self.categories.len());
+            self.categories.insert(position, category);
+        }
+
+        self.last_updated = now;
+        UpdateChannelCategoryResult::Success
+    }
+
+    pub fn delete(&mut self, id: u32, now: TimestampMillis) -> bool {
+        let original_len = self.categories.len();
+        self.categories.retain(|c| c.id != id);
+
+        if self.categories.len() != original_len {
+            self.last_updated = now;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn add_channel(
+        &mut self,
+        id: u32,
+        channel_id: ChannelId,
+        inherits_permissions: bool,
+        inherits_gate: bool,
+        now: TimestampMillis,
+    ) -> bool {
+        if !self.categories.iter().any(|c| c.id == id) {
+            return false;
+        }
+
+        self.remove_channels_from_all(&[channel_id]);
+        if let Some(category) = self.categories.iter_mut().find(|c| c.id == id) {
+            category.channels.push(channel_id);
+            if inherits_permissions {
+                category.channels_inheriting_permissions.push(channel_id);
+            }
+            if inherits_gate {
+                category.channels_inheriting_gate.push(channel_id);
+            }
+        }
+        self.last_updated = now;
+        true
+    }
+
+    // Called when a channel's permissions or gate are changed directly, after which they are no longer kept in sync
+    // with the category's defaults
+    pub fn stop_inheriting(&mut self, channel_id: ChannelId, permissions: bool, gate: bool) {
+        for category in self.categories.iter_mut() {
+            if permissions {
+                category.channels_inheriting_permissions.retain(|c| *c != channel_id);
+            }
+            if gate {
+                category.channels_inheriting_gate.retain(|c| *c != channel_id);
+            }
+        }
+    }
+
+    pub fn remove_channel(&mut self, channel_id: ChannelId, now: TimestampMillis) {
+        if self.category_of(channel_id).is_some() {
+            self.remove_channels_from_all(&[channel_id]);
+            self.last_updated = now;
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<&ChannelCategoryInternal> {
+        self.categories.iter().find(|c| c.id == id)
+    }
+
+    pub fn category_of(&self, channel_id: ChannelId) -> Option<&ChannelCategoryInternal> {
+        self.categories.iter().find(|c| c.channels.contains(&channel_id))
+    }
+
+    // Returns the categories in display order, with each category only including the channels for which
+    // `is_visible` returns true
+    pub fn summaries<F: Fn(ChannelId) -> bool>(&self, is_visible: F) -> Vec<ChannelCategory> {
+        self.categories
+            .iter()
+            .map(|c| ChannelCategory {
+                category_id: c.id,
+                name: c.name.clone(),
+                channels: c.channels.iter().copied().filter(|ch| is_visible(*ch)).collect(),
+                collapsed_by_default: c.collapsed_by_default,
+                default_permissions: c.default_permissions.clone(),
+                default_gate: c.default_gate.clone(),
+            })
+            .collect()
+    }
+
+    pub fn last_updated(&self) -> TimestampMillis {
+        self.last_updated
+    }
+
+    fn is_name_taken(&self, name: &str, current_id: Option<u32>) -> bool {
+        let name_upper = name.trim().to_uppercase();
+        self.categories
+            .iter()
+            .any(|c| Some(c.id) != current_id && c.name.trim().to_uppercase() == name_upper)
+    }
+
+    // Each channel can only belong to a single category
+    fn remove_channels_from_all(&mut self, channels: &[ChannelId]) {
+        for category in self.categories.iter_mut() {
+            category.channels.retain(|c| !channels.contains(c));
+            category.channels_inheriting_permissions.retain(|c| !channels.contains(c));
+            category.channels_inheriting_gate.retain(|c| !channels.contains(c));
+        }
+    }
+
+    fn generate_id<R: RngCore>(&self, rng: &mut R) -> u32 {
+        let ids: HashSet<_> = self.categories.iter().map(|c| c.id).collect();
+
+        loop {
+            let id: u32 = rng.gen();
+            if !ids.contains(&id) {
+                return id;
+            }
+        }
+    }
+}
+
+fn dedup(channels: Vec<ChannelId>) -> Vec<ChannelId> {
+    let mut seen = HashSet::new();
+    channels.into_iter().filter(|c| seen.insert(*c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn channels_only_belong_to_a_single_category() {
+        let mut categories = ChannelCategories::default();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let CreateChannelCategoryResult::Success(first) =
+            categories.create("First".to_string(), vec![1, 2, 3], false, None, None, &mut rng, 1)
+        else {
+            panic!()
+        };
+        let CreateChannelCategoryResult::Success(second) =
+            categories.create("Second".to_string(), vec![3, 4], false, None, None, &mut rng, 2)
+        else {
+            panic!()
+        };
+
+        assert_eq!(categories.get(first).unwrap().channels, vec![1, 2]);
+        assert_eq!(categories.category_of(3).unwrap().id, second);
+
+        assert!(categories.add_channel(first, 4, false, false, 3));
+        assert_eq!(categories.get(first).unwrap().channels, vec![1, 2, 4]);
+        assert_eq!(categories.get(second).unwrap().channels, vec![3]);
+        assert_eq!(categories.last_updated(), 3);
+    }
+
+    #[test]
+    fn update_reorders_categories() {
+        let mut categories = ChannelCategories::default();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for name in ["A", "B", "C"] {
+            categories.create(name.to_string(), Vec::new(), false, None, None, &mut rng, 1);
+        }
+        let c = categories.summaries(|_| true)[2].category_id;
+
+        let update = ChannelCategoryUpdate {
+            name: Some("b".to_string()),
+            channels: None,
+            collapsed_by_default: None,
+            default_permissions: OptionUpdate::NoChange,
+            default_gate: OptionUpdate::NoChange,
+            position: None,
+        };
+        assert!(matches!(
+            categories.update(c, update, 2),
+            UpdateChannelCategoryResult::NameTaken
+        ));
+
+        let update = ChannelCategoryUpdate {
+            name: None,
+            channels: None,
+            collapsed_by_default: Some(true),
+            default_permissions: OptionUpdate::NoChange,
+            default_gate: OptionUpdate::NoChange,
+            position: Some(0),
+        };
+        assert!(matches!(
+            categories.update(c, update, 2),
+            UpdateChannelCategoryResult::Success
+        ));
+
+        let names: Vec<_> = categories.summaries(|_| true).into_iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["C", "A", "B"]);
+        assert!(categories.get(c).unwrap().collapsed_by_default);
+    }
+
+    #[test]
+    fn channels_stop_inheriting_defaults_once_removed_or_overridden() {
+        let mut categories = ChannelCategories::default();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let CreateChannelCategoryResult::Success(first) = categories.create(
+            "First".to_string(),
+            Vec::new(),
+            false,
+            Some(GroupPermissions::default()),
+            Some(AccessGate::DiamondMember),
+            &mut rng,
+            1,
+        ) else {
+            panic!()
+        };
+        let CreateChannelCategoryResult::Success(second) =
+            categories.create("Second".to_string(), Vec::new(), false, None, None, &mut rng, 1)
+        else {
+            panic!()
+        };
+
+        for channel_id in [1, 2, 3] {
+            categories.add_channel(first, channel_id, true, true, 2);
+        }
+
+        categories.stop_inheriting(1, true, false);
+        categories.add_channel(second, 2, false, false, 3);
+
+        let category = categories.get(first).unwrap();
+        assert_eq!(category.channels_inheriting_permissions, vec![3]);
+        assert_eq!(category.channels_inheriting_gate, vec![1, 3]);
+
+        let update = ChannelCategoryUpdate {
+            name: None,
+            channels: None,
+            collapsed_by_default: None,
+            default_permissions: OptionUpdate::NoChange,
+            default_gate: OptionUpdate::SetToNone,
+            position: None,
+        };
+        categories.update(first, update, 4);
+
+        let category = categories.get(first).unwrap();
+        assert_eq!(category.channels_inheriting_permissions, vec![3]);
+        assert!(category.channels_inheriting_gate.is_empty());
+    }
+}
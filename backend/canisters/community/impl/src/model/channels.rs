@@ -1,5 +1,5 @@
-use chat_events::Reader;
-use group_chat_core::{CanLeaveResult, GroupChatCore, GroupMemberInternal, LeaveResult};
+use chat_events::{Reader, StableEventsPendingRemoval};
+use group_chat_core::{CanLeaveResult, GroupChatCore, GroupMemberInternal, GroupRoleInternal, LeaveResult};
 use rand::rngs::StdRng;
 use rand::Rng;
 use search::*;
@@ -8,9 +8,9 @@ use std::cmp::{max, Reverse};
 use std::collections::hash_map::Entry::Vacant;
 use std::collections::HashMap;
 use types::{
-    ChannelId, ChannelMatch, CommunityCanisterChannelSummary, CommunityCanisterChannelSummaryUpdates, CommunityId,
-    GroupMembership, GroupMembershipUpdates, GroupPermissionRole, GroupPermissions, MultiUserChat, Rules, TimestampMillis,
-    Timestamped, UserId, MAX_THREADS_IN_SUMMARY,
+    ChannelId, ChannelMatch, ChannelUserGroupPermissions, CommunityCanisterChannelSummary,
+    CommunityCanisterChannelSummaryUpdates, CommunityId, GroupMembership, GroupMembershipUpdates, GroupPermissionRole,
+    GroupPermissions, MultiUserChat, Rules, TimestampMillis, Timestamped, UserId, MAX_THREADS_IN_SUMMARY,
 };
 
 use super::members::CommunityMembers;
@@ -18,6 +18,8 @@ use super::members::CommunityMembers;
 #[derive(Serialize, Deserialize, Default)]
 pub struct Channels {
     channels: HashMap<ChannelId, Channel>,
+    #[serde(default)]
+    events_pending_removal: StableEventsPendingRemoval,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,6 +27,8 @@ pub struct Channel {
     pub id: ChannelId,
     pub chat: GroupChatCore,
     pub date_imported: Option<TimestampMillis>,
+    #[serde(default)]
+    pub user_group_permissions: Timestamped<ChannelUserGroupPermissions>,
 }
 
 impl Channels {
@@ -57,7 +61,10 @@ impl Channels {
             })
             .collect();
 
-        Channels { channels }
+        Channels {
+            channels,
+            events_pending_removal: StableEventsPendingRemoval::default(),
+        }
     }
 
     pub fn add(&mut self, channel: Channel) {
@@ -68,7 +75,18 @@ impl Channels {
     }
 
     pub fn delete(&mut self, channel_id: ChannelId) -> Option<Channel> {
-        self.channels.remove(&channel_id)
+        let mut channel = self.channels.remove(&channel_id)?;
+        self.events_pending_removal.push(&mut channel.chat.events);
+        Some(channel)
+    }
+
+    pub fn has_events_pending_removal(&self) -> bool {
+        !self.events_pending_removal.is_empty()
+    }
+
+    // Removes a batch of the events of deleted channels from stable memory, returning true once none remain
+    pub fn remove_events_pending_removal(&mut self, max_events: usize) -> bool {
+        self.events_pending_removal.remove_batch(max_events)
     }
 
     pub fn get(&self, channel_id: &ChannelId) -> Option<&Channel> {
@@ -128,13 +146,20 @@ impl Channels {
         self.channels.values_mut()
     }
 
-    pub fn search(&self, search_term: Option<String>, page_index: u32, page_size: u8) -> (Vec<ChannelMatch>, u32) {
+    // Only channels for which `filter` returns true are included
+    pub fn search<F: Fn(&Channel) -> bool>(
+        &self,
+        search_term: Option<String>,
+        filter: F,
+        page_index: u32,
+        page_size: u8,
+    ) -> (Vec<ChannelMatch>, u32) {
         let query = search_term.map(Query::parse);
 
         let mut matches: Vec<_> = self
             .channels
             .values()
-            .filter(|c| c.chat.is_public.value)
+            .filter(|c| filter(c))
             .map(|c| {
                 let score = if let Some(query) = &query {
                     let document: Document = c.into();
@@ -212,6 +237,7 @@ impl Channel {
                 now,
             ),
             date_imported: None,
+            user_group_permissions: Timestamped::default(),
         }
     }
 
@@ -231,6 +257,8 @@ impl Channel {
             chat.min_visible_indexes_for_new_members.unwrap_or_default()
         } else if let Some(invitation) = user_id.and_then(|user_id| chat.invited_users.get(&user_id)) {
             (invitation.min_visible_event_index, invitation.min_visible_message_index)
+        } else if user_id.map_or(false, |user_id| self.can_view_via_user_groups(user_id, community_members)) {
+            chat.min_visible_indexes_for_new_members.unwrap_or_default()
         } else {
             return None;
         };
@@ -267,11 +295,12 @@ impl Channel {
                 .rules_accepted
                 .as_ref()
                 .map_or(false, |version| version.value >= chat.rules.text.version),
+            lapsed: m.lapsed.value,
         });
 
         Some(CommunityCanisterChannelSummary {
             channel_id: self.id,
-            last_updated: self.chat.last_updated(user_id),
+            last_updated: self.last_updated(user_id),
             name: chat.name.value.clone(),
             description: chat.description.value.clone(),
             subtype: chat.subtype.value.clone(),
@@ -291,13 +320,24 @@ impl Channel {
             events_ttl: events_ttl.value,
             events_ttl_last_updated: events_ttl.timestamp,
             gate: chat.gate.value.clone(),
+            slow_mode: chat.slow_mode.value,
+            read_receipts_enabled: chat.read_receipts_enabled.value,
+            gate_reverification: chat.gate_reverification.value,
+            user_group_permissions: self.user_group_permissions.value.clone(),
             membership,
             video_call_in_progress: chat.events.video_call_in_progress().value.clone(),
         })
     }
 
     pub fn last_updated(&self, user_id: Option<UserId>) -> TimestampMillis {
-        max(self.chat.last_updated(user_id), self.date_imported.unwrap_or_default())
+        [
+            self.chat.last_updated(user_id),
+            self.date_imported.unwrap_or_default(),
+            self.user_group_permissions.timestamp,
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
     }
 
     pub fn summary_updates(
@@ -351,6 +391,11 @@ impl Channel {
                 .as_ref()
                 .filter(|accepted| updates.rules_changed || accepted.timestamp > since)
                 .map(|accepted| accepted.value >= chat.rules.text.version),
+            lapsed: m
+                .lapsed
+                .if_set_after(since)
+                .copied()
+                .map_or(OptionUpdate::NoChange, OptionUpdate::from_update),
         });
 
         ChannelUpdates::Updated(CommunityCanisterChannelSummaryUpdates {
@@ -373,6 +418,10 @@ impl Channel {
             events_ttl: updates.events_ttl,
             events_ttl_last_updated: updates.events_ttl_last_updated,
             gate: updates.gate,
+            slow_mode: updates.slow_mode,
+            read_receipts_enabled: updates.read_receipts_enabled,
+            gate_reverification: updates.gate_reverification,
+            user_group_permissions: self.user_group_permissions.if_set_after(since).cloned(),
             membership,
             video_call_in_progress: updates.video_call_in_progress,
         })
@@ -393,6 +442,42 @@ impl Channel {
         }
     }
 
+    pub fn set_user_group_permissions(&mut self, permissions: ChannelUserGroupPermissions, now: TimestampMillis) -> bool {
+        if self.user_group_permissions.value != permissions {
+            self.user_group_permissions = Timestamped::new(permissions, now);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn remove_user_group(&mut self, user_group_id: u32, now: TimestampMillis) -> bool {
+        self.user_group_permissions
+            .update(|p| p.remove_user_group(user_group_id), now)
+    }
+
+    pub fn can_view_via_user_groups(&self, user_id: UserId, community_members: &CommunityMembers) -> bool {
+        community_members.is_in_any_user_group(&user_id, &self.user_group_permissions.view)
+    }
+
+    // Channel moderators and above can always send messages, other members must belong to one of the
+    // permitted user groups if any have been set
+    pub fn can_send_messages_via_user_groups(&self, user_id: UserId, community_members: &CommunityMembers) -> bool {
+        let user_group_ids = &self.user_group_permissions.send_messages;
+
+        user_group_ids.is_empty()
+            || community_members.is_in_any_user_group(&user_id, user_group_ids)
+            || self
+                .chat
+                .members
+                .get(&user_id)
+                .map_or(false, |m| m.role.is_same_or_senior(GroupRoleInternal::Moderator))
+    }
+
+    pub fn is_exempt_from_slow_mode(&self, user_id: UserId, community_members: &CommunityMembers) -> bool {
+        community_members.is_in_any_user_group(&user_id, &self.user_group_permissions.exempt_from_slow_mode)
+    }
+
     fn can_view_latest_message(&self, is_channel_member: bool, is_community_member: bool, is_community_public: bool) -> bool {
         is_channel_member
             || (self.chat.is_public.value && !self.chat.has_payment_gate() && (is_community_member || is_community_public))
@@ -414,6 +499,7 @@ impl From<&Channel> for ChannelMatch {
             member_count: channel.chat.members.len(),
             gate: channel.chat.gate.value.clone(),
             subtype: channel.chat.subtype.value.clone(),
+            category_id: None,
         }
     }
 }
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use types::{CommunityCustomRole, CommunityPermission, TimestampMillis};
+
+pub const MAX_CUSTOM_ROLES: u32 = 50;
+pub const MIN_CUSTOM_ROLE_NAME_LENGTH: u32 = 2;
+pub const MAX_CUSTOM_ROLE_NAME_LENGTH: u32 = 25;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct CommunityCustomRoles {
+    roles: BTreeMap<u32, CommunityCustomRole>,
+    next_id: u32,
+    last_updated: TimestampMillis,
+}
+
+impl CommunityCustomRoles {
+    pub fn get(&self, id: u32) -> Option<&CommunityCustomRole> {
+        self.roles.get(&id)
+    }
+
+    pub fn exists(&self, id: u32) -> bool {
+        self.roles.contains_key(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CommunityCustomRole> {
+        self.roles.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.roles.len()
+    }
+
+    pub fn last_updated(&self) -> TimestampMillis {
+        self.last_updated
+    }
+
+    pub fn is_name_taken(&self, name: &str, exclude: Option<u32>) -> bool {
+        self.roles
+            .values()
+            .any(|r| Some(r.id) != exclude && r.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn create(&mut self, name: String, permissions: Vec<CommunityPermission>, now: TimestampMillis) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.roles.insert(
+            id,
+            CommunityCustomRole {
+                id,
+                name,
+                permissions: dedup(permissions),
+            },
+        );
+        self.last_updated = now;
+        id
+    }
+
+    pub fn update(
+        &mut self,
+        id: u32,
+        name: Option<String>,
+        permissions: Option<Vec<CommunityPermission>>,
+        now: TimestampMillis,
+    ) -> bool {
+        let Some(role) = self.roles.get_mut(&id) else {
+            return false;
+        };
+
+        if let Some(name) = name {
+            role.name = name;
+        }
+        if let Some(permissions) = permissions {
+            role.permissions = dedup(permissions);
+        }
+        self.last_updated = now;
+        true
+    }
+
+    pub fn delete(&mut self, id: u32, now: TimestampMillis) -> bool {
+        if self.roles.remove(&id).is_some() {
+            self.last_updated = now;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// The custom roles held by a community member, along with the union of the permissions they grant so that permission
+// checks don't need to look up each role. The permissions must be refreshed whenever a role definition changes.
+#[derive(Serialize, Deserialize, Clone, Default, Eq, PartialEq)]
+pub struct CommunityCustomRoleGrants {
+    #[serde(rename = "r")]
+    role_ids: BTreeSet<u32>,
+    #[serde(rename = "p", default, skip_serializing_if = "Vec::is_empty")]
+    permissions: Vec<CommunityPermission>,
+}
+
+impl CommunityCustomRoleGrants {
+    pub fn role_ids(&self) -> &BTreeSet<u32> {
+        &self.role_ids
+    }
+
+    pub fn holds(&self, role_id: u32) -> bool {
+        self.role_ids.contains(&role_id)
+    }
+
+    pub fn add(&mut self, role_id: u32, roles: &CommunityCustomRoles) -> bool {
+        if self.role_ids.insert(role_id) {
+            self.refresh(roles);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn remove(&mut self, role_id: u32, roles: &CommunityCustomRoles) -> bool {
+        if self.role_ids.remove(&role_id) {
+            self.refresh(roles);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn refresh(&mut self, roles: &CommunityCustomRoles) {
+        self.permissions = dedup(
+            self.role_ids
+                .iter()
+                .filter_map(|id| roles.get(*id))
+                .flat_map(|r| r.permissions.iter().copied())
+                .collect(),
+        );
+    }
+
+    pub fn grants(&self, permission: CommunityPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+fn dedup<T: PartialEq>(values: Vec<T>) -> Vec<T> {
+    let mut result = Vec::with_capacity(values.len());
+    for value in values {
+        if !result.contains(&value) {
+            result.push(value);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grants_reflect_role_definitions() {
+        let mut roles = CommunityCustomRoles::default();
+        let moderator = roles.create("Moderator".to_string(), vec![CommunityPermission::RemoveMembers], 1);
+        let recruiter = roles.create("Recruiter".to_string(), vec![CommunityPermission::InviteUsers], 1);
+
+        let mut grants = CommunityCustomRoleGrants::default();
+        assert!(grants.add(moderator, &roles));
+        assert!(grants.add(recruiter, &roles));
+        assert!(grants.grants(CommunityPermission::RemoveMembers));
+        assert!(!grants.grants(CommunityPermission::UpdateDetails));
+
+        roles.update(moderator, None, Some(vec![CommunityPermission::ManageUserGroups]), 2);
+        grants.refresh(&roles);
+        assert!(!grants.grants(CommunityPermission::RemoveMembers));
+        assert!(grants.grants(CommunityPermission::ManageUserGroups));
+
+        assert!(grants.remove(moderator, &roles));
+        assert!(!grants.grants(CommunityPermission::ManageUserGroups));
+        assert!(grants.holds(recruiter));
+    }
+}
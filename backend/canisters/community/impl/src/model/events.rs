@@ -3,9 +3,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use types::{
     AvatarChanged, BannerChanged, ChannelDeleted, ChannelId, ChatId, CommunityPermissionsChanged, CommunityRoleChanged,
-    DefaultChannelsChanged, EventIndex, EventWrapper, GroupCreated, GroupDescriptionChanged, GroupFrozen, GroupGateUpdated,
-    GroupInviteCodeChanged, GroupNameChanged, GroupRulesChanged, GroupUnfrozen, GroupVisibilityChanged, MemberJoined,
-    MemberLeft, MembersRemoved, PrimaryLanguageChanged, TimestampMillis, UserId, UsersBlocked, UsersInvited, UsersUnblocked,
+    CustomRoleAssigned, CustomRoleUnassigned, DefaultChannelsChanged, EventIndex, EventWrapper, GroupCreated,
+    GroupDescriptionChanged, GroupFrozen, GroupGateUpdated, GroupInviteCodeChanged, GroupNameChanged, GroupRulesChanged,
+    GroupUnfrozen, GroupVisibilityChanged, MemberJoined, MemberLeft, MembersLapsed, MembersRemoved, MembersRemovedByGate,
+    PrimaryLanguageChanged, TimestampMillis, UserId, UsersBlocked, UsersInvited, UsersUnblocked,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -40,6 +41,10 @@ pub enum CommunityEventInternal {
     DefaultChannelsChanged(Box<DefaultChannelsChanged>),
     PrimaryLanguageChanged(Box<PrimaryLanguageChanged>),
     GroupImported(Box<GroupImportedInternal>),
+    MembersLapsed(Box<MembersLapsed>),
+    MembersRemovedByGate(Box<MembersRemovedByGate>),
+    CustomRoleAssigned(Box<CustomRoleAssigned>),
+    CustomRoleUnassigned(Box<CustomRoleUnassigned>),
 }
 
 impl CommunityEvents {
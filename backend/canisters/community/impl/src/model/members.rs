@@ -1,10 +1,17 @@
+use crate::model::custom_roles::{
+    CommunityCustomRoleGrants, CommunityCustomRoles, MAX_CUSTOM_ROLES, MAX_CUSTOM_ROLE_NAME_LENGTH, MIN_CUSTOM_ROLE_NAME_LENGTH,
+};
 use crate::model::user_groups::{UserGroup, UserGroups};
 use candid::Principal;
+use group_chat_core::GateReverificationMembers;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry::Vacant;
 use std::collections::{HashMap, HashSet};
-use types::{ChannelId, CommunityMember, CommunityPermissions, CommunityRole, TimestampMillis, Timestamped, UserId, Version};
+use types::{
+    ChannelId, CommunityMember, CommunityPermission, CommunityPermissions, CommunityRole, FieldTooLongResult,
+    FieldTooShortResult, TimestampMillis, Timestamped, UserId, Version,
+};
 
 const MAX_MEMBERS_PER_COMMUNITY: u32 = 100_000;
 
@@ -13,6 +20,8 @@ pub struct CommunityMembers {
     members: HashMap<UserId, CommunityMemberInternal>,
     display_names_last_updated: TimestampMillis,
     user_groups: UserGroups,
+    #[serde(default)]
+    custom_roles: CommunityCustomRoles,
     // This includes the userIds of community members and also users invited to the community
     principal_to_user_id_map: HashMap<Principal, UserId>,
     blocked: HashSet<UserId>,
@@ -37,12 +46,15 @@ impl CommunityMembers {
             rules_accepted: Some(Timestamped::new(Version::zero(), now)),
             is_bot: false,
             display_name: Timestamped::default(),
+            lapsed: Timestamped::default(),
+            custom_roles: Timestamped::default(),
         };
 
         CommunityMembers {
             members: vec![(creator_user_id, member)].into_iter().collect(),
             display_names_last_updated: now,
             user_groups: UserGroups::default(),
+            custom_roles: CommunityCustomRoles::default(),
             principal_to_user_id_map: vec![(creator_principal, creator_user_id)].into_iter().collect(),
             blocked: HashSet::new(),
             admin_count: 0,
@@ -66,6 +78,8 @@ impl CommunityMembers {
                         rules_accepted: None,
                         is_bot,
                         display_name: Timestamped::default(),
+                        lapsed: Timestamped::default(),
+                        custom_roles: Timestamped::default(),
                     };
                     e.insert(member.clone());
                     self.add_user_id(principal, user_id);
@@ -211,6 +225,13 @@ impl CommunityMembers {
         self.user_groups.iter()
     }
 
+    pub fn is_in_any_user_group(&self, user_id: &UserId, user_group_ids: &[u32]) -> bool {
+        user_group_ids
+            .iter()
+            .filter_map(|id| self.user_groups.get(*id))
+            .any(|g| g.members.contains(user_id))
+    }
+
     pub fn user_groups_deleted_since(&self, since: TimestampMillis) -> Vec<u32> {
         self.user_groups.deleted_since(since)
     }
@@ -219,6 +240,179 @@ impl CommunityMembers {
         self.user_groups.last_updated()
     }
 
+    pub fn custom_roles(&self) -> &CommunityCustomRoles {
+        &self.custom_roles
+    }
+
+    pub fn create_custom_role(
+        &mut self,
+        user_id: UserId,
+        name: String,
+        permissions: Vec<CommunityPermission>,
+        now: TimestampMillis,
+    ) -> CustomRoleResult {
+        if let Err(error) = self.can_define_custom_role(user_id, None, Some(&name), Some(&permissions)) {
+            return error;
+        }
+
+        if self.custom_roles.len() as u32 >= MAX_CUSTOM_ROLES {
+            return CustomRoleResult::TooManyRoles(MAX_CUSTOM_ROLES);
+        }
+
+        let role_id = self.custom_roles.create(name, permissions, now);
+        CustomRoleResult::Success(CustomRoleChanges::new(role_id))
+    }
+
+    pub fn update_custom_role(
+        &mut self,
+        user_id: UserId,
+        role_id: u32,
+        name: Option<String>,
+        permissions: Option<Vec<CommunityPermission>>,
+        now: TimestampMillis,
+    ) -> CustomRoleResult {
+        if let Err(error) = self.can_define_custom_role(user_id, Some(role_id), name.as_deref(), permissions.as_deref()) {
+            return error;
+        }
+
+        if !self.custom_roles.update(role_id, name, permissions, now) {
+            return CustomRoleResult::RoleNotFound;
+        }
+
+        // Recalculate the permissions granted to each holder of the role now that its definition has changed
+        for member in self.members.values_mut().filter(|m| m.custom_roles.value.holds(role_id)) {
+            member.custom_roles.value.refresh(&self.custom_roles);
+        }
+
+        CustomRoleResult::Success(CustomRoleChanges::new(role_id))
+    }
+
+    pub fn delete_custom_role(&mut self, user_id: UserId, role_id: u32, now: TimestampMillis) -> CustomRoleResult {
+        if let Err(error) = self.can_define_custom_role(user_id, Some(role_id), None, None) {
+            return error;
+        }
+
+        if !self.custom_roles.delete(role_id, now) {
+            return CustomRoleResult::RoleNotFound;
+        }
+
+        let holders: Vec<_> = self
+            .members
+            .values()
+            .filter(|m| m.custom_roles.value.holds(role_id))
+            .map(|m| m.user_id)
+            .collect();
+
+        let mut changes = CustomRoleChanges::new(role_id);
+        changes.users_removed = holders
+            .into_iter()
+            .filter(|u| self.remove_custom_role(*u, role_id, now))
+            .collect();
+
+        CustomRoleResult::Success(changes)
+    }
+
+    pub fn assign_custom_role(
+        &mut self,
+        user_id: UserId,
+        role_id: u32,
+        users_to_add: Vec<UserId>,
+        users_to_remove: Vec<UserId>,
+        permissions: &CommunityPermissions,
+        now: TimestampMillis,
+    ) -> CustomRoleResult {
+        use CustomRoleResult::*;
+
+        let Some(member) = self.members.get(&user_id) else {
+            return UserNotInCommunity;
+        };
+        if member.suspended.value {
+            return UserSuspended;
+        }
+        // Only the member's role is considered here (rather than any custom roles they hold) so that custom roles
+        // can't be used to escalate privileges
+        if !member.role.can_change_roles(CommunityRole::Member, permissions) {
+            return NotAuthorized;
+        }
+        if !self.custom_roles.exists(role_id) {
+            return RoleNotFound;
+        }
+
+        let mut changes = CustomRoleChanges::new(role_id);
+        changes.users_added = users_to_add
+            .into_iter()
+            .filter(|u| self.add_custom_role(*u, role_id, now))
+            .collect();
+        changes.users_removed = users_to_remove
+            .into_iter()
+            .filter(|u| self.remove_custom_role(*u, role_id, now))
+            .collect();
+
+        Success(changes)
+    }
+
+    fn add_custom_role(&mut self, user_id: UserId, role_id: u32, now: TimestampMillis) -> bool {
+        let roles = &self.custom_roles;
+        self.members
+            .get_mut(&user_id)
+            .map_or(false, |m| m.custom_roles.update(|g| g.add(role_id, roles), now))
+    }
+
+    fn remove_custom_role(&mut self, user_id: UserId, role_id: u32, now: TimestampMillis) -> bool {
+        let roles = &self.custom_roles;
+        self.members
+            .get_mut(&user_id)
+            .map_or(false, |m| m.custom_roles.update(|g| g.remove(role_id, roles), now))
+    }
+
+    fn can_define_custom_role(
+        &self,
+        user_id: UserId,
+        role_id: Option<u32>,
+        name: Option<&str>,
+        permissions: Option<&[CommunityPermission]>,
+    ) -> Result<(), CustomRoleResult> {
+        use CustomRoleResult::*;
+
+        let Some(member) = self.members.get(&user_id) else {
+            return Err(UserNotInCommunity);
+        };
+        if member.suspended.value {
+            return Err(UserSuspended);
+        }
+        if !member.role.can_change_permissions() {
+            return Err(NotAuthorized);
+        }
+        if let Some(role_id) = role_id {
+            if !self.custom_roles.exists(role_id) {
+                return Err(RoleNotFound);
+            }
+        }
+        if let Some(name) = name {
+            let length = name.chars().count() as u32;
+            if length < MIN_CUSTOM_ROLE_NAME_LENGTH {
+                return Err(NameTooShort(FieldTooShortResult {
+                    length_provided: length,
+                    min_length: MIN_CUSTOM_ROLE_NAME_LENGTH,
+                }));
+            }
+            if length > MAX_CUSTOM_ROLE_NAME_LENGTH {
+                return Err(NameTooLong(FieldTooLongResult {
+                    length_provided: length,
+                    max_length: MAX_CUSTOM_ROLE_NAME_LENGTH,
+                }));
+            }
+            if self.custom_roles.is_name_taken(name, role_id) {
+                return Err(NameTaken);
+            }
+        }
+        // Granting `ChangeRoles` would allow holders to assign roles, including to themselves
+        if permissions.map_or(false, |ps| ps.contains(&CommunityPermission::ChangeRoles)) {
+            return Err(PermissionNotGrantable);
+        }
+        Ok(())
+    }
+
     pub fn display_names_last_updated(&self) -> TimestampMillis {
         self.display_names_last_updated
     }
@@ -332,6 +526,14 @@ impl CommunityMembers {
     }
 }
 
+impl GateReverificationMembers for CommunityMembers {
+    fn lapsed_mut(&mut self, user_id: &UserId) -> Option<(bool, &mut Timestamped<Option<TimestampMillis>>)> {
+        self.members
+            .get_mut(user_id)
+            .map(|m| (m.requires_gate_reverification(), &mut m.lapsed))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct CommunityMemberInternal {
     pub user_id: UserId,
@@ -343,6 +545,10 @@ pub struct CommunityMemberInternal {
     pub rules_accepted: Option<Timestamped<Version>>,
     pub is_bot: bool,
     display_name: Timestamped<Option<String>>,
+    #[serde(default)]
+    pub lapsed: Timestamped<Option<TimestampMillis>>,
+    #[serde(default)]
+    pub custom_roles: Timestamped<CommunityCustomRoleGrants>,
 }
 
 impl CommunityMemberInternal {
@@ -353,6 +559,11 @@ impl CommunityMemberInternal {
         }
     }
 
+    // Owners, admins and bots are exempt from periodic gate checks
+    pub fn requires_gate_reverification(&self) -> bool {
+        !self.is_bot && !matches!(self.role, CommunityRole::Owner | CommunityRole::Admin)
+    }
+
     pub fn accept_rules(&mut self, version: Version, now: TimestampMillis) {
         let already_accepted = self
             .rules_accepted
@@ -380,6 +591,8 @@ impl CommunityMemberInternal {
             self.channels_removed.last().map(|c| c.timestamp).unwrap_or_default(),
             self.rules_accepted.as_ref().map(|r| r.timestamp).unwrap_or_default(),
             self.display_name.timestamp,
+            self.lapsed.timestamp,
+            self.custom_roles.timestamp,
         ]
         .into_iter()
         .max()
@@ -389,6 +602,44 @@ impl CommunityMemberInternal {
     pub fn display_name(&self) -> &Timestamped<Option<String>> {
         &self.display_name
     }
+
+    // The following permission checks honour both the member's role and any custom roles they hold
+
+    pub fn has_permission(&self, permission: CommunityPermission, permissions: &CommunityPermissions) -> bool {
+        self.role.has_permission(permission, permissions) || self.custom_roles.value.grants(permission)
+    }
+
+    pub fn can_invite_users(&self, permissions: &CommunityPermissions) -> bool {
+        self.has_permission(CommunityPermission::InviteUsers, permissions)
+    }
+
+    pub fn can_remove_members(&self, permissions: &CommunityPermissions) -> bool {
+        self.has_permission(CommunityPermission::RemoveMembers, permissions)
+    }
+
+    pub fn can_remove_members_with_role(&self, member_role: CommunityRole, permissions: &CommunityPermissions) -> bool {
+        self.role.is_same_or_senior(member_role) && self.can_remove_members(permissions)
+    }
+
+    pub fn can_unblock_users(&self, permissions: &CommunityPermissions) -> bool {
+        self.can_remove_members(permissions)
+    }
+
+    pub fn can_update_details(&self, permissions: &CommunityPermissions) -> bool {
+        self.has_permission(CommunityPermission::UpdateDetails, permissions)
+    }
+
+    pub fn can_create_public_channel(&self, permissions: &CommunityPermissions) -> bool {
+        self.has_permission(CommunityPermission::CreatePublicChannel, permissions)
+    }
+
+    pub fn can_create_private_channel(&self, permissions: &CommunityPermissions) -> bool {
+        self.has_permission(CommunityPermission::CreatePrivateChannel, permissions)
+    }
+
+    pub fn can_manage_user_groups(&self, permissions: &CommunityPermissions) -> bool {
+        self.has_permission(CommunityPermission::ManageUserGroups, permissions)
+    }
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -408,6 +659,36 @@ pub enum ChangeRoleResult {
     UserSuspended,
 }
 
+pub enum CustomRoleResult {
+    Success(CustomRoleChanges),
+    UserNotInCommunity,
+    UserSuspended,
+    NotAuthorized,
+    RoleNotFound,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+    TooManyRoles(u32),
+}
+
+// The users who were actually given or who lost the role, so that the corresponding events can be recorded
+pub struct CustomRoleChanges {
+    pub role_id: u32,
+    pub users_added: Vec<UserId>,
+    pub users_removed: Vec<UserId>,
+}
+
+impl CustomRoleChanges {
+    fn new(role_id: u32) -> CustomRoleChanges {
+        CustomRoleChanges {
+            role_id,
+            users_added: Vec::new(),
+            users_removed: Vec::new(),
+        }
+    }
+}
+
 pub struct ChangeRoleSuccessResult {
     pub caller_id: UserId,
     pub prev_role: CommunityRole,
@@ -420,6 +701,7 @@ impl From<CommunityMemberInternal> for CommunityMember {
             date_added: p.date_added,
             role: p.role,
             display_name: p.display_name.value,
+            custom_roles: p.custom_roles.value.role_ids().iter().copied().collect(),
         }
     }
 }
@@ -431,6 +713,7 @@ impl From<&CommunityMemberInternal> for CommunityMember {
             date_added: p.date_added,
             role: p.role,
             display_name: p.display_name.value.clone(),
+            custom_roles: p.custom_roles.value.role_ids().iter().copied().collect(),
         }
     }
 }
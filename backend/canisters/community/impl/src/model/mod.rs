@@ -1,4 +1,6 @@
+pub mod channel_categories;
 pub mod channels;
+pub mod custom_roles;
 pub mod events;
 pub mod groups_being_imported;
 pub mod invited_users;
@@ -37,7 +37,7 @@ fn can_start_video_call(
     call_type: VideoCallType,
     chat: &GroupChatCore,
 ) -> bool {
-    if !member.role.is_permitted(chat.permissions.start_video_call) {
+    if !member.can_start_video_call(&chat.permissions) {
         return false;
     }
 
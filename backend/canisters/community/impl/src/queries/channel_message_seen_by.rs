@@ -0,0 +1,31 @@
+use crate::{read_state, RuntimeState};
+use community_canister::channel_message_seen_by::{Response::*, *};
+use group_chat_core::MessageSeenByResult;
+use ic_cdk::query;
+
+#[query]
+fn channel_message_seen_by(args: Args) -> Response {
+    read_state(|state| channel_message_seen_by_impl(args, state))
+}
+
+fn channel_message_seen_by_impl(args: Args, state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.get(caller).map(|m| m.user_id) else {
+        return UserNotInCommunity;
+    };
+
+    if let Some(channel) = state.data.channels.get(&args.channel_id) {
+        match channel.chat.message_seen_by(user_id, args.message_index) {
+            MessageSeenByResult::Success(seen_by) => Success(SuccessResult {
+                seen_by_count: seen_by.count,
+                seen_by: seen_by.users,
+            }),
+            MessageSeenByResult::UserNotInGroup => UserNotInChannel,
+            MessageSeenByResult::ReadReceiptsDisabled => ReadReceiptsDisabled,
+            MessageSeenByResult::MessageNotFound => MessageNotFound,
+            MessageSeenByResult::MessageTooOld => MessageTooOld,
+        }
+    } else {
+        ChannelNotFound
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{read_state, RuntimeState};
+use community_canister::channel_quiz_leaderboard::{Response::*, *};
+use group_chat_core::QuizLeaderboardResult;
+use ic_cdk::query;
+
+#[query]
+fn channel_quiz_leaderboard(args: Args) -> Response {
+    read_state(|state| channel_quiz_leaderboard_impl(args, state))
+}
+
+fn channel_quiz_leaderboard_impl(args: Args, state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.get(caller).map(|m| m.user_id) else {
+        return UserNotInCommunity;
+    };
+
+    if let Some(channel) = state.data.channels.get(&args.channel_id) {
+        match channel.chat.quiz_leaderboard(user_id, args.max_results) {
+            QuizLeaderboardResult::Success(leaderboard, caller) => Success(SuccessResult { leaderboard, caller }),
+            QuizLeaderboardResult::UserNotInGroup => UserNotInChannel,
+        }
+    } else {
+        ChannelNotFound
+    }
+}
@@ -28,7 +28,27 @@ fn explore_channels_impl(args: Args, state: &RuntimeState) -> Response {
         return PrivateCommunity;
     }
 
-    let (matches, total) = state.data.channels.search(args.search_term, args.page_index, args.page_size);
+    let user_id = state.data.members.lookup_user_id(caller);
+    let categories = &state.data.channel_categories;
+    let (mut matches, total) = state.data.channels.search(
+        args.search_term,
+        |channel| {
+            // Private channels are included if the caller can see them via one of their user groups
+            let is_visible = channel.chat.is_public.value
+                || user_id.map_or(false, |u| channel.can_view_via_user_groups(u, &state.data.members));
+
+            is_visible
+                && args
+                    .category_id
+                    .map_or(true, |id| categories.category_of(channel.id).map_or(false, |c| c.id == id))
+        },
+        args.page_index,
+        args.page_size,
+    );
+
+    for channel_match in matches.iter_mut() {
+        channel_match.category_id = categories.category_of(channel_match.id).map(|c| c.id);
+    }
 
     Success(SuccessResult { matches, total })
 }
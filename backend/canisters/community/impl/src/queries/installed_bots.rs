@@ -0,0 +1,28 @@
+use crate::{read_state, RuntimeState};
+use community_canister::installed_bots::{Response::*, *};
+use ic_cdk::query;
+
+#[query]
+fn installed_bots(args: Args) -> Response {
+    read_state(|state| installed_bots_impl(args, state))
+}
+
+fn installed_bots_impl(args: Args, state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+
+    let Some(member) = state.data.members.get(caller) else {
+        return UserNotInCommunity;
+    };
+
+    let Some(channel) = state.data.channels.get(&args.channel_id) else {
+        return ChannelNotFound;
+    };
+
+    if channel.chat.members.get(&member.user_id).is_none() {
+        return UserNotInChannel;
+    }
+
+    Success(SuccessResult {
+        bots: channel.chat.installed_bots(),
+    })
+}
@@ -12,7 +12,7 @@ fn invite_code_impl(state: &RuntimeState) -> Response {
     let caller = state.env.caller();
 
     if let Some(member) = state.data.members.get(caller) {
-        if member.role.can_invite_users(&state.data.permissions) {
+        if member.can_invite_users(&state.data.permissions) {
             Success(SuccessResult {
                 code: if state.data.invite_code_enabled { state.data.invite_code } else { None },
             })
@@ -0,0 +1,31 @@
+use crate::{read_state, RuntimeState};
+use community_canister::message_edit_history::{Response::*, *};
+use group_chat_core::MessageEditHistoryResult;
+use ic_cdk::query;
+
+#[query]
+fn message_edit_history(args: Args) -> Response {
+    read_state(|state| message_edit_history_impl(args, state))
+}
+
+fn message_edit_history_impl(args: Args, state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.get(caller).map(|m| m.user_id) else {
+        return UserNotInCommunity;
+    };
+
+    if let Some(channel) = state.data.channels.get(&args.channel_id) {
+        match channel
+            .chat
+            .message_edit_history(user_id, args.thread_root_message_index, args.message_id)
+        {
+            MessageEditHistoryResult::Success(versions) => Success(SuccessResult { versions }),
+            MessageEditHistoryResult::UserNotInGroup => UserNotInChannel,
+            MessageEditHistoryResult::NotAuthorized => NotAuthorized,
+            MessageEditHistoryResult::ThreadNotFound => ThreadNotFound,
+            MessageEditHistoryResult::MessageNotFound => MessageNotFound,
+        }
+    } else {
+        ChannelNotFound
+    }
+}
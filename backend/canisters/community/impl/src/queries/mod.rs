@@ -2,6 +2,9 @@ use crate::RuntimeState;
 use types::TimestampMillis;
 
 mod c2c_can_issue_access_token_for_channel;
+mod c2c_is_member;
+mod channel_message_seen_by;
+mod channel_quiz_leaderboard;
 mod channel_summary;
 mod channel_summary_updates;
 mod deleted_message;
@@ -12,6 +15,7 @@ mod explore_channels;
 mod http_request;
 mod invite_code;
 mod local_user_index;
+mod message_edit_history;
 mod messages_by_message_index;
 mod search_channel;
 mod selected_channel_initial;
@@ -32,3 +36,4 @@ fn check_replica_up_to_date(latest_known_update: Option<TimestampMillis>, state:
     }
     Ok(())
 }
+pub mod installed_bots;
@@ -37,6 +37,7 @@ fn selected_channel_initial_impl(args: Args, state: &RuntimeState) -> Response {
             invited_users: chat.invited_users.users(),
             pinned_messages: chat.pinned_messages(min_visible_message_index),
             chat_rules: chat.rules.value.clone().into(),
+            custom_roles: chat.custom_roles.iter().cloned().collect(),
         })
     } else {
         ChannelNotFound
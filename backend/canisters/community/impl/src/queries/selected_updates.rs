@@ -52,6 +52,8 @@ fn selected_updates_impl(args: Args, state: &RuntimeState) -> Response {
             .map(|u| u.into())
             .collect(),
         user_groups_deleted: data.members.user_groups_deleted_since(args.updates_since),
+        custom_roles: (data.members.custom_roles().last_updated() > args.updates_since)
+            .then(|| data.members.custom_roles().iter().cloned().collect()),
     };
 
     let mut user_updates_handler = UserUpdatesHandler {
@@ -67,6 +69,11 @@ fn selected_updates_impl(args: Args, state: &RuntimeState) -> Response {
                     user_updates_handler.mark_member_updated(&mut result, *user_id, true);
                 }
             }
+            CommunityEventInternal::MembersRemovedByGate(p) => {
+                for user_id in p.user_ids.iter() {
+                    user_updates_handler.mark_member_updated(&mut result, *user_id, true);
+                }
+            }
             CommunityEventInternal::MemberJoined(p) => {
                 user_updates_handler.mark_member_updated(&mut result, p.user_id, false);
             }
@@ -78,6 +85,16 @@ fn selected_updates_impl(args: Args, state: &RuntimeState) -> Response {
                     user_updates_handler.mark_member_updated(&mut result, *user_id, false);
                 }
             }
+            CommunityEventInternal::CustomRoleAssigned(r) => {
+                for user_id in r.user_ids.iter() {
+                    user_updates_handler.mark_member_updated(&mut result, *user_id, false);
+                }
+            }
+            CommunityEventInternal::CustomRoleUnassigned(r) => {
+                for user_id in r.user_ids.iter() {
+                    user_updates_handler.mark_member_updated(&mut result, *user_id, false);
+                }
+            }
             CommunityEventInternal::UsersBlocked(ub) => {
                 for user_id in ub.user_ids.iter() {
                     user_updates_handler.mark_user_blocked_updated(&mut result, *user_id, true);
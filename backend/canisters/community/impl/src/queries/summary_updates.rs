@@ -70,6 +70,8 @@ fn summary_updates_impl(
         && channels_removed.is_empty()
         && state.data.events.latest_event_timestamp() <= updates_since
         && state.data.members.user_groups_last_updated() <= updates_since
+        && state.data.channel_categories.last_updated() <= updates_since
+        && state.data.gate_reverification.timestamp <= updates_since
         && member_last_updated <= updates_since
     {
         return SuccessNoUpdates;
@@ -116,12 +118,27 @@ fn summary_updates_impl(
                 Some(display_name) => OptionUpdate::SetToSome(display_name.clone()),
                 None => OptionUpdate::SetToNone,
             }),
+        lapsed: m
+            .lapsed
+            .if_set_after(updates_since)
+            .copied()
+            .map_or(OptionUpdate::NoChange, OptionUpdate::from_update),
     });
 
+    // The channels visible within each category depend on which channels the member has joined, so
+    // the categories are resent whenever the member's channels may have changed
+    let channel_categories = (state.data.channel_categories.last_updated() > updates_since
+        || member_last_updated > updates_since
+        || !channels_added.is_empty()
+        || !channels_removed.is_empty())
+    .then(|| state.data.channel_categories_summaries(member));
+
     let last_updated = [
         member_last_updated,
         state.data.events.latest_event_timestamp(),
         state.data.members.user_groups_last_updated(),
+        state.data.channel_categories.last_updated(),
+        state.data.gate_reverification.timestamp,
     ]
     .into_iter()
     .chain(channels_added.iter().map(|c| c.last_updated))
@@ -141,6 +158,12 @@ fn summary_updates_impl(
         permissions: updates_from_events.permissions,
         frozen: updates_from_events.frozen,
         gate: updates_from_events.gate,
+        gate_reverification: state
+            .data
+            .gate_reverification
+            .if_set_after(updates_since)
+            .copied()
+            .map_or(OptionUpdate::NoChange, OptionUpdate::from_update),
         primary_language: updates_from_events.primary_language,
         latest_event_index: updates_from_events.latest_event_index,
         channels_added,
@@ -155,6 +178,7 @@ fn summary_updates_impl(
             .map(|u| u.into())
             .collect(),
         user_groups_deleted: state.data.members.user_groups_deleted_since(updates_since),
+        channel_categories,
         metrics: state.data.cached_chat_metrics.if_set_after(updates_since).cloned(),
     })
 }
@@ -221,10 +245,13 @@ fn process_events(since: TimestampMillis, member: Option<&CommunityMemberInterna
             }
             CommunityEventInternal::MemberJoined(_)
             | CommunityEventInternal::MembersRemoved(_)
+            | CommunityEventInternal::MembersRemovedByGate(_)
             | CommunityEventInternal::MemberLeft(_)
             | CommunityEventInternal::UsersBlocked(_)
             | CommunityEventInternal::UsersUnblocked(_)
-            | CommunityEventInternal::GroupImported(_) => {
+            | CommunityEventInternal::GroupImported(_)
+            | CommunityEventInternal::CustomRoleAssigned(_)
+            | CommunityEventInternal::CustomRoleUnassigned(_) => {
                 updates.members_changed = true;
             }
             CommunityEventInternal::PermissionsChanged(p) => {
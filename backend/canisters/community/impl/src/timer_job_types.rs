@@ -1,14 +1,18 @@
 use crate::activity_notifications::handle_activity_notification;
 use crate::jobs::import_groups::{finalize_group_import, mark_import_complete, process_channel_members};
 use crate::updates::end_video_call::end_video_call_impl;
+use crate::updates::remove_member::remove_membership_from_user_canister;
 use crate::{mutate_state, read_state};
 use canister_timer_jobs::Job;
 use chat_events::MessageContentInternal;
-use ledger_utils::process_transaction;
+use group_chat_core::GATE_REVERIFICATION_BATCH_SIZE;
+use ic_cdk::api::management_canister::main::raw_rand;
+use ledger_utils::{create_pending_transaction, process_transaction};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tracing::error;
 use types::{BlobReference, CanisterId, ChannelId, ChatId, MessageId, MessageIndex, PendingCryptoTransaction, UserId};
-use utils::consts::MEMO_PRIZE_REFUND;
+use utils::consts::{MEMO_PRIZE_CLAIM, MEMO_PRIZE_REFUND, OPENCHAT_BOT_USER_ID};
 use utils::time::{DAY_IN_MS, MINUTE_IN_MS, NANOS_PER_MILLISECOND, SECOND_IN_MS};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -21,11 +25,16 @@ pub enum TimerJob {
     ProcessGroupImportChannelMembers(ProcessGroupImportChannelMembersJob),
     MarkGroupImportComplete(MarkGroupImportCompleteJob),
     RefundPrize(RefundPrizeJob),
+    DrawPrize(DrawPrizeJob),
+    TransferPrize(TransferPrizeJob),
     MakeTransfer(MakeTransferJob),
     NotifyEscrowCanisterOfDeposit(NotifyEscrowCanisterOfDepositJob),
     CancelP2PSwapInEscrowCanister(CancelP2PSwapInEscrowCanisterJob),
     MarkP2PSwapExpired(MarkP2PSwapExpiredJob),
     MarkVideoCallEnded(MarkVideoCallEndedJob),
+    ReverifyCommunityGate(ReverifyCommunityGateJob),
+    ReverifyChannelGate(ReverifyChannelGateJob),
+    UnmuteChannelMember(UnmuteChannelMemberJob),
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -45,6 +54,8 @@ pub struct EndPollJob {
     pub channel_id: ChannelId,
     pub thread_root_message_index: Option<MessageIndex>,
     pub message_index: MessageIndex,
+    #[serde(default)]
+    pub attempt: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -75,6 +86,22 @@ pub struct RefundPrizeJob {
     pub message_index: MessageIndex,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DrawPrizeJob {
+    pub channel_id: ChannelId,
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub message_index: MessageIndex,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TransferPrizeJob {
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub winner: UserId,
+    pub pending_transaction: PendingCryptoTransaction,
+    pub attempt: u32,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MakeTransferJob {
     pub pending_transaction: PendingCryptoTransaction,
@@ -136,6 +163,23 @@ pub struct MarkP2PSwapExpiredJob {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MarkVideoCallEndedJob(pub community_canister::end_video_call::Args);
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReverifyCommunityGateJob {
+    pub after: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReverifyChannelGateJob {
+    pub channel_id: ChannelId,
+    pub after: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnmuteChannelMemberJob {
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+}
+
 impl Job for TimerJob {
     fn execute(self) {
         match self {
@@ -147,11 +191,16 @@ impl Job for TimerJob {
             TimerJob::ProcessGroupImportChannelMembers(job) => job.execute(),
             TimerJob::MarkGroupImportComplete(job) => job.execute(),
             TimerJob::RefundPrize(job) => job.execute(),
+            TimerJob::DrawPrize(job) => job.execute(),
+            TimerJob::TransferPrize(job) => job.execute(),
             TimerJob::MakeTransfer(job) => job.execute(),
             TimerJob::NotifyEscrowCanisterOfDeposit(job) => job.execute(),
             TimerJob::CancelP2PSwapInEscrowCanister(job) => job.execute(),
             TimerJob::MarkP2PSwapExpired(job) => job.execute(),
             TimerJob::MarkVideoCallEnded(job) => job.execute(),
+            TimerJob::ReverifyCommunityGate(job) => job.execute(),
+            TimerJob::ReverifyChannelGate(job) => job.execute(),
+            TimerJob::UnmuteChannelMember(job) => job.execute(),
         }
     }
 }
@@ -189,13 +238,17 @@ impl Job for HardDeleteMessageContentJob {
                             if state
                                 .data
                                 .timer_jobs
-                                .cancel_job(|job| {
-                                    if let TimerJob::RefundPrize(j) = job {
+                                .cancel_job(|job| match job {
+                                    TimerJob::RefundPrize(j) => {
                                         j.thread_root_message_index == self.thread_root_message_index
                                             && j.message_index == message_index
-                                    } else {
-                                        false
                                     }
+                                    TimerJob::DrawPrize(j) => {
+                                        j.channel_id == self.channel_id
+                                            && j.thread_root_message_index == self.thread_root_message_index
+                                            && j.message_index == message_index
+                                    }
+                                    _ => false,
                                 })
                                 .is_some()
                             {
@@ -225,17 +278,77 @@ impl Job for DeleteFileReferencesJob {
 
 impl Job for EndPollJob {
     fn execute(self) {
-        mutate_state(|state| {
+        let awaiting_vote_weights = mutate_state(|state| {
             let now = state.env.now();
-            if let Some(channel) = state.data.channels.get_mut(&self.channel_id) {
-                channel
-                    .chat
-                    .events
-                    .end_poll(self.thread_root_message_index, self.message_index, now);
-
+            if self.attempt == 0 {
+                let channel = state.data.channels.get_mut(&self.channel_id)?;
+                channel.chat.end_poll(self.thread_root_message_index, self.message_index, now);
                 handle_activity_notification(state);
             }
+
+            let channel = state.data.channels.get_mut(&self.channel_id)?;
+            let (weighting, end_date, voters) = channel
+                .chat
+                .events
+                .poll_awaiting_vote_weights(self.thread_root_message_index, self.message_index)?;
+
+            if self.attempt >= 10 {
+                // Stop retrying, any voters whose weights are still unknown have no weight
+                if channel.chat.events.record_poll_vote_weights(
+                    self.thread_root_message_index,
+                    self.message_index,
+                    HashMap::new(),
+                    true,
+                    now,
+                ) {
+                    handle_activity_notification(state);
+                }
+                return None;
+            }
+
+            // Queue up the next attempt before looking up the weights, so that the results are still calculated if any
+            // of the lookups fail or the canister is upgraded while they are in progress
+            state.data.timer_jobs.enqueue_job(
+                TimerJob::EndPoll(EndPollJob {
+                    attempt: self.attempt + 1,
+                    ..self.clone()
+                }),
+                now + 5 * MINUTE_IN_MS,
+                now,
+            );
+            Some((weighting, end_date, voters))
         });
+
+        // For weighted polls, the results can only be calculated once each voter's weight has been looked up. The
+        // weights are taken as at the time the poll ended and are added to the poll's pending tally as they are found
+        if let Some((weighting, end_date, voters)) = awaiting_vote_weights {
+            ic_cdk::spawn(async move {
+                let weights = gated_groups::lookup_vote_weights(weighting, voters, end_date).await;
+
+                mutate_state(|state| {
+                    let now = state.env.now();
+                    if let Some(channel) = state.data.channels.get_mut(&self.channel_id) {
+                        if channel.chat.events.record_poll_vote_weights(
+                            self.thread_root_message_index,
+                            self.message_index,
+                            weights,
+                            false,
+                            now,
+                        ) {
+                            state.data.timer_jobs.cancel_job(|job| match job {
+                                TimerJob::EndPoll(j) => {
+                                    j.channel_id == self.channel_id
+                                        && j.thread_root_message_index == self.thread_root_message_index
+                                        && j.message_index == self.message_index
+                                }
+                                _ => false,
+                            });
+                            handle_activity_notification(state);
+                        }
+                    }
+                });
+            });
+        }
     }
 }
 
@@ -283,6 +396,120 @@ impl Job for RefundPrizeJob {
     }
 }
 
+impl Job for DrawPrizeJob {
+    fn execute(self) {
+        ic_cdk::spawn(draw_prize(self));
+
+        async fn draw_prize(job: DrawPrizeJob) {
+            let Some(seed): Option<[u8; 32]> = raw_rand().await.ok().and_then(|(bytes,)| bytes.try_into().ok()) else {
+                // Try again shortly, the draw can only be made once a random seed has been obtained
+                mutate_state(|state| {
+                    let now = state.env.now();
+                    state
+                        .data
+                        .timer_jobs
+                        .enqueue_job(TimerJob::DrawPrize(job), now + MINUTE_IN_MS, now);
+                });
+                return;
+            };
+
+            // The draw, along with a transfer job for each winner and the refund of any prizes which weren't won, is
+            // recorded in a single update so that none of them can be lost if the canister is upgraded
+            mutate_state(|state| {
+                let now = state.env.now();
+                let now_nanos = state.env.now_nanos();
+                let Some(channel) = state.data.channels.get_mut(&job.channel_id) else {
+                    return;
+                };
+                let Some(draw) = channel.chat.events.draw_prize_winners(job.message_index, seed, now) else {
+                    return;
+                };
+
+                for (winner, amount) in draw.winners {
+                    let pending_transaction = create_pending_transaction(
+                        draw.token.clone(),
+                        draw.ledger_canister_id,
+                        amount,
+                        draw.fee,
+                        winner,
+                        Some(&MEMO_PRIZE_CLAIM),
+                        now_nanos,
+                    );
+                    state.data.timer_jobs.enqueue_job(
+                        TimerJob::TransferPrize(TransferPrizeJob {
+                            channel_id: job.channel_id,
+                            message_id: draw.message_id,
+                            winner,
+                            pending_transaction,
+                            attempt: 0,
+                        }),
+                        now,
+                        now,
+                    );
+                }
+
+                // Prizes which have been won stay reserved until they are transferred, so this only refunds the prizes
+                // which weren't won
+                state.data.timer_jobs.enqueue_job(
+                    TimerJob::RefundPrize(RefundPrizeJob {
+                        channel_id: job.channel_id,
+                        thread_root_message_index: job.thread_root_message_index,
+                        message_index: job.message_index,
+                    }),
+                    now,
+                    now,
+                );
+
+                handle_activity_notification(state);
+            });
+        }
+    }
+}
+
+impl Job for TransferPrizeJob {
+    fn execute(self) {
+        let sender = read_state(|state| state.env.canister_id());
+        ic_cdk::spawn(transfer_prize(self, sender));
+
+        async fn transfer_prize(mut job: TransferPrizeJob, sender: CanisterId) {
+            let result = process_transaction(job.pending_transaction.clone(), sender).await;
+
+            mutate_state(|state| {
+                let now = state.env.now();
+                match result {
+                    Ok(completed) => {
+                        let Some(channel) = state.data.channels.get_mut(&job.channel_id) else {
+                            return;
+                        };
+                        channel.chat.events.claim_prize(
+                            job.message_id,
+                            job.winner,
+                            completed,
+                            state.env.rng(),
+                            &mut state.data.event_store_client,
+                            now,
+                        );
+                        handle_activity_notification(state);
+                    }
+                    Err(failed) => {
+                        // The prize stays reserved for the winner, so keep retrying, backing off up to once an hour
+                        error!(?failed, winner = ?job.winner, attempt = job.attempt, "Failed to transfer prize to draw winner");
+                        if (job.pending_transaction.created() / NANOS_PER_MILLISECOND) + DAY_IN_MS < now {
+                            job.pending_transaction.set_created(now * NANOS_PER_MILLISECOND);
+                        }
+                        let delay = MINUTE_IN_MS * (job.attempt as u64 + 1).min(60);
+                        job.attempt += 1;
+                        state
+                            .data
+                            .timer_jobs
+                            .enqueue_job(TimerJob::TransferPrize(job), now + delay, now);
+                    }
+                }
+            });
+        }
+    }
+}
+
 impl Job for MakeTransferJob {
     fn execute(self) {
         let sender = read_state(|state| state.env.canister_id());
@@ -318,6 +545,8 @@ impl Job for NotifyEscrowCanisterOfDepositJob {
                 &escrow_canister::notify_deposit::Args {
                     swap_id: self.swap_id,
                     user_id: Some(self.user_id),
+                    token1_amount: None,
+                    fill_id: None,
                 },
             )
             .await
@@ -420,3 +649,158 @@ impl Job for MarkVideoCallEndedJob {
         mutate_state(|state| end_video_call_impl(self.0, state));
     }
 }
+
+impl Job for ReverifyCommunityGateJob {
+    fn execute(self) {
+        let Some((gate, user_ids, this_canister, user_index_canister, group_index_canister, now)) = read_state(|state| {
+            state
+                .data
+                .gate_to_reverify(self.after, GATE_REVERIFICATION_BATCH_SIZE)
+                .map(|(gate, user_ids)| {
+                    (
+                        gate,
+                        user_ids,
+                        state.env.canister_id(),
+                        state.data.user_index_canister_id,
+                        state.data.group_index_canister_id,
+                        state.env.now(),
+                    )
+                })
+        }) else {
+            return;
+        };
+
+        // A full batch means there may be more members to check, so the next batch follows on from the last user
+        let next_batch_after = (user_ids.len() == GATE_REVERIFICATION_BATCH_SIZE)
+            .then(|| user_ids.last().copied())
+            .flatten();
+
+        ic_cdk::spawn(async move {
+            let result = gated_groups::reverify_members(
+                gate.clone(),
+                user_ids,
+                this_canister,
+                user_index_canister,
+                group_index_canister,
+                now,
+            )
+            .await;
+
+            mutate_state(|state| {
+                let now = state.env.now();
+
+                // If the gate has been changed while the checks were in progress then the results are no longer valid
+                if state.data.gate.value.as_ref() == Some(&gate) {
+                    let removed = state
+                        .data
+                        .apply_gate_reverification_results(result.passed, result.failed, now);
+
+                    for user_id in removed {
+                        remove_membership_from_user_canister(
+                            user_id,
+                            OPENCHAT_BOT_USER_ID,
+                            false,
+                            state.data.name.clone(),
+                            state.data.is_public,
+                            &mut state.data.fire_and_forget_handler,
+                        );
+                    }
+
+                    handle_activity_notification(state);
+
+                    if let Some(after) = next_batch_after {
+                        state.data.continue_gate_reverification(after, now);
+                        return;
+                    }
+                }
+
+                state.data.schedule_gate_reverification(now);
+            });
+        });
+    }
+}
+
+impl Job for ReverifyChannelGateJob {
+    fn execute(self) {
+        let channel_id = self.channel_id;
+        let Some((gate, user_ids, this_canister, user_index_canister, group_index_canister, now)) = read_state(|state| {
+            state
+                .data
+                .channels
+                .get(&channel_id)
+                .and_then(|c| c.chat.gate_to_reverify(self.after, GATE_REVERIFICATION_BATCH_SIZE))
+                .map(|(gate, user_ids)| {
+                    (
+                        gate,
+                        user_ids,
+                        state.env.canister_id(),
+                        state.data.user_index_canister_id,
+                        state.data.group_index_canister_id,
+                        state.env.now(),
+                    )
+                })
+        }) else {
+            return;
+        };
+
+        // A full batch means there may be more members to check, so the next batch follows on from the last user
+        let next_batch_after = (user_ids.len() == GATE_REVERIFICATION_BATCH_SIZE)
+            .then(|| user_ids.last().copied())
+            .flatten();
+
+        ic_cdk::spawn(async move {
+            let result = gated_groups::reverify_members(
+                gate.clone(),
+                user_ids,
+                this_canister,
+                user_index_canister,
+                group_index_canister,
+                now,
+            )
+            .await;
+
+            mutate_state(|state| {
+                let now = state.env.now();
+
+                let Some(channel) = state.data.channels.get_mut(&channel_id) else {
+                    return;
+                };
+
+                // If the gate has been changed while the checks were in progress then the results are no longer valid
+                if channel.chat.gate.value.as_ref() == Some(&gate) {
+                    let removed = channel
+                        .chat
+                        .apply_gate_reverification_results(result.passed, result.failed, now);
+
+                    for user_id in removed {
+                        if let Some(member) = state.data.members.get_by_user_id_mut(&user_id) {
+                            member.leave(channel_id, now);
+                        }
+                    }
+
+                    handle_activity_notification(state);
+
+                    if let Some(after) = next_batch_after {
+                        state.data.continue_channel_gate_reverification(channel_id, after, now);
+                        return;
+                    }
+                }
+
+                state.data.schedule_channel_gate_reverification(channel_id, now);
+            });
+        });
+    }
+}
+
+impl Job for UnmuteChannelMemberJob {
+    fn execute(self) {
+        mutate_state(|state| {
+            if let Some(channel) = state.data.channels.get_mut(&self.channel_id) {
+                let now = state.env.now();
+                if channel.chat.unmute_member_if_expired(self.user_id, now) {
+                    handle_activity_notification(state);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,108 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::model::events::CommunityEventInternal;
+use crate::model::members::AddResult;
+use crate::{mutate_state, read_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::add_bot::{Response::*, *};
+use group_chat_core::{AddBotResult, CanAddBotResult};
+use ic_cdk::update;
+use types::{CanisterId, MemberJoined, SlashCommandSchema};
+
+#[update]
+#[trace]
+async fn add_bot(args: Args) -> Response {
+    run_regular_jobs();
+
+    let user_index_canister_id = match read_state(|state| prepare(&args, state)) {
+        Ok(ok) => ok,
+        Err(response) => return response,
+    };
+
+    let c2c_args = user_index_canister::c2c_bot_commands::Args { bot_id: args.bot_id };
+    match user_index_canister_c2c_client::c2c_bot_commands(user_index_canister_id, &c2c_args).await {
+        Ok(user_index_canister::c2c_bot_commands::Response::Success(result)) => {
+            mutate_state(|state| commit(args, result.username, result.commands, state))
+        }
+        Ok(user_index_canister::c2c_bot_commands::Response::BotNotFound) => BotNotFound,
+        Err(error) => InternalError(format!("{error:?}")),
+    }
+}
+
+fn prepare(args: &Args, state: &RuntimeState) -> Result<CanisterId, Response> {
+    if state.data.is_frozen() {
+        return Err(CommunityFrozen);
+    }
+
+    let caller = state.env.caller();
+    let Some(member) = state.data.members.get(caller) else {
+        return Err(UserNotInCommunity);
+    };
+    if member.suspended.value {
+        return Err(UserSuspended);
+    }
+
+    let Some(channel) = state.data.channels.get(&args.channel_id) else {
+        return Err(ChannelNotFound);
+    };
+
+    match channel.chat.can_add_bot(member.user_id) {
+        CanAddBotResult::Yes => Ok(state.data.user_index_canister_id),
+        CanAddBotResult::UserSuspended => Err(UserSuspended),
+        CanAddBotResult::UserNotInGroup => Err(UserNotInChannel),
+        CanAddBotResult::NotAuthorized => Err(NotAuthorized),
+    }
+}
+
+fn commit(args: Args, bot_name: String, commands: Vec<SlashCommandSchema>, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.get(caller).map(|m| m.user_id) else {
+        return UserNotInCommunity;
+    };
+
+    let Some(channel) = state.data.channels.get_mut(&args.channel_id) else {
+        return ChannelNotFound;
+    };
+
+    let now = state.env.now();
+
+    // The bot must be a member of the community before it can be added to one of its channels
+    let added_to_community = match state.data.members.add(args.bot_id, args.bot_id.into(), true, now) {
+        AddResult::Success(_) => true,
+        AddResult::AlreadyInCommunity => false,
+        AddResult::Blocked => return BotBlocked,
+    };
+
+    let result = channel.chat.add_bot(user_id, args.bot_id, bot_name, commands, now);
+
+    if matches!(result, AddBotResult::Success) {
+        if added_to_community {
+            state.data.events.push_event(
+                CommunityEventInternal::MemberJoined(Box::new(MemberJoined {
+                    user_id: args.bot_id,
+                    invited_by: Some(user_id),
+                })),
+                now,
+            );
+        }
+        state.data.members.mark_member_joined_channel(&args.bot_id, args.channel_id);
+    } else if added_to_community {
+        state.data.members.remove(&args.bot_id, now);
+    }
+
+    match result {
+        AddBotResult::Success => {
+            handle_activity_notification(state);
+            Success
+        }
+        AddBotResult::UserSuspended => UserSuspended,
+        AddBotResult::UserNotInGroup => UserNotInChannel,
+        AddBotResult::NotAuthorized => NotAuthorized,
+        AddBotResult::NotABot => BotNotFound,
+        AddBotResult::Blocked => BotBlocked,
+        AddBotResult::MemberLimitReached(limit) => UserLimitReached(limit),
+    }
+}
@@ -8,8 +8,8 @@ use ic_cdk::update;
 use std::collections::HashMap;
 use std::iter::zip;
 use types::{
-    AccessGate, AddedToChannelNotification, CanisterId, ChannelId, EventIndex, MembersAdded, MessageIndex, Notification,
-    TimestampNanos, UserId,
+    AccessGate, AddedToChannelNotification, BotEvent, BotMembersJoinedEvent, CanisterId, ChannelId, EventIndex, MembersAdded,
+    MessageIndex, Notification, TimestampMillis, UserId,
 };
 
 #[update]
@@ -55,9 +55,12 @@ async fn add_members_to_channel(args: Args) -> Response {
                         user_id: *user_id,
                         diamond_membership_expires_at: diamond_membership_expiry_dates.get(user_id).copied(),
                         this_canister: prepare_result.this_canister,
+                        user_index_canister: prepare_result.user_index_canister,
+                        group_index_canister: prepare_result.group_index_canister,
                         unique_person_proof: None,
                         verified_credential_args: None,
-                        now: prepare_result.now_nanos,
+                        user_details: None,
+                        now: prepare_result.now,
                     },
                 )
             })
@@ -105,7 +108,9 @@ struct PrepareResult {
     is_bot: bool,
     member_display_name: Option<String>,
     this_canister: CanisterId,
-    now_nanos: TimestampNanos,
+    user_index_canister: CanisterId,
+    group_index_canister: CanisterId,
+    now: TimestampMillis,
 }
 
 #[allow(clippy::result_large_err)]
@@ -128,7 +133,7 @@ fn prepare(args: &Args, state: &RuntimeState) -> Result<PrepareResult, Response>
                 Err(UserLimitReached(limit))
             } else if let Some(channel_member) = channel.chat.members.get(&user_id) {
                 let permissions = &channel.chat.permissions;
-                if !channel_member.role.can_add_members(permissions) {
+                if !channel_member.can_add_members(permissions) {
                     return Err(NotAuthorized);
                 }
 
@@ -147,7 +152,9 @@ fn prepare(args: &Args, state: &RuntimeState) -> Result<PrepareResult, Response>
                     is_bot: member.is_bot,
                     member_display_name: member.display_name().value.clone(),
                     this_canister: state.env.canister_id(),
-                    now_nanos: state.env.now_nanos(),
+                    user_index_canister: state.data.user_index_canister_id,
+                    group_index_canister: state.data.group_index_canister_id,
+                    now: state.env.now(),
                 })
             } else {
                 Err(UserNotInChannel)
@@ -219,6 +226,14 @@ fn commit(
             });
         }
 
+        channel.chat.bots.push_event(
+            BotEvent::MembersJoined(BotMembersJoinedEvent {
+                user_ids: users_added.clone(),
+                added_by: Some(added_by),
+            }),
+            now,
+        );
+
         let event = MembersAdded {
             user_ids: users_added.clone(),
             added_by,
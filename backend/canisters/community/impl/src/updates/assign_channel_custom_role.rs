@@ -0,0 +1,53 @@
+use crate::{activity_notifications::handle_activity_notification, mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::assign_channel_custom_role::{Response::*, *};
+use group_chat_core::CustomRoleResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn assign_channel_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| assign_channel_custom_role_impl(args, state))
+}
+
+fn assign_channel_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let user_id = match state.data.members.get(caller) {
+        Some(m) if m.suspended.value => return UserSuspended,
+        Some(m) => m.user_id,
+        _ => return UserNotInCommunity,
+    };
+
+    let mut users_to_add = args.users_to_add;
+    for user_group_id in args.user_group_ids {
+        let Some(user_group) = state.data.members.get_user_group(user_group_id) else {
+            return UserGroupNotFound(user_group_id);
+        };
+        users_to_add.extend(user_group.members.value.iter().copied());
+    }
+
+    if let Some(channel) = state.data.channels.get_mut(&args.channel_id) {
+        let now = state.env.now();
+        match channel
+            .chat
+            .assign_custom_role(user_id, args.role_id, users_to_add, args.users_to_remove, now)
+        {
+            CustomRoleResult::Success(_) => {
+                handle_activity_notification(state);
+                Success
+            }
+            CustomRoleResult::UserNotInGroup => UserNotInChannel,
+            CustomRoleResult::UserSuspended => UserSuspended,
+            CustomRoleResult::RoleNotFound => RoleNotFound,
+            _ => NotAuthorized,
+        }
+    } else {
+        ChannelNotFound
+    }
+}
@@ -0,0 +1,52 @@
+use crate::model::members::CustomRoleResult;
+use crate::{activity_notifications::handle_activity_notification, mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::assign_custom_role::{Response::*, *};
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn assign_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| assign_custom_role_impl(args, state))
+}
+
+fn assign_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.lookup_user_id(caller) else {
+        return UserNotInCommunity;
+    };
+
+    let mut users_to_add = args.users_to_add;
+    for user_group_id in args.user_group_ids {
+        let Some(user_group) = state.data.members.get_user_group(user_group_id) else {
+            return UserGroupNotFound(user_group_id);
+        };
+        users_to_add.extend(user_group.members.value.iter().copied());
+    }
+
+    let now = state.env.now();
+    match state.data.members.assign_custom_role(
+        user_id,
+        args.role_id,
+        users_to_add,
+        args.users_to_remove,
+        &state.data.permissions,
+        now,
+    ) {
+        CustomRoleResult::Success(changes) => {
+            state.data.push_custom_role_events(changes, user_id, now);
+            handle_activity_notification(state);
+            Success
+        }
+        CustomRoleResult::UserNotInCommunity => UserNotInCommunity,
+        CustomRoleResult::UserSuspended => UserSuspended,
+        CustomRoleResult::RoleNotFound => RoleNotFound,
+        _ => NotAuthorized,
+    }
+}
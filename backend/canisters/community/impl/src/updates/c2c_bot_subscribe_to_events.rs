@@ -0,0 +1,29 @@
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_api_macros::update_msgpack;
+use canister_tracing_macros::trace;
+use community_canister::c2c_bot_subscribe_to_events::{Response::*, *};
+
+#[update_msgpack]
+#[trace]
+fn c2c_bot_subscribe_to_events(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| c2c_bot_subscribe_to_events_impl(args, state))
+}
+
+fn c2c_bot_subscribe_to_events_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let bot_id = state.env.caller().into();
+    let Some(channel) = state.data.channels.get_mut(&args.channel_id) else {
+        return ChannelNotFound;
+    };
+
+    if channel.chat.bots.set_subscriptions(&bot_id, args.event_types) {
+        Success
+    } else {
+        BotNotInstalled
+    }
+}
@@ -31,7 +31,7 @@ pub(crate) fn invite_users_to_community_impl(args: Args, state: &mut RuntimeStat
         }
 
         // The original caller must be authorized to invite other users
-        if !state.data.is_public && !member.role.can_invite_users(&state.data.permissions) {
+        if !state.data.is_public && !member.can_invite_users(&state.data.permissions) {
             return NotAuthorized;
         }
 
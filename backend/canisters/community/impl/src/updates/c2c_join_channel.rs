@@ -14,7 +14,10 @@ use gated_groups::{
     CheckVerifiedCredentialGateArgs,
 };
 use group_chat_core::AddResult;
-use types::{AccessGate, ChannelId, MemberJoined, TimestampMillis, UniquePersonProof, VerifiedCredentialGateArgs};
+use types::{
+    AccessGate, BotEvent, BotMembersJoinedEvent, ChannelId, MemberJoined, TimestampMillis, UniquePersonProof,
+    VerifiedCredentialGateArgs,
+};
 
 #[update_msgpack(guard = "caller_is_user_index_or_local_user_index")]
 #[trace]
@@ -139,7 +142,10 @@ fn is_permitted_to_join(
                         .summary(Some(channel_member.user_id), true, state.data.is_public, &state.data.members)
                         .unwrap(),
                 )))
-            } else if !channel.chat.is_public.value && channel.chat.invited_users.get(&member.user_id).is_none() {
+            } else if !channel.chat.is_public.value
+                && channel.chat.invited_users.get(&member.user_id).is_none()
+                && !channel.can_view_via_user_groups(member.user_id, &state.data.members)
+            {
                 Err(NotInvited)
             } else if let Some(limit) = channel.chat.members.user_limit_reached() {
                 Err(MemberLimitReached(limit))
@@ -153,6 +159,8 @@ fn is_permitted_to_join(
                             user_id: member.user_id,
                             diamond_membership_expires_at,
                             this_canister: state.env.canister_id(),
+                            user_index_canister: state.data.user_index_canister_id,
+                            group_index_canister: state.data.group_index_canister_id,
                             unique_person_proof,
                             verified_credential_args: verified_credential_args.map(|vc| CheckVerifiedCredentialGateArgs {
                                 user_ii_principal: vc.user_ii_principal,
@@ -161,6 +169,7 @@ fn is_permitted_to_join(
                                 ii_canister_id: state.data.internet_identity_canister_id,
                                 ii_origin: vc.ii_origin,
                             }),
+                            user_details: None,
                             now: state.env.now(),
                         },
                     )
@@ -246,6 +255,14 @@ pub(crate) fn join_channel_unchecked(
         AddResult::Success(_) => {
             let invitation = channel.chat.invited_users.remove(&member.user_id, now);
 
+            channel.chat.bots.push_event(
+                BotEvent::MembersJoined(BotMembersJoinedEvent {
+                    user_ids: vec![member.user_id],
+                    added_by: invitation.as_ref().map(|i| i.invited_by),
+                }),
+                now,
+            );
+
             if channel.chat.is_public.value {
                 channel.chat.events.mark_member_added_to_public_channel(member.user_id, now);
             } else {
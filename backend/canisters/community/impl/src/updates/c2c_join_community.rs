@@ -75,6 +75,8 @@ fn is_permitted_to_join(args: &Args, state: &RuntimeState) -> Result<Option<(Acc
                     user_id: args.user_id,
                     diamond_membership_expires_at: args.diamond_membership_expires_at,
                     this_canister: state.env.canister_id(),
+                    user_index_canister: state.data.user_index_canister_id,
+                    group_index_canister: state.data.group_index_canister_id,
                     unique_person_proof: args.unique_person_proof.clone(),
                     verified_credential_args: args.verified_credential_args.as_ref().map(|vc| {
                         CheckVerifiedCredentialGateArgs {
@@ -85,6 +87,7 @@ fn is_permitted_to_join(args: &Args, state: &RuntimeState) -> Result<Option<(Acc
                             ii_origin: vc.ii_origin.clone(),
                         }
                     }),
+                    user_details: None,
                     now: state.env.now(),
                 },
             )
@@ -0,0 +1,34 @@
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_api_macros::update_msgpack;
+use canister_tracing_macros::trace;
+use community_canister::c2c_mark_read::{Response::*, *};
+
+#[update_msgpack]
+#[trace]
+fn c2c_mark_read(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| c2c_mark_read_impl(args, state))
+}
+
+fn c2c_mark_read_impl(args: Args, state: &mut RuntimeState) -> Response {
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.get(caller).map(|m| m.user_id) else {
+        return UserNotInCommunity;
+    };
+
+    let mut read_receipts_disabled = Vec::new();
+    for channel_read in args.channels {
+        if let Some(channel) = state.data.channels.get_mut(&channel_read.channel_id) {
+            if channel.chat.read_receipts_enabled.value {
+                channel
+                    .chat
+                    .mark_read(user_id, channel_read.previous, channel_read.read_up_to);
+            } else {
+                read_receipts_disabled.push(channel_read.channel_id);
+            }
+        }
+    }
+
+    Success(SuccessResult { read_receipts_disabled })
+}
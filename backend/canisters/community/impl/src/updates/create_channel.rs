@@ -12,7 +12,7 @@ use group_chat_core::GroupChatCore;
 use ic_cdk::update;
 use rand::Rng;
 use std::collections::HashMap;
-use types::{AccessGate, ChannelId, MultiUserChat, TimestampMillis, UserId};
+use types::{AccessGate, ChannelId, MultiUserChat, TimestampMillis, Timestamped, UserId};
 use utils::document_validation::validate_avatar;
 use utils::text_validation::{
     validate_description, validate_group_name, validate_rules, NameValidationError, RulesValidationError,
@@ -20,23 +20,28 @@ use utils::text_validation::{
 
 #[update]
 #[trace]
-async fn create_channel(args: Args) -> Response {
+async fn create_channel(mut args: Args) -> Response {
     run_regular_jobs();
 
+    // Apply the category defaults first since they may determine which gate is set on the channel
+    let inherited = read_state(|state| apply_channel_category_defaults(&mut args, state));
+
     let diamond_membership_expiry_dates: HashMap<_, _> = match get_diamond_membership_expiry_dates_if_needed(&args).await {
         Ok(expiry_dates) => expiry_dates,
         Err(response) => return response,
     };
 
-    mutate_state(|state| create_channel_impl(args, false, diamond_membership_expiry_dates, state))
+    mutate_state(|state| create_channel_impl(args, false, inherited, diamond_membership_expiry_dates, state))
 }
 
 #[update_msgpack(guard = "caller_is_proposals_bot")]
 #[trace]
-fn c2c_create_proposals_channel(args: Args) -> Response {
+fn c2c_create_proposals_channel(mut args: Args) -> Response {
     run_regular_jobs();
 
     mutate_state(|state| {
+        let inherited = apply_channel_category_defaults(&mut args, state);
+
         let caller = state.env.caller();
 
         if let Some(response) = join_community_impl(
@@ -61,13 +66,14 @@ fn c2c_create_proposals_channel(args: Args) -> Response {
             }
         }
 
-        create_channel_impl(args, true, HashMap::new(), state)
+        create_channel_impl(args, true, inherited, HashMap::new(), state)
     })
 }
 
 fn create_channel_impl(
     args: Args,
     is_proposals_channel: bool,
+    inherited: InheritedCategoryDefaults,
     diamond_membership_expiry_dates: HashMap<UserId, TimestampMillis>,
     state: &mut RuntimeState,
 ) -> Response {
@@ -81,13 +87,20 @@ fn create_channel_impl(
             return UserSuspended;
         }
 
+        if args
+            .category_id
+            .map_or(false, |id| state.data.channel_categories.get(id).is_none())
+        {
+            return ChannelCategoryNotFound;
+        }
+
         let subtype = is_proposals_channel.then_some(args.subtype).flatten();
 
         if !is_proposals_channel {
             let is_authorized = if args.is_public {
-                member.role.can_create_public_channel(&state.data.permissions)
+                member.can_create_public_channel(&state.data.permissions)
             } else {
-                member.role.can_create_private_channel(&state.data.permissions)
+                member.can_create_private_channel(&state.data.permissions)
             };
 
             if !is_authorized {
@@ -143,6 +156,7 @@ fn create_channel_impl(
                 id: channel_id,
                 chat,
                 date_imported: None,
+                user_group_permissions: Timestamped::default(),
             };
 
             if args.is_public {
@@ -168,6 +182,13 @@ fn create_channel_impl(
 
             state.data.channels.add(channel);
 
+            if let Some(category_id) = args.category_id {
+                state
+                    .data
+                    .channel_categories
+                    .add_channel(category_id, channel_id, inherited.permissions, inherited.gate, now);
+            }
+
             handle_activity_notification(state);
             Success(SuccessResult { channel_id })
         }
@@ -176,6 +197,27 @@ fn create_channel_impl(
     }
 }
 
+#[derive(Default)]
+struct InheritedCategoryDefaults {
+    permissions: bool,
+    gate: bool,
+}
+
+fn apply_channel_category_defaults(args: &mut Args, state: &RuntimeState) -> InheritedCategoryDefaults {
+    let mut inherited = InheritedCategoryDefaults::default();
+    if let Some(category) = args.category_id.and_then(|id| state.data.channel_categories.get(id)) {
+        if args.permissions_v2.is_none() && category.default_permissions.is_some() {
+            args.permissions_v2.clone_from(&category.default_permissions);
+            inherited.permissions = true;
+        }
+        if args.gate.is_none() && category.default_gate.is_some() {
+            args.gate.clone_from(&category.default_gate);
+            inherited.gate = true;
+        }
+    }
+    inherited
+}
+
 async fn get_diamond_membership_expiry_dates_if_needed(args: &Args) -> Result<HashMap<UserId, TimestampMillis>, Response> {
     if let Some(AccessGate::DiamondMember) = &args.gate {
         let (local_user_index_canister_id, user_ids) = read_state(|state| {
@@ -0,0 +1,59 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::model::channel_categories::{CreateChannelCategoryResult, MAX_CHANNEL_CATEGORIES};
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::create_channel_category::{Response::*, *};
+use ic_cdk::update;
+use utils::text_validation::{validate_channel_category_name, StringLengthValidationError};
+
+#[update]
+#[trace]
+fn create_channel_category(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| create_channel_category_impl(args, state))
+}
+
+fn create_channel_category_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    match state.data.members.get(caller) {
+        Some(m) if m.suspended.value => return UserSuspended,
+        Some(m) if m.can_update_details(&state.data.permissions) => {}
+        _ => return NotAuthorized,
+    }
+
+    if let Err(error) = validate_channel_category_name(&args.name) {
+        return match error {
+            StringLengthValidationError::TooShort(s) => NameTooShort(s),
+            StringLengthValidationError::TooLong(l) => NameTooLong(l),
+        };
+    }
+    if args.default_gate.as_ref().map_or(false, |g| !g.validate()) {
+        return AccessGateInvalid;
+    }
+    if args.channel_ids.iter().any(|c| state.data.channels.get(c).is_none()) {
+        return ChannelNotFound;
+    }
+
+    let now = state.env.now();
+    match state.data.channel_categories.create(
+        args.name.trim().to_string(),
+        args.channel_ids,
+        args.collapsed_by_default,
+        args.default_permissions,
+        args.default_gate,
+        state.env.rng(),
+        now,
+    ) {
+        CreateChannelCategoryResult::Success(category_id) => {
+            handle_activity_notification(state);
+            Success(SuccessResult { category_id })
+        }
+        CreateChannelCategoryResult::NameTaken => NameTaken,
+        CreateChannelCategoryResult::MaxCategoriesCreated => MaxChannelCategoriesCreated(MAX_CHANNEL_CATEGORIES),
+    }
+}
@@ -0,0 +1,46 @@
+use crate::model::members::CustomRoleResult;
+use crate::{activity_notifications::handle_activity_notification, mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::create_custom_role::{Response::*, *};
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn create_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| create_custom_role_impl(args, state))
+}
+
+fn create_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.lookup_user_id(caller) else {
+        return UserNotInCommunity;
+    };
+
+    let now = state.env.now();
+    match state
+        .data
+        .members
+        .create_custom_role(user_id, args.name, args.permissions, now)
+    {
+        CustomRoleResult::Success(changes) => {
+            handle_activity_notification(state);
+            Success(SuccessResult {
+                role_id: changes.role_id,
+            })
+        }
+        CustomRoleResult::UserNotInCommunity => UserNotInCommunity,
+        CustomRoleResult::UserSuspended => UserSuspended,
+        CustomRoleResult::NotAuthorized | CustomRoleResult::RoleNotFound => NotAuthorized,
+        CustomRoleResult::NameTooShort(result) => NameTooShort(result),
+        CustomRoleResult::NameTooLong(result) => NameTooLong(result),
+        CustomRoleResult::NameTaken => NameTaken,
+        CustomRoleResult::PermissionNotGrantable => PermissionNotGrantable,
+        CustomRoleResult::TooManyRoles(max) => TooManyRoles(max),
+    }
+}
@@ -24,7 +24,7 @@ fn create_user_group_impl(args: Args, state: &mut RuntimeState) -> Response {
             return UserSuspended;
         }
 
-        if !member.role.can_manage_user_groups(&state.data.permissions) {
+        if !member.can_manage_user_groups(&state.data.permissions) {
             NotAuthorized
         } else if let Err(error) = validate_user_group_name(&args.name) {
             match error {
@@ -33,6 +33,7 @@ fn delete_channel_impl(channel_id: ChannelId, state: &mut RuntimeState) -> Respo
                 if channel_member.role.can_delete_group() {
                     let now = state.env.now();
                     let channel = state.data.channels.delete(channel_id).expect("Channel should exist");
+                    crate::jobs::remove_events_of_deleted_chats::start_job_if_required(state);
 
                     state.data.events.push_event(
                         CommunityEventInternal::ChannelDeleted(Box::new(ChannelDeleted {
@@ -47,6 +48,8 @@ fn delete_channel_impl(channel_id: ChannelId, state: &mut RuntimeState) -> Respo
                         state.data.members.mark_member_left_channel(&user_id, channel_id, now);
                     }
 
+                    state.data.channel_categories.remove_channel(channel_id, now);
+
                     handle_activity_notification(state);
 
                     Success
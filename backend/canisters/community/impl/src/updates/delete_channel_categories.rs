@@ -0,0 +1,39 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::delete_channel_categories::{Response::*, *};
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn delete_channel_categories(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| delete_channel_categories_impl(args, state))
+}
+
+fn delete_channel_categories_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    match state.data.members.get(caller) {
+        Some(m) if m.suspended.value => UserSuspended,
+        Some(m) if m.can_update_details(&state.data.permissions) => {
+            let now = state.env.now();
+
+            let mut updated = false;
+            for category_id in args.category_ids {
+                if state.data.channel_categories.delete(category_id, now) {
+                    updated = true;
+                }
+            }
+            if updated {
+                handle_activity_notification(state);
+            }
+            Success
+        }
+        _ => NotAuthorized,
+    }
+}
@@ -0,0 +1,37 @@
+use crate::model::members::CustomRoleResult;
+use crate::{activity_notifications::handle_activity_notification, mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::delete_custom_role::{Response::*, *};
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn delete_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| delete_custom_role_impl(args, state))
+}
+
+fn delete_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.members.lookup_user_id(caller) else {
+        return UserNotInCommunity;
+    };
+
+    let now = state.env.now();
+    match state.data.members.delete_custom_role(user_id, args.role_id, now) {
+        CustomRoleResult::Success(changes) => {
+            state.data.push_custom_role_events(changes, user_id, now);
+            handle_activity_notification(state);
+            Success
+        }
+        CustomRoleResult::UserNotInCommunity => UserNotInCommunity,
+        CustomRoleResult::UserSuspended => UserSuspended,
+        CustomRoleResult::RoleNotFound => RoleNotFound,
+        _ => NotAuthorized,
+    }
+}
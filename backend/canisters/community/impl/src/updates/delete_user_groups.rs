@@ -20,12 +20,15 @@ fn delete_user_groups_impl(args: Args, state: &mut RuntimeState) -> Response {
     let caller = state.env.caller();
     match state.data.members.get(caller) {
         Some(m) if m.suspended.value => UserSuspended,
-        Some(m) if m.role.can_manage_user_groups(&state.data.permissions) => {
+        Some(m) if m.can_manage_user_groups(&state.data.permissions) => {
             let now = state.env.now();
 
             let mut updated = false;
             for user_group_id in args.user_group_ids {
                 if state.data.members.delete_user_group(user_group_id, now) {
+                    for channel in state.data.channels.iter_mut() {
+                        channel.remove_user_group(user_group_id, now);
+                    }
                     updated = true;
                 }
             }
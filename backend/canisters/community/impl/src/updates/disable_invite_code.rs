@@ -26,7 +26,7 @@ fn disable_invite_code_impl(state: &mut RuntimeState) -> Response {
             return UserSuspended;
         }
 
-        if member.role.can_invite_users(&state.data.permissions) {
+        if member.can_invite_users(&state.data.permissions) {
             state.data.invite_code_enabled = false;
 
             let now = state.env.now();
@@ -97,7 +97,7 @@ fn prepare(state: &RuntimeState) -> Result<PrepareResult, Response> {
             return Err(UserSuspended);
         }
 
-        if participant.role.can_invite_users(&state.data.permissions) {
+        if participant.can_invite_users(&state.data.permissions) {
             return Ok(PrepareResult {
                 caller,
                 code: state.data.invite_code,
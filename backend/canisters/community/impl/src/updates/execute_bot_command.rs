@@ -0,0 +1,187 @@
+use crate::updates::send_message::process_send_message_result;
+use crate::{mutate_state, read_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::execute_bot_command::{Response::*, *};
+use group_chat_core::PrepareBotCommandResult;
+use ic_cdk::update;
+use rand::Rng;
+use types::{BotMessageFailure, CanisterId, ChannelId, Chat, MessageIndex, UserId};
+
+#[update]
+#[trace]
+async fn execute_bot_command(args: Args) -> Response {
+    run_regular_jobs();
+
+    let user_index_canister_id = match read_state(|state| check_bot_installed(&args, state)) {
+        Ok(ok) => ok,
+        Err(response) => return response,
+    };
+
+    // The bot's commands may have changed since it was installed, so they are refreshed before the command is validated
+    let c2c_args = user_index_canister::c2c_bot_commands::Args { bot_id: args.bot_id };
+    match user_index_canister_c2c_client::c2c_bot_commands(user_index_canister_id, &c2c_args).await {
+        Ok(user_index_canister::c2c_bot_commands::Response::Success(result)) => mutate_state(|state| {
+            if let Some(channel) = state.data.channels.get_mut(&args.channel_id) {
+                channel.chat.bots.refresh(&args.bot_id, result.username, result.commands);
+            }
+        }),
+        Ok(user_index_canister::c2c_bot_commands::Response::BotNotFound) => return BotNotFound,
+        Err(error) => return InternalError(format!("{error:?}")),
+    }
+
+    let (user_id, chat) = match read_state(|state| prepare(&args, state)) {
+        Ok(ok) => ok,
+        Err(response) => return response,
+    };
+
+    let c2c_args = bot_api::handle_command::Args {
+        chat,
+        thread_root_message_index: args.thread_root_message_index,
+        initiator: user_id,
+        command: args.command,
+    };
+
+    match bot_c2c_client::handle_command(args.bot_id.into(), &c2c_args).await {
+        Ok(bot_api::handle_command::Response::Success(result)) => mutate_state(|state| {
+            Success(send_bot_messages(
+                args.channel_id,
+                args.bot_id,
+                args.thread_root_message_index,
+                result,
+                state,
+            ))
+        }),
+        Ok(bot_api::handle_command::Response::Error(error)) => BotError(error),
+        Err(error) => InternalError(format!("{error:?}")),
+    }
+}
+
+fn check_bot_installed(args: &Args, state: &RuntimeState) -> Result<CanisterId, Response> {
+    if state.data.is_frozen() {
+        return Err(CommunityFrozen);
+    }
+
+    let caller = state.env.caller();
+    if state.data.members.get(caller).is_none() {
+        return Err(UserNotInCommunity);
+    }
+
+    let Some(channel) = state.data.channels.get(&args.channel_id) else {
+        return Err(ChannelNotFound);
+    };
+
+    if channel.chat.bots.get(&args.bot_id).is_none() {
+        return Err(BotNotFound);
+    }
+
+    Ok(state.data.user_index_canister_id)
+}
+
+fn prepare(args: &Args, state: &RuntimeState) -> Result<(UserId, Chat), Response> {
+    if state.data.is_frozen() {
+        return Err(CommunityFrozen);
+    }
+
+    let caller = state.env.caller();
+    let Some(member) = state.data.members.get(caller) else {
+        return Err(UserNotInCommunity);
+    };
+    if member.suspended.value {
+        return Err(UserSuspended);
+    }
+
+    let Some(channel) = state.data.channels.get(&args.channel_id) else {
+        return Err(ChannelNotFound);
+    };
+
+    match channel
+        .chat
+        .prepare_bot_command(member.user_id, args.bot_id, args.thread_root_message_index, &args.command)
+    {
+        PrepareBotCommandResult::Success => {
+            Ok((member.user_id, Chat::Channel(state.env.canister_id().into(), args.channel_id)))
+        }
+        PrepareBotCommandResult::UserSuspended => Err(UserSuspended),
+        PrepareBotCommandResult::UserNotInGroup => Err(UserNotInChannel),
+        PrepareBotCommandResult::ThreadMessageNotFound => Err(ThreadMessageNotFound),
+        PrepareBotCommandResult::BotNotFound => Err(BotNotFound),
+        PrepareBotCommandResult::CommandNotFound => Err(CommandNotFound),
+        PrepareBotCommandResult::InvalidCommand(error) => Err(InvalidCommand(error)),
+        PrepareBotCommandResult::NotAuthorized => Err(NotAuthorized),
+        PrepareBotCommandResult::BotNotAuthorized => Err(BotNotAuthorized),
+    }
+}
+
+// The bot's messages are sent as if the bot itself had sent them, so they are subject to the same permission checks
+// as any other member's messages
+fn send_bot_messages(
+    channel_id: ChannelId,
+    bot_id: UserId,
+    thread_root_message_index: Option<MessageIndex>,
+    result: bot_api::handle_command::SuccessResult,
+    state: &mut RuntimeState,
+) -> SuccessResult {
+    let mut message_indexes = Vec::new();
+    let mut failed_messages = Vec::new();
+
+    for message in result.messages {
+        let message_id = message.message_id.unwrap_or_else(|| state.env.rng().gen());
+        let Some(channel) = state.data.channels.get_mut(&channel_id) else {
+            failed_messages.push(BotMessageFailure {
+                message_id,
+                reason: "ChannelNotFound".to_string(),
+            });
+            continue;
+        };
+
+        let now = state.env.now();
+        let block_level_markdown = message.block_level_markdown.unwrap_or_default();
+
+        let send_message_result = channel.chat.validate_and_send_message(
+            bot_id,
+            true,
+            false,
+            thread_root_message_index,
+            message_id,
+            message.content,
+            None,
+            Vec::new(),
+            false,
+            None,
+            false,
+            state.data.proposals_bot_user_id,
+            block_level_markdown,
+            &mut state.data.event_store_client,
+            now,
+        );
+
+        let channel_name = channel.chat.name.value.clone();
+        let channel_avatar_id = channel.chat.avatar.as_ref().map(|d| d.id);
+
+        match process_send_message_result(
+            send_message_result,
+            bot_id,
+            result.bot_name.clone(),
+            result.bot_display_name.clone(),
+            channel_id,
+            channel_name,
+            channel_avatar_id,
+            thread_root_message_index,
+            Vec::new(),
+            Vec::new(),
+            now,
+            state,
+        ) {
+            community_canister::send_message::Response::Success(r) => message_indexes.push(r.message_index),
+            response => failed_messages.push(BotMessageFailure {
+                message_id,
+                reason: format!("{response:?}"),
+            }),
+        }
+    }
+
+    SuccessResult {
+        message_indexes,
+        failed_messages,
+    }
+}
@@ -1,6 +1,10 @@
 pub mod accept_p2p_swap;
+pub mod add_bot;
 pub mod add_members_to_channel;
 pub mod add_reaction;
+pub mod assign_channel_custom_role;
+pub mod assign_custom_role;
+pub mod c2c_bot_subscribe_to_events;
 pub mod c2c_delete_community;
 pub mod c2c_freeze_community;
 pub mod c2c_invite_users;
@@ -8,6 +12,7 @@ pub mod c2c_invite_users_to_channel;
 pub mod c2c_join_channel;
 pub mod c2c_join_community;
 pub mod c2c_leave_community;
+pub mod c2c_mark_read;
 pub mod c2c_notify_p2p_swap_status_change;
 pub mod c2c_set_user_suspended;
 pub mod c2c_tip_message;
@@ -20,23 +25,32 @@ pub mod change_channel_role;
 pub mod change_role;
 pub mod claim_prize;
 pub mod create_channel;
+pub mod create_channel_category;
+pub mod create_channel_custom_role;
+pub mod create_custom_role;
 pub mod create_user_group;
 pub mod decline_invitation;
 pub mod delete_channel;
+pub mod delete_channel_categories;
+pub mod delete_channel_custom_role;
+pub mod delete_custom_role;
 pub mod delete_messages;
 pub mod delete_user_groups;
 pub mod disable_invite_code;
 pub mod edit_message;
 pub mod enable_invite_code;
 pub mod end_video_call;
+pub mod execute_bot_command;
 pub mod follow_thread;
 pub mod import_group;
 pub mod join_video_call;
 pub mod leave_channel;
+pub mod mute_channel_member;
 pub mod pin_message;
 pub mod register_poll_vote;
 pub mod register_proposal_vote;
 pub mod register_proposal_vote_v2;
+pub mod remove_bot;
 pub mod remove_member;
 pub mod remove_member_from_channel;
 pub mod remove_reaction;
@@ -50,6 +64,9 @@ pub mod unblock_user;
 pub mod undelete_messages;
 pub mod unfollow_thread;
 pub mod update_channel;
+pub mod update_channel_category;
+pub mod update_channel_custom_role;
 pub mod update_community;
+pub mod update_custom_role;
 pub mod update_user_group;
 pub mod wallet_receive;
@@ -0,0 +1,58 @@
+use crate::timer_job_types::{TimerJob, UnmuteChannelMemberJob};
+use crate::{activity_notifications::handle_activity_notification, mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::mute_channel_member::{Response::*, *};
+use group_chat_core::MuteMemberResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn mute_channel_member(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| mute_channel_member_impl(args, state))
+}
+
+fn mute_channel_member_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let user_id = match state.data.members.get(caller) {
+        Some(m) if m.suspended.value => return UserSuspended,
+        Some(m) => m.user_id,
+        _ => return UserNotInCommunity,
+    };
+
+    if let Some(channel) = state.data.channels.get_mut(&args.channel_id) {
+        let now = state.env.now();
+        match channel
+            .chat
+            .mute_member(user_id, args.user_id, args.duration, args.reason, now)
+        {
+            MuteMemberResult::Success(muted_until) => {
+                state.data.timer_jobs.enqueue_job(
+                    TimerJob::UnmuteChannelMember(UnmuteChannelMemberJob {
+                        channel_id: args.channel_id,
+                        user_id: args.user_id,
+                    }),
+                    muted_until,
+                    now,
+                );
+
+                handle_activity_notification(state);
+                Success(SuccessResult { muted_until })
+            }
+            MuteMemberResult::UserSuspended => UserSuspended,
+            MuteMemberResult::UserNotInGroup => UserNotInChannel,
+            MuteMemberResult::TargetUserNotInGroup => TargetUserNotInChannel,
+            MuteMemberResult::NotAuthorized => NotAuthorized,
+            MuteMemberResult::CannotMuteSelf => CannotMuteSelf,
+            MuteMemberResult::DurationInvalid => DurationInvalid,
+            MuteMemberResult::ReasonTooLong(max_length) => ReasonTooLong(max_length),
+        }
+    } else {
+        ChannelNotFound
+    }
+}
@@ -49,6 +49,7 @@ fn register_poll_vote_impl(args: Args, state: &mut RuntimeState) -> Response {
         thread_root_message_index: args.thread_root_message_index,
         message_index: args.message_index,
         option_index: args.poll_option,
+        ranking: args.ranking.unwrap_or_default(),
         operation: args.operation,
         now,
         correlation_id: 0,
@@ -64,5 +65,6 @@ fn register_poll_vote_impl(args: Args, state: &mut RuntimeState) -> Response {
         RegisterPollVoteResult::PollNotFound => PollNotFound,
         RegisterPollVoteResult::OptionIndexOutOfRange => OptionIndexOutOfRange,
         RegisterPollVoteResult::UserCannotChangeVote => UserCannotChangeVote,
+        RegisterPollVoteResult::InvalidRanking => InvalidRanking,
     }
 }
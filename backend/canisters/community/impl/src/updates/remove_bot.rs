@@ -0,0 +1,51 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::remove_bot::{Response::*, *};
+use group_chat_core::RemoveBotResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn remove_bot(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| remove_bot_impl(args, state))
+}
+
+fn remove_bot_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(member) = state.data.members.get(caller) else {
+        return UserNotInCommunity;
+    };
+    if member.suspended.value {
+        return UserSuspended;
+    }
+
+    let user_id = member.user_id;
+
+    let Some(channel) = state.data.channels.get_mut(&args.channel_id) else {
+        return ChannelNotFound;
+    };
+
+    let now = state.env.now();
+    match channel.chat.remove_bot(user_id, args.bot_id, now) {
+        RemoveBotResult::Success => {
+            state
+                .data
+                .members
+                .mark_member_left_channel(&args.bot_id, args.channel_id, now);
+
+            handle_activity_notification(state);
+            Success
+        }
+        RemoveBotResult::UserSuspended => UserSuspended,
+        RemoveBotResult::UserNotInGroup => UserNotInChannel,
+        RemoveBotResult::NotAuthorized => NotAuthorized,
+        RemoveBotResult::BotNotFound => BotNotFound,
+    }
+}
@@ -86,10 +86,7 @@ fn prepare(user_id: UserId, block: bool, state: &RuntimeState) -> Result<Prepare
             };
 
             // Check if the caller is authorized to remove the user
-            if member
-                .role
-                .can_remove_members_with_role(user_to_remove_role, &state.data.permissions)
-            {
+            if member.can_remove_members_with_role(user_to_remove_role, &state.data.permissions) {
                 Ok(PrepareResult {
                     removed_by: member.user_id,
                     local_user_index_canister_id: state.data.local_user_index_canister_id,
@@ -148,7 +145,7 @@ fn commit(user_id: UserId, block: bool, removed_by: UserId, state: &mut RuntimeS
     }
 }
 
-fn remove_membership_from_user_canister(
+pub(crate) fn remove_membership_from_user_canister(
     user_id: UserId,
     removed_by: UserId,
     blocked: bool,
@@ -51,7 +51,7 @@ fn build_c2c_args(args: &Args, state: &RuntimeState) -> Result<(c2c_report_messa
             let chat = &channel.chat;
 
             if let Some(channel_member) = chat.members.get(&user_id) {
-                if args.delete && !channel_member.role.can_delete_messages(&chat.permissions) {
+                if args.delete && !channel_member.can_delete_messages(&chat.permissions) {
                     return Err(NotAuthorized);
                 }
 
@@ -67,6 +67,9 @@ fn build_c2c_args(args: &Args, state: &RuntimeState) -> Result<(c2c_report_messa
                                 chat_id: MultiUserChat::Channel(state.env.canister_id().into(), args.channel_id),
                                 thread_root_message_index: args.thread_root_message_index,
                                 message,
+                                edit_history: events_reader
+                                    .message_edit_history(args.message_id.into(), Some(user_id))
+                                    .unwrap_or_default(),
                                 already_deleted: args.delete,
                                 is_public: channel.chat.is_public.value && state.data.is_public,
                             },
@@ -1,7 +1,9 @@
 use crate::activity_notifications::handle_activity_notification;
 use crate::model::members::CommunityMembers;
 use crate::model::user_groups::UserGroup;
-use crate::timer_job_types::{DeleteFileReferencesJob, EndPollJob, MarkP2PSwapExpiredJob, RefundPrizeJob, TimerJob};
+use crate::timer_job_types::{
+    DeleteFileReferencesJob, DrawPrizeJob, EndPollJob, MarkP2PSwapExpiredJob, RefundPrizeJob, TimerJob,
+};
 use crate::{mutate_state, run_regular_jobs, Data, RuntimeState};
 use canister_api_macros::{update_candid_and_msgpack, update_msgpack};
 use canister_tracing_macros::trace;
@@ -44,12 +46,18 @@ fn send_message_impl(args: Args, state: &mut RuntimeState) -> Response {
     };
 
     if let Some(channel) = state.data.channels.get_mut(&args.channel_id) {
+        if !channel.can_send_messages_via_user_groups(user_id, &state.data.members) {
+            return NotAuthorized;
+        }
+
         let now = state.env.now();
         let users_mentioned = extract_users_mentioned(args.mentioned, args.content.text(), &state.data.members);
+        let exempt_from_slow_mode = channel.is_exempt_from_slow_mode(user_id, &state.data.members);
 
         let result = channel.chat.validate_and_send_message(
             user_id,
             is_bot,
+            exempt_from_slow_mode,
             args.thread_root_message_index,
             args.message_id,
             args.content,
@@ -99,11 +107,17 @@ fn c2c_send_message_impl(args: C2CArgs, state: &mut RuntimeState) -> C2CResponse
     }
 
     if let Some(channel) = state.data.channels.get_mut(&args.channel_id) {
+        if !channel.can_send_messages_via_user_groups(user_id, &state.data.members) {
+            return NotAuthorized;
+        }
+
         let now = state.env.now();
         let users_mentioned = extract_users_mentioned(args.mentioned, args.content.text(), &state.data.members);
+        let exempt_from_slow_mode = channel.is_exempt_from_slow_mode(user_id, &state.data.members);
 
         let result = channel.chat.send_message(
             user_id,
+            exempt_from_slow_mode,
             args.thread_root_message_index,
             args.message_id,
             args.content,
@@ -178,7 +192,7 @@ fn validate_caller(community_rules_accepted: Option<Version>, state: &mut Runtim
 }
 
 #[allow(clippy::too_many_arguments)]
-fn process_send_message_result(
+pub(crate) fn process_send_message_result(
     result: SendMessageResult,
     sender: UserId,
     sender_username: String,
@@ -252,6 +266,7 @@ fn process_send_message_result(
         SendMessageResult::UserSuspended => UserSuspended,
         SendMessageResult::RulesNotAccepted => RulesNotAccepted,
         SendMessageResult::InvalidRequest(error) => InvalidRequest(error),
+        SendMessageResult::RateLimited(retry_at) => RateLimited(retry_at),
     }
 }
 
@@ -282,6 +297,7 @@ fn register_timer_jobs(
                         channel_id,
                         thread_root_message_index,
                         message_index: message_event.event.message_index,
+                        attempt: 0,
                     }),
                     end_date,
                     now,
@@ -289,15 +305,22 @@ fn register_timer_jobs(
             }
         }
         MessageContent::Prize(p) => {
-            data.timer_jobs.enqueue_job(
+            let message_index = message_event.event.message_index;
+            // Prize draws refund any unclaimed prizes once the winners have been paid
+            let job = if p.draw {
+                TimerJob::DrawPrize(DrawPrizeJob {
+                    channel_id,
+                    thread_root_message_index,
+                    message_index,
+                })
+            } else {
                 TimerJob::RefundPrize(RefundPrizeJob {
                     channel_id,
                     thread_root_message_index,
-                    message_index: message_event.event.message_index,
-                }),
-                p.end_date,
-                now,
-            );
+                    message_index,
+                })
+            };
+            data.timer_jobs.enqueue_job(job, p.end_date, now);
         }
         MessageContent::P2PSwap(c) => {
             data.timer_jobs.enqueue_job(
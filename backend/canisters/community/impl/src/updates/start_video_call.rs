@@ -36,9 +36,11 @@ fn start_video_call_impl(args: Args, state: &mut RuntimeState) -> Response {
 
     let sender = args.initiator;
     let now = state.env.now();
+    let exempt_from_slow_mode = channel.is_exempt_from_slow_mode(sender, &state.data.members);
 
     let result = match channel.chat.send_message(
         sender,
+        exempt_from_slow_mode,
         None,
         args.message_id,
         MessageContentInternal::VideoCall(VideoCallContentInternal {
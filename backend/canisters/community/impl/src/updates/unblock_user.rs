@@ -32,7 +32,7 @@ fn unblock_user_impl(args: Args, state: &mut RuntimeState) -> Response {
         let unblocked_by = caller_member.user_id;
         if unblocked_by == args.user_id {
             CannotUnblockSelf
-        } else if caller_member.role.can_unblock_users(&state.data.permissions) {
+        } else if caller_member.can_unblock_users(&state.data.permissions) {
             let now = state.env.now();
 
             state.data.members.unblock(&args.user_id);
@@ -27,6 +27,33 @@ fn update_channel_impl(mut args: Args, state: &mut RuntimeState) -> Response {
         }
     }
 
+    if let OptionUpdate::SetToSome(gate_reverification) = &args.gate_reverification {
+        let gate = state
+            .data
+            .channels
+            .get(&args.channel_id)
+            .and_then(|c| args.gate.as_ref().apply_to(c.chat.gate.value.as_ref()));
+
+        if !gate_reverification.validate() || !gate.map_or(false, |g| g.can_be_reverified()) {
+            return AccessGateInvalid;
+        }
+    }
+
+    if let OptionUpdate::SetToSome(slow_mode) = &args.slow_mode {
+        if !slow_mode.validate() {
+            return SlowModeInvalid;
+        }
+    }
+
+    if let Some(user_group_permissions) = &args.user_group_permissions {
+        if let Some(user_group_id) = user_group_permissions
+            .user_group_ids()
+            .find(|id| state.data.members.get_user_group(*id).is_none())
+        {
+            return UserGroupNotFound(user_group_id);
+        }
+    }
+
     if let Some(name) = &args.name {
         if state.data.channels.is_name_taken(name, Some(args.channel_id)) {
             return NameTaken;
@@ -37,7 +64,24 @@ fn update_channel_impl(mut args: Args, state: &mut RuntimeState) -> Response {
         let caller = state.env.caller();
 
         if let Some(member) = state.data.members.get(caller) {
+            if let Some(user_group_permissions) = &args.user_group_permissions {
+                // Making the channel visible to user groups or syncing its membership from user groups requires the
+                // same authority within the channel as making it public or adding members to it directly
+                let current = &channel.user_group_permissions.value;
+                let channel_member = channel.chat.members.get(&member.user_id);
+
+                if (user_group_permissions.view != current.view
+                    && !channel_member.map_or(false, |m| m.role.can_change_group_visibility()))
+                    || (user_group_permissions.sync_membership != current.sync_membership
+                        && !channel_member.map_or(false, |m| m.can_add_members(&channel.chat.permissions.value)))
+                {
+                    return NotAuthorized;
+                }
+            }
+
             let now = state.env.now();
+            let gate_updated = args.gate.has_update();
+            let permissions_updated = args.permissions_v2.is_some();
             match channel.chat.update(
                 member.user_id,
                 args.name,
@@ -51,6 +95,13 @@ fn update_channel_impl(mut args: Args, state: &mut RuntimeState) -> Response {
                 now,
             ) {
                 UpdateResult::Success(result) => {
+                    let gate_reverification_updated = channel.chat.set_gate_reverification(args.gate_reverification, now);
+                    channel.chat.set_slow_mode(args.slow_mode, now);
+                    channel.chat.set_read_receipts_enabled(args.read_receipts_enabled, now);
+                    let user_group_permissions_updated = args
+                        .user_group_permissions
+                        .map_or(false, |p| channel.set_user_group_permissions(p, now));
+
                     if channel.chat.is_public.value && channel.chat.gate.is_none() {
                         // If the channel has just been made public or had its gate removed, join
                         // existing community members to the channel
@@ -63,6 +114,22 @@ fn update_channel_impl(mut args: Args, state: &mut RuntimeState) -> Response {
                         }
                     }
 
+                    if permissions_updated || gate_updated {
+                        state
+                            .data
+                            .channel_categories
+                            .stop_inheriting(args.channel_id, permissions_updated, gate_updated);
+                    }
+
+                    // Membership is only synced for ungated channels, so removing the gate may allow it to be synced
+                    if user_group_permissions_updated || matches!(result.gate_update, OptionUpdate::SetToNone) {
+                        state.data.sync_channel_members_from_user_groups(args.channel_id, now);
+                    }
+
+                    if gate_updated || gate_reverification_updated {
+                        state.data.schedule_channel_gate_reverification(args.channel_id, now);
+                    }
+
                     handle_activity_notification(state);
                     SuccessV2(SuccessResult {
                         rules_version: result.rules_version,
@@ -0,0 +1,129 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::model::channel_categories::{ChannelCategoryUpdate, UpdateChannelCategoryResult};
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::update_channel_category::{Response::*, *};
+use ic_cdk::update;
+use types::{OptionUpdate, UserId};
+use utils::text_validation::{validate_channel_category_name, StringLengthValidationError};
+
+#[update]
+#[trace]
+fn update_channel_category(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| update_channel_category_impl(args, state))
+}
+
+fn update_channel_category_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let user_id = match state.data.members.get(caller) {
+        Some(m) if m.suspended.value => return UserSuspended,
+        Some(m) if m.can_update_details(&state.data.permissions) => m.user_id,
+        _ => return NotAuthorized,
+    };
+
+    if let Some(name) = &args.name {
+        if let Err(error) = validate_channel_category_name(name) {
+            return match error {
+                StringLengthValidationError::TooShort(s) => NameTooShort(s),
+                StringLengthValidationError::TooLong(l) => NameTooLong(l),
+            };
+        }
+    }
+    if let OptionUpdate::SetToSome(gate) = &args.default_gate {
+        if !gate.validate() {
+            return AccessGateInvalid;
+        }
+    }
+    if args
+        .channel_ids
+        .as_ref()
+        .map_or(false, |ids| ids.iter().any(|c| state.data.channels.get(c).is_none()))
+    {
+        return ChannelNotFound;
+    }
+
+    let permissions_updated = matches!(args.default_permissions, OptionUpdate::SetToSome(_));
+    let gate_updated = matches!(args.default_gate, OptionUpdate::SetToSome(_));
+
+    let update = ChannelCategoryUpdate {
+        name: args.name.map(|n| n.trim().to_string()),
+        channels: args.channel_ids,
+        collapsed_by_default: args.collapsed_by_default,
+        default_permissions: args.default_permissions,
+        default_gate: args.default_gate,
+        position: args.position,
+    };
+
+    let now = state.env.now();
+    match state.data.channel_categories.update(args.category_id, update, now) {
+        UpdateChannelCategoryResult::Success => {
+            update_inheriting_channels(args.category_id, user_id, permissions_updated, gate_updated, state);
+            handle_activity_notification(state);
+            Success
+        }
+        UpdateChannelCategoryResult::NotFound => ChannelCategoryNotFound,
+        UpdateChannelCategoryResult::NameTaken => NameTaken,
+    }
+}
+
+// Applies the category's updated defaults to the channels which are still using them
+fn update_inheriting_channels(
+    category_id: u32,
+    updated_by: UserId,
+    permissions_updated: bool,
+    gate_updated: bool,
+    state: &mut RuntimeState,
+) {
+    let Some(category) = state.data.channel_categories.get(category_id) else {
+        return;
+    };
+
+    let permissions = category
+        .default_permissions
+        .clone()
+        .filter(|_| permissions_updated)
+        .map(|p| (p, category.channels_inheriting_permissions.clone()));
+    let gate = category
+        .default_gate
+        .clone()
+        .filter(|_| gate_updated)
+        .map(|g| (g, category.channels_inheriting_gate.clone()));
+
+    let now = state.env.now();
+
+    if let Some((permissions, channel_ids)) = permissions {
+        for channel_id in channel_ids {
+            if let Some(channel) = state.data.channels.get_mut(&channel_id) {
+                channel.chat.set_permissions(permissions.clone(), updated_by, now);
+            }
+        }
+    }
+
+    if let Some((gate, channel_ids)) = gate {
+        for channel_id in channel_ids {
+            if let Some(channel) = state.data.channels.get_mut(&channel_id) {
+                let result = channel.chat.do_update(
+                    updated_by,
+                    None,
+                    None,
+                    None,
+                    OptionUpdate::NoChange,
+                    None,
+                    OptionUpdate::SetToSome(gate.clone()),
+                    None,
+                    OptionUpdate::NoChange,
+                    now,
+                );
+                if result.gate_update.has_update() {
+                    state.data.schedule_channel_gate_reverification(channel_id, now);
+                }
+            }
+        }
+    }
+}
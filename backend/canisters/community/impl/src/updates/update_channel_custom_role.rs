@@ -0,0 +1,53 @@
+use crate::{activity_notifications::handle_activity_notification, mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use community_canister::update_channel_custom_role::{Response::*, *};
+use group_chat_core::CustomRoleResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn update_channel_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| update_channel_custom_role_impl(args, state))
+}
+
+fn update_channel_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return CommunityFrozen;
+    }
+
+    let caller = state.env.caller();
+    let user_id = match state.data.members.get(caller) {
+        Some(m) if m.suspended.value => return UserSuspended,
+        Some(m) => m.user_id,
+        _ => return UserNotInCommunity,
+    };
+
+    if let Some(channel) = state.data.channels.get_mut(&args.channel_id) {
+        let now = state.env.now();
+        match channel.chat.update_custom_role(
+            user_id,
+            args.role_id,
+            args.name,
+            args.permissions,
+            args.message_permissions,
+            now,
+        ) {
+            CustomRoleResult::Success(_) => {
+                handle_activity_notification(state);
+                Success
+            }
+            CustomRoleResult::UserNotInGroup => UserNotInChannel,
+            CustomRoleResult::UserSuspended => UserSuspended,
+            CustomRoleResult::NotAuthorized | CustomRoleResult::TooManyRoles(_) => NotAuthorized,
+            CustomRoleResult::RoleNotFound => RoleNotFound,
+            CustomRoleResult::NameTooShort(result) => NameTooShort(result),
+            CustomRoleResult::NameTooLong(result) => NameTooLong(result),
+            CustomRoleResult::NameTaken => NameTaken,
+            CustomRoleResult::PermissionNotGrantable => PermissionNotGrantable,
+        }
+    } else {
+        ChannelNotFound
+    }
+}
@@ -122,6 +122,12 @@ fn prepare(args: &Args, state: &RuntimeState) -> Result<PrepareResult, Response>
     let banner_update = args.banner.as_ref().expand();
     let gate = args.gate.as_ref().apply_to(state.data.gate.value.as_ref());
 
+    if let OptionUpdate::SetToSome(gate_reverification) = &args.gate_reverification {
+        if !gate_reverification.validate() || !gate.map_or(false, |g| g.can_be_reverified()) {
+            return Err(AccessGateInvalid);
+        }
+    }
+
     if let Some(name) = &args.name {
         if let Err(error) = validate_community_name(name, state.data.is_public) {
             return Err(match error {
@@ -167,7 +173,7 @@ fn prepare(args: &Args, state: &RuntimeState) -> Result<PrepareResult, Response>
         }
 
         let permissions = &state.data.permissions;
-        if !member.role.can_update_details(permissions)
+        if !member.can_update_details(permissions)
             || (args.permissions.is_some() && !member.role.can_change_permissions())
             || (args.public.is_some() && !member.role.can_change_community_visibility())
         {
@@ -301,8 +307,11 @@ fn commit(my_user_id: UserId, args: Args, state: &mut RuntimeState) -> SuccessRe
         );
     }
 
+    let mut reschedule_gate_reverification = false;
+
     if let Some(gate) = args.gate.expand() {
         if state.data.gate.value != gate {
+            reschedule_gate_reverification = true;
             state.data.gate = Timestamped::new(gate.clone(), now);
 
             state.data.events.push_event(
@@ -315,6 +324,17 @@ fn commit(my_user_id: UserId, args: Args, state: &mut RuntimeState) -> SuccessRe
         }
     }
 
+    if let Some(gate_reverification) = args.gate_reverification.expand() {
+        if state.data.gate_reverification.value != gate_reverification {
+            state.data.gate_reverification = Timestamped::new(gate_reverification, now);
+            reschedule_gate_reverification = true;
+        }
+    }
+
+    if reschedule_gate_reverification {
+        state.data.schedule_gate_reverification(now);
+    }
+
     if let Some(public) = args.public {
         if state.data.is_public != public {
             state.data.is_public = public;
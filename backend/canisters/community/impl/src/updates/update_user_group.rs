@@ -24,7 +24,7 @@ fn update_user_group_impl(args: Args, state: &mut RuntimeState) -> Response {
             return UserSuspended;
         }
 
-        if !member.role.can_manage_user_groups(&state.data.permissions) {
+        if !member.can_manage_user_groups(&state.data.permissions) {
             NotAuthorized
         } else if let Err(error) = args.name.as_ref().map_or(Ok(()), |n| validate_user_group_name(n)) {
             match error {
@@ -34,12 +34,16 @@ fn update_user_group_impl(args: Args, state: &mut RuntimeState) -> Response {
             }
         } else {
             let now = state.env.now();
+            let users_added = args.users_to_add.clone();
 
             if state
                 .data
                 .members
                 .update_user_group(args.user_group_id, args.name, args.users_to_add, args.users_to_remove, now)
             {
+                state
+                    .data
+                    .add_users_to_channels_synced_from_user_group(args.user_group_id, &users_added, now);
                 handle_activity_notification(state);
                 Success
             } else {
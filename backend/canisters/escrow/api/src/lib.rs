@@ -1,9 +1,9 @@
-use candid::{Deserialize, Principal};
+use candid::{CandidType, Deserialize, Principal};
 use icrc_ledger_types::icrc1::account::Subaccount;
 use serde::Serialize;
 use sha256::sha256;
 use types::icrc1::CompletedCryptoTransaction;
-use types::{P2PSwapLocation, TimestampMillis, UserId};
+use types::{P2PSwapLocation, TimestampMillis, TokenInfo, UserId};
 
 mod lifecycle;
 mod updates;
@@ -14,12 +14,19 @@ pub use updates::*;
 #[derive(Serialize, Deserialize, Debug)]
 pub enum SwapStatus {
     Open,
+    PartiallyFilled(Box<SwapStatusPartiallyFilled>),
     Cancelled(Box<SwapStatusCancelled>),
     Expired(Box<SwapStatusExpired>),
     Accepted(Box<SwapStatusAccepted>),
     Completed(Box<SwapStatusCompleted>),
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SwapStatusPartiallyFilled {
+    pub fills: Vec<SwapFill>,
+    pub token1_remaining: u128,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SwapStatusCancelled {
     pub cancelled_at: TimestampMillis,
@@ -44,6 +51,29 @@ pub struct SwapStatusCompleted {
     pub token0_transfer_out: CompletedCryptoTransaction,
     pub token1_transfer_out: CompletedCryptoTransaction,
     pub refunds: Vec<CompletedCryptoTransaction>,
+    // The fields above describe the first fill, this contains every fill of the swap
+    #[serde(default)]
+    pub fills: Vec<SwapFill>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SwapAsset {
+    pub token: TokenInfo,
+    pub amount: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SwapFill {
+    pub user_id: UserId,
+    // Distinguishes between the fills made by the same user, each of which is deposited into its own subaccount
+    #[serde(default)]
+    pub fill_id: u32,
+    pub filled_at: TimestampMillis,
+    pub token1_amount: u128,
+    // The amount of each token0 asset owed to the user, starting with token0 followed by any additional token0s
+    pub token0_amounts: Vec<u128>,
+    pub token0_transfers_out: Vec<CompletedCryptoTransaction>,
+    pub token1_transfer_out: Option<CompletedCryptoTransaction>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -60,3 +90,17 @@ pub fn deposit_subaccount(user_id: UserId, swap_id: u32) -> Subaccount {
     bytes.extend_from_slice(&swap_id.to_be_bytes());
     sha256(&bytes)
 }
+
+// The subaccount into which a user deposits token1 for one of their fills. The user's first fill uses their original
+// deposit subaccount, each subsequent fill has its own subaccount.
+pub fn fill_deposit_subaccount(user_id: UserId, swap_id: u32, fill_id: u32) -> Subaccount {
+    if fill_id == 0 {
+        return deposit_subaccount(user_id, swap_id);
+    }
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(Principal::from(user_id).as_slice());
+    bytes.extend_from_slice(&swap_id.to_be_bytes());
+    bytes.extend_from_slice(&fill_id.to_be_bytes());
+    sha256(&bytes)
+}
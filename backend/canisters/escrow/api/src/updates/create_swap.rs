@@ -1,3 +1,4 @@
+use crate::SwapAsset;
 use candid::{CandidType, Principal};
 use serde::{Deserialize, Serialize};
 use types::{CanisterId, P2PSwapLocation, TimestampMillis, TokenInfo};
@@ -9,6 +10,13 @@ pub struct Args {
     pub token0_amount: u128,
     pub token1: TokenInfo,
     pub token1_amount: u128,
+    // Tokens offered alongside token0, all of which are exchanged atomically for token1
+    #[serde(default)]
+    pub additional_token0s: Vec<SwapAsset>,
+    // If set, the swap can be filled by several users, each filling at least this amount of token1 (or whatever remains)
+    // and receiving a pro-rata share of the token0s
+    #[serde(default)]
+    pub min_fill_token1_amount: Option<u128>,
     pub expires_at: TimestampMillis,
     pub additional_admins: Vec<Principal>,
     pub canister_to_notify: Option<CanisterId>,
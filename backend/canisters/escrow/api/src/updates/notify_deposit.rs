@@ -6,6 +6,13 @@ use types::UserId;
 pub struct Args {
     pub swap_id: u32,
     pub user_id: Option<UserId>,
+    // The amount of token1 being filled, defaults to the full amount remaining
+    #[serde(default)]
+    pub token1_amount: Option<u128>,
+    // Identifies which of the user's fills the deposit is for, allowing a user to fill the same swap several times.
+    // The deposit for each fill must be made into `fill_deposit_subaccount`. Defaults to 0
+    #[serde(default)]
+    pub fill_id: Option<u32>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
@@ -16,6 +23,7 @@ pub enum Response {
     SwapCancelled,
     SwapExpired,
     SwapNotFound,
+    InvalidFillAmount(String),
     InternalError(String),
 }
 
@@ -1,7 +1,7 @@
 use crate::model::pending_payments_queue::{PendingPayment, PendingPaymentReason};
 use crate::{mutate_state, read_state, RuntimeState};
 use candid::Principal;
-use escrow_canister::{deposit_subaccount, SwapStatus};
+use escrow_canister::{fill_deposit_subaccount, SwapStatus};
 use ic_cdk_timers::TimerId;
 use icrc_ledger_types::icrc1::account::Account;
 use icrc_ledger_types::icrc1::transfer::TransferArg;
@@ -44,7 +44,11 @@ async fn process_payment(pending_payment: PendingPayment) {
     let created_at_time = pending_payment.timestamp * NANOS_PER_MILLISECOND;
 
     let args = TransferArg {
-        from_subaccount: Some(deposit_subaccount(from_user, pending_payment.swap_id)),
+        from_subaccount: Some(fill_deposit_subaccount(
+            from_user,
+            pending_payment.swap_id,
+            pending_payment.from_fill_id,
+        )),
         to: Principal::from(pending_payment.user_id).into(),
         fee: Some(pending_payment.token_info.fee.into()),
         created_at_time: Some(created_at_time),
@@ -72,13 +76,24 @@ async fn process_payment(pending_payment: PendingPayment) {
                         block_index,
                     };
                     let notify_status_change = match pending_payment.reason {
-                        PendingPaymentReason::Swap(_) => {
-                            if pending_payment.token_info.ledger == swap.token0.ledger {
-                                swap.token0_transfer_out = Some(transfer);
+                        PendingPaymentReason::Swap(other_user_id) => {
+                            // Payments of token1 go to the swap creator, all others go to the user who made the fill
+                            let fill_user_id = if pending_payment.token_info.ledger == swap.token1.ledger {
+                                other_user_id
                             } else {
-                                swap.token1_transfer_out = Some(transfer);
+                                pending_payment.user_id
+                            };
+                            if let Some(fill) = swap.fill_mut(fill_user_id, pending_payment.fill_id) {
+                                if pending_payment.token_info.ledger == swap.token1.ledger {
+                                    fill.token1_transfer_out = Some(transfer);
+                                } else {
+                                    fill.token0_transfers_out.push(transfer);
+                                }
                             }
-                            swap.is_complete()
+                            let fill_complete = swap
+                                .fill(fill_user_id, pending_payment.fill_id)
+                                .map_or(false, |f| swap.is_fill_complete(f));
+                            swap.is_complete() || (fill_complete && swap.allows_partial_fills())
                         }
                         PendingPaymentReason::Refund => {
                             swap.refunds.push(transfer);
@@ -92,10 +92,12 @@ pub struct Metrics {
 pub struct SwapMetrics {
     pub total: u32,
     pub open: u32,
+    pub partially_filled: u32,
     pub cancelled: u32,
     pub expired: u32,
     pub accepted: u32,
     pub completed: u32,
+    pub fills: u32,
 }
 
 #[derive(Serialize, Debug)]
@@ -1,4 +1,5 @@
 use crate::model::swaps::Swap;
+use escrow_canister::SwapFill;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use types::{TimestampMillis, TokenInfo, UserId};
@@ -13,25 +14,66 @@ impl PendingPaymentsQueue {
         self.pending_payments.push_back(pending_payment);
     }
 
+    // Pays the user who made the fill their share of each token0 and pays the swap creator the token1 amount
+    pub fn push_fill_payments(&mut self, swap: &Swap, fill: &SwapFill, now: TimestampMillis) {
+        for (asset, amount) in swap.token0s().into_iter().zip(fill.token0_amounts.iter().copied()) {
+            if amount > 0 {
+                self.push(PendingPayment {
+                    user_id: fill.user_id,
+                    timestamp: now,
+                    token_info: asset.token,
+                    amount,
+                    swap_id: swap.id,
+                    fill_id: fill.fill_id,
+                    from_fill_id: 0,
+                    reason: PendingPaymentReason::Swap(swap.created_by),
+                });
+            }
+        }
+        self.push(PendingPayment {
+            user_id: swap.created_by,
+            timestamp: now,
+            token_info: swap.token1.clone(),
+            amount: fill.token1_amount,
+            swap_id: swap.id,
+            fill_id: fill.fill_id,
+            from_fill_id: fill.fill_id,
+            reason: PendingPaymentReason::Swap(fill.user_id),
+        });
+    }
+
     pub fn push_refunds(&mut self, swap: &Swap, now: TimestampMillis) {
         if swap.token0_received {
-            self.push(PendingPayment {
-                user_id: swap.created_by,
-                timestamp: now,
-                token_info: swap.token0.clone(),
-                amount: swap.amount0,
-                swap_id: swap.id,
-                reason: PendingPaymentReason::Refund,
-            });
-        }
-        if swap.token1_received {
-            if let Some((accepted_by, _)) = swap.accepted_by {
+            // Any fees which were deposited to cover transfers that are no longer needed are refunded too
+            let fills_paid = swap.fills.len() as u128;
+            let unused_transfers = swap.max_token0_transfers().saturating_sub(fills_paid + 1);
+
+            for (asset, remaining) in swap.token0s().into_iter().zip(swap.token0s_remaining()) {
+                let amount = remaining + asset.token.fee * unused_transfers;
+                if amount > 0 {
+                    self.push(PendingPayment {
+                        user_id: swap.created_by,
+                        timestamp: now,
+                        token_info: asset.token,
+                        amount,
+                        swap_id: swap.id,
+                        fill_id: 0,
+                        from_fill_id: 0,
+                        reason: PendingPaymentReason::Refund,
+                    });
+                }
+            }
+        } else {
+            // Fills are only paid out once token0 has been received, so until then they are refunded
+            for fill in swap.fills.iter() {
                 self.push(PendingPayment {
-                    user_id: accepted_by,
+                    user_id: fill.user_id,
                     timestamp: now,
                     token_info: swap.token1.clone(),
-                    amount: swap.amount1,
+                    amount: fill.token1_amount,
                     swap_id: swap.id,
+                    fill_id: fill.fill_id,
+                    from_fill_id: fill.fill_id,
                     reason: PendingPaymentReason::Refund,
                 });
             }
@@ -54,6 +96,12 @@ pub struct PendingPayment {
     pub token_info: TokenInfo,
     pub amount: u128,
     pub swap_id: u32,
+    // The fill which the payment relates to
+    #[serde(default)]
+    pub fill_id: u32,
+    // The fill whose deposit the payment is made from, or 0 if it is made from the swap creator's deposit
+    #[serde(default)]
+    pub from_fill_id: u32,
     pub reason: PendingPaymentReason,
 }
 
@@ -1,6 +1,9 @@
 use crate::SwapMetrics;
 use candid::Principal;
-use escrow_canister::{SwapStatus, SwapStatusAccepted, SwapStatusCancelled, SwapStatusCompleted, SwapStatusExpired};
+use escrow_canister::{
+    SwapAsset, SwapFill, SwapStatus, SwapStatusAccepted, SwapStatusCancelled, SwapStatusCompleted, SwapStatusExpired,
+    SwapStatusPartiallyFilled,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use types::{icrc1::CompletedCryptoTransaction, CanisterId, P2PSwapLocation, TimestampMillis, TokenInfo, UserId};
@@ -34,11 +37,13 @@ impl Swaps {
         for swap in self.map.values() {
             match swap.status(now) {
                 SwapStatus::Open => metrics.open += 1,
+                SwapStatus::PartiallyFilled(_) => metrics.partially_filled += 1,
                 SwapStatus::Cancelled(_) => metrics.cancelled += 1,
                 SwapStatus::Expired(_) => metrics.expired += 1,
                 SwapStatus::Accepted(_) => metrics.accepted += 1,
                 SwapStatus::Completed(_) => metrics.completed += 1,
             }
+            metrics.fills += swap.fills.len() as u32;
         }
 
         metrics
@@ -46,6 +51,7 @@ impl Swaps {
 }
 
 #[derive(Serialize, Deserialize)]
+#[serde(from = "SwapCombined")]
 pub struct Swap {
     pub id: u32,
     pub location: P2PSwapLocation,
@@ -53,20 +59,90 @@ pub struct Swap {
     pub created_by: UserId,
     pub token0: TokenInfo,
     pub amount0: u128,
+    pub additional_token0s: Vec<SwapAsset>,
     pub token1: TokenInfo,
     pub amount1: u128,
+    pub min_fill_amount1: Option<u128>,
     pub expires_at: TimestampMillis,
     pub cancelled_at: Option<TimestampMillis>,
-    pub accepted_by: Option<(UserId, TimestampMillis)>,
     pub token0_received: bool,
-    pub token1_received: bool,
-    pub token0_transfer_out: Option<CompletedCryptoTransaction>,
-    pub token1_transfer_out: Option<CompletedCryptoTransaction>,
+    pub fills: Vec<SwapFill>,
     pub refunds: Vec<CompletedCryptoTransaction>,
     pub additional_admins: Vec<Principal>,
     pub canister_to_notify: Option<CanisterId>,
 }
 
+// Swaps used to only support a single acceptor, whose details were held in the `accepted_by`, `token1_received` and
+// `transfer_out` fields. These are now converted into a fill.
+#[derive(Deserialize)]
+struct SwapCombined {
+    id: u32,
+    location: P2PSwapLocation,
+    created_at: TimestampMillis,
+    created_by: UserId,
+    token0: TokenInfo,
+    amount0: u128,
+    #[serde(default)]
+    additional_token0s: Vec<SwapAsset>,
+    token1: TokenInfo,
+    amount1: u128,
+    #[serde(default)]
+    min_fill_amount1: Option<u128>,
+    expires_at: TimestampMillis,
+    cancelled_at: Option<TimestampMillis>,
+    #[serde(default)]
+    accepted_by: Option<(UserId, TimestampMillis)>,
+    token0_received: bool,
+    #[serde(default)]
+    token1_received: bool,
+    #[serde(default)]
+    token0_transfer_out: Option<CompletedCryptoTransaction>,
+    #[serde(default)]
+    token1_transfer_out: Option<CompletedCryptoTransaction>,
+    #[serde(default)]
+    fills: Vec<SwapFill>,
+    refunds: Vec<CompletedCryptoTransaction>,
+    additional_admins: Vec<Principal>,
+    canister_to_notify: Option<CanisterId>,
+}
+
+impl From<SwapCombined> for Swap {
+    fn from(value: SwapCombined) -> Self {
+        let mut fills = value.fills;
+        if let Some((user_id, filled_at)) = value.accepted_by.filter(|_| value.token1_received) {
+            fills.push(SwapFill {
+                user_id,
+                fill_id: 0,
+                filled_at,
+                token1_amount: value.amount1,
+                token0_amounts: vec![value.amount0],
+                token0_transfers_out: value.token0_transfer_out.into_iter().collect(),
+                token1_transfer_out: value.token1_transfer_out,
+            });
+        }
+
+        Swap {
+            id: value.id,
+            location: value.location,
+            created_at: value.created_at,
+            created_by: value.created_by,
+            token0: value.token0,
+            amount0: value.amount0,
+            additional_token0s: value.additional_token0s,
+            token1: value.token1,
+            amount1: value.amount1,
+            min_fill_amount1: value.min_fill_amount1,
+            expires_at: value.expires_at,
+            cancelled_at: value.cancelled_at,
+            token0_received: value.token0_received,
+            fills,
+            refunds: value.refunds,
+            additional_admins: value.additional_admins,
+            canister_to_notify: value.canister_to_notify,
+        }
+    }
+}
+
 impl Swap {
     pub fn new(id: u32, caller: UserId, args: escrow_canister::create_swap::Args, now: TimestampMillis) -> Swap {
         Swap {
@@ -76,15 +152,14 @@ impl Swap {
             created_by: caller,
             token0: args.token0,
             amount0: args.token0_amount,
+            additional_token0s: args.additional_token0s,
             token1: args.token1,
             amount1: args.token1_amount,
+            min_fill_amount1: args.min_fill_token1_amount,
             expires_at: args.expires_at,
             cancelled_at: None,
-            accepted_by: None,
             token0_received: false,
-            token1_received: false,
-            token0_transfer_out: None,
-            token1_transfer_out: None,
+            fills: Vec::new(),
             refunds: Vec::new(),
             additional_admins: args.additional_admins,
             canister_to_notify: args.canister_to_notify,
@@ -95,21 +170,125 @@ impl Swap {
         self.created_by == principal.into() || self.additional_admins.contains(&principal)
     }
 
+    // Each of the assets being offered, starting with token0 followed by any additional token0s
+    pub fn token0s(&self) -> Vec<SwapAsset> {
+        let mut token0s = vec![SwapAsset {
+            token: self.token0.clone(),
+            amount: self.amount0,
+        }];
+        token0s.extend(self.additional_token0s.iter().cloned());
+        token0s
+    }
+
+    // The maximum number of transfers out of each token0 which may be needed, each of which incurs a fee
+    pub fn max_token0_transfers(&self) -> u128 {
+        self.min_fill_amount1.map_or(1, |min_fill| self.amount1.div_ceil(min_fill))
+    }
+
+    pub fn allows_partial_fills(&self) -> bool {
+        self.min_fill_amount1.is_some()
+    }
+
+    pub fn fill(&self, user_id: UserId, fill_id: u32) -> Option<&SwapFill> {
+        self.fills.iter().find(|f| f.user_id == user_id && f.fill_id == fill_id)
+    }
+
+    pub fn fill_mut(&mut self, user_id: UserId, fill_id: u32) -> Option<&mut SwapFill> {
+        self.fills.iter_mut().find(|f| f.user_id == user_id && f.fill_id == fill_id)
+    }
+
+    pub fn token1_remaining(&self) -> u128 {
+        self.amount1 - self.fills.iter().map(|f| f.token1_amount).sum::<u128>()
+    }
+
+    pub fn is_fully_filled(&self) -> bool {
+        self.token1_remaining() == 0
+    }
+
+    // The amount of each token0 which has not yet been allocated to a fill
+    pub fn token0s_remaining(&self) -> Vec<u128> {
+        self.token0s()
+            .iter()
+            .enumerate()
+            .map(|(i, a)| a.amount - self.fills.iter().map(|f| f.token0_amounts[i]).sum::<u128>())
+            .collect()
+    }
+
+    pub fn validate_fill_amount(&self, token1_amount: u128) -> Result<(), String> {
+        let remaining = self.token1_remaining();
+        if token1_amount == 0 {
+            Err("Fill amount cannot be 0".to_string())
+        } else if token1_amount > remaining {
+            Err(format!("Fill amount cannot exceed the amount remaining ({remaining})"))
+        } else if token1_amount == remaining {
+            Ok(())
+        } else if let Some(min_fill) = self.min_fill_amount1 {
+            if token1_amount < min_fill {
+                Err(format!("Fill amount must be at least {min_fill}"))
+            } else if self.token0_amounts_for_fill(token1_amount).contains(&0) {
+                Err("Fill amount is too small".to_string())
+            } else {
+                Ok(())
+            }
+        } else {
+            Err("This swap can only be filled in full".to_string())
+        }
+    }
+
+    // Each token0 is split pro-rata, with the final fill receiving whatever remains so that nothing is left over
+    pub fn token0_amounts_for_fill(&self, token1_amount: u128) -> Vec<u128> {
+        if token1_amount == self.token1_remaining() {
+            self.token0s_remaining()
+        } else {
+            self.token0s()
+                .iter()
+                .map(|a| a.amount * token1_amount / self.amount1)
+                .collect()
+        }
+    }
+
+    pub fn add_fill(&mut self, user_id: UserId, fill_id: u32, token1_amount: u128, now: TimestampMillis) -> &SwapFill {
+        let token0_amounts = self.token0_amounts_for_fill(token1_amount);
+        self.fills.push(SwapFill {
+            user_id,
+            fill_id,
+            filled_at: now,
+            token1_amount,
+            token0_amounts,
+            token0_transfers_out: Vec::new(),
+            token1_transfer_out: None,
+        });
+        self.fills.last().unwrap()
+    }
+
+    // Token0 amounts which round down to 0 are never transferred
+    pub fn is_fill_complete(&self, fill: &SwapFill) -> bool {
+        fill.token1_transfer_out.is_some()
+            && fill.token0_transfers_out.len() == fill.token0_amounts.iter().filter(|a| **a > 0).count()
+    }
+
     pub fn is_complete(&self) -> bool {
-        self.token0_transfer_out.is_some() && self.token1_transfer_out.is_some()
+        self.is_fully_filled() && self.fills.iter().all(|f| self.is_fill_complete(f))
     }
 
     pub fn status(&self, now: TimestampMillis) -> SwapStatus {
-        if let Some((accepted_by, accepted_at)) = self.token0_received.then_some(self.accepted_by).flatten() {
-            if let (Some(token0_transfer_out), Some(token1_transfer_out)) =
-                (self.token0_transfer_out.clone(), self.token1_transfer_out.clone())
-            {
+        if let Some(first_fill) = self.fills.first().filter(|_| self.token0_received && self.is_fully_filled()) {
+            let accepted_by = first_fill.user_id;
+            let accepted_at = first_fill.filled_at;
+
+            if self.is_complete() {
                 SwapStatus::Completed(Box::new(SwapStatusCompleted {
                     accepted_by,
                     accepted_at,
-                    token0_transfer_out,
-                    token1_transfer_out,
+                    token0_transfer_out: first_fill
+                        .token0_transfers_out
+                        .iter()
+                        .find(|t| t.ledger == self.token0.ledger)
+                        .cloned()
+                        .unwrap(),
+                    token1_transfer_out: first_fill.token1_transfer_out.clone().unwrap(),
                     refunds: self.refunds.clone(),
+                    fills: self.fills.clone(),
                 }))
             } else {
                 SwapStatus::Accepted(Box::new(SwapStatusAccepted {
@@ -126,8 +305,84 @@ impl Swap {
             SwapStatus::Expired(Box::new(SwapStatusExpired {
                 refunds: self.refunds.clone(),
             }))
+        } else if !self.fills.is_empty() && !self.is_fully_filled() {
+            SwapStatus::PartiallyFilled(Box::new(SwapStatusPartiallyFilled {
+                fills: self.fills.clone(),
+                token1_remaining: self.token1_remaining(),
+            }))
         } else {
             SwapStatus::Open
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use escrow_canister::{deposit_subaccount, fill_deposit_subaccount};
+    use types::{Chat, Cryptocurrency};
+
+    #[test]
+    fn token0s_split_pro_rata_across_fills() {
+        let mut swap = swap();
+
+        assert!(swap.validate_fill_amount(50).is_err());
+        assert!(swap.validate_fill_amount(400).is_err());
+        assert!(swap.validate_fill_amount(100).is_ok());
+        assert_eq!(swap.max_token0_transfers(), 3);
+
+        assert_eq!(swap.add_fill(user(1), 0, 100, 1).token0_amounts, vec![333, 3]);
+        assert_eq!(swap.add_fill(user(2), 0, 100, 2).token0_amounts, vec![333, 3]);
+        assert!(matches!(swap.status(3), SwapStatus::PartiallyFilled(_)));
+
+        // The final fill receives whatever remains
+        assert_eq!(swap.add_fill(user(3), 0, 100, 3).token0_amounts, vec![334, 4]);
+        assert!(swap.is_fully_filled());
+        assert_eq!(swap.token0s_remaining(), vec![0, 0]);
+    }
+
+    #[test]
+    fn same_user_can_fill_in_tranches() {
+        let mut swap = swap();
+
+        swap.add_fill(user(1), 0, 100, 1);
+        swap.add_fill(user(1), 1, 100, 2);
+
+        assert!(swap.fill(user(1), 0).is_some());
+        assert!(swap.fill(user(1), 1).is_some());
+        assert!(swap.fill(user(1), 2).is_none());
+        assert_eq!(swap.token1_remaining(), 100);
+
+        // Each fill is deposited into its own subaccount, the first using the user's original deposit subaccount
+        assert_eq!(fill_deposit_subaccount(user(1), 0, 0), deposit_subaccount(user(1), 0));
+        assert_ne!(fill_deposit_subaccount(user(1), 0, 0), fill_deposit_subaccount(user(1), 0, 1));
+    }
+
+    fn swap() -> Swap {
+        Swap::new(
+            0,
+            user(0),
+            escrow_canister::create_swap::Args {
+                location: P2PSwapLocation::from_message(Chat::Direct(user(1).into()), None, 0.into()),
+                token0: Cryptocurrency::InternetComputer.try_into().unwrap(),
+                token0_amount: 1000,
+                token1: Cryptocurrency::CHAT.try_into().unwrap(),
+                token1_amount: 300,
+                additional_token0s: vec![SwapAsset {
+                    token: Cryptocurrency::CKBTC.try_into().unwrap(),
+                    amount: 10,
+                }],
+                min_fill_token1_amount: Some(100),
+                expires_at: 1000,
+                additional_admins: Vec::new(),
+                canister_to_notify: None,
+            },
+            0,
+        )
+    }
+
+    fn user(index: u8) -> UserId {
+        Principal::from_slice(&[index]).into()
+    }
+}
@@ -16,7 +16,7 @@ fn cancel_swap_impl(args: Args, state: &mut RuntimeState) -> Response {
 
         if !swap.is_admin(caller) {
             NotAuthorized
-        } else if swap.accepted_by.is_some() {
+        } else if swap.is_fully_filled() {
             SwapAlreadyAccepted
         } else if swap.expires_at < now {
             SwapExpired
@@ -3,6 +3,7 @@ use crate::{mutate_state, RuntimeState};
 use canister_api_macros::update_candid_and_msgpack;
 use canister_tracing_macros::trace;
 use escrow_canister::create_swap::{Response::*, *};
+use std::collections::HashSet;
 use types::TimestampMillis;
 
 #[update_candid_and_msgpack]
@@ -28,13 +29,42 @@ fn create_swap_impl(args: Args, state: &mut RuntimeState) -> Response {
     }
 }
 
+const MAX_ADDITIONAL_TOKEN0S: usize = 4;
+
 fn validate_swap(args: &Args, now: TimestampMillis) -> Result<(), String> {
+    let ledgers: HashSet<_> = args
+        .additional_token0s
+        .iter()
+        .map(|a| a.token.ledger)
+        .chain([args.token0.ledger, args.token1.ledger])
+        .collect();
+
     if args.token0.ledger == args.token1.ledger {
         Err("Token0 must be different to token1".to_string())
-    } else if args.token0_amount == 0 {
+    } else if ledgers.len() != args.additional_token0s.len() + 2 {
+        Err("Each token in the swap must be different".to_string())
+    } else if args.additional_token0s.len() > MAX_ADDITIONAL_TOKEN0S {
+        Err(format!(
+            "No more than {MAX_ADDITIONAL_TOKEN0S} additional token0s can be included"
+        ))
+    } else if args.token0_amount == 0 || args.additional_token0s.iter().any(|a| a.amount == 0) {
         Err("Token0 amount cannot be 0".to_string())
     } else if args.token1_amount == 0 {
         Err("Token1 amount cannot be 0".to_string())
+    } else if args
+        .min_fill_token1_amount
+        .map_or(false, |m| m == 0 || m > args.token1_amount)
+    {
+        Err("Minimum fill amount must be greater than 0 and no more than the token1 amount".to_string())
+    } else if args
+        .additional_token0s
+        .iter()
+        .map(|a| a.amount)
+        .chain([args.token0_amount])
+        .any(|a| a.checked_mul(args.token1_amount).is_none())
+    {
+        // Partial fills are calculated pro-rata, which would overflow
+        Err("Token amounts are too large".to_string())
     } else if args.expires_at < now {
         Err("Expiry cannot be in the past".to_string())
     } else {
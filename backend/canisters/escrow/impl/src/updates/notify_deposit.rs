@@ -1,9 +1,8 @@
-use crate::model::pending_payments_queue::{PendingPayment, PendingPaymentReason};
 use crate::{mutate_state, RuntimeState};
 use canister_api_macros::update_candid_and_msgpack;
 use canister_tracing_macros::trace;
-use escrow_canister::deposit_subaccount;
 use escrow_canister::notify_deposit::{Response::*, *};
+use escrow_canister::{deposit_subaccount, fill_deposit_subaccount};
 use icrc_ledger_types::icrc1::account::Account;
 use types::{CanisterId, UserId};
 
@@ -12,66 +11,41 @@ use types::{CanisterId, UserId};
 async fn notify_deposit(args: Args) -> Response {
     let PrepareResult {
         user_id,
-        ledger,
+        fill_id,
         account,
-        balance_required,
+        deposits_required,
+        token1_amount,
     } = match mutate_state(|state| prepare(&args, state)) {
         Ok(ok) => ok,
         Err(response) => return response,
     };
 
-    match icrc_ledger_canister_c2c_client::icrc1_balance_of(ledger, &account)
-        .await
-        .map(|b| u128::try_from(b.0).unwrap())
-    {
-        Ok(balance) => mutate_state(|state| {
-            let swap = state.data.swaps.get_mut(args.swap_id).unwrap();
-            if balance < balance_required {
-                BalanceTooLow(BalanceTooLowResult {
+    for (ledger, balance_required) in deposits_required {
+        match icrc_ledger_canister_c2c_client::icrc1_balance_of(ledger, &account)
+            .await
+            .map(|b| u128::try_from(b.0).unwrap())
+        {
+            Ok(balance) if balance < balance_required => {
+                return BalanceTooLow(BalanceTooLowResult {
                     balance,
                     balance_required,
                 })
-            } else {
-                let now = state.env.now();
-                if user_id == swap.created_by {
-                    swap.token0_received = true;
-                } else {
-                    swap.accepted_by = Some((user_id, now));
-                    swap.token1_received = true;
-                }
-                let complete = swap.token0_received && swap.token1_received;
-                if complete {
-                    let accepted_by = swap.accepted_by.unwrap().0;
-                    state.data.pending_payments_queue.push(PendingPayment {
-                        user_id: swap.created_by,
-                        timestamp: now,
-                        token_info: swap.token1.clone(),
-                        amount: swap.amount1,
-                        swap_id: swap.id,
-                        reason: PendingPaymentReason::Swap(accepted_by),
-                    });
-                    state.data.pending_payments_queue.push(PendingPayment {
-                        user_id: accepted_by,
-                        timestamp: now,
-                        token_info: swap.token0.clone(),
-                        amount: swap.amount0,
-                        swap_id: swap.id,
-                        reason: PendingPaymentReason::Swap(swap.created_by),
-                    });
-                    crate::jobs::make_pending_payments::start_job_if_required(state);
-                }
-                Success(SuccessResult { complete })
             }
-        }),
-        Err(error) => InternalError(format!("{error:?}")),
+            Ok(_) => {}
+            Err(error) => return InternalError(format!("{error:?}")),
+        }
     }
+
+    mutate_state(|state| commit(args.swap_id, user_id, fill_id, token1_amount, state))
 }
 
 struct PrepareResult {
     user_id: UserId,
-    ledger: CanisterId,
+    fill_id: u32,
     account: Account,
-    balance_required: u128,
+    // The ledger of each token which must have been deposited, along with the balance required
+    deposits_required: Vec<(CanisterId, u128)>,
+    token1_amount: u128,
 }
 
 fn prepare(args: &Args, state: &mut RuntimeState) -> Result<PrepareResult, Response> {
@@ -83,44 +57,99 @@ fn prepare(args: &Args, state: &mut RuntimeState) -> Result<PrepareResult, Respo
             Err(SwapExpired)
         } else {
             let user_id = args.user_id.unwrap_or_else(|| state.env.caller().into());
+            let fill_id = args.fill_id.unwrap_or_default();
 
             if swap.created_by == user_id {
+                let account = Account {
+                    owner: state.env.canister_id(),
+                    subaccount: Some(deposit_subaccount(user_id, swap.id)),
+                };
                 if swap.token0_received {
                     Err(Success(SuccessResult {
-                        complete: swap.token1_received,
+                        complete: swap.is_fully_filled(),
                     }))
                 } else {
+                    // Enough must be deposited to cover the fee of each transfer out
+                    let max_transfers = swap.max_token0_transfers();
                     Ok(PrepareResult {
                         user_id,
-                        ledger: swap.token0.ledger,
+                        fill_id: 0,
+                        account,
+                        deposits_required: swap
+                            .token0s()
+                            .into_iter()
+                            .map(|a| (a.token.ledger, a.amount + a.token.fee * max_transfers))
+                            .collect(),
+                        token1_amount: 0,
+                    })
+                }
+            } else if swap.fill(user_id, fill_id).is_some() {
+                Err(Success(SuccessResult {
+                    complete: swap.token0_received,
+                }))
+            } else if swap.is_fully_filled() {
+                Err(SwapAlreadyAccepted)
+            } else {
+                let token1_amount = args.token1_amount.unwrap_or_else(|| swap.token1_remaining());
+                if let Err(error) = swap.validate_fill_amount(token1_amount) {
+                    Err(InvalidFillAmount(error))
+                } else {
+                    Ok(PrepareResult {
+                        user_id,
+                        fill_id,
                         account: Account {
                             owner: state.env.canister_id(),
-                            subaccount: Some(deposit_subaccount(user_id, swap.id)),
+                            subaccount: Some(fill_deposit_subaccount(user_id, swap.id, fill_id)),
                         },
-                        balance_required: swap.amount0 + swap.token0.fee,
+                        deposits_required: vec![(swap.token1.ledger, token1_amount + swap.token1.fee)],
+                        token1_amount,
                     })
                 }
-            } else if let Some((accepted_by, _)) = swap.accepted_by {
-                if accepted_by == user_id {
-                    Err(Success(SuccessResult {
-                        complete: swap.token0_received,
-                    }))
-                } else {
-                    Err(SwapAlreadyAccepted)
-                }
-            } else {
-                Ok(PrepareResult {
-                    user_id,
-                    ledger: swap.token1.ledger,
-                    account: Account {
-                        owner: state.env.canister_id(),
-                        subaccount: Some(deposit_subaccount(user_id, swap.id)),
-                    },
-                    balance_required: swap.amount1 + swap.token1.fee,
-                })
             }
         }
     } else {
         Err(SwapNotFound)
     }
 }
+
+fn commit(swap_id: u32, user_id: UserId, fill_id: u32, token1_amount: u128, state: &mut RuntimeState) -> Response {
+    let swap = state.data.swaps.get_mut(swap_id).unwrap();
+    let now = state.env.now();
+
+    if user_id == swap.created_by {
+        if !swap.token0_received {
+            swap.token0_received = true;
+            // Any fills which were made before token0 was received can now be paid out
+            for fill in swap.fills.iter() {
+                state.data.pending_payments_queue.push_fill_payments(swap, fill, now);
+            }
+        }
+        let complete = swap.is_fully_filled();
+        crate::jobs::make_pending_payments::start_job_if_required(state);
+        return Success(SuccessResult { complete });
+    }
+
+    // The swap may have changed while the balance was being checked
+    if swap.fill(user_id, fill_id).is_some() {
+        Success(SuccessResult {
+            complete: swap.token0_received,
+        })
+    } else if swap.cancelled_at.is_some() {
+        SwapCancelled
+    } else if swap.expires_at < now {
+        SwapExpired
+    } else if swap.is_fully_filled() {
+        SwapAlreadyAccepted
+    } else if let Err(error) = swap.validate_fill_amount(token1_amount) {
+        InvalidFillAmount(error)
+    } else {
+        swap.add_fill(user_id, fill_id, token1_amount, now);
+        let complete = swap.token0_received;
+        if complete {
+            let fill = swap.fills.last().unwrap();
+            state.data.pending_payments_queue.push_fill_payments(swap, fill, now);
+            crate::jobs::make_pending_payments::start_job_if_required(state);
+        }
+        Success(SuccessResult { complete })
+    }
+}
@@ -6,11 +6,15 @@ fn main() {
     generate_candid_method!(group, events, query);
     generate_candid_method!(group, events_by_index, query);
     generate_candid_method!(group, events_window, query);
+    generate_candid_method!(group, installed_bots, query);
     generate_candid_method!(group, invite_code, query);
     generate_candid_method!(group, local_user_index, query);
+    generate_candid_method!(group, message_edit_history, query);
+    generate_candid_method!(group, message_seen_by, query);
     generate_candid_method!(group, messages_by_message_index, query);
     generate_candid_method!(group, thread_previews, query);
     generate_candid_method!(group, public_summary, query);
+    generate_candid_method!(group, quiz_leaderboard, query);
     generate_candid_method!(group, rules, query);
     generate_candid_method!(group, search_messages, query);
     generate_candid_method!(group, selected_initial, query);
@@ -20,24 +24,31 @@ fn main() {
     generate_candid_method!(group, video_call_participants, query);
 
     generate_candid_method!(group, accept_p2p_swap, update);
+    generate_candid_method!(group, add_bot, update);
     generate_candid_method!(group, add_reaction, update);
+    generate_candid_method!(group, assign_custom_role, update);
     generate_candid_method!(group, block_user, update);
     generate_candid_method!(group, cancel_p2p_swap, update);
     generate_candid_method!(group, change_role, update);
     generate_candid_method!(group, claim_prize, update);
     generate_candid_method!(group, convert_into_community, update);
+    generate_candid_method!(group, create_custom_role, update);
     generate_candid_method!(group, decline_invitation, update);
+    generate_candid_method!(group, delete_custom_role, update);
     generate_candid_method!(group, delete_messages, update);
     generate_candid_method!(group, disable_invite_code, update);
     generate_candid_method!(group, edit_message_v2, update);
     generate_candid_method!(group, end_video_call, update);
+    generate_candid_method!(group, execute_bot_command, update);
     generate_candid_method!(group, enable_invite_code, update);
     generate_candid_method!(group, follow_thread, update);
     generate_candid_method!(group, join_video_call, update);
+    generate_candid_method!(group, mute_member, update);
     generate_candid_method!(group, pin_message_v2, update);
     generate_candid_method!(group, register_poll_vote, update);
     generate_candid_method!(group, register_proposal_vote, update);
     generate_candid_method!(group, register_proposal_vote_v2, update);
+    generate_candid_method!(group, remove_bot, update);
     generate_candid_method!(group, remove_participant, update);
     generate_candid_method!(group, remove_reaction, update);
     generate_candid_method!(group, report_message, update);
@@ -50,6 +61,7 @@ fn main() {
     generate_candid_method!(group, undelete_messages, update);
     generate_candid_method!(group, unfollow_thread, update);
     generate_candid_method!(group, unpin_message, update);
+    generate_candid_method!(group, update_custom_role, update);
     generate_candid_method!(group, update_group_v2, update);
 
     candid::export_service!();
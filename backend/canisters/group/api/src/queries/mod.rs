@@ -3,6 +3,7 @@ pub mod c2c_events;
 pub mod c2c_events_by_index;
 pub mod c2c_events_internal;
 pub mod c2c_events_window;
+pub mod c2c_is_member;
 pub mod c2c_name_and_members;
 pub mod c2c_summary;
 pub mod c2c_summary_updates;
@@ -10,10 +11,14 @@ pub mod deleted_message;
 pub mod events;
 pub mod events_by_index;
 pub mod events_window;
+pub mod installed_bots;
 pub mod invite_code;
 pub mod local_user_index;
+pub mod message_edit_history;
+pub mod message_seen_by;
 pub mod messages_by_message_index;
 pub mod public_summary;
+pub mod quiz_leaderboard;
 pub mod rules;
 pub mod search_messages;
 pub mod selected_initial;
@@ -1,6 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
-use types::{Empty, EventIndex, GroupMember, MessageIndex, TimestampMillis, UserId, VersionedRules};
+use types::{CustomRole, Empty, EventIndex, GroupMember, MessageIndex, TimestampMillis, UserId, VersionedRules};
 
 pub type Args = Empty;
 
@@ -20,4 +20,5 @@ pub struct SuccessResult {
     pub invited_users: Vec<UserId>,
     pub pinned_messages: Vec<MessageIndex>,
     pub chat_rules: VersionedRules,
+    pub custom_roles: Vec<CustomRole>,
 }
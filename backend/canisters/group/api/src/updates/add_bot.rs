@@ -0,0 +1,21 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::UserId;
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub bot_id: UserId,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    NotAuthorized,
+    CallerNotInGroup,
+    BotNotFound,
+    BotBlocked,
+    ParticipantLimitReached(u32),
+    UserSuspended,
+    ChatFrozen,
+    InternalError(String),
+}
@@ -0,0 +1,20 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::UserId;
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub role_id: u32,
+    pub users_to_add: Vec<UserId>,
+    pub users_to_remove: Vec<UserId>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    CallerNotInGroup,
+    NotAuthorized,
+    RoleNotFound,
+    UserSuspended,
+    ChatFrozen,
+}
@@ -0,0 +1,16 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::BotEventType;
+
+// Called by a bot which is installed in the group. An empty list of event types cancels the subscription.
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub event_types: Vec<BotEventType>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    BotNotInstalled,
+    ChatFrozen,
+}
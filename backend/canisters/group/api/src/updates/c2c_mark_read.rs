@@ -0,0 +1,17 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::MessageIndex;
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub read_up_to: MessageIndex,
+    // The position previously reported, if any, so that groups which only track counts don't double count
+    pub previous: Option<MessageIndex>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    ReadReceiptsDisabled,
+    UserNotInGroup,
+}
@@ -1,6 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
-use types::{CompletedCryptoTransaction, FailedCryptoTransaction, MessageId};
+use types::{CompletedCryptoTransaction, FailedCryptoTransaction, GateCheckFailedReason, MessageId};
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Args {
@@ -11,6 +11,8 @@ pub struct Args {
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub enum Response {
     Success,
+    // Returned for prize draws, whose winners are picked once the prize ends
+    EnteredDraw,
     CallerNotInGroup,
     UserSuspended,
     ChatFrozen,
@@ -18,6 +20,9 @@ pub enum Response {
     AlreadyClaimed,
     PrizeFullyClaimed,
     PrizeEnded,
+    NotEligible,
+    GateCheckFailed(GateCheckFailedReason),
+    InternalError(String),
     TransferFailed(String, FailedCryptoTransaction),
     FailedAfterTransfer(String, CompletedCryptoTransaction),
 }
@@ -0,0 +1,29 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{FieldTooLongResult, FieldTooShortResult, GroupPermission, MessagePermission};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub name: String,
+    pub permissions: Vec<GroupPermission>,
+    pub message_permissions: Vec<MessagePermission>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    CallerNotInGroup,
+    NotAuthorized,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+    TooManyRoles(u32),
+    UserSuspended,
+    ChatFrozen,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub role_id: u32,
+}
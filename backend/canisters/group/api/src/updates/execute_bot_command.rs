@@ -0,0 +1,34 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{BotMessageFailure, MessageIndex, SlashCommand, UserId};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub bot_id: UserId,
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub command: SlashCommand,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    CallerNotInGroup,
+    ThreadMessageNotFound,
+    BotNotFound,
+    CommandNotFound,
+    InvalidCommand(String),
+    NotAuthorized,
+    BotNotAuthorized,
+    BotError(String),
+    UserSuspended,
+    ChatFrozen,
+    InternalError(String),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    // The indexes of the messages sent by the bot in response to the command
+    pub message_indexes: Vec<MessageIndex>,
+    // The messages returned by the bot which could not be sent, eg. because the bot lacks permission to send them
+    pub failed_messages: Vec<BotMessageFailure>,
+}
@@ -1,12 +1,16 @@
 pub mod accept_p2p_swap;
+pub mod add_bot;
 pub mod add_reaction;
+pub mod assign_custom_role;
 pub mod block_user;
+pub mod c2c_bot_subscribe_to_events;
 pub mod c2c_delete_group;
 pub mod c2c_export_group;
 pub mod c2c_freeze_group;
 pub mod c2c_invite_users;
 pub mod c2c_join_group;
 pub mod c2c_leave_group;
+pub mod c2c_mark_read;
 pub mod c2c_report_message_v2;
 pub mod c2c_send_message;
 pub mod c2c_set_user_suspended;
@@ -20,18 +24,23 @@ pub mod cancel_p2p_swap;
 pub mod change_role;
 pub mod claim_prize;
 pub mod convert_into_community;
+pub mod create_custom_role;
 pub mod decline_invitation;
+pub mod delete_custom_role;
 pub mod delete_messages;
 pub mod disable_invite_code;
 pub mod edit_message_v2;
 pub mod enable_invite_code;
 pub mod end_video_call;
+pub mod execute_bot_command;
 pub mod follow_thread;
 pub mod join_video_call;
+pub mod mute_member;
 pub mod pin_message_v2;
 pub mod register_poll_vote;
 pub mod register_proposal_vote;
 pub mod register_proposal_vote_v2;
+pub mod remove_bot;
 pub mod remove_participant;
 pub mod remove_reaction;
 pub mod report_message;
@@ -44,4 +53,5 @@ pub mod unblock_user;
 pub mod undelete_messages;
 pub mod unfollow_thread;
 pub mod unpin_message;
+pub mod update_custom_role;
 pub mod update_group_v2;
@@ -0,0 +1,28 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{Milliseconds, TimestampMillis, UserId};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub user_id: UserId,
+    pub duration: Milliseconds,
+    pub reason: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    CallerNotInGroup,
+    UserNotInGroup,
+    CannotMuteSelf,
+    DurationInvalid,
+    ReasonTooLong(u32),
+    NotAuthorized,
+    UserSuspended,
+    ChatFrozen,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub muted_until: TimestampMillis,
+}
@@ -34,6 +34,7 @@ pub enum Response {
     InvalidRequest(String),
     ChatFrozen,
     RulesNotAccepted,
+    RateLimited(TimestampMillis),
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
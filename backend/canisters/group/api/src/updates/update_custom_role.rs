@@ -0,0 +1,25 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{FieldTooLongResult, FieldTooShortResult, GroupPermission, MessagePermission};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub role_id: u32,
+    pub name: Option<String>,
+    pub permissions: Option<Vec<GroupPermission>>,
+    pub message_permissions: Option<Vec<MessagePermission>>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success,
+    CallerNotInGroup,
+    NotAuthorized,
+    RoleNotFound,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+    UserSuspended,
+    ChatFrozen,
+}
@@ -1,8 +1,8 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use types::{
-    AccessGate, Document, FieldTooLongResult, FieldTooShortResult, Milliseconds, OptionUpdate, OptionalGroupPermissions,
-    UpdatedRules, Version,
+    AccessGate, Document, FieldTooLongResult, FieldTooShortResult, GateReverification, Milliseconds, OptionUpdate,
+    OptionalGroupPermissions, SlowMode, UpdatedRules, Version,
 };
 
 #[derive(CandidType, Serialize, Deserialize, Debug, Default)]
@@ -14,6 +14,12 @@ pub struct Args {
     pub permissions_v2: Option<OptionalGroupPermissions>,
     pub events_ttl: OptionUpdate<Milliseconds>,
     pub gate: OptionUpdate<AccessGate>,
+    #[serde(default)]
+    pub gate_reverification: OptionUpdate<GateReverification>,
+    #[serde(default)]
+    pub slow_mode: OptionUpdate<SlowMode>,
+    #[serde(default)]
+    pub read_receipts_enabled: Option<bool>,
     pub public: Option<bool>,
     pub correlation_id: u64,
 }
@@ -31,6 +37,7 @@ pub enum Response {
     RulesTooLong(FieldTooLongResult),
     AvatarTooBig(FieldTooLongResult),
     AccessGateInvalid,
+    SlowModeInvalid,
     NameTaken,
     UserSuspended,
     ChatFrozen,
@@ -7,6 +7,7 @@ generate_c2c_call!(c2c_events);
 generate_c2c_call!(c2c_events_by_index);
 generate_c2c_call!(c2c_events_internal);
 generate_c2c_call!(c2c_events_window);
+generate_c2c_call!(c2c_is_member);
 generate_c2c_call!(c2c_name_and_members);
 generate_c2c_call!(c2c_summary);
 generate_c2c_call!(c2c_summary_updates);
@@ -17,12 +18,14 @@ generate_candid_c2c_call!(invite_code);
 generate_candid_c2c_call!(selected_initial);
 
 // Updates
+generate_c2c_call!(c2c_bot_subscribe_to_events);
 generate_c2c_call!(c2c_delete_group);
 generate_c2c_call!(c2c_export_group);
 generate_c2c_call!(c2c_freeze_group);
 generate_c2c_call!(c2c_invite_users);
 generate_c2c_call!(c2c_join_group);
 generate_c2c_call!(c2c_leave_group);
+generate_c2c_call!(c2c_mark_read);
 generate_c2c_call!(c2c_report_message_v2);
 generate_c2c_call!(c2c_send_message);
 generate_c2c_call!(c2c_set_user_suspended);
@@ -1,7 +1,13 @@
 use crate::RuntimeState;
 
 pub mod make_pending_payments;
+pub mod migrate_events_to_stable_memory;
+pub mod populate_search_index;
+pub mod push_bot_events;
 
 pub(crate) fn start(state: &RuntimeState) {
     make_pending_payments::start_job_if_required(state);
+    migrate_events_to_stable_memory::start_job_if_required(state);
+    populate_search_index::start_job_if_required(state);
+    push_bot_events::start_job_if_required(state);
 }
@@ -1,7 +1,7 @@
 use crate::memory::{get_instruction_counts_data_memory, get_instruction_counts_index_memory};
 use crate::model::new_joiner_rewards::{NewJoinerRewardMetrics, NewJoinerRewardStatus, NewJoinerRewards};
 use crate::new_joiner_rewards::process_new_joiner_reward;
-use crate::timer_job_types::{RemoveExpiredEventsJob, TimerJob};
+use crate::timer_job_types::{RemoveExpiredEventsJob, ReverifyGateJob, TimerJob};
 use crate::updates::c2c_freeze_group::freeze_group_impl;
 use activity_notification_state::ActivityNotificationState;
 use candid::Principal;
@@ -14,7 +14,9 @@ use fire_and_forget_handler::FireAndForgetHandler;
 use group_chat_core::{
     AddResult as AddMemberResult, GroupChatCore, GroupMemberInternal, GroupRoleInternal, InvitedUsersResult, UserInvitation,
 };
-use group_community_common::{PaymentReceipts, PaymentRecipient, PendingPayment, PendingPaymentReason, PendingPaymentsQueue};
+use group_community_common::{
+    BotEventSyncQueue, PaymentReceipts, PaymentRecipient, PendingPayment, PendingPaymentReason, PendingPaymentsQueue,
+};
 use instruction_counts_log::{InstructionCountEntry, InstructionCountFunctionId, InstructionCountsLog};
 use msgpack::serialize_then_unwrap;
 use notifications_canister::c2c_push_notification;
@@ -190,6 +192,7 @@ impl RuntimeState {
                 .rules_accepted
                 .as_ref()
                 .map_or(false, |version| version.value >= chat.rules.text.version),
+            lapsed: member.lapsed.value,
         };
 
         GroupCanisterGroupChatSummary {
@@ -222,6 +225,9 @@ impl RuntimeState {
             events_ttl: events_ttl.value,
             events_ttl_last_updated: events_ttl.timestamp,
             gate: chat.gate.value.clone(),
+            slow_mode: chat.slow_mode.value,
+            read_receipts_enabled: chat.read_receipts_enabled.value,
+            gate_reverification: chat.gate_reverification.value,
             rules_accepted: membership.rules_accepted,
             membership: Some(membership),
             video_call_in_progress: chat.events.video_call_in_progress().value.clone(),
@@ -265,7 +271,7 @@ impl RuntimeState {
             ChatFrozen
         } else {
             let transfers_required = self.prepare_transfers_for_import_into_community();
-            let serialized = serialize_then_unwrap(&self.data.chat);
+            let serialized = chat_events::serialize_for_export(&self.data.chat);
             let total_bytes = serialized.len() as u64;
 
             if let Some(community_id) = community.community_id() {
@@ -447,6 +453,8 @@ struct Data {
     #[serde(with = "serde_bytes")]
     pub ic_root_key: Vec<u8>,
     pub event_store_client: EventStoreClient<CdkRuntime>,
+    #[serde(default)]
+    pub bot_event_sync_queue: BotEventSyncQueue,
 }
 
 fn init_instruction_counts_log() -> InstructionCountsLog {
@@ -533,6 +541,7 @@ impl Data {
             event_store_client: EventStoreClientBuilder::new(local_group_index_canister_id, CdkRuntime::default())
                 .with_flush_delay(Duration::from_millis(5 * MINUTE_IN_MS))
                 .build(),
+            bot_event_sync_queue: BotEventSyncQueue::default(),
         }
     }
 
@@ -630,6 +639,27 @@ impl Data {
         );
     }
 
+    pub fn schedule_gate_reverification(&mut self, now: TimestampMillis) {
+        self.timer_jobs.cancel_jobs(|j| matches!(j, TimerJob::ReverifyGate(_)));
+
+        if self.chat.gate.value.is_some() {
+            if let Some(gate_reverification) = self.chat.gate_reverification.value {
+                self.timer_jobs.enqueue_job(
+                    TimerJob::ReverifyGate(ReverifyGateJob { after: None }),
+                    now + gate_reverification.interval,
+                    now,
+                );
+            }
+        }
+    }
+
+    // Queues the next batch of members to be re-verified straight away
+    pub fn continue_gate_reverification(&mut self, after: UserId, now: TimestampMillis) {
+        self.timer_jobs.cancel_jobs(|j| matches!(j, TimerJob::ReverifyGate(_)));
+        self.timer_jobs
+            .enqueue_job(TimerJob::ReverifyGate(ReverifyGateJob { after: Some(after) }), now, now);
+    }
+
     pub fn handle_event_expiry(&mut self, expiry: TimestampMillis, now: TimestampMillis) {
         if self.next_event_expiry.map_or(true, |ex| expiry < ex) {
             self.next_event_expiry = Some(expiry);
@@ -0,0 +1,19 @@
+use crate::guards::caller_is_group_index;
+use crate::read_state;
+use crate::RuntimeState;
+use canister_api_macros::query_msgpack;
+use group_canister::c2c_is_member::*;
+
+#[query_msgpack(guard = "caller_is_group_index")]
+fn c2c_is_member(args: Args) -> Response {
+    read_state(|state| c2c_is_member_impl(args, state))
+}
+
+fn c2c_is_member_impl(args: Args, state: &RuntimeState) -> Response {
+    state
+        .data
+        .chat
+        .members
+        .get(&args.user_id)
+        .map_or(false, |m| !m.suspended.value)
+}
@@ -0,0 +1,20 @@
+use crate::{read_state, RuntimeState};
+use group_canister::installed_bots::{Response::*, *};
+use ic_cdk::query;
+
+#[query]
+fn installed_bots(_args: Args) -> Response {
+    read_state(installed_bots_impl)
+}
+
+fn installed_bots_impl(state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+
+    if state.data.get_member(caller).is_none() {
+        return CallerNotInGroup;
+    }
+
+    Success(SuccessResult {
+        bots: state.data.chat.installed_bots(),
+    })
+}
@@ -0,0 +1,28 @@
+use crate::{read_state, RuntimeState};
+use group_canister::message_edit_history::{Response::*, *};
+use group_chat_core::MessageEditHistoryResult;
+use ic_cdk::query;
+
+#[query]
+fn message_edit_history(args: Args) -> Response {
+    read_state(|state| message_edit_history_impl(args, state))
+}
+
+fn message_edit_history_impl(args: Args, state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.lookup_user_id(caller) else {
+        return CallerNotInGroup;
+    };
+
+    match state
+        .data
+        .chat
+        .message_edit_history(user_id, args.thread_root_message_index, args.message_id)
+    {
+        MessageEditHistoryResult::Success(versions) => Success(SuccessResult { versions }),
+        MessageEditHistoryResult::UserNotInGroup => CallerNotInGroup,
+        MessageEditHistoryResult::NotAuthorized => NotAuthorized,
+        MessageEditHistoryResult::ThreadNotFound => ThreadMessageNotFound,
+        MessageEditHistoryResult::MessageNotFound => MessageNotFound,
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{read_state, RuntimeState};
+use group_canister::message_seen_by::{Response::*, *};
+use group_chat_core::MessageSeenByResult;
+use ic_cdk::query;
+
+#[query]
+fn message_seen_by(args: Args) -> Response {
+    read_state(|state| message_seen_by_impl(args, state))
+}
+
+fn message_seen_by_impl(args: Args, state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.lookup_user_id(caller) else {
+        return CallerNotInGroup;
+    };
+
+    match state.data.chat.message_seen_by(user_id, args.message_index) {
+        MessageSeenByResult::Success(seen_by) => Success(SuccessResult {
+            seen_by_count: seen_by.count,
+            seen_by: seen_by.users,
+        }),
+        MessageSeenByResult::UserNotInGroup => CallerNotInGroup,
+        MessageSeenByResult::ReadReceiptsDisabled => ReadReceiptsDisabled,
+        MessageSeenByResult::MessageNotFound => MessageNotFound,
+        MessageSeenByResult::MessageTooOld => MessageTooOld,
+    }
+}
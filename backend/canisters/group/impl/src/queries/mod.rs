@@ -3,6 +3,7 @@ use types::TimestampMillis;
 
 mod c2c_can_issue_access_token;
 mod c2c_events_internal;
+mod c2c_is_member;
 mod c2c_name_and_members;
 mod deleted_message;
 mod events;
@@ -11,8 +12,11 @@ mod events_window;
 mod http_request;
 mod invite_code;
 mod local_user_index;
+mod message_edit_history;
+mod message_seen_by;
 mod messages_by_message_index;
 mod public_summary;
+mod quiz_leaderboard;
 mod rules;
 mod search_messages;
 mod selected_initial;
@@ -31,3 +35,4 @@ fn check_replica_up_to_date(latest_known_update: Option<TimestampMillis>, state:
     }
     Ok(())
 }
+pub mod installed_bots;
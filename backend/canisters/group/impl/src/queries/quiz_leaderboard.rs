@@ -0,0 +1,21 @@
+use crate::{read_state, RuntimeState};
+use group_canister::quiz_leaderboard::{Response::*, *};
+use group_chat_core::QuizLeaderboardResult;
+use ic_cdk::query;
+
+#[query]
+fn quiz_leaderboard(args: Args) -> Response {
+    read_state(|state| quiz_leaderboard_impl(args, state))
+}
+
+fn quiz_leaderboard_impl(args: Args, state: &RuntimeState) -> Response {
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.lookup_user_id(caller) else {
+        return CallerNotInGroup;
+    };
+
+    match state.data.chat.quiz_leaderboard(user_id, args.max_results) {
+        QuizLeaderboardResult::Success(leaderboard, caller) => Success(SuccessResult { leaderboard, caller }),
+        QuizLeaderboardResult::UserNotInGroup => CallerNotInGroup,
+    }
+}
@@ -67,6 +67,11 @@ fn summary_updates_impl(updates_since: TimestampMillis, on_behalf_of: Option<Pri
             .as_ref()
             .filter(|accepted| updates.rules_changed || accepted.timestamp > updates_since)
             .map(|accepted| accepted.value >= chat.rules.text.version),
+        lapsed: member
+            .lapsed
+            .if_set_after(updates_since)
+            .copied()
+            .map_or(OptionUpdate::NoChange, OptionUpdate::from_update),
     };
 
     Success(SuccessResult {
@@ -102,6 +107,9 @@ fn summary_updates_impl(updates_since: TimestampMillis, on_behalf_of: Option<Pri
             events_ttl: updates.events_ttl,
             events_ttl_last_updated: updates.events_ttl_last_updated,
             gate: updates.gate,
+            slow_mode: updates.slow_mode,
+            read_receipts_enabled: updates.read_receipts_enabled,
+            gate_reverification: updates.gate_reverification,
             rules_accepted: membership.rules_accepted,
             membership: Some(membership),
             video_call_in_progress: updates.video_call_in_progress,
@@ -1,12 +1,16 @@
 use crate::updates::end_video_call::end_video_call_impl;
+use crate::updates::remove_participant::remove_membership_from_user_canister;
 use crate::{activity_notifications::handle_activity_notification, mutate_state, read_state};
 use canister_timer_jobs::Job;
 use chat_events::MessageContentInternal;
-use ledger_utils::process_transaction;
+use group_chat_core::GATE_REVERIFICATION_BATCH_SIZE;
+use ic_cdk::api::management_canister::main::raw_rand;
+use ledger_utils::{create_pending_transaction, process_transaction};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tracing::error;
 use types::{BlobReference, CanisterId, MessageId, MessageIndex, P2PSwapStatus, PendingCryptoTransaction, UserId};
-use utils::consts::MEMO_PRIZE_REFUND;
+use utils::consts::{MEMO_PRIZE_CLAIM, MEMO_PRIZE_REFUND, OPENCHAT_BOT_USER_ID};
 use utils::time::{DAY_IN_MS, MINUTE_IN_MS, NANOS_PER_MILLISECOND, SECOND_IN_MS};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -15,12 +19,16 @@ pub enum TimerJob {
     DeleteFileReferences(DeleteFileReferencesJob),
     EndPoll(EndPollJob),
     RefundPrize(RefundPrizeJob),
+    DrawPrize(DrawPrizeJob),
+    TransferPrize(TransferPrizeJob),
     MakeTransfer(MakeTransferJob),
     RemoveExpiredEvents(RemoveExpiredEventsJob),
     NotifyEscrowCanisterOfDeposit(NotifyEscrowCanisterOfDepositJob),
     CancelP2PSwapInEscrowCanister(CancelP2PSwapInEscrowCanisterJob),
     MarkP2PSwapExpired(MarkP2PSwapExpiredJob),
     MarkVideoCallEnded(MarkVideoCallEndedJob),
+    ReverifyGate(ReverifyGateJob),
+    UnmuteMember(UnmuteMemberJob),
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -38,6 +46,8 @@ pub struct DeleteFileReferencesJob {
 pub struct EndPollJob {
     pub thread_root_message_index: Option<MessageIndex>,
     pub message_index: MessageIndex,
+    #[serde(default)]
+    pub attempt: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -46,6 +56,20 @@ pub struct RefundPrizeJob {
     pub message_index: MessageIndex,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DrawPrizeJob {
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub message_index: MessageIndex,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TransferPrizeJob {
+    pub message_id: MessageId,
+    pub winner: UserId,
+    pub pending_transaction: PendingCryptoTransaction,
+    pub attempt: u32,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MakeTransferJob {
     pub pending_transaction: PendingCryptoTransaction,
@@ -106,6 +130,16 @@ pub struct MarkP2PSwapExpiredJob {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MarkVideoCallEndedJob(pub group_canister::end_video_call::Args);
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReverifyGateJob {
+    pub after: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnmuteMemberJob {
+    pub user_id: UserId,
+}
+
 impl Job for TimerJob {
     fn execute(self) {
         match self {
@@ -113,12 +147,16 @@ impl Job for TimerJob {
             TimerJob::DeleteFileReferences(job) => job.execute(),
             TimerJob::EndPoll(job) => job.execute(),
             TimerJob::RefundPrize(job) => job.execute(),
+            TimerJob::DrawPrize(job) => job.execute(),
+            TimerJob::TransferPrize(job) => job.execute(),
             TimerJob::MakeTransfer(job) => job.execute(),
             TimerJob::RemoveExpiredEvents(job) => job.execute(),
             TimerJob::NotifyEscrowCanisterOfDeposit(job) => job.execute(),
             TimerJob::CancelP2PSwapInEscrowCanister(job) => job.execute(),
             TimerJob::MarkP2PSwapExpired(job) => job.execute(),
             TimerJob::MarkVideoCallEnded(job) => job.execute(),
+            TimerJob::ReverifyGate(job) => job.execute(),
+            TimerJob::UnmuteMember(job) => job.execute(),
         }
     }
 }
@@ -158,13 +196,16 @@ impl Job for HardDeleteMessageContentJob {
                             if state
                                 .data
                                 .timer_jobs
-                                .cancel_job(|job| {
-                                    if let TimerJob::RefundPrize(j) = job {
+                                .cancel_job(|job| match job {
+                                    TimerJob::RefundPrize(j) => {
                                         j.thread_root_message_index == self.thread_root_message_index
                                             && j.message_index == message_index
-                                    } else {
-                                        false
                                     }
+                                    TimerJob::DrawPrize(j) => {
+                                        j.thread_root_message_index == self.thread_root_message_index
+                                            && j.message_index == message_index
+                                    }
+                                    _ => false,
                                 })
                                 .is_some()
                             {
@@ -203,16 +244,76 @@ impl Job for DeleteFileReferencesJob {
 
 impl Job for EndPollJob {
     fn execute(self) {
-        mutate_state(|state| {
+        let awaiting_vote_weights = mutate_state(|state| {
             let now = state.env.now();
-            state
+            if self.attempt == 0 {
+                state
+                    .data
+                    .chat
+                    .end_poll(self.thread_root_message_index, self.message_index, now);
+                handle_activity_notification(state);
+            }
+
+            let (weighting, end_date, voters) = state
                 .data
                 .chat
                 .events
-                .end_poll(self.thread_root_message_index, self.message_index, now);
+                .poll_awaiting_vote_weights(self.thread_root_message_index, self.message_index)?;
+
+            if self.attempt >= 10 {
+                // Stop retrying, any voters whose weights are still unknown have no weight
+                if state.data.chat.events.record_poll_vote_weights(
+                    self.thread_root_message_index,
+                    self.message_index,
+                    HashMap::new(),
+                    true,
+                    now,
+                ) {
+                    handle_activity_notification(state);
+                }
+                return None;
+            }
 
-            handle_activity_notification(state);
+            // Queue up the next attempt before looking up the weights, so that the results are still calculated if any
+            // of the lookups fail or the canister is upgraded while they are in progress
+            state.data.timer_jobs.enqueue_job(
+                TimerJob::EndPoll(EndPollJob {
+                    attempt: self.attempt + 1,
+                    ..self.clone()
+                }),
+                now + 5 * MINUTE_IN_MS,
+                now,
+            );
+            Some((weighting, end_date, voters))
         });
+
+        // For weighted polls, the results can only be calculated once each voter's weight has been looked up. The
+        // weights are taken as at the time the poll ended and are added to the poll's pending tally as they are found
+        if let Some((weighting, end_date, voters)) = awaiting_vote_weights {
+            ic_cdk::spawn(async move {
+                let weights = gated_groups::lookup_vote_weights(weighting, voters, end_date).await;
+
+                mutate_state(|state| {
+                    let now = state.env.now();
+                    if state.data.chat.events.record_poll_vote_weights(
+                        self.thread_root_message_index,
+                        self.message_index,
+                        weights,
+                        false,
+                        now,
+                    ) {
+                        state.data.timer_jobs.cancel_job(|job| match job {
+                            TimerJob::EndPoll(j) => {
+                                j.thread_root_message_index == self.thread_root_message_index
+                                    && j.message_index == self.message_index
+                            }
+                            _ => false,
+                        });
+                        handle_activity_notification(state);
+                    }
+                });
+            });
+        }
     }
 }
 
@@ -232,6 +333,112 @@ impl Job for RefundPrizeJob {
     }
 }
 
+impl Job for DrawPrizeJob {
+    fn execute(self) {
+        ic_cdk::spawn(draw_prize(self));
+
+        async fn draw_prize(job: DrawPrizeJob) {
+            let Some(seed): Option<[u8; 32]> = raw_rand().await.ok().and_then(|(bytes,)| bytes.try_into().ok()) else {
+                // Try again shortly, the draw can only be made once a random seed has been obtained
+                mutate_state(|state| {
+                    let now = state.env.now();
+                    state
+                        .data
+                        .timer_jobs
+                        .enqueue_job(TimerJob::DrawPrize(job), now + MINUTE_IN_MS, now);
+                });
+                return;
+            };
+
+            // The draw, along with a transfer job for each winner and the refund of any prizes which weren't won, is
+            // recorded in a single update so that none of them can be lost if the canister is upgraded
+            mutate_state(|state| {
+                let now = state.env.now();
+                let now_nanos = state.env.now_nanos();
+                let Some(draw) = state.data.chat.events.draw_prize_winners(job.message_index, seed, now) else {
+                    return;
+                };
+
+                for (winner, amount) in draw.winners {
+                    let pending_transaction = create_pending_transaction(
+                        draw.token.clone(),
+                        draw.ledger_canister_id,
+                        amount,
+                        draw.fee,
+                        winner,
+                        Some(&MEMO_PRIZE_CLAIM),
+                        now_nanos,
+                    );
+                    state.data.timer_jobs.enqueue_job(
+                        TimerJob::TransferPrize(TransferPrizeJob {
+                            message_id: draw.message_id,
+                            winner,
+                            pending_transaction,
+                            attempt: 0,
+                        }),
+                        now,
+                        now,
+                    );
+                }
+
+                // Prizes which have been won stay reserved until they are transferred, so this only refunds the prizes
+                // which weren't won
+                state.data.timer_jobs.enqueue_job(
+                    TimerJob::RefundPrize(RefundPrizeJob {
+                        thread_root_message_index: job.thread_root_message_index,
+                        message_index: job.message_index,
+                    }),
+                    now,
+                    now,
+                );
+
+                handle_activity_notification(state);
+            });
+        }
+    }
+}
+
+impl Job for TransferPrizeJob {
+    fn execute(self) {
+        let sender = read_state(|state| state.env.canister_id());
+        ic_cdk::spawn(transfer_prize(self, sender));
+
+        async fn transfer_prize(mut job: TransferPrizeJob, sender: CanisterId) {
+            let result = process_transaction(job.pending_transaction.clone(), sender).await;
+
+            mutate_state(|state| {
+                let now = state.env.now();
+                match result {
+                    Ok(completed) => {
+                        state.data.chat.events.claim_prize(
+                            job.message_id,
+                            job.winner,
+                            completed,
+                            state.env.rng(),
+                            &mut state.data.event_store_client,
+                            now,
+                        );
+                        handle_activity_notification(state);
+                    }
+                    Err(failed) => {
+                        // The prize stays reserved for the winner, so keep retrying, backing off up to once an hour
+                        error!(?failed, winner = ?job.winner, attempt = job.attempt, "Failed to transfer prize to draw winner");
+                        if (job.pending_transaction.created() / NANOS_PER_MILLISECOND) + DAY_IN_MS < now {
+                            job.pending_transaction.set_created(now * NANOS_PER_MILLISECOND);
+                        }
+                        let delay = MINUTE_IN_MS * (job.attempt as u64 + 1).min(60);
+                        job.attempt += 1;
+                        state
+                            .data
+                            .timer_jobs
+                            .enqueue_job(TimerJob::TransferPrize(job), now + delay, now);
+                    }
+                }
+            });
+        }
+    }
+}
+
 impl Job for MakeTransferJob {
     fn execute(self) {
         let sender = read_state(|state| state.env.canister_id());
@@ -273,6 +480,8 @@ impl Job for NotifyEscrowCanisterOfDepositJob {
                 &escrow_canister::notify_deposit::Args {
                     swap_id: self.swap_id,
                     user_id: Some(self.user_id),
+                    token1_amount: None,
+                    fill_id: None,
                 },
             )
             .await
@@ -369,3 +578,87 @@ impl Job for MarkVideoCallEndedJob {
         mutate_state(|state| end_video_call_impl(self.0, state));
     }
 }
+
+impl Job for ReverifyGateJob {
+    fn execute(self) {
+        let Some((gate, user_ids, this_canister, user_index_canister, group_index_canister, now)) = read_state(|state| {
+            state
+                .data
+                .chat
+                .gate_to_reverify(self.after, GATE_REVERIFICATION_BATCH_SIZE)
+                .map(|(gate, user_ids)| {
+                    (
+                        gate,
+                        user_ids,
+                        state.env.canister_id(),
+                        state.data.user_index_canister_id,
+                        state.data.group_index_canister_id,
+                        state.env.now(),
+                    )
+                })
+        }) else {
+            return;
+        };
+
+        // A full batch means there may be more members to check, so the next batch follows on from the last user
+        let next_batch_after = (user_ids.len() == GATE_REVERIFICATION_BATCH_SIZE)
+            .then(|| user_ids.last().copied())
+            .flatten();
+
+        ic_cdk::spawn(async move {
+            let result = gated_groups::reverify_members(
+                gate.clone(),
+                user_ids,
+                this_canister,
+                user_index_canister,
+                group_index_canister,
+                now,
+            )
+            .await;
+
+            mutate_state(|state| {
+                let now = state.env.now();
+
+                // If the gate has been changed while the checks were in progress then the results are no longer valid
+                if state.data.chat.gate.value.as_ref() == Some(&gate) {
+                    let removed = state
+                        .data
+                        .chat
+                        .apply_gate_reverification_results(result.passed, result.failed, now);
+
+                    for user_id in removed {
+                        state.data.remove_principal(user_id);
+                        remove_membership_from_user_canister(
+                            user_id,
+                            OPENCHAT_BOT_USER_ID,
+                            false,
+                            state.data.chat.name.value.clone(),
+                            state.data.chat.is_public.value,
+                            &mut state.data.fire_and_forget_handler,
+                        );
+                    }
+
+                    handle_activity_notification(state);
+
+                    if let Some(after) = next_batch_after {
+                        state.data.continue_gate_reverification(after, now);
+                        return;
+                    }
+                }
+
+                state.data.schedule_gate_reverification(now);
+            });
+        });
+    }
+}
+
+impl Job for UnmuteMemberJob {
+    fn execute(self) {
+        mutate_state(|state| {
+            let now = state.env.now();
+            if state.data.chat.unmute_member_if_expired(self.user_id, now) {
+                handle_activity_notification(state);
+            }
+        });
+    }
+}
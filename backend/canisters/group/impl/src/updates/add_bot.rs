@@ -0,0 +1,70 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::{mutate_state, read_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use group_canister::add_bot::{Response::*, *};
+use group_chat_core::{AddBotResult, CanAddBotResult};
+use ic_cdk::update;
+use types::{CanisterId, SlashCommandSchema, UserId};
+
+#[update]
+#[trace]
+async fn add_bot(args: Args) -> Response {
+    run_regular_jobs();
+
+    let user_index_canister_id = match read_state(prepare) {
+        Ok(ok) => ok,
+        Err(response) => return response,
+    };
+
+    let c2c_args = user_index_canister::c2c_bot_commands::Args { bot_id: args.bot_id };
+    match user_index_canister_c2c_client::c2c_bot_commands(user_index_canister_id, &c2c_args).await {
+        Ok(user_index_canister::c2c_bot_commands::Response::Success(result)) => {
+            mutate_state(|state| commit(args.bot_id, result.username, result.commands, state))
+        }
+        Ok(user_index_canister::c2c_bot_commands::Response::BotNotFound) => BotNotFound,
+        Err(error) => InternalError(format!("{error:?}")),
+    }
+}
+
+fn prepare(state: &RuntimeState) -> Result<CanisterId, Response> {
+    if state.data.is_frozen() {
+        return Err(ChatFrozen);
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.lookup_user_id(caller) else {
+        return Err(CallerNotInGroup);
+    };
+
+    match state.data.chat.can_add_bot(user_id) {
+        CanAddBotResult::Yes => Ok(state.data.user_index_canister_id),
+        CanAddBotResult::UserSuspended => Err(UserSuspended),
+        CanAddBotResult::UserNotInGroup => Err(CallerNotInGroup),
+        CanAddBotResult::NotAuthorized => Err(NotAuthorized),
+    }
+}
+
+fn commit(bot_id: UserId, bot_name: String, commands: Vec<SlashCommandSchema>, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return ChatFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.lookup_user_id(caller) else {
+        return CallerNotInGroup;
+    };
+
+    let now = state.env.now();
+    match state.data.chat.add_bot(user_id, bot_id, bot_name, commands, now) {
+        AddBotResult::Success => {
+            handle_activity_notification(state);
+            Success
+        }
+        AddBotResult::UserSuspended => UserSuspended,
+        AddBotResult::UserNotInGroup => CallerNotInGroup,
+        AddBotResult::NotAuthorized => NotAuthorized,
+        AddBotResult::NotABot => BotNotFound,
+        AddBotResult::Blocked => BotBlocked,
+        AddBotResult::MemberLimitReached(limit) => ParticipantLimitReached(limit),
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_api_macros::update_msgpack;
+use canister_tracing_macros::trace;
+use group_canister::c2c_bot_subscribe_to_events::{Response::*, *};
+
+#[update_msgpack]
+#[trace]
+fn c2c_bot_subscribe_to_events(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| c2c_bot_subscribe_to_events_impl(args, state))
+}
+
+fn c2c_bot_subscribe_to_events_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return ChatFrozen;
+    }
+
+    let bot_id = state.env.caller().into();
+    if state.data.chat.bots.set_subscriptions(&bot_id, args.event_types) {
+        Success
+    } else {
+        BotNotInstalled
+    }
+}
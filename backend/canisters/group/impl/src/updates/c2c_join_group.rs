@@ -7,7 +7,7 @@ use chat_events::ChatEventInternal;
 use gated_groups::{check_if_passes_gate, CheckGateArgs, CheckIfPassesGateResult, CheckVerifiedCredentialGateArgs};
 use group_canister::c2c_join_group::{Response::*, *};
 use group_chat_core::AddResult;
-use types::{AccessGate, MemberJoined, UsersUnblocked};
+use types::{AccessGate, BotEvent, BotMembersJoinedEvent, MemberJoined, UsersUnblocked};
 
 #[update_msgpack(guard = "caller_is_user_index_or_local_user_index")]
 #[trace]
@@ -50,6 +50,8 @@ fn is_permitted_to_join(args: &Args, state: &RuntimeState) -> Result<Option<(Acc
                     user_id: args.user_id,
                     diamond_membership_expires_at: args.diamond_membership_expires_at,
                     this_canister: state.env.canister_id(),
+                    user_index_canister: state.data.user_index_canister_id,
+                    group_index_canister: state.data.group_index_canister_id,
                     unique_person_proof: args.unique_person_proof.clone(),
                     verified_credential_args: args.verified_credential_args.as_ref().map(|vc| {
                         CheckVerifiedCredentialGateArgs {
@@ -60,6 +62,7 @@ fn is_permitted_to_join(args: &Args, state: &RuntimeState) -> Result<Option<(Acc
                             ii_origin: vc.ii_origin.clone(),
                         }
                     }),
+                    user_details: None,
                     now: state.env.now(),
                 },
             )
@@ -121,6 +124,13 @@ fn c2c_join_group_impl(args: Args, state: &mut RuntimeState) -> Response {
                 user_id: args.user_id,
                 invited_by: invitation.map(|i| i.invited_by),
             };
+            state.data.chat.bots.push_event(
+                BotEvent::MembersJoined(BotMembersJoinedEvent {
+                    user_ids: vec![args.user_id],
+                    added_by: event.invited_by,
+                }),
+                now,
+            );
             state.data.chat.events.push_main_event(
                 ChatEventInternal::ParticipantJoined(Box::new(event)),
                 args.correlation_id,
@@ -0,0 +1,26 @@
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_api_macros::update_msgpack;
+use canister_tracing_macros::trace;
+use group_canister::c2c_mark_read::{Response::*, *};
+
+#[update_msgpack]
+#[trace]
+fn c2c_mark_read(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| c2c_mark_read_impl(args, state))
+}
+
+fn c2c_mark_read_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if !state.data.chat.read_receipts_enabled.value {
+        return ReadReceiptsDisabled;
+    }
+
+    let user_id = state.env.caller().into();
+
+    if state.data.chat.mark_read(user_id, args.previous, args.read_up_to) {
+        Success
+    } else {
+        UserNotInGroup
+    }
+}
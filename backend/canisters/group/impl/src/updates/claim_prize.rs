@@ -1,11 +1,12 @@
 use crate::activity_notifications::handle_activity_notification;
-use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use crate::{mutate_state, read_state, run_regular_jobs, RuntimeState};
 use canister_tracing_macros::trace;
-use chat_events::ReservePrizeResult;
+use chat_events::{EnterPrizeDrawResult, ReservePrizeResult};
+use gated_groups::CheckIfPassesGateResult;
 use group_canister::claim_prize::{Response::*, *};
 use ic_cdk::update;
 use ledger_utils::{create_pending_transaction, process_transaction};
-use types::{CanisterId, CompletedCryptoTransaction, PendingCryptoTransaction, UserId};
+use types::{AccessGate, CanisterId, CompletedCryptoTransaction, PendingCryptoTransaction, TimestampMillis, UserId};
 use utils::consts::MEMO_PRIZE_CLAIM;
 
 #[update]
@@ -13,6 +14,33 @@ use utils::consts::MEMO_PRIZE_CLAIM;
 async fn claim_prize(args: Args) -> Response {
     run_regular_jobs();
 
+    let requirements = match read_state(|state| requirements(&args, state)) {
+        Ok(r) => r,
+        Err(response) => return *response,
+    };
+
+    if let Some(gate) = requirements.gate {
+        match gated_groups::check_if_member_passes_gate(
+            gate,
+            requirements.user_id,
+            requirements.this_canister_id,
+            requirements.user_index_canister_id,
+            requirements.group_index_canister_id,
+            requirements.now,
+        )
+        .await
+        {
+            CheckIfPassesGateResult::Success => {}
+            CheckIfPassesGateResult::Failed(reason) => return GateCheckFailed(reason),
+            CheckIfPassesGateResult::InternalError(error) => return InternalError(error),
+        }
+    }
+
+    // Prize draws are entered now and the winners are picked once the prize ends
+    if requirements.draw {
+        return mutate_state(|state| enter_draw(&args, state));
+    }
+
     // Validate the request and reserve a prize
     let prepare_result = match mutate_state(|state| prepare(&args, state)) {
         Ok(c) => c,
@@ -42,6 +70,75 @@ async fn claim_prize(args: Args) -> Response {
     }
 }
 
+struct Requirements {
+    user_id: UserId,
+    draw: bool,
+    gate: Option<AccessGate>,
+    this_canister_id: CanisterId,
+    user_index_canister_id: CanisterId,
+    group_index_canister_id: CanisterId,
+    now: TimestampMillis,
+}
+
+fn requirements(args: &Args, state: &RuntimeState) -> Result<Requirements, Box<Response>> {
+    if state.data.is_frozen() {
+        return Err(Box::new(ChatFrozen));
+    }
+
+    let Some(member) = state.data.get_member(state.env.caller()) else {
+        return Err(Box::new(CallerNotInGroup));
+    };
+
+    if member.suspended.value {
+        return Err(Box::new(UserSuspended));
+    }
+
+    let Some(requirements) = state
+        .data
+        .chat
+        .events
+        .prize_requirements(args.message_id, member.min_visible_event_index())
+    else {
+        return Err(Box::new(MessageNotFound));
+    };
+
+    Ok(Requirements {
+        user_id: member.user_id,
+        draw: requirements.draw,
+        gate: requirements.gate,
+        this_canister_id: state.env.canister_id(),
+        user_index_canister_id: state.data.user_index_canister_id,
+        group_index_canister_id: state.data.group_index_canister_id,
+        now: state.env.now(),
+    })
+}
+
+fn enter_draw(args: &Args, state: &mut RuntimeState) -> Response {
+    let Some(member) = state.data.get_member(state.env.caller()) else {
+        return CallerNotInGroup;
+    };
+
+    let now = state.env.now();
+    let min_visible_event_index = member.min_visible_event_index();
+    let user_id = member.user_id;
+
+    match state
+        .data
+        .chat
+        .events
+        .enter_prize_draw(args.message_id, min_visible_event_index, user_id, now)
+    {
+        EnterPrizeDrawResult::Success => {
+            handle_activity_notification(state);
+            EnteredDraw
+        }
+        EnterPrizeDrawResult::AlreadyEntered => AlreadyClaimed,
+        EnterPrizeDrawResult::NotEligible => NotEligible,
+        EnterPrizeDrawResult::PrizeEnded => PrizeEnded,
+        EnterPrizeDrawResult::NotADraw | EnterPrizeDrawResult::MessageNotFound => MessageNotFound,
+    }
+}
+
 struct PrepareResult {
     pub transaction: PendingCryptoTransaction,
     pub group: CanisterId,
@@ -77,6 +174,7 @@ fn prepare(args: &Args, state: &mut RuntimeState) -> Result<PrepareResult, Box<R
                 ReservePrizeResult::MessageNotFound => return Err(Box::new(MessageNotFound)),
                 ReservePrizeResult::PrizeFullyClaimed => return Err(Box::new(PrizeFullyClaimed)),
                 ReservePrizeResult::PrizeEnded => return Err(Box::new(PrizeEnded)),
+                ReservePrizeResult::NotEligible => return Err(Box::new(NotEligible)),
             };
 
         let transaction = create_pending_transaction(token, ledger, amount, fee, user_id, Some(&MEMO_PRIZE_CLAIM), now_nanos);
@@ -0,0 +1,45 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use group_canister::create_custom_role::{Response::*, *};
+use group_chat_core::CustomRoleResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn create_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| create_custom_role_impl(args, state))
+}
+
+fn create_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return ChatFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.get_member(caller).map(|m| m.user_id) else {
+        return CallerNotInGroup;
+    };
+
+    let now = state.env.now();
+    match state
+        .data
+        .chat
+        .create_custom_role(user_id, args.name, args.permissions, args.message_permissions, now)
+    {
+        CustomRoleResult::Success(role_id) => {
+            handle_activity_notification(state);
+            Success(SuccessResult { role_id })
+        }
+        CustomRoleResult::UserNotInGroup => CallerNotInGroup,
+        CustomRoleResult::UserSuspended => UserSuspended,
+        CustomRoleResult::NotAuthorized | CustomRoleResult::RoleNotFound => NotAuthorized,
+        CustomRoleResult::NameTooShort(result) => NameTooShort(result),
+        CustomRoleResult::NameTooLong(result) => NameTooLong(result),
+        CustomRoleResult::NameTaken => NameTaken,
+        CustomRoleResult::PermissionNotGrantable => PermissionNotGrantable,
+        CustomRoleResult::TooManyRoles(max) => TooManyRoles(max),
+    }
+}
@@ -0,0 +1,37 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use group_canister::delete_custom_role::{Response::*, *};
+use group_chat_core::CustomRoleResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn delete_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| delete_custom_role_impl(args, state))
+}
+
+fn delete_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return ChatFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.get_member(caller).map(|m| m.user_id) else {
+        return CallerNotInGroup;
+    };
+
+    let now = state.env.now();
+    match state.data.chat.delete_custom_role(user_id, args.role_id, now) {
+        CustomRoleResult::Success(_) => {
+            handle_activity_notification(state);
+            Success
+        }
+        CustomRoleResult::UserNotInGroup => CallerNotInGroup,
+        CustomRoleResult::UserSuspended => UserSuspended,
+        CustomRoleResult::RoleNotFound => RoleNotFound,
+        _ => NotAuthorized,
+    }
+}
@@ -25,7 +25,7 @@ fn disable_invite_code_impl(args: Args, state: &mut RuntimeState) -> Response {
             return UserSuspended;
         }
 
-        if member.role.can_invite_users(&state.data.chat.permissions) {
+        if member.can_invite_users(&state.data.chat.permissions) {
             let user_id = member.user_id;
             state.data.invite_code_enabled = false;
 
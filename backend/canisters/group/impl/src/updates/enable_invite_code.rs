@@ -103,7 +103,7 @@ fn prepare(state: &RuntimeState) -> Result<PrepareResult, Response> {
             return Err(UserSuspended);
         }
 
-        if member.role.can_invite_users(&state.data.chat.permissions) {
+        if member.can_invite_users(&state.data.chat.permissions) {
             return Ok(PrepareResult {
                 caller,
                 code: state.data.invite_code,
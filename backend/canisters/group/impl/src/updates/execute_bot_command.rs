@@ -0,0 +1,151 @@
+use crate::updates::send_message::process_send_message_result;
+use crate::{mutate_state, read_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use group_canister::execute_bot_command::{Response::*, *};
+use group_chat_core::PrepareBotCommandResult;
+use ic_cdk::update;
+use rand::Rng;
+use types::{BotMessageFailure, CanisterId, Chat, MessageIndex, UserId};
+
+#[update]
+#[trace]
+async fn execute_bot_command(args: Args) -> Response {
+    run_regular_jobs();
+
+    let user_index_canister_id = match read_state(|state| check_bot_installed(&args, state)) {
+        Ok(ok) => ok,
+        Err(response) => return response,
+    };
+
+    // The bot's commands may have changed since it was installed, so they are refreshed before the command is validated
+    let c2c_args = user_index_canister::c2c_bot_commands::Args { bot_id: args.bot_id };
+    match user_index_canister_c2c_client::c2c_bot_commands(user_index_canister_id, &c2c_args).await {
+        Ok(user_index_canister::c2c_bot_commands::Response::Success(result)) => mutate_state(|state| {
+            state.data.chat.bots.refresh(&args.bot_id, result.username, result.commands);
+        }),
+        Ok(user_index_canister::c2c_bot_commands::Response::BotNotFound) => return BotNotFound,
+        Err(error) => return InternalError(format!("{error:?}")),
+    }
+
+    let (user_id, chat) = match read_state(|state| prepare(&args, state)) {
+        Ok(ok) => ok,
+        Err(response) => return response,
+    };
+
+    let c2c_args = bot_api::handle_command::Args {
+        chat,
+        thread_root_message_index: args.thread_root_message_index,
+        initiator: user_id,
+        command: args.command,
+    };
+
+    match bot_c2c_client::handle_command(args.bot_id.into(), &c2c_args).await {
+        Ok(bot_api::handle_command::Response::Success(result)) => {
+            mutate_state(|state| Success(send_bot_messages(args.bot_id, args.thread_root_message_index, result, state)))
+        }
+        Ok(bot_api::handle_command::Response::Error(error)) => BotError(error),
+        Err(error) => InternalError(format!("{error:?}")),
+    }
+}
+
+fn check_bot_installed(args: &Args, state: &RuntimeState) -> Result<CanisterId, Response> {
+    if state.data.is_frozen() {
+        return Err(ChatFrozen);
+    }
+
+    let caller = state.env.caller();
+    if state.data.lookup_user_id(caller).is_none() {
+        return Err(CallerNotInGroup);
+    }
+
+    if state.data.chat.bots.get(&args.bot_id).is_none() {
+        return Err(BotNotFound);
+    }
+
+    Ok(state.data.user_index_canister_id)
+}
+
+fn prepare(args: &Args, state: &RuntimeState) -> Result<(UserId, Chat), Response> {
+    if state.data.is_frozen() {
+        return Err(ChatFrozen);
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.lookup_user_id(caller) else {
+        return Err(CallerNotInGroup);
+    };
+
+    match state
+        .data
+        .chat
+        .prepare_bot_command(user_id, args.bot_id, args.thread_root_message_index, &args.command)
+    {
+        PrepareBotCommandResult::Success => Ok((user_id, Chat::Group(state.env.canister_id().into()))),
+        PrepareBotCommandResult::UserSuspended => Err(UserSuspended),
+        PrepareBotCommandResult::UserNotInGroup => Err(CallerNotInGroup),
+        PrepareBotCommandResult::ThreadMessageNotFound => Err(ThreadMessageNotFound),
+        PrepareBotCommandResult::BotNotFound => Err(BotNotFound),
+        PrepareBotCommandResult::CommandNotFound => Err(CommandNotFound),
+        PrepareBotCommandResult::InvalidCommand(error) => Err(InvalidCommand(error)),
+        PrepareBotCommandResult::NotAuthorized => Err(NotAuthorized),
+        PrepareBotCommandResult::BotNotAuthorized => Err(BotNotAuthorized),
+    }
+}
+
+// The bot's messages are sent as if the bot itself had sent them, so they are subject to the same permission checks
+// as any other member's messages
+fn send_bot_messages(
+    bot_id: UserId,
+    thread_root_message_index: Option<MessageIndex>,
+    result: bot_api::handle_command::SuccessResult,
+    state: &mut RuntimeState,
+) -> SuccessResult {
+    let mut message_indexes = Vec::new();
+    let mut failed_messages = Vec::new();
+
+    for message in result.messages {
+        let now = state.env.now();
+        let message_id = message.message_id.unwrap_or_else(|| state.env.rng().gen());
+        let block_level_markdown = message.block_level_markdown.unwrap_or_default();
+
+        let send_message_result = state.data.chat.validate_and_send_message(
+            bot_id,
+            true,
+            false,
+            thread_root_message_index,
+            message_id,
+            message.content,
+            None,
+            Vec::new(),
+            false,
+            None,
+            false,
+            state.data.proposals_bot_user_id,
+            block_level_markdown,
+            &mut state.data.event_store_client,
+            now,
+        );
+
+        match process_send_message_result(
+            send_message_result,
+            bot_id,
+            result.bot_name.clone(),
+            result.bot_display_name.clone(),
+            thread_root_message_index,
+            Vec::new(),
+            now,
+            state,
+        ) {
+            group_canister::send_message_v2::Response::Success(r) => message_indexes.push(r.message_index),
+            response => failed_messages.push(BotMessageFailure {
+                message_id,
+                reason: format!("{response:?}"),
+            }),
+        }
+    }
+
+    SuccessResult {
+        message_indexes,
+        failed_messages,
+    }
+}
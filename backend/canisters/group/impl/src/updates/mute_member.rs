@@ -0,0 +1,51 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::timer_job_types::{TimerJob, UnmuteMemberJob};
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use group_canister::mute_member::{Response::*, *};
+use group_chat_core::MuteMemberResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn mute_member(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| mute_member_impl(args, state))
+}
+
+fn mute_member_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return ChatFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.get_member(caller).map(|m| m.user_id) else {
+        return CallerNotInGroup;
+    };
+
+    let now = state.env.now();
+    match state
+        .data
+        .chat
+        .mute_member(user_id, args.user_id, args.duration, args.reason, now)
+    {
+        MuteMemberResult::Success(muted_until) => {
+            state.data.timer_jobs.enqueue_job(
+                TimerJob::UnmuteMember(UnmuteMemberJob { user_id: args.user_id }),
+                muted_until,
+                now,
+            );
+
+            handle_activity_notification(state);
+            Success(SuccessResult { muted_until })
+        }
+        MuteMemberResult::UserSuspended => UserSuspended,
+        MuteMemberResult::UserNotInGroup => CallerNotInGroup,
+        MuteMemberResult::TargetUserNotInGroup => UserNotInGroup,
+        MuteMemberResult::NotAuthorized => NotAuthorized,
+        MuteMemberResult::CannotMuteSelf => CannotMuteSelf,
+        MuteMemberResult::DurationInvalid => DurationInvalid,
+        MuteMemberResult::ReasonTooLong(max_length) => ReasonTooLong(max_length),
+    }
+}
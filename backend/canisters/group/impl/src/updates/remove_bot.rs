@@ -0,0 +1,37 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use group_canister::remove_bot::{Response::*, *};
+use group_chat_core::RemoveBotResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn remove_bot(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| remove_bot_impl(args, state))
+}
+
+fn remove_bot_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return ChatFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.lookup_user_id(caller) else {
+        return CallerNotInGroup;
+    };
+
+    let now = state.env.now();
+    match state.data.chat.remove_bot(user_id, args.bot_id, now) {
+        RemoveBotResult::Success => {
+            handle_activity_notification(state);
+            Success
+        }
+        RemoveBotResult::UserSuspended => UserSuspended,
+        RemoveBotResult::UserNotInGroup => CallerNotInGroup,
+        RemoveBotResult::NotAuthorized => NotAuthorized,
+        RemoveBotResult::BotNotFound => BotNotFound,
+    }
+}
@@ -128,7 +128,7 @@ fn commit(user_to_remove: UserId, block: bool, removed_by: UserId, state: &mut R
     }
 }
 
-fn remove_membership_from_user_canister(
+pub(crate) fn remove_membership_from_user_canister(
     user_to_remove: UserId,
     removed_by: UserId,
     blocked: bool,
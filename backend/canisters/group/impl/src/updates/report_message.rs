@@ -47,7 +47,7 @@ fn build_c2c_args(args: &Args, state: &RuntimeState) -> Result<(c2c_report_messa
             return Err(UserSuspended);
         }
 
-        if args.delete && !member.role.can_delete_messages(&chat.permissions) {
+        if args.delete && !member.can_delete_messages(&chat.permissions) {
             return Err(NotAuthorized);
         }
 
@@ -64,6 +64,9 @@ fn build_c2c_args(args: &Args, state: &RuntimeState) -> Result<(c2c_report_messa
                         chat_id: MultiUserChat::Group(state.env.canister_id().into()),
                         thread_root_message_index: args.thread_root_message_index,
                         message,
+                        edit_history: events_reader
+                            .message_edit_history(args.message_id.into(), Some(user_id))
+                            .unwrap_or_default(),
                         already_deleted: args.delete,
                         is_public: state.data.chat.is_public.value,
                     },
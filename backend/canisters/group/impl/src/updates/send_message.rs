@@ -1,5 +1,5 @@
 use crate::activity_notifications::handle_activity_notification;
-use crate::timer_job_types::{DeleteFileReferencesJob, EndPollJob, MarkP2PSwapExpiredJob, RefundPrizeJob};
+use crate::timer_job_types::{DeleteFileReferencesJob, DrawPrizeJob, EndPollJob, MarkP2PSwapExpiredJob, RefundPrizeJob};
 use crate::{mutate_state, run_regular_jobs, Data, RuntimeState, TimerJob};
 use canister_api_macros::{update_candid_and_msgpack, update_msgpack};
 use canister_tracing_macros::trace;
@@ -34,6 +34,7 @@ fn send_message_impl(args: Args, state: &mut RuntimeState) -> Response {
             let result = state.data.chat.validate_and_send_message(
                 user_id,
                 is_bot,
+                false,
                 args.thread_root_message_index,
                 args.message_id,
                 args.content,
@@ -73,6 +74,7 @@ fn c2c_send_message_impl(args: C2CArgs, state: &mut RuntimeState) -> C2CResponse
             let now = state.env.now();
             let result = state.data.chat.send_message(
                 user_id,
+                false,
                 args.thread_root_message_index,
                 args.message_id,
                 args.content,
@@ -127,7 +129,7 @@ fn validate_caller(state: &RuntimeState) -> Result<Caller, Response> {
 }
 
 #[allow(clippy::too_many_arguments)]
-fn process_send_message_result(
+pub(crate) fn process_send_message_result(
     result: SendMessageResult,
     sender: UserId,
     sender_username: String,
@@ -181,6 +183,7 @@ fn process_send_message_result(
         SendMessageResult::UserSuspended => UserSuspended,
         SendMessageResult::RulesNotAccepted => RulesNotAccepted,
         SendMessageResult::InvalidRequest(error) => InvalidRequest(error),
+        SendMessageResult::RateLimited(retry_at) => RateLimited(retry_at),
     }
 }
 
@@ -209,6 +212,7 @@ fn register_timer_jobs(
                     TimerJob::EndPoll(EndPollJob {
                         thread_root_message_index,
                         message_index: message_event.event.message_index,
+                        attempt: 0,
                     }),
                     end_date,
                     now,
@@ -216,14 +220,20 @@ fn register_timer_jobs(
             }
         }
         MessageContent::Prize(p) => {
-            data.timer_jobs.enqueue_job(
+            let message_index = message_event.event.message_index;
+            // Prize draws refund any unclaimed prizes once the winners have been paid
+            let job = if p.draw {
+                TimerJob::DrawPrize(DrawPrizeJob {
+                    thread_root_message_index,
+                    message_index,
+                })
+            } else {
                 TimerJob::RefundPrize(RefundPrizeJob {
                     thread_root_message_index,
-                    message_index: message_event.event.message_index,
-                }),
-                p.end_date,
-                now,
-            );
+                    message_index,
+                })
+            };
+            data.timer_jobs.enqueue_job(job, p.end_date, now);
         }
         MessageContent::P2PSwap(c) => {
             data.timer_jobs.enqueue_job(
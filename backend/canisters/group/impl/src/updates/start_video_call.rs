@@ -35,6 +35,7 @@ fn start_video_call_impl(args: Args, state: &mut RuntimeState) -> Response {
 
     let result = match state.data.chat.send_message(
         sender,
+        false,
         None,
         args.message_id,
         MessageContentInternal::VideoCall(VideoCallContentInternal {
@@ -0,0 +1,48 @@
+use crate::activity_notifications::handle_activity_notification;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use group_canister::update_custom_role::{Response::*, *};
+use group_chat_core::CustomRoleResult;
+use ic_cdk::update;
+
+#[update]
+#[trace]
+fn update_custom_role(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| update_custom_role_impl(args, state))
+}
+
+fn update_custom_role_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.is_frozen() {
+        return ChatFrozen;
+    }
+
+    let caller = state.env.caller();
+    let Some(user_id) = state.data.get_member(caller).map(|m| m.user_id) else {
+        return CallerNotInGroup;
+    };
+
+    let now = state.env.now();
+    match state.data.chat.update_custom_role(
+        user_id,
+        args.role_id,
+        args.name,
+        args.permissions,
+        args.message_permissions,
+        now,
+    ) {
+        CustomRoleResult::Success(_) => {
+            handle_activity_notification(state);
+            Success
+        }
+        CustomRoleResult::UserNotInGroup => CallerNotInGroup,
+        CustomRoleResult::UserSuspended => UserSuspended,
+        CustomRoleResult::NotAuthorized | CustomRoleResult::TooManyRoles(_) => NotAuthorized,
+        CustomRoleResult::RoleNotFound => RoleNotFound,
+        CustomRoleResult::NameTooShort(result) => NameTooShort(result),
+        CustomRoleResult::NameTooLong(result) => NameTooLong(result),
+        CustomRoleResult::NameTaken => NameTaken,
+        CustomRoleResult::PermissionNotGrantable => PermissionNotGrantable,
+    }
+}
@@ -95,6 +95,18 @@ fn prepare(args: &Args, state: &RuntimeState) -> Result<PrepareResult, Response>
     let caller = state.env.caller();
     let gate = args.gate.as_ref().apply_to(state.data.chat.gate.value.as_ref());
 
+    if let OptionUpdate::SetToSome(gate_reverification) = &args.gate_reverification {
+        if !gate_reverification.validate() || !gate.map_or(false, |g| g.can_be_reverified()) {
+            return Err(AccessGateInvalid);
+        }
+    }
+
+    if let OptionUpdate::SetToSome(slow_mode) = &args.slow_mode {
+        if !slow_mode.validate() {
+            return Err(SlowModeInvalid);
+        }
+    }
+
     if let Some(member) = state.data.get_member(caller) {
         let permissions = args.permissions_v2.as_ref();
 
@@ -141,6 +153,12 @@ fn prepare(args: &Args, state: &RuntimeState) -> Result<PrepareResult, Response>
 }
 
 fn commit(my_user_id: UserId, args: Args, state: &mut RuntimeState) -> SuccessResult {
+    let now = state.env.now();
+    let gate_updated = args.gate.has_update();
+    let gate_reverification_updated = state.data.chat.set_gate_reverification(args.gate_reverification, now);
+    state.data.chat.set_slow_mode(args.slow_mode, now);
+    state.data.chat.set_read_receipts_enabled(args.read_receipts_enabled, now);
+
     let result = state.data.chat.do_update(
         my_user_id,
         args.name,
@@ -151,9 +169,13 @@ fn commit(my_user_id: UserId, args: Args, state: &mut RuntimeState) -> SuccessRe
         args.gate,
         args.public,
         args.events_ttl,
-        state.env.now(),
+        now,
     );
 
+    if gate_updated || gate_reverification_updated {
+        state.data.schedule_gate_reverification(now);
+    }
+
     handle_activity_notification(state);
     SuccessResult {
         rules_version: result.rules_version,
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use types::{ChatId, CommunityId, UserId};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub group_or_community: GroupOrCommunity,
+    pub user_id: UserId,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum GroupOrCommunity {
+    Group(ChatId),
+    Community(CommunityId),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(bool),
+    NotFound,
+    InternalError(String),
+}
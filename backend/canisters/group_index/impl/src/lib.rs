@@ -380,6 +380,20 @@ pub struct AccessGateMetrics {
     pub token_balance: u32,
     #[serde(default)]
     pub composite: u32,
+    #[serde(default)]
+    pub account_age: u32,
+    #[serde(default)]
+    pub chit: u32,
+    #[serde(default)]
+    pub referred_by: u32,
+    #[serde(default)]
+    pub group_member: u32,
+    #[serde(default)]
+    pub community_member: u32,
+    #[serde(default)]
+    pub nft: u32,
+    #[serde(default)]
+    pub not: u32,
 }
 
 impl AccessGateMetrics {
@@ -393,6 +407,13 @@ impl AccessGateMetrics {
             AccessGate::Payment(_) => self.payment += 1,
             AccessGate::TokenBalance(_) => self.token_balance += 1,
             AccessGate::Composite(_) => self.composite += 1,
+            AccessGate::AccountAge(_) => self.account_age += 1,
+            AccessGate::Chit(_) => self.chit += 1,
+            AccessGate::ReferredBy(_) => self.referred_by += 1,
+            AccessGate::GroupMember(_) => self.group_member += 1,
+            AccessGate::CommunityMember(_) => self.community_member += 1,
+            AccessGate::Nft(_) => self.nft += 1,
+            AccessGate::Not(_) => self.not += 1,
         }
     }
 }
@@ -3,6 +3,7 @@ use crate::model::private_communities::PrivateCommunityInfo;
 use crate::MARK_ACTIVE_DURATION;
 use search::{Document, Query};
 use serde::{Deserialize, Serialize};
+use std::cmp;
 use std::collections::HashMap;
 use types::{AccessGate, CommunityId, CommunityMatch, FrozenCommunityInfo, PublicCommunityActivity, TimestampMillis};
 
@@ -68,16 +69,20 @@ impl PublicCommunities {
             .filter(|c| !c.is_frozen())
             .filter(|c| include_moderation_flags.contains(*c.moderation_flags()))
             .filter(|c| languages.is_empty() || languages.contains(&c.primary_language))
-            .map(|c| {
+            .filter_map(|c| {
                 let score = if let Some(query) = &query {
                     let document: Document = c.into();
-                    document.calculate_score(query)
+                    if !document.is_match(query) {
+                        return None;
+                    }
+                    // A query made up of only exclusions matches without scoring
+                    cmp::max(1, document.calculate_score(query))
                 } else if c.hotness_score > 0 {
                     c.hotness_score
                 } else {
                     c.activity.member_count
                 };
-                (score, c)
+                Some((score, c))
             })
             .filter(|(score, _)| *score > 0)
             .collect();
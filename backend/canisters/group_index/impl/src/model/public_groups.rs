@@ -53,16 +53,20 @@ impl PublicGroups {
         let mut matches: Vec<_> = self
             .iter()
             .filter(|c| !c.is_frozen())
-            .map(|c| {
+            .filter_map(|c| {
                 let score = if let Some(query) = &query {
                     let document: Document = c.into();
-                    document.calculate_score(query)
+                    if !document.is_match(query) {
+                        return None;
+                    }
+                    // A query made up of only exclusions matches without scoring
+                    cmp::max(1, document.calculate_score(query))
                 } else if c.hotness_score > 0 {
                     c.hotness_score
                 } else {
                     cmp::max(1, c.activity.member_count)
                 };
-                (score, c)
+                Some((score, c))
             })
             .filter(|(score, _)| *score > 0)
             .collect();
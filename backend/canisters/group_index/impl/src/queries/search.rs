@@ -106,6 +106,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn exclusions_and_phrases_respected() {
+        let state = setup_runtime_state();
+
+        let response = search_impl(
+            Args {
+                max_results: 10,
+                search_term: "sausages -beans".to_string(),
+            },
+            &state,
+        );
+
+        if let Response::Success(result) = response {
+            assert_eq!(1, result.matches.len());
+            assert_eq!(result.matches[0].id, Principal::from_slice(&[7]).into());
+        } else {
+            panic!();
+        }
+
+        let response = search_impl(
+            Args {
+                max_results: 10,
+                search_term: "\"hash browns\"".to_string(),
+            },
+            &state,
+        );
+
+        if let Response::Success(result) = response {
+            assert_eq!(1, result.matches.len());
+            assert_eq!(result.matches[0].id, Principal::from_slice(&[4]).into());
+        } else {
+            panic!();
+        }
+    }
+
     fn setup_runtime_state() -> RuntimeState {
         let env = TestEnv::default();
         let mut data = Data::default();
@@ -0,0 +1,48 @@
+use crate::guards::caller_is_group_or_community_canister;
+use crate::{read_state, RuntimeState};
+use canister_api_macros::update_msgpack;
+use canister_tracing_macros::trace;
+use group_index_canister::c2c_is_member::{Response::*, *};
+
+// Groups and communities only reveal their members to the group_index, so membership gates are checked via here
+#[update_msgpack(guard = "caller_is_group_or_community_canister")]
+#[trace]
+async fn c2c_is_member(args: Args) -> Response {
+    if !read_state(|state| exists(args.group_or_community, state)) {
+        return NotFound;
+    }
+
+    let result = match args.group_or_community {
+        GroupOrCommunity::Group(chat_id) => {
+            group_canister_c2c_client::c2c_is_member(
+                chat_id.into(),
+                &group_canister::c2c_is_member::Args { user_id: args.user_id },
+            )
+            .await
+        }
+        GroupOrCommunity::Community(community_id) => {
+            community_canister_c2c_client::c2c_is_member(
+                community_id.into(),
+                &community_canister::c2c_is_member::Args { user_id: args.user_id },
+            )
+            .await
+        }
+    };
+
+    match result {
+        Ok(is_member) => Success(is_member),
+        Err(error) => InternalError(format!("{error:?}")),
+    }
+}
+
+fn exists(group_or_community: GroupOrCommunity, state: &RuntimeState) -> bool {
+    match group_or_community {
+        GroupOrCommunity::Group(chat_id) => {
+            state.data.public_groups.get(&chat_id).is_some() || state.data.private_groups.get(&chat_id).is_some()
+        }
+        GroupOrCommunity::Community(community_id) => {
+            state.data.public_communities.get(&community_id).is_some()
+                || state.data.private_communities.get(&community_id).is_some()
+        }
+    }
+}
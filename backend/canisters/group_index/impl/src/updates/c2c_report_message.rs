@@ -14,6 +14,7 @@ async fn c2c_report_message(args: Args) -> Response {
         reporter: args.reporter,
         thread_root_message_index: args.thread_root_message_index,
         message: args.message,
+        edit_history: args.edit_history,
         already_deleted: args.already_deleted,
         is_public: args.is_public,
     };
@@ -5,6 +5,7 @@ pub mod c2c_create_community;
 pub mod c2c_create_group;
 pub mod c2c_delete_community;
 pub mod c2c_delete_group;
+pub mod c2c_is_member;
 pub mod c2c_make_community_private;
 pub mod c2c_make_private;
 pub mod c2c_mark_active;
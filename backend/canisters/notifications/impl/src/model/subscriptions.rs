@@ -19,9 +19,11 @@ impl Subscriptions {
         match self.subscriptions.entry(user_id) {
             Occupied(e) => {
                 let subscriptions = e.into_mut();
-                if !subscriptions.iter().any(|s| *s == subscription) {
-                    subscriptions.push(subscription);
+                if let Some(existing) = subscriptions.iter_mut().find(|s| s.key() == subscription.key()) {
+                    *existing = subscription;
+                    return;
                 }
+                subscriptions.push(subscription);
             }
             Vacant(e) => {
                 e.insert(vec![subscription]);
@@ -41,15 +43,10 @@ impl Subscriptions {
         }
     }
 
-    pub fn remove(&mut self, user_id: UserId, p256dh_key: &str) -> bool {
+    pub fn remove(&mut self, user_id: UserId, key: &str) -> bool {
         if let Occupied(mut e) = self.subscriptions.entry(user_id) {
             let subs = e.get_mut();
-            if let Some(index) = subs
-                .iter()
-                .enumerate()
-                .find(|(_, s)| s.keys.p256dh.as_str() == p256dh_key)
-                .map(|(i, _)| i)
-            {
+            if let Some(index) = subs.iter().enumerate().find(|(_, s)| s.key() == key).map(|(i, _)| i) {
                 subs.remove(index);
                 if subs.is_empty() {
                     e.remove();
@@ -77,6 +77,8 @@ mod tests {
                     p256dh: "1234657890".to_string(),
                     auth: "0987654321".to_string(),
                 },
+                push_service: None,
+                digest_mode: None,
             },
         );
 
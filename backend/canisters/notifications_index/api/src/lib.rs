@@ -26,5 +26,6 @@ pub struct SubscriptionAdded {
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct SubscriptionRemoved {
     pub user_id: UserId,
+    // The p256dh key of Web Push subscriptions or the registration token of native push subscriptions
     pub p256dh_key: String,
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Args {
+    // The p256dh key of Web Push subscriptions or the registration token of native push subscriptions
     pub p256dh_key: String,
 }
 
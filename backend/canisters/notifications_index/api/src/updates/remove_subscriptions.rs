@@ -10,6 +10,7 @@ pub struct Args {
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct UserSubscriptions {
     pub user_id: UserId,
+    // The p256dh keys of Web Push subscriptions or the registration tokens of native push subscriptions
     pub p256dh_keys: Vec<String>,
 }
 
@@ -17,11 +17,13 @@ impl Subscriptions {
         match self.subscriptions.entry(user_id) {
             Occupied(e) => {
                 let subscriptions = e.into_mut();
-                if subscriptions.iter().any(|s| *s == subscription) {
+                if let Some(existing) = subscriptions.iter_mut().find(|s| s.key() == subscription.key()) {
+                    // Same subscription with updated settings (eg. digest mode toggled), so overwrite it
+                    *existing = subscription;
                     return removed;
                 }
                 if subscriptions.len() >= 10 {
-                    removed.extend(subscriptions.drain(..subscriptions.len() - 9).map(|s| s.keys.p256dh));
+                    removed.extend(subscriptions.drain(..subscriptions.len() - 9).map(|s| s.key().to_string()));
                 };
                 subscriptions.push(subscription);
             }
@@ -40,15 +42,11 @@ impl Subscriptions {
         }
     }
 
-    pub fn remove(&mut self, user_id: UserId, p256dh_key: &str) -> bool {
+    // `key` is the p256dh key of Web Push subscriptions or the registration token of native push subscriptions
+    pub fn remove(&mut self, user_id: UserId, key: &str) -> bool {
         if let Occupied(mut e) = self.subscriptions.entry(user_id) {
             let subs = e.get_mut();
-            if let Some(index) = subs
-                .iter()
-                .enumerate()
-                .find(|(_, s)| s.keys.p256dh.as_str() == p256dh_key)
-                .map(|(i, _)| i)
-            {
+            if let Some(index) = subs.iter().enumerate().find(|(_, s)| s.key() == key).map(|(i, _)| i) {
                 subs.remove(index);
                 if subs.is_empty() {
                     e.remove();
@@ -60,9 +58,9 @@ impl Subscriptions {
         false
     }
 
-    pub fn exists(&self, user_id: &UserId, p256dh_key: String) -> bool {
+    pub fn exists(&self, user_id: &UserId, key: String) -> bool {
         match self.subscriptions.get(user_id) {
-            Some(subscriptions) => subscriptions.iter().any(|s| s.keys.p256dh == p256dh_key),
+            Some(subscriptions) => subscriptions.iter().any(|s| s.key() == key),
             None => false,
         }
     }
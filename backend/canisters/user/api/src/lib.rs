@@ -1,3 +1,4 @@
+use crate::swap_tokens::ExchangeArgs;
 use candid::CandidType;
 use chat_events::MessageContentInternal;
 use serde::{Deserialize, Serialize};
@@ -5,7 +6,7 @@ use std::collections::HashMap;
 use types::{
     CanisterId, ChannelId, ChannelLatestMessageIndex, Chat, ChatId, ChitEarned, CommunityId, Cryptocurrency,
     DiamondMembershipPlanDuration, EventIndex, MessageContent, MessageContentInitial, MessageId, MessageIndex, Milliseconds,
-    P2PSwapStatus, PhoneNumber, Reaction, SuspensionDuration, TimestampMillis, User, UserId,
+    P2PSwapStatus, PhoneNumber, Reaction, SuspensionDuration, TimestampMillis, TokenInfo, User, UserId,
 };
 
 mod lifecycle;
@@ -297,3 +298,68 @@ pub struct NamedAccount {
     pub name: String,
     pub account: String,
 }
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduledMessage {
+    pub id: u64,
+    pub chat: Chat,
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub message_id: MessageId,
+    pub content: MessageContentInitial,
+    pub mentioned: Vec<User>,
+    pub block_level_markdown: bool,
+    pub created: TimestampMillis,
+    pub send_at: TimestampMillis,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SwapOrder {
+    pub id: u64,
+    pub input_token: TokenInfo,
+    pub output_token: TokenInfo,
+    pub exchange_args: ExchangeArgs,
+    pub order_type: SwapOrderType,
+    pub created: TimestampMillis,
+    pub status: SwapOrderStatus,
+    pub next_check_at: Option<TimestampMillis>,
+    // The ids of the swaps made by this order, whose progress can be seen via `token_swap_status`
+    pub swaps: Vec<u128>,
+    pub swap_in_progress: Option<u128>,
+    pub total_input_amount: u128,
+    pub total_output_amount: u128,
+    pub last_error: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum SwapOrderType {
+    // Swaps `input_amount` once an exchange quotes at least `min_output_amount` for it. If a swap split across
+    // exchanges is only partially filled, the remainder is swapped later at no worse a rate.
+    Limit(LimitOrder),
+    // Swaps `input_amount` every `interval` until `max_swaps` swaps have been made or the order is cancelled
+    Recurring(RecurringSwap),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct LimitOrder {
+    pub input_amount: u128,
+    pub min_output_amount: u128,
+    pub expires_at: Option<TimestampMillis>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct RecurringSwap {
+    pub input_amount: u128,
+    pub interval: Milliseconds,
+    // Each swap's minimum output is its quote reduced by this many basis points
+    pub max_slippage_bps: u32,
+    pub max_swaps: Option<u32>,
+    pub start_at: Option<TimestampMillis>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SwapOrderStatus {
+    Active,
+    Completed,
+    Expired,
+    Cancelled,
+}
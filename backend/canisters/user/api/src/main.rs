@@ -13,10 +13,13 @@ fn main() {
     generate_candid_method!(user, hot_group_exclusions, query);
     generate_candid_method!(user, initial_state, query);
     generate_candid_method!(user, local_user_index, query);
+    generate_candid_method!(user, message_edit_history, query);
     generate_candid_method!(user, messages_by_message_index, query);
     generate_candid_method!(user, public_profile, query);
     generate_candid_method!(user, search_messages, query);
     generate_candid_method!(user, saved_crypto_accounts, query);
+    generate_candid_method!(user, scheduled_messages, query);
+    generate_candid_method!(user, swap_order_status, query);
     generate_candid_method!(user, token_swap_status, query);
     generate_candid_method!(user, updates, query);
 
@@ -28,9 +31,12 @@ fn main() {
     generate_candid_method!(user, block_user, update);
     generate_candid_method!(user, cancel_message_reminder, update);
     generate_candid_method!(user, cancel_p2p_swap, update);
+    generate_candid_method!(user, cancel_scheduled_message, update);
+    generate_candid_method!(user, cancel_swap_order, update);
     generate_candid_method!(user, claim_daily_chit, update);
     generate_candid_method!(user, create_community, update);
     generate_candid_method!(user, create_group, update);
+    generate_candid_method!(user, create_swap_order, update);
     generate_candid_method!(user, delete_community, update);
     generate_candid_method!(user, delete_direct_chat, update);
     generate_candid_method!(user, delete_group, update);
@@ -46,10 +52,12 @@ fn main() {
     generate_candid_method!(user, mark_read, update);
     generate_candid_method!(user, mute_notifications, update);
     generate_candid_method!(user, pin_chat_v2, update);
+    generate_candid_method!(user, quote_swap, update);
     generate_candid_method!(user, remove_reaction, update);
     generate_candid_method!(user, report_message, update);
     generate_candid_method!(user, retrieve_btc, update);
     generate_candid_method!(user, save_crypto_account, update);
+    generate_candid_method!(user, schedule_message, update);
     generate_candid_method!(user, send_message_with_transfer_to_channel, update);
     generate_candid_method!(user, send_message_with_transfer_to_group, update);
     generate_candid_method!(user, send_message_v2, update);
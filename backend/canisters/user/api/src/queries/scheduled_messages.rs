@@ -0,0 +1,11 @@
+use crate::ScheduledMessage;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::Empty;
+
+pub type Args = Empty;
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(Vec<ScheduledMessage>),
+}
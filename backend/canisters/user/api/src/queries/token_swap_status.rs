@@ -1,3 +1,4 @@
+use crate::swap_tokens::SwapRouteLeg;
 pub use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use types::TimestampMillis;
@@ -21,7 +22,10 @@ pub struct TokenSwapStatus {
     pub notify_dex: SwapSubtask<()>,
     pub amount_swapped: SwapSubtask<Result<u128, String>>,
     pub withdraw_from_dex: SwapSubtask<u128>,
+    pub unspent_input_withdrawn: u128,
     pub success: Option<bool>,
+    pub route: Option<Vec<SwapRouteLeg>>,
+    pub legs: Vec<TokenSwapStatus>,
 }
 
 type SwapSubtask<T = ()> = Option<Result<T, String>>;
@@ -0,0 +1,25 @@
+use crate::swap_tokens::ExchangeArgs;
+use crate::SwapOrderType;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{Milliseconds, TokenInfo};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub input_token: TokenInfo,
+    pub output_token: TokenInfo,
+    pub exchange_args: ExchangeArgs,
+    pub order_type: SwapOrderType,
+    pub pin: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(u64),
+    InvalidRequest(String),
+    TooManySwapOrders(u32),
+    UserSuspended,
+    PinRequired,
+    PinIncorrect(Milliseconds),
+    TooManyFailedPinAttempts(Milliseconds),
+}
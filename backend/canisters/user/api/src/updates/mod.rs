@@ -21,9 +21,12 @@ pub mod c2c_set_user_suspended;
 pub mod c2c_vote_on_proposal;
 pub mod cancel_message_reminder;
 pub mod cancel_p2p_swap;
+pub mod cancel_scheduled_message;
+pub mod cancel_swap_order;
 pub mod claim_daily_chit;
 pub mod create_community;
 pub mod create_group;
+pub mod create_swap_order;
 pub mod delete_community;
 pub mod delete_direct_chat;
 pub mod delete_group;
@@ -39,10 +42,12 @@ pub mod mark_achievements_seen;
 pub mod mark_read;
 pub mod mute_notifications;
 pub mod pin_chat_v2;
+pub mod quote_swap;
 pub mod remove_reaction;
 pub mod report_message;
 pub mod retrieve_btc;
 pub mod save_crypto_account;
+pub mod schedule_message;
 pub mod send_message_v2;
 pub mod send_message_with_transfer_to_channel;
 pub mod send_message_with_transfer_to_group;
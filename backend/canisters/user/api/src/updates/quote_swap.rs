@@ -0,0 +1,34 @@
+use crate::swap_tokens::{ExchangeArgs, SwapRouteLeg};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{CanisterId, ExchangeId, TokenInfo};
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Args {
+    pub input_token: TokenInfo,
+    pub output_token: TokenInfo,
+    pub input_amount: u128,
+    pub candidates: Vec<ExchangeArgs>,
+    pub allow_split: bool,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(SuccessResult),
+    NoRouteFound,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct SuccessResult {
+    pub quotes: Vec<ExchangeQuote>,
+    pub route: Vec<SwapRouteLeg>,
+    pub amount_out: u128,
+}
+
+// The amount out if the full input amount were swapped on this exchange, after all fees
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ExchangeQuote {
+    pub exchange_id: ExchangeId,
+    pub swap_canister_id: CanisterId,
+    pub amount_out: Result<u128, String>,
+}
@@ -0,0 +1,33 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use types::{Chat, InvalidPollReason, MessageContentInitial, MessageIndex, Milliseconds, TimestampMillis, User};
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub struct Args {
+    pub chat: Chat,
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub content: MessageContentInitial,
+    pub mentioned: Vec<User>,
+    pub block_level_markdown: bool,
+    pub send_at: TimestampMillis,
+    pub pin: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Debug)]
+pub enum Response {
+    Success(u64),
+    SendAtInThePast,
+    TooManyScheduledMessages(u32),
+    ChatNotFound,
+    MessageEmpty,
+    TextTooLong(u32),
+    InvalidPoll(InvalidPollReason),
+    InvalidRequest(String),
+    TransferCannotBeZero,
+    TransferCannotBeToSelf,
+    RecipientBlocked,
+    UserSuspended,
+    PinRequired,
+    PinIncorrect(Milliseconds),
+    TooManyFailedPinAttempts(Milliseconds),
+}
@@ -31,6 +31,8 @@ pub enum Response {
     UserNotInCommunity(Option<CompletedCryptoTransaction>),
     UserNotInChannel(CompletedCryptoTransaction),
     ChannelNotFound(CompletedCryptoTransaction),
+    NotAuthorized(CompletedCryptoTransaction),
+    RateLimited(TimestampMillis, CompletedCryptoTransaction),
     CryptocurrencyNotSupported(Cryptocurrency),
     InvalidRequest(String),
     TransferFailed(String),
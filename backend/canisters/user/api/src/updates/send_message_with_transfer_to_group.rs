@@ -28,6 +28,8 @@ pub enum Response {
     TextTooLong(u32),
     RecipientBlocked,
     CallerNotInGroup(Option<CompletedCryptoTransaction>),
+    NotAuthorized(CompletedCryptoTransaction),
+    RateLimited(TimestampMillis, CompletedCryptoTransaction),
     CryptocurrencyNotSupported(Cryptocurrency),
     InvalidRequest(String),
     TransferFailed(String),
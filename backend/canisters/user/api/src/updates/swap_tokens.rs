@@ -17,13 +17,25 @@ pub struct Args {
 pub enum ExchangeArgs {
     ICPSwap(ICPSwapArgs),
     Sonic(SonicArgs),
+    ICDex(ICDexArgs),
+    BestRoute(BestRouteArgs),
 }
 
 impl ExchangeArgs {
-    pub fn exchange_id(&self) -> ExchangeId {
+    // Returns None for `BestRoute` since the exchange(s) are only chosen once the swap starts
+    pub fn exchange_id(&self) -> Option<ExchangeId> {
         match self {
-            ExchangeArgs::ICPSwap(_) => ExchangeId::ICPSwap,
-            ExchangeArgs::Sonic(_) => ExchangeId::Sonic,
+            ExchangeArgs::ICPSwap(_) => Some(ExchangeId::ICPSwap),
+            ExchangeArgs::Sonic(_) => Some(ExchangeId::Sonic),
+            ExchangeArgs::ICDex(_) => Some(ExchangeId::ICDex),
+            ExchangeArgs::BestRoute(_) => None,
+        }
+    }
+
+    pub fn swap_canister_id(&self) -> Option<CanisterId> {
+        match self {
+            ExchangeArgs::ICPSwap(a) | ExchangeArgs::Sonic(a) | ExchangeArgs::ICDex(a) => Some(a.swap_canister_id),
+            ExchangeArgs::BestRoute(_) => None,
         }
     }
 }
@@ -36,10 +48,30 @@ pub struct ICPSwapArgs {
 
 pub type SonicArgs = ICPSwapArgs;
 
+// For ICDex, token0 is the pair's base token and token1 is its quote token
+pub type ICDexArgs = ICPSwapArgs;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BestRouteArgs {
+    // Each candidate must be a single exchange (ie. not `BestRoute`)
+    pub candidates: Vec<ExchangeArgs>,
+    // If true, the input may be split across multiple exchanges
+    pub allow_split: bool,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SwapRouteLeg {
+    pub exchange_id: ExchangeId,
+    pub swap_canister_id: CanisterId,
+    pub input_amount: u128,
+    pub expected_amount_out: u128,
+}
+
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub enum Response {
     Success(SuccessResult),
     SwapFailed,
+    NoRouteFound,
     PinRequired,
     PinIncorrect(Milliseconds),
     TooManyFailedPinAttempts(Milliseconds),
@@ -49,4 +81,6 @@ pub enum Response {
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct SuccessResult {
     pub amount_out: u128,
+    // Any of the input which the exchange didn't use, which has been returned after the ledger fee
+    pub unspent_input_withdrawn: u128,
 }
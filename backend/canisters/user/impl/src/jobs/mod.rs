@@ -1,7 +1,13 @@
 use crate::RuntimeState;
 
+pub(crate) mod migrate_events_to_stable_memory;
+pub(crate) mod populate_search_index;
 pub(crate) mod push_user_canister_events;
+pub(crate) mod remove_events_of_deleted_chats;
 
 pub(crate) fn start(state: &RuntimeState) {
+    migrate_events_to_stable_memory::start_job_if_required(state);
+    populate_search_index::start_job_if_required(state);
     push_user_canister_events::start_job_if_required(state);
+    remove_events_of_deleted_chats::start_job_if_required(state);
 }
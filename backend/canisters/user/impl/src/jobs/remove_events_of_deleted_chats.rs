@@ -0,0 +1,43 @@
+use crate::{mutate_state, RuntimeState};
+use ic_cdk_timers::TimerId;
+use std::cell::Cell;
+use std::time::Duration;
+use tracing::{info, trace};
+
+const BATCH_SIZE: usize = 100;
+const MAX_INSTRUCTIONS_PER_EXECUTION: u64 = 5_000_000_000;
+
+thread_local! {
+    static TIMER_ID: Cell<Option<TimerId>> = Cell::default();
+}
+
+pub(crate) fn start_job_if_required(state: &RuntimeState) -> bool {
+    if TIMER_ID.get().is_none() && state.data.direct_chats.has_events_pending_removal() {
+        let timer_id = ic_cdk_timers::set_timer_interval(Duration::ZERO, run);
+        TIMER_ID.set(Some(timer_id));
+        trace!("'remove_events_of_deleted_chats' job started");
+        true
+    } else {
+        false
+    }
+}
+
+fn run() {
+    if mutate_state(remove_events) {
+        if let Some(timer_id) = TIMER_ID.take() {
+            ic_cdk_timers::clear_timer(timer_id);
+            info!("'remove_events_of_deleted_chats' job completed");
+        }
+    }
+}
+
+// Keeps removing batches of the events of deleted direct chats from stable memory until either every event has been
+// removed (in which case it returns true) or the instruction limit for this execution is approached
+fn remove_events(state: &mut RuntimeState) -> bool {
+    while !state.data.direct_chats.remove_events_pending_removal(BATCH_SIZE) {
+        if ic_cdk::api::instruction_counter() >= MAX_INSTRUCTIONS_PER_EXECUTION {
+            return false;
+        }
+    }
+    true
+}
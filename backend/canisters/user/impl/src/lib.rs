@@ -6,6 +6,8 @@ use crate::model::group_chats::GroupChats;
 use crate::model::hot_group_exclusions::HotGroupExclusions;
 use crate::model::p2p_swaps::P2PSwaps;
 use crate::model::pin_number::PinNumber;
+use crate::model::scheduled_messages::ScheduledMessages;
+use crate::model::swap_orders::SwapOrders;
 use crate::model::token_swaps::TokenSwaps;
 use crate::timer_job_types::{RemoveExpiredEventsJob, TimerJob};
 use candid::Principal;
@@ -215,6 +217,10 @@ struct Data {
     pub next_event_expiry: Option<TimestampMillis>,
     pub token_swaps: TokenSwaps,
     pub p2p_swaps: P2PSwaps,
+    #[serde(default)]
+    pub scheduled_messages: ScheduledMessages,
+    #[serde(default)]
+    pub swap_orders: SwapOrders,
     pub user_canister_events_queue: CanisterEventSyncQueue<UserCanisterEvent>,
     pub video_call_operators: Vec<Principal>,
     pub event_store_client: EventStoreClient<CdkRuntime>,
@@ -275,6 +281,8 @@ impl Data {
             next_event_expiry: None,
             token_swaps: TokenSwaps::default(),
             p2p_swaps: P2PSwaps::default(),
+            scheduled_messages: ScheduledMessages::default(),
+            swap_orders: SwapOrders::default(),
             user_canister_events_queue: CanisterEventSyncQueue::default(),
             video_call_operators,
             event_store_client: EventStoreClientBuilder::new(local_user_index_canister_id, CdkRuntime::default())
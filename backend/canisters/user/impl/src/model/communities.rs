@@ -23,6 +23,10 @@ impl Communities {
         self.communities.contains_key(community_id)
     }
 
+    pub fn get(&self, community_id: &CommunityId) -> Option<&Community> {
+        self.communities.get(community_id)
+    }
+
     pub fn get_mut(&mut self, community_id: &CommunityId) -> Option<&mut Community> {
         self.communities.get_mut(community_id)
     }
@@ -1,4 +1,4 @@
-use crate::model::group_chat::{GroupChat, GroupMessagesRead};
+use crate::model::group_chat::{GroupChat, GroupMessagesRead, ReadUpToAdvanced};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use types::{CanisterId, ChannelId, CommunityId, TimestampMillis, Timestamped};
@@ -52,20 +52,37 @@ impl Community {
         .unwrap()
     }
 
-    pub fn mark_read(&mut self, channels_read: Vec<user_canister::mark_read::ChannelMessagesRead>, now: TimestampMillis) {
+    pub fn mark_read(
+        &mut self,
+        channels_read: Vec<user_canister::mark_read::ChannelMessagesRead>,
+        now: TimestampMillis,
+    ) -> Vec<(ChannelId, ReadUpToAdvanced)> {
+        let mut advanced = Vec::new();
         for channel_messages_read in channels_read {
-            self.channels
-                .entry(channel_messages_read.channel_id)
-                .or_insert(Channel::new(channel_messages_read.channel_id))
+            let channel_id = channel_messages_read.channel_id;
+            if let Some(read_up_to) = self
+                .channels
+                .entry(channel_id)
+                .or_insert(Channel::new(channel_id))
                 .messages_read
                 .mark_read(
                     channel_messages_read.read_up_to,
                     channel_messages_read.threads,
                     channel_messages_read.date_read_pinned,
                     now,
-                );
+                )
+            {
+                advanced.push((channel_id, read_up_to));
+            }
         }
         self.last_read = now;
+        advanced
+    }
+
+    pub fn set_read_receipts_enabled(&mut self, channel_id: ChannelId, enabled: bool, now: TimestampMillis) {
+        if let Some(channel) = self.channels.get_mut(&channel_id) {
+            channel.messages_read.set_read_receipts_enabled(enabled, now);
+        }
     }
 
     pub fn import_group(&mut self, channel_id: ChannelId, group: GroupChat, now: TimestampMillis) {
@@ -1,9 +1,9 @@
 use crate::model::direct_chat::DirectChat;
-use chat_events::{ChatInternal, ChatMetricsInternal};
+use chat_events::{ChatInternal, ChatMetricsInternal, StableEventsPendingRemoval};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry::Vacant;
 use std::collections::{BTreeSet, HashMap};
-use types::{ChatId, TimestampMillis, Timestamped, UserId};
+use types::{Chat, ChatId, TimestampMillis, Timestamped, UserId};
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct DirectChats {
@@ -11,6 +11,8 @@ pub struct DirectChats {
     pinned: Timestamped<Vec<ChatId>>,
     metrics: ChatMetricsInternal,
     chats_removed: BTreeSet<(TimestampMillis, ChatId)>,
+    #[serde(default)]
+    events_pending_removal: StableEventsPendingRemoval,
 }
 
 impl DirectChats {
@@ -29,7 +31,10 @@ impl DirectChats {
         anonymized_id: u128,
         now: TimestampMillis,
     ) -> &mut DirectChat {
-        if let Vacant(e) = self.direct_chats.entry(their_user_id.into()) {
+        let chat_id = their_user_id.into();
+        self.events_pending_removal.remove_chat(Chat::Direct(chat_id));
+
+        if let Vacant(e) = self.direct_chats.entry(chat_id) {
             e.insert(DirectChat::new(their_user_id, is_bot, None, anonymized_id, now))
         } else {
             unreachable!()
@@ -95,6 +100,15 @@ impl DirectChats {
         &self.metrics
     }
 
+    pub fn has_events_pending_removal(&self) -> bool {
+        !self.events_pending_removal.is_empty()
+    }
+
+    // Removes a batch of the events of deleted chats from stable memory, returning true once none remain
+    pub fn remove_events_pending_removal(&mut self, max_events: usize) -> bool {
+        self.events_pending_removal.remove_batch(max_events)
+    }
+
     pub fn exists(&self, chat_id: &ChatId) -> bool {
         self.direct_chats.contains_key(chat_id)
     }
@@ -114,7 +128,8 @@ impl DirectChats {
     }
 
     pub fn remove(&mut self, chat_id: ChatId, now: TimestampMillis) -> Option<DirectChat> {
-        if let Some(chat) = self.direct_chats.remove(&chat_id) {
+        if let Some(mut chat) = self.direct_chats.remove(&chat_id) {
+            self.events_pending_removal.push(&mut chat.events);
             self.chats_removed.insert((now, chat_id));
             Some(chat)
         } else {
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use types::{CanisterId, ChatId, MessageIndex, TimestampMillis, Timestamped};
+use types::{CanisterId, ChatId, MessageIndex, Milliseconds, TimestampMillis, Timestamped};
 use user_canister::mark_read::ThreadRead;
-use utils::time::HOUR_IN_MS;
+use utils::time::{DAY_IN_MS, HOUR_IN_MS};
 use utils::timestamped_map::TimestampedMap;
 
 #[derive(Serialize, Deserialize)]
@@ -56,8 +56,8 @@ impl GroupChat {
         threads: Vec<ThreadRead>,
         date_read_pinned: Option<TimestampMillis>,
         now: TimestampMillis,
-    ) {
-        self.messages_read.mark_read(read_up_to, threads, date_read_pinned, now);
+    ) -> Option<ReadUpToAdvanced> {
+        self.messages_read.mark_read(read_up_to, threads, date_read_pinned, now)
     }
 
     pub fn to_summary(&self) -> user_canister::GroupChatSummary {
@@ -82,11 +82,20 @@ impl GroupChat {
     }
 }
 
+// How long we wait before reporting reads again to a chat which last told us its read receipts are disabled
+const READ_RECEIPTS_DISABLED_RECHECK_INTERVAL: Milliseconds = DAY_IN_MS;
+
 #[derive(Serialize, Deserialize, Default)]
 pub struct GroupMessagesRead {
     pub read_by_me_up_to: Timestamped<Option<MessageIndex>>,
     pub threads_read: TimestampedMap<MessageIndex, MessageIndex>,
     pub date_read_pinned: Timestamped<Option<TimestampMillis>>,
+    // Whether the chat's read receipts were enabled as of the last time it told us, if it ever has
+    #[serde(default)]
+    pub read_receipts_enabled: Option<Timestamped<bool>>,
+    // The highest position reported to the chat's canister. This never decreases, even if `read_by_me_up_to` does.
+    #[serde(default)]
+    pub read_up_to_reported: Option<MessageIndex>,
 }
 
 impl GroupMessagesRead {
@@ -96,7 +105,9 @@ impl GroupMessagesRead {
         threads: Vec<ThreadRead>,
         date_read_pinned: Option<TimestampMillis>,
         now: TimestampMillis,
-    ) {
+    ) -> Option<ReadUpToAdvanced> {
+        let mut advanced = None;
+
         if let Some(message_index) = read_up_to {
             // Update `read_by_me_up_to` if the new value is higher or the old value is > 1 hour old.
             // By allowing `read_by_me_up_to` to decrease we can handle the case where it has
@@ -108,6 +119,16 @@ impl GroupMessagesRead {
             {
                 self.read_by_me_up_to = Timestamped::new(Some(message_index), now);
             }
+
+            // Only positions beyond the highest one already reported are reported, so the chat never sees a
+            // decrease and never counts the same messages twice
+            if self.read_up_to_reported < Some(message_index) && self.should_report_reads(now) {
+                advanced = Some(ReadUpToAdvanced {
+                    previous: self.read_up_to_reported,
+                    read_up_to: message_index,
+                });
+                self.read_up_to_reported = Some(message_index);
+            }
         }
 
         for thread in threads {
@@ -117,6 +138,24 @@ impl GroupMessagesRead {
         if date_read_pinned > self.date_read_pinned.value {
             self.date_read_pinned = Timestamped::new(date_read_pinned, now);
         }
+
+        advanced
+    }
+
+    // Records whether the chat's read receipts are enabled, as reported by the chat's canister. The chat clears its read
+    // receipts when they are disabled, so if they are enabled again we start reporting from scratch.
+    pub fn set_read_receipts_enabled(&mut self, enabled: bool, now: TimestampMillis) {
+        if !enabled {
+            self.read_up_to_reported = None;
+        }
+        self.read_receipts_enabled = Some(Timestamped::new(enabled, now));
+    }
+
+    fn should_report_reads(&self, now: TimestampMillis) -> bool {
+        match &self.read_receipts_enabled {
+            Some(enabled) if !enabled.value => now.saturating_sub(enabled.timestamp) > READ_RECEIPTS_DISABLED_RECHECK_INTERVAL,
+            _ => true,
+        }
     }
 
     pub fn read_by_me_up_to_updates(&self, updates_since: TimestampMillis) -> Option<MessageIndex> {
@@ -134,3 +173,48 @@ impl GroupMessagesRead {
         self.date_read_pinned.if_set_after(updates_since).copied().flatten()
     }
 }
+
+// Returned when `read_by_me_up_to` moves forward so that the chat's canister can be told, allowing it to track which
+// members have read each message (if the chat has read receipts enabled)
+pub struct ReadUpToAdvanced {
+    pub previous: Option<MessageIndex>,
+    pub read_up_to: MessageIndex,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reported_position_never_decreases() {
+        let mut messages_read = GroupMessagesRead::default();
+
+        let advanced = messages_read.mark_read(Some(10.into()), Vec::new(), None, 1).unwrap();
+        assert_eq!((advanced.previous, advanced.read_up_to), (None, 10.into()));
+
+        // After an hour the read position can move backwards, but that is not reported
+        let now = 2 + HOUR_IN_MS;
+        assert!(messages_read.mark_read(Some(5.into()), Vec::new(), None, now).is_none());
+        assert_eq!(messages_read.read_by_me_up_to.value, Some(5.into()));
+
+        // Nor is moving forwards again up to the position previously reported
+        assert!(messages_read.mark_read(Some(8.into()), Vec::new(), None, now).is_none());
+
+        let advanced = messages_read.mark_read(Some(12.into()), Vec::new(), None, now).unwrap();
+        assert_eq!((advanced.previous, advanced.read_up_to), (Some(10.into()), 12.into()));
+    }
+
+    #[test]
+    fn reads_not_reported_while_read_receipts_disabled() {
+        let mut messages_read = GroupMessagesRead::default();
+        messages_read.mark_read(Some(10.into()), Vec::new(), None, 1);
+        messages_read.set_read_receipts_enabled(false, 1);
+
+        assert!(messages_read.mark_read(Some(12.into()), Vec::new(), None, 2).is_none());
+
+        // Once the recheck interval has passed, reads are reported from scratch
+        let now = 2 + READ_RECEIPTS_DISABLED_RECHECK_INTERVAL;
+        let advanced = messages_read.mark_read(Some(14.into()), Vec::new(), None, now).unwrap();
+        assert_eq!((advanced.previous, advanced.read_up_to), (None, 14.into()));
+    }
+}
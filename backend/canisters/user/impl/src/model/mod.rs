@@ -10,6 +10,8 @@ pub mod group_chats;
 pub mod hot_group_exclusions;
 pub mod p2p_swaps;
 pub mod pin_number;
+pub mod scheduled_messages;
 pub mod streak;
+pub mod swap_orders;
 pub mod token_swaps;
 pub mod unread_message_index_map;
@@ -0,0 +1,106 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use user_canister::ScheduledMessage;
+
+pub const MAX_SCHEDULED_MESSAGES: usize = 100;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ScheduledMessages {
+    messages: BTreeMap<u64, ScheduledMessage>,
+    next_id: u64,
+}
+
+impl ScheduledMessages {
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.messages.len() >= MAX_SCHEDULED_MESSAGES
+    }
+
+    pub fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub fn add(&mut self, message: ScheduledMessage) {
+        self.messages.insert(message.id, message);
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<ScheduledMessage> {
+        self.messages.remove(&id)
+    }
+
+    // Returns the messages in the order in which they are due to be sent
+    pub fn iter(&self) -> impl Iterator<Item = &ScheduledMessage> {
+        self.messages.values().sorted_by_key(|m| (m.send_at, m.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use types::{Chat, MessageContentInitial, MessageId, TextContent};
+
+    #[test]
+    fn scheduled_messages_are_returned_in_order() {
+        let mut messages = ScheduledMessages::default();
+        for send_at in [300, 100, 200] {
+            let id = messages.next_id();
+            messages.add(message(id, send_at));
+        }
+
+        let send_at: Vec<_> = messages.iter().map(|m| m.send_at).collect();
+        assert_eq!(send_at, vec![100, 200, 300]);
+        let ids: Vec<_> = messages.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![2, 3, 1]);
+        assert_eq!(messages.len(), 3);
+    }
+
+    #[test]
+    fn cancelled_messages_are_removed() {
+        let mut messages = ScheduledMessages::default();
+        let id = messages.next_id();
+        messages.add(message(id, 100));
+
+        assert_eq!(messages.remove(id).map(|m| m.id), Some(id));
+        assert!(messages.remove(id).is_none());
+        assert_eq!(messages.len(), 0);
+
+        // Ids aren't reused once a message is cancelled
+        assert_eq!(messages.next_id(), id + 1);
+    }
+
+    #[test]
+    fn full_once_max_messages_scheduled() {
+        let mut messages = ScheduledMessages::default();
+        for _ in 0..MAX_SCHEDULED_MESSAGES {
+            assert!(!messages.is_full());
+            let id = messages.next_id();
+            messages.add(message(id, 100));
+        }
+        assert!(messages.is_full());
+
+        messages.remove(1);
+        assert!(!messages.is_full());
+    }
+
+    fn message(id: u64, send_at: u64) -> ScheduledMessage {
+        ScheduledMessage {
+            id,
+            chat: Chat::Direct(Principal::from_slice(&[1]).into()),
+            thread_root_message_index: None,
+            message_id: MessageId::from(id as u128),
+            content: MessageContentInitial::Text(TextContent {
+                text: "hello".to_string(),
+            }),
+            mentioned: Vec::new(),
+            block_level_markdown: false,
+            created: 0,
+            send_at,
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use crate::model::token_swaps::{TokenSwap, TokenSwaps};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use types::{Milliseconds, TimestampMillis};
+use user_canister::{LimitOrder, SwapOrder, SwapOrderStatus, SwapOrderType};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SwapOrders {
+    orders: BTreeMap<u64, SwapOrder>,
+    next_id: u64,
+}
+
+impl SwapOrders {
+    pub fn active_count(&self) -> usize {
+        self.orders.values().filter(|o| o.status == SwapOrderStatus::Active).count()
+    }
+
+    pub fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub fn add(&mut self, order: SwapOrder) {
+        self.orders.insert(order.id, order);
+    }
+
+    pub fn get(&self, id: u64) -> Option<&SwapOrder> {
+        self.orders.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut SwapOrder> {
+        self.orders.get_mut(&id)
+    }
+
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let Some(order) = self.orders.get_mut(&id) else {
+            return false;
+        };
+
+        if order.status == SwapOrderStatus::Active {
+            order.status = SwapOrderStatus::Cancelled;
+            order.next_check_at = None;
+        }
+        true
+    }
+
+    pub fn record_swap_started(&mut self, id: u64, swap_id: u128) {
+        if let Some(order) = self.orders.get_mut(&id) {
+            order.swaps.push(swap_id);
+            order.swap_in_progress = Some(swap_id);
+        }
+    }
+
+    // Limit orders are completed once their input has been swapped, recurring swaps once they have made
+    // `max_swaps` swaps, whether or not each of those succeeded
+    pub fn record_swap_result(&mut self, id: u64, swap_id: u128, fill: SwapFill) {
+        let Some(order) = self.orders.get_mut(&id) else {
+            return;
+        };
+        if order.swap_in_progress != Some(swap_id) {
+            return;
+        }
+        order.swap_in_progress = None;
+        order.total_input_amount += fill.input_amount;
+        order.total_output_amount += fill.output_amount;
+        order.last_error = fill.error;
+
+        let completed = match &order.order_type {
+            // Any remainder too small to cover the transfer fees can't be swapped
+            SwapOrderType::Limit(_) => remaining_input_amount(order) <= 2 * order.input_token.fee,
+            SwapOrderType::Recurring(r) => r.max_swaps.map_or(false, |m| order.swaps.len() as u32 >= m),
+        };
+
+        if order.status == SwapOrderStatus::Active && completed {
+            order.status = SwapOrderStatus::Completed;
+            order.next_check_at = None;
+        }
+    }
+
+    pub fn record_error(&mut self, id: u64, error: String) {
+        if let Some(order) = self.orders.get_mut(&id) {
+            order.last_error = Some(error);
+        }
+    }
+}
+
+pub fn input_amount(order_type: &SwapOrderType) -> u128 {
+    match order_type {
+        SwapOrderType::Limit(l) => l.input_amount,
+        SwapOrderType::Recurring(r) => r.input_amount,
+    }
+}
+
+// Limit orders may be filled over several swaps, since a swap split across exchanges can partially succeed
+pub fn remaining_input_amount(order: &SwapOrder) -> u128 {
+    match &order.order_type {
+        SwapOrderType::Limit(l) => l.input_amount.saturating_sub(order.total_input_amount),
+        SwapOrderType::Recurring(r) => r.input_amount,
+    }
+}
+
+// The remainder of a partially filled limit order must be swapped at no worse a rate than the order itself
+pub fn limit_order_min_output_amount(limit_order: &LimitOrder, input_amount: u128) -> u128 {
+    if input_amount >= limit_order.input_amount {
+        return limit_order.min_output_amount;
+    }
+    limit_order
+        .min_output_amount
+        .checked_mul(input_amount)
+        .map(|a| a / limit_order.input_amount)
+        .unwrap_or_else(|| limit_order.min_output_amount / limit_order.input_amount * input_amount)
+}
+
+// The amounts swapped by a single swap made for an order. A `BestRoute` swap split across several exchanges may be
+// partially filled if some of its legs succeed while others fail.
+#[derive(Debug, Default)]
+pub struct SwapFill {
+    pub input_amount: u128,
+    pub output_amount: u128,
+    pub error: Option<String>,
+}
+
+impl SwapFill {
+    pub fn failed(error: String) -> SwapFill {
+        SwapFill {
+            error: Some(error),
+            ..Default::default()
+        }
+    }
+}
+
+// Sums the input used and output received by each part of the swap which succeeded, where the parts are the swap's
+// legs if it was routed via `BestRoute`, otherwise the swap itself
+pub fn swap_fill(swap_id: u128, token_swaps: &TokenSwaps) -> SwapFill {
+    let Some(swap) = token_swaps.get(swap_id) else {
+        return SwapFill::failed("Swap not found".to_string());
+    };
+
+    let legs = token_swaps.legs(swap_id);
+    let parts = if legs.is_empty() { std::slice::from_ref(swap) } else { legs };
+    let succeeded: Vec<&TokenSwap> = parts
+        .iter()
+        .filter(|s| s.success.as_ref().map_or(false, |t| t.value))
+        .collect();
+
+    let fully_succeeded = token_swaps.status(swap_id).and_then(|s| s.success) == Some(true);
+
+    SwapFill {
+        input_amount: succeeded
+            .iter()
+            .map(|s| s.args.input_amount.saturating_sub(s.unspent_input_withdrawn))
+            .sum(),
+        output_amount: succeeded
+            .iter()
+            .map(|s| match s.withdrawn_from_dex_at.as_ref().map(|t| &t.value) {
+                Some(Ok(amount)) => *amount,
+                _ => 0,
+            })
+            .sum(),
+        error: (!fully_succeeded).then(|| "Swap failed".to_string()),
+    }
+}
+
+// Recurring swaps stay on their original schedule, skipping any runs which were missed
+pub fn next_recurring_run(previous_run: TimestampMillis, interval: Milliseconds, now: TimestampMillis) -> TimestampMillis {
+    let next = previous_run + interval;
+    if next > now {
+        next
+    } else {
+        let missed = (now - next) / interval + 1;
+        next + missed * interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use types::{Cryptocurrency, ExchangeId, Timestamped, TokenInfo};
+    use user_canister::swap_tokens::{Args, BestRouteArgs, ExchangeArgs, ICPSwapArgs, SwapRouteLeg};
+    use user_canister::RecurringSwap;
+
+    #[test]
+    fn limit_order_completed_by_successful_swap() {
+        let mut orders = SwapOrders::default();
+        orders.add(order(
+            1,
+            SwapOrderType::Limit(LimitOrder {
+                input_amount: 1000,
+                min_output_amount: 500,
+                expires_at: None,
+            }),
+        ));
+
+        orders.record_swap_started(1, 10);
+        orders.record_swap_result(1, 10, SwapFill::failed("Slippage".to_string()));
+        assert_eq!(orders.get(1).unwrap().status, SwapOrderStatus::Active);
+
+        orders.record_swap_started(1, 11);
+        orders.record_swap_result(1, 11, filled(1000, 550));
+
+        let order = orders.get(1).unwrap();
+        assert_eq!(order.status, SwapOrderStatus::Completed);
+        assert_eq!(order.swaps, vec![10, 11]);
+        assert_eq!(order.total_input_amount, 1000);
+        assert_eq!(order.total_output_amount, 550);
+        assert!(order.last_error.is_none());
+    }
+
+    #[test]
+    fn recurring_swap_completed_after_max_swaps() {
+        let mut orders = SwapOrders::default();
+        orders.add(order(
+            1,
+            SwapOrderType::Recurring(RecurringSwap {
+                input_amount: 100,
+                interval: 1000,
+                max_slippage_bps: 50,
+                max_swaps: Some(2),
+                start_at: None,
+            }),
+        ));
+
+        orders.record_swap_started(1, 10);
+        orders.record_swap_result(1, 10, filled(100, 50));
+        assert_eq!(orders.get(1).unwrap().status, SwapOrderStatus::Active);
+
+        orders.record_swap_started(1, 11);
+        orders.record_swap_result(1, 11, SwapFill::failed("Insufficient funds".to_string()));
+
+        let order = orders.get(1).unwrap();
+        assert_eq!(order.status, SwapOrderStatus::Completed);
+        assert_eq!(order.total_input_amount, 100);
+        assert_eq!(order.total_output_amount, 50);
+    }
+
+    #[test]
+    fn partially_failed_split_swap_reduces_remaining_input() {
+        let mut orders = SwapOrders::default();
+        let limit_order = LimitOrder {
+            input_amount: 1000,
+            min_output_amount: 500,
+            expires_at: None,
+        };
+        orders.add(order(1, SwapOrderType::Limit(limit_order.clone())));
+
+        // The swap is split 600/400 across two exchanges, the first leg succeeds and the second fails
+        let mut token_swaps = TokenSwaps::default();
+        let mut swap = TokenSwap::new(
+            swap_args(
+                10,
+                1000,
+                ExchangeArgs::BestRoute(BestRouteArgs {
+                    candidates: Vec::new(),
+                    allow_split: true,
+                }),
+            ),
+            0,
+        );
+        swap.route = Some(vec![route_leg(600, 330), route_leg(400, 220)]);
+        swap.success = Some(Timestamped::new(false, 0));
+        token_swaps.upsert(swap);
+        for (index, (input_amount, withdrawn, success)) in [(600, 330, true), (400, 390, false)].into_iter().enumerate() {
+            let mut leg = TokenSwap::new_leg(swap_args(10, input_amount, icpswap_args()), index as u32, 0);
+            leg.withdrawn_from_dex_at = Some(Timestamped::new(Ok(withdrawn), 0));
+            leg.success = Some(Timestamped::new(success, 0));
+            token_swaps.upsert(leg);
+        }
+
+        let fill = swap_fill(10, &token_swaps);
+        assert_eq!(fill.input_amount, 600);
+        assert_eq!(fill.output_amount, 330);
+        assert!(fill.error.is_some());
+
+        orders.record_swap_started(1, 10);
+        orders.record_swap_result(1, 10, fill);
+
+        let order = orders.get(1).unwrap();
+        assert_eq!(order.status, SwapOrderStatus::Active);
+        assert_eq!(order.total_input_amount, 600);
+        assert_eq!(order.total_output_amount, 330);
+        assert_eq!(remaining_input_amount(order), 400);
+        assert_eq!(limit_order_min_output_amount(&limit_order, 400), 200);
+
+        orders.record_swap_started(1, 11);
+        orders.record_swap_result(1, 11, filled(400, 210));
+
+        let order = orders.get(1).unwrap();
+        assert_eq!(order.status, SwapOrderStatus::Completed);
+        assert_eq!(order.total_input_amount, 1000);
+        assert_eq!(order.total_output_amount, 540);
+    }
+
+    #[test]
+    fn next_recurring_run_skips_missed_runs() {
+        assert_eq!(next_recurring_run(1000, 100, 1050), 1100);
+        assert_eq!(next_recurring_run(1000, 100, 1100), 1200);
+        assert_eq!(next_recurring_run(1000, 100, 1350), 1400);
+    }
+
+    fn filled(input_amount: u128, output_amount: u128) -> SwapFill {
+        SwapFill {
+            input_amount,
+            output_amount,
+            error: None,
+        }
+    }
+
+    fn token() -> TokenInfo {
+        TokenInfo {
+            token: Cryptocurrency::InternetComputer,
+            ledger: Principal::anonymous(),
+            decimals: 8,
+            fee: 10,
+        }
+    }
+
+    fn icpswap_args() -> ExchangeArgs {
+        ExchangeArgs::ICPSwap(ICPSwapArgs {
+            swap_canister_id: Principal::anonymous(),
+            zero_for_one: true,
+        })
+    }
+
+    fn swap_args(swap_id: u128, input_amount: u128, exchange_args: ExchangeArgs) -> Args {
+        Args {
+            swap_id,
+            input_token: token(),
+            output_token: token(),
+            input_amount,
+            exchange_args,
+            min_output_amount: 0,
+            pin: None,
+        }
+    }
+
+    fn route_leg(input_amount: u128, expected_amount_out: u128) -> SwapRouteLeg {
+        SwapRouteLeg {
+            exchange_id: ExchangeId::ICPSwap,
+            swap_canister_id: Principal::anonymous(),
+            input_amount,
+            expected_amount_out,
+        }
+    }
+
+    fn order(id: u64, order_type: SwapOrderType) -> SwapOrder {
+        SwapOrder {
+            id,
+            input_token: token(),
+            output_token: token(),
+            exchange_args: icpswap_args(),
+            order_type,
+            created: 0,
+            status: SwapOrderStatus::Active,
+            next_check_at: Some(0),
+            swaps: Vec::new(),
+            swap_in_progress: None,
+            total_input_amount: 0,
+            total_output_amount: 0,
+            last_error: None,
+        }
+    }
+}
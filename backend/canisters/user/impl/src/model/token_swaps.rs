@@ -2,11 +2,15 @@ use icrc_ledger_types::icrc1::account::Account;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use types::{TimestampMillis, Timestamped};
+use user_canister::swap_tokens::SwapRouteLeg;
 use user_canister::token_swap_status::TokenSwapStatus;
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct TokenSwaps {
     swaps: HashMap<u128, TokenSwap>,
+    // When a swap is routed via one or more exchanges, each leg is processed as its own swap
+    #[serde(default)]
+    legs: HashMap<u128, Vec<TokenSwap>>,
 }
 
 impl TokenSwaps {
@@ -17,15 +21,47 @@ impl TokenSwaps {
     }
 
     pub fn upsert(&mut self, swap: TokenSwap) {
-        self.swaps.insert(swap.args.swap_id, swap);
+        if let Some(leg) = swap.leg {
+            let legs = self.legs.entry(swap.args.swap_id).or_default();
+            if let Some(existing) = legs.get_mut(leg as usize) {
+                *existing = swap;
+            } else {
+                legs.push(swap);
+            }
+        } else {
+            self.swaps.insert(swap.args.swap_id, swap);
+        }
     }
 
     pub fn get(&self, swap_id: u128) -> Option<&TokenSwap> {
         self.swaps.get(&swap_id)
     }
 
+    pub fn legs(&self, swap_id: u128) -> &[TokenSwap] {
+        self.legs.get(&swap_id).map_or(&[], |l| l.as_slice())
+    }
+
+    pub fn status(&self, swap_id: u128) -> Option<TokenSwapStatus> {
+        let mut status: TokenSwapStatus = self.get(swap_id)?.clone().into();
+
+        let legs = self.legs(swap_id);
+        if !legs.is_empty() {
+            status.legs = legs.iter().cloned().map(|l| l.into()).collect();
+            status.unspent_input_withdrawn = status.legs.iter().map(|l| l.unspent_input_withdrawn).sum();
+            if status.legs.iter().any(|l| l.success == Some(false)) {
+                status.success = Some(false);
+            } else if status.route.as_ref().map_or(false, |r| r.len() == status.legs.len())
+                && status.legs.iter().all(|l| l.success == Some(true))
+            {
+                status.success = Some(true);
+            }
+        }
+
+        Some(status)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &TokenSwap> {
-        self.swaps.values()
+        self.swaps.values().chain(self.legs.values().flatten())
     }
 }
 
@@ -38,7 +74,16 @@ pub struct TokenSwap {
     pub notified_dex_at: SwapSubtask,
     pub amount_swapped: SwapSubtask<Result<u128, String>>,
     pub withdrawn_from_dex_at: SwapSubtask<u128>,
+    // Set when the exchange didn't use all of the input and the remainder was withdrawn as part of the swap
+    #[serde(default)]
+    pub unspent_input_withdrawn: u128,
     pub success: Option<Timestamped<bool>>,
+    // Only set for swaps which are legs of a `BestRoute` swap
+    #[serde(default)]
+    pub leg: Option<u32>,
+    // Only set for `BestRoute` swaps once the route has been chosen
+    #[serde(default)]
+    pub route: Option<Vec<SwapRouteLeg>>,
 }
 
 type SwapSubtask<T = ()> = Option<Timestamped<Result<T, String>>>;
@@ -53,7 +98,17 @@ impl TokenSwap {
             notified_dex_at: None,
             amount_swapped: None,
             withdrawn_from_dex_at: None,
+            unspent_input_withdrawn: 0,
             success: None,
+            leg: None,
+            route: None,
+        }
+    }
+
+    pub fn new_leg(args: user_canister::swap_tokens::Args, leg: u32, now: TimestampMillis) -> TokenSwap {
+        TokenSwap {
+            leg: Some(leg),
+            ..TokenSwap::new(args, now)
         }
     }
 }
@@ -67,7 +122,10 @@ impl From<TokenSwap> for TokenSwapStatus {
             notify_dex: value.notified_dex_at.map(|t| t.value.map(|_| ())),
             amount_swapped: value.amount_swapped.as_ref().map(|t| t.value.clone()),
             withdraw_from_dex: value.withdrawn_from_dex_at.map(|t| t.value),
+            unspent_input_withdrawn: value.unspent_input_withdrawn,
             success: value.success.map(|t| t.value),
+            route: value.route,
+            legs: Vec::new(),
         }
     }
 }
@@ -0,0 +1,31 @@
+use crate::guards::caller_is_owner;
+use crate::{read_state, RuntimeState};
+use chat_events::Reader;
+use ic_cdk::query;
+use types::EventIndex;
+use user_canister::message_edit_history::{Response::*, *};
+
+#[query(guard = "caller_is_owner")]
+fn message_edit_history(args: Args) -> Response {
+    read_state(|state| message_edit_history_impl(args, state))
+}
+
+fn message_edit_history_impl(args: Args, state: &RuntimeState) -> Response {
+    if let Some(chat) = state.data.direct_chats.get(&args.user_id.into()) {
+        let Some(events_reader) = chat
+            .events
+            .events_reader(EventIndex::default(), args.thread_root_message_index)
+        else {
+            return ThreadMessageNotFound;
+        };
+
+        let my_user_id = state.env.canister_id().into();
+        if let Some(versions) = events_reader.message_edit_history(args.message_id.into(), Some(my_user_id)) {
+            Success(SuccessResult { versions })
+        } else {
+            MessageNotFound
+        }
+    } else {
+        ChatNotFound
+    }
+}
@@ -15,10 +15,13 @@ pub mod hot_group_exclusions;
 pub mod http_request;
 pub mod initial_state;
 pub mod local_user_index;
+pub mod message_edit_history;
 pub mod messages_by_message_index;
 pub mod public_profile;
 pub mod saved_crypto_accounts;
+pub mod scheduled_messages;
 pub mod search_messages;
+pub mod swap_order_status;
 pub mod token_swap_status;
 pub mod updates;
 
@@ -2,11 +2,12 @@ use crate::guards::caller_is_owner;
 use crate::{read_state, RuntimeState};
 use ic_cdk::query;
 use search::Query;
-use types::EventIndex;
+use types::{EventIndex, UserId};
 use user_canister::search_messages::{Response::*, *};
 
 const MIN_TERM_LENGTH: u8 = 3;
 const MAX_TERM_LENGTH: u8 = 30;
+const MAX_QUERY_LENGTH: u8 = 200;
 
 #[query(guard = "caller_is_owner")]
 fn search_messages(args: Args) -> Response {
@@ -14,13 +15,21 @@ fn search_messages(args: Args) -> Response {
 }
 
 fn search_messages_impl(args: Args, state: &RuntimeState) -> Response {
-    let term_length = args.search_term.len() as u8;
+    if args.search_term.len() > MAX_QUERY_LENGTH as usize {
+        return TermTooLong(MAX_QUERY_LENGTH);
+    }
+
+    let Ok(mut query) = Query::try_parse(&args.search_term) else {
+        return InvalidTerm;
+    };
 
-    if term_length < MIN_TERM_LENGTH {
+    let term_length = query.free_text_length();
+
+    if !query.has_filters() && term_length < MIN_TERM_LENGTH as usize {
         return TermTooShort(MIN_TERM_LENGTH);
     }
 
-    if term_length > MAX_TERM_LENGTH {
+    if term_length > MAX_TERM_LENGTH as usize {
         return TermTooLong(MAX_TERM_LENGTH);
     }
 
@@ -29,8 +38,17 @@ fn search_messages_impl(args: Args, state: &RuntimeState) -> Response {
         Some(dc) => dc,
     };
 
-    let my_user_id = state.env.canister_id().into();
-    let query = Query::parse(args.search_term);
+    let my_user_id: UserId = state.env.canister_id().into();
+
+    // The only senders in a direct chat are the 2 participants, so we can resolve their usernames
+    // via the `from:@me` and `from:@them` aliases
+    for username in std::mem::take(&mut query.usernames) {
+        match username.as_str() {
+            "me" => query.users.insert(my_user_id),
+            "them" => query.users.insert(args.user_id),
+            _ => return InvalidTerm,
+        };
+    }
 
     let matches =
         direct_chat
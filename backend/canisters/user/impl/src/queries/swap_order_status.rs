@@ -0,0 +1,17 @@
+use crate::guards::caller_is_owner;
+use crate::{read_state, RuntimeState};
+use ic_cdk::query;
+use user_canister::swap_order_status::{Response::*, *};
+
+#[query(guard = "caller_is_owner")]
+fn swap_order_status(args: Args) -> Response {
+    read_state(|state| swap_order_status_impl(args, state))
+}
+
+fn swap_order_status_impl(args: Args, state: &RuntimeState) -> Response {
+    if let Some(order) = state.data.swap_orders.get(args.order_id).cloned() {
+        Success(order)
+    } else {
+        NotFound
+    }
+}
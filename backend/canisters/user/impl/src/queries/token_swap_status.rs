@@ -9,8 +9,8 @@ fn token_swap_status(args: Args) -> Response {
 }
 
 fn token_swap_status_impl(args: Args, state: &RuntimeState) -> Response {
-    if let Some(token_swap) = state.data.token_swaps.get(args.swap_id).cloned() {
-        Success(token_swap.into())
+    if let Some(status) = state.data.token_swaps.status(args.swap_id) {
+        Success(status)
     } else {
         NotFound
     }
@@ -1,5 +1,7 @@
 use crate::model::token_swaps::TokenSwap;
+use crate::updates::create_swap_order::process_swap_order;
 use crate::updates::end_video_call::end_video_call_impl;
+use crate::updates::schedule_message::send_scheduled_message;
 use crate::updates::swap_tokens::process_token_swap;
 use crate::{mutate_state, openchat_bot, read_state};
 use canister_timer_jobs::Job;
@@ -25,6 +27,8 @@ pub enum TimerJob {
     SendMessageToGroup(Box<SendMessageToGroupJob>),
     SendMessageToChannel(Box<SendMessageToChannelJob>),
     MarkVideoCallEnded(MarkVideoCallEndedJob),
+    SendScheduledMessage(SendScheduledMessageJob),
+    ProcessSwapOrder(ProcessSwapOrderJob),
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -118,6 +122,18 @@ pub struct SendMessageToChannelJob {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MarkVideoCallEndedJob(pub user_canister::end_video_call::Args);
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SendScheduledMessageJob {
+    pub scheduled_message_id: u64,
+    #[serde(default)]
+    pub attempt: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProcessSwapOrderJob {
+    pub order_id: u64,
+}
+
 impl Job for TimerJob {
     fn execute(self) {
         match self {
@@ -133,6 +149,8 @@ impl Job for TimerJob {
             TimerJob::SendMessageToGroup(job) => job.execute(),
             TimerJob::SendMessageToChannel(job) => job.execute(),
             TimerJob::MarkVideoCallEnded(job) => job.execute(),
+            TimerJob::SendScheduledMessage(job) => job.execute(),
+            TimerJob::ProcessSwapOrder(job) => job.execute(),
         }
     }
 }
@@ -258,6 +276,8 @@ impl Job for NotifyEscrowCanisterOfDepositJob {
                 &escrow_canister::notify_deposit::Args {
                     swap_id: self.swap_id,
                     user_id: None,
+                    token1_amount: None,
+                    fill_id: None,
                 },
             )
             .await
@@ -383,3 +403,17 @@ impl Job for MarkVideoCallEndedJob {
         mutate_state(|state| end_video_call_impl(self.0, state));
     }
 }
+
+impl Job for SendScheduledMessageJob {
+    fn execute(self) {
+        if let Some(message) = mutate_state(|state| state.data.scheduled_messages.remove(self.scheduled_message_id)) {
+            ic_cdk::spawn(send_scheduled_message(message, self.attempt));
+        }
+    }
+}
+
+impl Job for ProcessSwapOrderJob {
+    fn execute(self) {
+        ic_cdk::spawn(process_swap_order(self.order_id));
+    }
+}
@@ -0,0 +1,33 @@
+use super::swap_client::{SwapClient, SwapSuccess};
+use async_trait::async_trait;
+use ic_cdk::api::call::CallResult;
+use icdex_client::ICDexSwapClient;
+use icrc_ledger_types::icrc1::account::Account;
+
+#[async_trait]
+impl SwapClient for ICDexSwapClient {
+    async fn deposit_account(&self) -> CallResult<Account> {
+        self.deposit_account().await
+    }
+
+    async fn deposit(&self, amount: u128) -> CallResult<()> {
+        self.deposit(amount).await
+    }
+
+    async fn quote(&self, amount: u128) -> CallResult<u128> {
+        self.quote(amount).await
+    }
+
+    async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<SwapSuccess, String>> {
+        self.swap(amount, min_amount_out).await.map(|r| {
+            r.map(|s| SwapSuccess {
+                amount_out: s.amount_out,
+                unspent_input_withdrawn: s.unspent_input_withdrawn,
+            })
+        })
+    }
+
+    async fn withdraw(&self, successful_swap: bool, amount: u128) -> CallResult<u128> {
+        self.withdraw(successful_swap, amount).await
+    }
+}
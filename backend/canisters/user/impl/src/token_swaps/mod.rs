@@ -1,3 +1,54 @@
+use icdex_client::ICDexSwapClient;
+use icpswap_client::ICPSwapClient;
+use sonic_client::SonicClient;
+use swap_client::SwapClient;
+use types::{CanisterId, TokenInfo};
+use user_canister::swap_tokens::ExchangeArgs;
+
+pub mod icdex;
 pub mod icpswap;
+pub mod routing;
 pub mod sonic;
 pub mod swap_client;
+
+// Returns None for `BestRoute` since that must first be resolved into a route via `routing::find_best_route`
+pub fn build_swap_client(
+    exchange_args: &ExchangeArgs,
+    input_token: TokenInfo,
+    output_token: TokenInfo,
+    this_canister_id: CanisterId,
+) -> Option<Box<dyn SwapClient>> {
+    match exchange_args {
+        ExchangeArgs::ICPSwap(icpswap) => {
+            let (token0, token1) = if icpswap.zero_for_one { (input_token, output_token) } else { (output_token, input_token) };
+            Some(Box::new(ICPSwapClient::new(
+                this_canister_id,
+                icpswap.swap_canister_id,
+                token0,
+                token1,
+                icpswap.zero_for_one,
+            )))
+        }
+        ExchangeArgs::Sonic(sonic) => {
+            let (token0, token1) = if sonic.zero_for_one { (input_token, output_token) } else { (output_token, input_token) };
+            Some(Box::new(SonicClient::new(
+                this_canister_id,
+                sonic.swap_canister_id,
+                token0,
+                token1,
+                sonic.zero_for_one,
+            )))
+        }
+        ExchangeArgs::ICDex(icdex) => {
+            let (token0, token1) = if icdex.zero_for_one { (input_token, output_token) } else { (output_token, input_token) };
+            Some(Box::new(ICDexSwapClient::new(
+                this_canister_id,
+                icdex.swap_canister_id,
+                token0,
+                token1,
+                icdex.zero_for_one,
+            )))
+        }
+        ExchangeArgs::BestRoute(_) => None,
+    }
+}
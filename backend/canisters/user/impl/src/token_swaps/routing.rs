@@ -0,0 +1,219 @@
+use super::build_swap_client;
+use futures::future::join_all;
+use ic_cdk::api::call::CallResult;
+use types::{CanisterId, TokenInfo};
+use user_canister::quote_swap::ExchangeQuote;
+use user_canister::swap_tokens::{ExchangeArgs, SwapRouteLeg};
+
+// When splitting is allowed, the input amount is divided into this many equal parts, each of which can be
+// routed to any of the candidate exchanges
+const SPLIT_PARTS: usize = 4;
+
+pub struct Route {
+    pub legs: Vec<RouteLeg>,
+    pub amount_out: u128,
+}
+
+pub struct RouteLeg {
+    pub exchange_args: ExchangeArgs,
+    pub input_amount: u128,
+    pub expected_amount_out: u128,
+}
+
+impl RouteLeg {
+    pub fn summary(&self) -> SwapRouteLeg {
+        SwapRouteLeg {
+            exchange_id: self.exchange_args.exchange_id().unwrap(),
+            swap_canister_id: self.exchange_args.swap_canister_id().unwrap(),
+            input_amount: self.input_amount,
+            expected_amount_out: self.expected_amount_out,
+        }
+    }
+}
+
+// Returns the exchanges to quote for the given args and whether the input may be split across them
+pub fn candidates(exchange_args: &ExchangeArgs) -> (Vec<ExchangeArgs>, bool) {
+    match exchange_args {
+        ExchangeArgs::BestRoute(route_args) => (route_args.candidates.clone(), route_args.allow_split),
+        args => (vec![args.clone()], false),
+    }
+}
+
+// Quotes each candidate exchange, then picks the route giving the greatest amount out after all fees,
+// including the ledger fees paid on each leg, so a split is only chosen if it outweighs those extra fees
+pub async fn find_best_route(
+    this_canister_id: CanisterId,
+    input_token: &TokenInfo,
+    output_token: &TokenInfo,
+    input_amount: u128,
+    candidates: &[ExchangeArgs],
+    allow_split: bool,
+) -> (Vec<ExchangeQuote>, Option<Route>) {
+    let candidates: Vec<_> = candidates
+        .iter()
+        .filter(|c| !matches!(c, ExchangeArgs::BestRoute(_)))
+        .collect();
+
+    let parts = if allow_split && candidates.len() > 1 { SPLIT_PARTS } else { 1 };
+
+    let futures: Vec<_> = candidates
+        .iter()
+        .flat_map(|c| {
+            (1..=parts).map(move |p| {
+                quote_amount_out(
+                    c,
+                    input_token,
+                    output_token,
+                    amount_for_parts(input_amount, p, parts),
+                    this_canister_id,
+                )
+            })
+        })
+        .collect();
+
+    let results = join_all(futures).await;
+    let amounts_out: Vec<Vec<Option<u128>>> = results
+        .chunks(parts)
+        .map(|c| c.iter().map(|r| r.as_ref().ok().copied()).collect())
+        .collect();
+
+    let quotes = candidates
+        .iter()
+        .zip(results.chunks(parts))
+        .map(|(c, r)| ExchangeQuote {
+            exchange_id: c.exchange_id().unwrap(),
+            swap_canister_id: c.swap_canister_id().unwrap(),
+            amount_out: r[parts - 1].clone().map_err(|error| format!("{error:?}")),
+        })
+        .collect();
+
+    let route = allocate_parts(&amounts_out, parts).map(|(allocation, amount_out)| {
+        let mut legs: Vec<_> = allocation
+            .into_iter()
+            .enumerate()
+            .filter(|(_, p)| *p > 0)
+            .map(|(index, p)| RouteLeg {
+                exchange_args: candidates[index].clone(),
+                input_amount: amount_for_parts(input_amount, p, parts),
+                expected_amount_out: amounts_out[index][p - 1].unwrap_or_default(),
+            })
+            .collect();
+
+        // Any remainder from dividing the input into parts goes to the first leg
+        let allocated: u128 = legs.iter().map(|l| l.input_amount).sum();
+        if let Some(first) = legs.first_mut() {
+            first.input_amount += input_amount - allocated;
+        }
+
+        Route { legs, amount_out }
+    });
+
+    (quotes, route)
+}
+
+// Mirrors the fees paid when the swap is processed: the input token fee is paid when transferring to the
+// exchange and again when the exchange deposits the tokens, then the output token fee is paid on withdrawal
+async fn quote_amount_out(
+    exchange_args: &ExchangeArgs,
+    input_token: &TokenInfo,
+    output_token: &TokenInfo,
+    input_amount: u128,
+    this_canister_id: CanisterId,
+) -> CallResult<u128> {
+    let amount_to_swap = input_amount.saturating_sub(2 * input_token.fee);
+    if amount_to_swap == 0 {
+        return Ok(0);
+    }
+
+    let swap_client = build_swap_client(exchange_args, input_token.clone(), output_token.clone(), this_canister_id).unwrap();
+
+    swap_client
+        .quote(amount_to_swap)
+        .await
+        .map(|amount_out| amount_out.saturating_sub(output_token.fee))
+}
+
+fn amount_for_parts(input_amount: u128, parts: usize, total_parts: usize) -> u128 {
+    input_amount / total_parts as u128 * parts as u128
+}
+
+// `amounts_out[c][p - 1]` is the amount out from routing `p` parts of the input to candidate `c`, or None if
+// that candidate couldn't be quoted. Returns the number of parts to route to each candidate so that all
+// parts are used and the total amount out is maximised, along with that total.
+fn allocate_parts(amounts_out: &[Vec<Option<u128>>], parts: usize) -> Option<(Vec<usize>, u128)> {
+    // `best[n]` is the best (total, allocation) using exactly `n` parts across the candidates seen so far
+    let mut best: Vec<Option<(u128, Vec<usize>)>> = vec![None; parts + 1];
+    best[0] = Some((0, Vec::new()));
+
+    for candidate_amounts in amounts_out {
+        let mut next: Vec<Option<(u128, Vec<usize>)>> = vec![None; parts + 1];
+
+        for (used, previous) in best.iter().enumerate() {
+            let Some((total, allocation)) = previous else {
+                continue;
+            };
+
+            for p in 0..=(parts - used) {
+                let amount_out =
+                    if p == 0 { Some(0) } else { candidate_amounts.get(p - 1).copied().flatten().filter(|a| *a > 0) };
+                let Some(amount_out) = amount_out else {
+                    continue;
+                };
+
+                let new_total = total + amount_out;
+                if next[used + p].as_ref().map_or(true, |(t, _)| new_total > *t) {
+                    let mut new_allocation = allocation.clone();
+                    new_allocation.push(p);
+                    next[used + p] = Some((new_total, new_allocation));
+                }
+            }
+        }
+
+        best = next;
+    }
+
+    best.pop()
+        .flatten()
+        .filter(|(total, _)| *total > 0)
+        .map(|(total, allocation)| (allocation, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_exchange_chosen_if_splitting_gives_less() {
+        let amounts_out = vec![
+            vec![Some(240), Some(490), Some(740), Some(1000)],
+            vec![Some(200), Some(400), Some(600), Some(800)],
+        ];
+
+        assert_eq!(allocate_parts(&amounts_out, 4), Some((vec![4, 0], 1000)));
+    }
+
+    #[test]
+    fn input_split_if_it_gives_more() {
+        // The first exchange has little liquidity so its price gets much worse as the amount increases
+        let amounts_out = vec![
+            vec![Some(300), Some(450), Some(500), Some(520)],
+            vec![Some(200), Some(390), Some(570), Some(740)],
+        ];
+
+        assert_eq!(allocate_parts(&amounts_out, 4), Some((vec![1, 3], 870)));
+    }
+
+    #[test]
+    fn failed_quotes_are_skipped() {
+        let amounts_out = vec![vec![None, None, None, None], vec![Some(200), Some(400), Some(600), Some(800)]];
+
+        assert_eq!(allocate_parts(&amounts_out, 4), Some((vec![0, 4], 800)));
+    }
+
+    #[test]
+    fn no_route_if_all_quotes_failed() {
+        let amounts_out = vec![vec![None], vec![Some(0)]];
+
+        assert_eq!(allocate_parts(&amounts_out, 1), None);
+    }
+}
@@ -1,4 +1,4 @@
-use super::swap_client::SwapClient;
+use super::swap_client::{SwapClient, SwapSuccess};
 use async_trait::async_trait;
 use ic_cdk::api::call::CallResult;
 use icrc_ledger_types::icrc1::account::Account;
@@ -14,8 +14,17 @@ impl SwapClient for SonicClient {
         self.deposit(amount).await.map(|_| ())
     }
 
-    async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<u128, String>> {
-        self.swap(amount, min_amount_out).await
+    async fn quote(&self, amount: u128) -> CallResult<u128> {
+        self.quote(amount).await
+    }
+
+    async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<SwapSuccess, String>> {
+        self.swap(amount, min_amount_out).await.map(|r| {
+            r.map(|amount_out| SwapSuccess {
+                amount_out,
+                unspent_input_withdrawn: 0,
+            })
+        })
     }
 
     async fn withdraw(&self, successful_swap: bool, amount: u128) -> CallResult<u128> {
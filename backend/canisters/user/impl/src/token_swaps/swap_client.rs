@@ -6,6 +6,15 @@ use icrc_ledger_types::icrc1::account::Account;
 pub trait SwapClient {
     async fn deposit_account(&self) -> CallResult<Account>;
     async fn deposit(&self, amount: u128) -> CallResult<()>;
-    async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<u128, String>>;
+    // The amount out from swapping `amount` after the exchange's fees, excluding ledger fees
+    async fn quote(&self, amount: u128) -> CallResult<u128>;
+    async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<SwapSuccess, String>>;
     async fn withdraw(&self, successful_swap: bool, amount: u128) -> CallResult<u128>;
 }
+
+pub struct SwapSuccess {
+    pub amount_out: u128,
+    // Exchanges which may not use all of the input (eg. ICDex, whose orders are in whole units) withdraw the
+    // remainder as part of the swap
+    pub unspent_input_withdrawn: u128,
+}
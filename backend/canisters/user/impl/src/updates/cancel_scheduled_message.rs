@@ -0,0 +1,30 @@
+use crate::guards::caller_is_owner;
+use crate::timer_job_types::TimerJob;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use ic_cdk::update;
+use user_canister::cancel_scheduled_message::{Response::*, *};
+
+#[update(guard = "caller_is_owner")]
+#[trace]
+fn cancel_scheduled_message(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| cancel_scheduled_message_impl(args.scheduled_message_id, state))
+}
+
+fn cancel_scheduled_message_impl(scheduled_message_id: u64, state: &mut RuntimeState) -> Response {
+    if state.data.scheduled_messages.remove(scheduled_message_id).is_none() {
+        return NotFound;
+    }
+
+    state.data.timer_jobs.cancel_jobs(|j| {
+        if let TimerJob::SendScheduledMessage(job) = j {
+            job.scheduled_message_id == scheduled_message_id
+        } else {
+            false
+        }
+    });
+
+    Success
+}
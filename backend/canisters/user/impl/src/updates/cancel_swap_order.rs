@@ -0,0 +1,33 @@
+use crate::guards::caller_is_owner;
+use crate::timer_job_types::TimerJob;
+use crate::{mutate_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use ic_cdk::update;
+use user_canister::cancel_swap_order::{Response::*, *};
+
+// Any swap already in progress is left to complete
+#[update(guard = "caller_is_owner")]
+#[trace]
+fn cancel_swap_order(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| cancel_swap_order_impl(args.order_id, state))
+}
+
+fn cancel_swap_order_impl(order_id: u64, state: &mut RuntimeState) -> Response {
+    if !state.data.swap_orders.cancel(order_id) {
+        return NotFound;
+    }
+
+    state.data.timer_jobs.cancel_jobs(
+        |j| {
+            if let TimerJob::ProcessSwapOrder(job) = j {
+                job.order_id == order_id
+            } else {
+                false
+            }
+        },
+    );
+
+    Success
+}
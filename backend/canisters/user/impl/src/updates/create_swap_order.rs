@@ -0,0 +1,265 @@
+use crate::guards::caller_is_owner;
+use crate::model::pin_number::VerifyPinError;
+use crate::model::swap_orders::{
+    input_amount, limit_order_min_output_amount, next_recurring_run, remaining_input_amount, swap_fill,
+};
+use crate::model::token_swaps::TokenSwap;
+use crate::timer_job_types::{ProcessSwapOrderJob, TimerJob};
+use crate::token_swaps::routing::{candidates, find_best_route};
+use crate::updates::swap_tokens::execute_token_swap;
+use crate::{mutate_state, read_state, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use ic_cdk::update;
+use rand::Rng;
+use types::{Milliseconds, TimestampMillis};
+use user_canister::create_swap_order::{Response::*, *};
+use user_canister::swap_tokens::ExchangeArgs;
+use user_canister::{swap_tokens, SwapOrder, SwapOrderStatus, SwapOrderType};
+use utils::time::{HOUR_IN_MS, MINUTE_IN_MS};
+
+const MAX_ACTIVE_SWAP_ORDERS: usize = 20;
+const LIMIT_ORDER_CHECK_INTERVAL: Milliseconds = MINUTE_IN_MS;
+const MIN_RECURRING_SWAP_INTERVAL: Milliseconds = HOUR_IN_MS;
+// Swaps are retried for a few minutes at most, so any swap unresolved after this long is treated as failed
+const MAX_SWAP_DURATION: Milliseconds = HOUR_IN_MS;
+
+#[update(guard = "caller_is_owner")]
+#[trace]
+fn create_swap_order(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| create_swap_order_impl(args, state))
+}
+
+fn create_swap_order_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.suspended.value {
+        return UserSuspended;
+    }
+
+    let now = state.env.now();
+    if let Err(error) = validate(&args, now) {
+        return InvalidRequest(error);
+    }
+
+    if state.data.swap_orders.active_count() >= MAX_ACTIVE_SWAP_ORDERS {
+        return TooManySwapOrders(MAX_ACTIVE_SWAP_ORDERS as u32);
+    }
+
+    // The PIN is verified now since the user won't be around to provide it when the swaps are made
+    if let Err(error) = state.data.pin_number.verify(args.pin.as_deref(), now) {
+        return match error {
+            VerifyPinError::PinRequired => PinRequired,
+            VerifyPinError::PinIncorrect(delay) => PinIncorrect(delay),
+            VerifyPinError::TooManyFailedAttempted(delay) => TooManyFailedPinAttempts(delay),
+        };
+    }
+
+    let first_check_at = match &args.order_type {
+        SwapOrderType::Limit(_) => now,
+        SwapOrderType::Recurring(r) => r.start_at.unwrap_or(now),
+    };
+
+    let id = state.data.swap_orders.next_id();
+    state.data.swap_orders.add(SwapOrder {
+        id,
+        input_token: args.input_token,
+        output_token: args.output_token,
+        exchange_args: args.exchange_args,
+        order_type: args.order_type,
+        created: now,
+        status: SwapOrderStatus::Active,
+        next_check_at: Some(first_check_at),
+        swaps: Vec::new(),
+        swap_in_progress: None,
+        total_input_amount: 0,
+        total_output_amount: 0,
+        last_error: None,
+    });
+
+    state.data.timer_jobs.enqueue_job(
+        TimerJob::ProcessSwapOrder(ProcessSwapOrderJob { order_id: id }),
+        first_check_at,
+        now,
+    );
+
+    Success(id)
+}
+
+fn validate(args: &Args, now: TimestampMillis) -> Result<(), String> {
+    if args.input_token.ledger == args.output_token.ledger {
+        return Err("Input and output tokens must be different".to_string());
+    }
+    if let ExchangeArgs::BestRoute(route_args) = &args.exchange_args {
+        if route_args.candidates.is_empty() {
+            return Err("At least one exchange must be provided".to_string());
+        }
+    }
+    if input_amount(&args.order_type) <= 2 * args.input_token.fee {
+        return Err("Input amount must be greater than the transfer fees".to_string());
+    }
+
+    match &args.order_type {
+        SwapOrderType::Limit(l) => {
+            if l.min_output_amount == 0 {
+                return Err("Minimum output amount must be greater than zero".to_string());
+            }
+            if l.expires_at.map_or(false, |ts| ts <= now) {
+                return Err("Expiry must be in the future".to_string());
+            }
+        }
+        SwapOrderType::Recurring(r) => {
+            if r.interval < MIN_RECURRING_SWAP_INTERVAL {
+                return Err(format!("Interval must be at least {MIN_RECURRING_SWAP_INTERVAL}ms"));
+            }
+            if r.max_slippage_bps > 10_000 {
+                return Err("Max slippage cannot exceed 10000 basis points".to_string());
+            }
+            if r.max_swaps == Some(0) {
+                return Err("Max swaps must be greater than zero".to_string());
+            }
+            if r.start_at.map_or(false, |ts| ts < now) {
+                return Err("Start time cannot be in the past".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+// Quotes the order and, if the quote is acceptable, makes the swap. The next check is scheduled before any
+// awaits so that the order keeps running even if this attempt fails part way through.
+pub(crate) async fn process_swap_order(order_id: u64) {
+    let Some(order) = mutate_state(|state| prepare_swap_order(order_id, state)) else {
+        return;
+    };
+
+    let input_amount = remaining_input_amount(&order);
+    let this_canister_id = read_state(|state| state.env.canister_id());
+    let (candidates, allow_split) = candidates(&order.exchange_args);
+
+    let (_, route) = find_best_route(
+        this_canister_id,
+        &order.input_token,
+        &order.output_token,
+        input_amount,
+        &candidates,
+        allow_split,
+    )
+    .await;
+
+    let min_output_amount = match (&order.order_type, route) {
+        (SwapOrderType::Limit(l), Some(route)) => {
+            let min_output_amount = limit_order_min_output_amount(l, input_amount);
+            if route.amount_out < min_output_amount {
+                return;
+            }
+            min_output_amount
+        }
+        (SwapOrderType::Limit(_), None) => return,
+        (SwapOrderType::Recurring(r), Some(route)) => route.amount_out - route.amount_out * r.max_slippage_bps as u128 / 10_000,
+        (SwapOrderType::Recurring(_), None) => {
+            mutate_state(|state| {
+                state
+                    .data
+                    .swap_orders
+                    .record_error(order_id, "Unable to get a quote".to_string())
+            });
+            return;
+        }
+    };
+
+    let Some(token_swap) = mutate_state(|state| start_swap(order, input_amount, min_output_amount, state)) else {
+        return;
+    };
+    let swap_id = token_swap.args.swap_id;
+
+    let error = match execute_token_swap(token_swap).await {
+        swap_tokens::Response::Success(_) => None,
+        // The swap will be retried in the background and resolved on a later check
+        swap_tokens::Response::InternalError(_) => return,
+        response => Some(format!("{response:?}")),
+    };
+
+    mutate_state(|state| {
+        let mut fill = swap_fill(swap_id, &state.data.token_swaps);
+        if error.is_some() {
+            fill.error = error;
+        }
+        state.data.swap_orders.record_swap_result(order_id, swap_id, fill);
+    });
+}
+
+// Schedules the next check, resolves any swap still in progress, then returns the order if a swap should be
+// attempted now
+fn prepare_swap_order(order_id: u64, state: &mut RuntimeState) -> Option<SwapOrder> {
+    let now = state.env.now();
+    let order = state.data.swap_orders.get(order_id)?;
+    if order.status != SwapOrderStatus::Active {
+        return None;
+    }
+
+    if let Some(swap_id) = order.swap_in_progress {
+        match state.data.token_swaps.status(swap_id) {
+            Some(status) if status.success.is_none() && now < status.started + MAX_SWAP_DURATION => {}
+            _ => {
+                let fill = swap_fill(swap_id, &state.data.token_swaps);
+                state.data.swap_orders.record_swap_result(order_id, swap_id, fill);
+            }
+        }
+    }
+
+    let order = state.data.swap_orders.get_mut(order_id)?;
+    if order.status != SwapOrderStatus::Active {
+        return None;
+    }
+
+    let next_check_at = match &order.order_type {
+        SwapOrderType::Limit(l) => {
+            if l.expires_at.map_or(false, |ts| ts <= now) {
+                order.status = SwapOrderStatus::Expired;
+                order.next_check_at = None;
+                return None;
+            }
+            let next = now + LIMIT_ORDER_CHECK_INTERVAL;
+            l.expires_at.map_or(next, |ts| ts.min(next))
+        }
+        SwapOrderType::Recurring(r) => next_recurring_run(order.next_check_at.unwrap_or(now), r.interval, now),
+    };
+    order.next_check_at = Some(next_check_at);
+
+    // Recurring swaps which are still in progress when the next one is due skip that run
+    let order = order.swap_in_progress.is_none().then(|| order.clone());
+
+    state.data.timer_jobs.enqueue_job(
+        TimerJob::ProcessSwapOrder(ProcessSwapOrderJob { order_id }),
+        next_check_at,
+        now,
+    );
+
+    order
+}
+
+fn start_swap(order: SwapOrder, input_amount: u128, min_output_amount: u128, state: &mut RuntimeState) -> Option<TokenSwap> {
+    // The order may have been cancelled while it was being quoted
+    let current = state.data.swap_orders.get(order.id)?;
+    if current.status != SwapOrderStatus::Active || current.swap_in_progress.is_some() {
+        return None;
+    }
+
+    let now = state.env.now();
+    let swap_id: u128 = state.env.rng().gen();
+    let token_swap = state.data.token_swaps.push_new(
+        swap_tokens::Args {
+            swap_id,
+            input_token: order.input_token,
+            output_token: order.output_token,
+            input_amount,
+            exchange_args: order.exchange_args,
+            min_output_amount,
+            pin: None,
+        },
+        now,
+    );
+    state.data.swap_orders.record_swap_started(order.id, swap_id);
+
+    Some(token_swap)
+}
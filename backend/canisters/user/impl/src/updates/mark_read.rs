@@ -2,6 +2,7 @@ use crate::guards::caller_is_owner;
 use crate::{mutate_state, run_regular_jobs, RuntimeState};
 use canister_tracing_macros::trace;
 use ic_cdk::update;
+use types::{ChatId, CommunityId};
 use user_canister::mark_read::{Response::*, *};
 use user_canister::{MarkMessagesReadArgs, UserCanisterEvent};
 use utils::consts::OPENCHAT_BOT_USER_ID;
@@ -19,12 +20,20 @@ fn mark_read_impl(args: Args, state: &mut RuntimeState) -> Response {
 
     for chat_messages_read in args.messages_read {
         if let Some(group_chat) = state.data.group_chats.get_mut(&chat_messages_read.chat_id) {
-            group_chat.mark_read(
+            if let Some(advanced) = group_chat.mark_read(
                 chat_messages_read.read_up_to,
                 chat_messages_read.threads,
                 chat_messages_read.date_read_pinned,
                 now,
-            );
+            ) {
+                ic_cdk::spawn(report_group_read_up_to(
+                    chat_messages_read.chat_id,
+                    group_canister::c2c_mark_read::Args {
+                        read_up_to: advanced.read_up_to,
+                        previous: advanced.previous,
+                    },
+                ));
+            }
         } else if let Some(direct_chat) = state.data.direct_chats.get_mut(&chat_messages_read.chat_id) {
             if let Some(read_up_to) = chat_messages_read.read_up_to {
                 if read_up_to
@@ -55,9 +64,60 @@ fn mark_read_impl(args: Args, state: &mut RuntimeState) -> Response {
 
     for community_messages_read in args.community_messages_read {
         if let Some(community) = state.data.communities.get_mut(&community_messages_read.community_id) {
-            community.mark_read(community_messages_read.channels_read, now);
+            let channels: Vec<_> = community
+                .mark_read(community_messages_read.channels_read, now)
+                .into_iter()
+                .map(|(channel_id, advanced)| community_canister::c2c_mark_read::ChannelReadUpTo {
+                    channel_id,
+                    read_up_to: advanced.read_up_to,
+                    previous: advanced.previous,
+                })
+                .collect();
+
+            // Read positions for all of the community's channels are batched into a single call
+            if !channels.is_empty() {
+                ic_cdk::spawn(report_channels_read_up_to(
+                    community_messages_read.community_id,
+                    community_canister::c2c_mark_read::Args { channels },
+                ));
+            }
         }
     }
 
     Success
 }
+
+// The responses tell us whether each chat has read receipts enabled, which we cache so that we can stop reporting reads
+// to chats which have them disabled
+async fn report_group_read_up_to(chat_id: ChatId, args: group_canister::c2c_mark_read::Args) {
+    let enabled = match group_canister_c2c_client::c2c_mark_read(chat_id.into(), &args).await {
+        Ok(group_canister::c2c_mark_read::Response::Success) => true,
+        Ok(group_canister::c2c_mark_read::Response::ReadReceiptsDisabled) => false,
+        _ => return,
+    };
+
+    mutate_state(|state| {
+        let now = state.env.now();
+        if let Some(group_chat) = state.data.group_chats.get_mut(&chat_id) {
+            group_chat.messages_read.set_read_receipts_enabled(enabled, now);
+        }
+    });
+}
+
+async fn report_channels_read_up_to(community_id: CommunityId, args: community_canister::c2c_mark_read::Args) {
+    let Ok(community_canister::c2c_mark_read::Response::Success(result)) =
+        community_canister_c2c_client::c2c_mark_read(community_id.into(), &args).await
+    else {
+        return;
+    };
+
+    mutate_state(|state| {
+        let now = state.env.now();
+        if let Some(community) = state.data.communities.get_mut(&community_id) {
+            for channel_id in args.channels.into_iter().map(|c| c.channel_id) {
+                let enabled = !result.read_receipts_disabled.contains(&channel_id);
+                community.set_read_receipts_enabled(channel_id, enabled, now);
+            }
+        }
+    });
+}
@@ -0,0 +1,35 @@
+use crate::guards::caller_is_owner;
+use crate::token_swaps::routing::find_best_route;
+use crate::{read_state, run_regular_jobs};
+use canister_tracing_macros::trace;
+use ic_cdk::update;
+use user_canister::quote_swap::{Response::*, *};
+
+// This is an update rather than a query because quoting requires calling the exchanges, which may be on other subnets
+#[update(guard = "caller_is_owner")]
+#[trace]
+async fn quote_swap(args: Args) -> Response {
+    run_regular_jobs();
+
+    let this_canister_id = read_state(|state| state.env.canister_id());
+
+    let (quotes, route) = find_best_route(
+        this_canister_id,
+        &args.input_token,
+        &args.output_token,
+        args.input_amount,
+        &args.candidates,
+        args.allow_split,
+    )
+    .await;
+
+    if let Some(route) = route {
+        Success(SuccessResult {
+            quotes,
+            route: route.legs.iter().map(|l| l.summary()).collect(),
+            amount_out: route.amount_out,
+        })
+    } else {
+        NoRouteFound
+    }
+}
@@ -48,6 +48,9 @@ fn build_c2c_args(args: &Args, state: &RuntimeState) -> Result<(c2c_report_messa
                     chat_id: Chat::Direct(args.them.into()),
                     thread_root_message_index: None,
                     message,
+                    edit_history: events_reader
+                        .message_edit_history(args.message_id.into(), Some(user_id))
+                        .unwrap_or_default(),
                     already_deleted: args.delete,
                     is_public: false,
                 },
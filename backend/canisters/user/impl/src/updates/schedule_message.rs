@@ -0,0 +1,335 @@
+use crate::guards::caller_is_owner;
+use crate::model::pin_number::VerifyPinError;
+use crate::model::scheduled_messages::MAX_SCHEDULED_MESSAGES;
+use crate::timer_job_types::{SendScheduledMessageJob, TimerJob};
+use crate::updates::send_message::send_message_v2_impl;
+use crate::updates::send_message_with_transfer::{
+    send_message_with_transfer_to_channel_impl, send_message_with_transfer_to_group_impl,
+};
+use crate::{mutate_state, openchat_bot, run_regular_jobs, RuntimeState};
+use canister_tracing_macros::trace;
+use ic_cdk::update;
+use rand::Rng;
+use types::{CanisterId, Chat, ContentValidationError, CryptoTransaction, MessageContentInitial, TimestampNanos, UserId};
+use user_canister::schedule_message::{Response::*, *};
+use user_canister::{
+    send_message_v2, send_message_with_transfer_to_channel, send_message_with_transfer_to_group, ScheduledMessage,
+};
+use utils::time::SECOND_IN_MS;
+
+// Sends which fail due to the call to the group or community failing are retried this many times
+const MAX_SEND_ATTEMPTS: u32 = 5;
+
+#[update(guard = "caller_is_owner")]
+#[trace]
+fn schedule_message(args: Args) -> Response {
+    run_regular_jobs();
+
+    mutate_state(|state| schedule_message_impl(args, state))
+}
+
+fn schedule_message_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if state.data.suspended.value {
+        return UserSuspended;
+    }
+
+    let now = state.env.now();
+    if args.send_at <= now {
+        return SendAtInThePast;
+    }
+
+    if state.data.scheduled_messages.is_full() {
+        return TooManyScheduledMessages(MAX_SCHEDULED_MESSAGES as u32);
+    }
+
+    let my_user_id: UserId = state.env.canister_id().into();
+    match args.chat {
+        Chat::Direct(chat_id) => {
+            if state.data.blocked_users.contains(&UserId::from(CanisterId::from(chat_id))) {
+                return RecipientBlocked;
+            }
+        }
+        Chat::Group(chat_id) => {
+            if !state.data.group_chats.exists(&chat_id) {
+                return ChatNotFound;
+            }
+        }
+        Chat::Channel(community_id, channel_id) => {
+            if !state
+                .data
+                .communities
+                .get(&community_id)
+                .map_or(false, |c| c.channels.contains_key(&channel_id))
+            {
+                return ChatNotFound;
+            }
+        }
+    }
+
+    match &args.content {
+        MessageContentInitial::Crypto(c) => {
+            if c.recipient == my_user_id {
+                return TransferCannotBeToSelf;
+            }
+            if state.data.blocked_users.contains(&c.recipient) {
+                return RecipientBlocked;
+            }
+            if !matches!(c.transfer, CryptoTransaction::Pending(_)) {
+                return InvalidRequest("Transaction must be of type 'Pending'".to_string());
+            }
+        }
+        MessageContentInitial::Prize(c) => {
+            if !matches!(c.transfer, CryptoTransaction::Pending(_)) {
+                return InvalidRequest("Transaction must be of type 'Pending'".to_string());
+            }
+        }
+        MessageContentInitial::P2PSwap(_) if args.chat == Chat::Direct(my_user_id.into()) => {
+            return TransferCannotBeToSelf;
+        }
+        _ => {}
+    }
+
+    // The content is validated as of the time it will be sent
+    let is_direct_chat = matches!(args.chat, Chat::Direct(_));
+    if let Err(error) = args
+        .content
+        .validate_for_new_message(is_direct_chat, false, false, args.send_at)
+    {
+        return match error {
+            ContentValidationError::Empty => MessageEmpty,
+            ContentValidationError::TextTooLong(max_length) => TextTooLong(max_length),
+            ContentValidationError::InvalidPoll(reason) => InvalidPoll(reason),
+            ContentValidationError::TransferCannotBeZero => TransferCannotBeZero,
+            ContentValidationError::PrizeEndDateInThePast => {
+                InvalidRequest("Prize end date must be after the message is sent".to_string())
+            }
+            ContentValidationError::InvalidPrizeGate => {
+                InvalidRequest("Prize gates cannot require payments or verified credentials".to_string())
+            }
+            ContentValidationError::InvalidTypeForForwarding | ContentValidationError::Unauthorized => {
+                InvalidRequest("User unauthorized to send messages of this type".to_string())
+            }
+        };
+    }
+
+    // The PIN is verified now since the user won't be around to provide it when the message is sent
+    if args.content.contains_crypto_transfer() {
+        if let Err(error) = state.data.pin_number.verify(args.pin.as_deref(), now) {
+            return match error {
+                VerifyPinError::PinRequired => PinRequired,
+                VerifyPinError::PinIncorrect(delay) => PinIncorrect(delay),
+                VerifyPinError::TooManyFailedAttempted(delay) => TooManyFailedPinAttempts(delay),
+            };
+        }
+    }
+
+    let id = state.data.scheduled_messages.next_id();
+    state.data.scheduled_messages.add(ScheduledMessage {
+        id,
+        chat: args.chat,
+        thread_root_message_index: args.thread_root_message_index,
+        // Generated up front so that if a send is retried, the recipient can recognise it as the same message
+        message_id: state.env.rng().gen(),
+        content: args.content,
+        mentioned: args.mentioned,
+        block_level_markdown: args.block_level_markdown,
+        created: now,
+        send_at: args.send_at,
+    });
+
+    state.data.timer_jobs.enqueue_job(
+        TimerJob::SendScheduledMessage(SendScheduledMessageJob {
+            scheduled_message_id: id,
+            attempt: 0,
+        }),
+        args.send_at,
+        now,
+    );
+
+    Success(id)
+}
+
+// If the message fails to send, the user is notified via a message from the OpenChat Bot
+pub(crate) async fn send_scheduled_message(message: ScheduledMessage, attempt: u32) {
+    if let Err(Some(error)) = send_scheduled_message_inner(message, attempt).await {
+        mutate_state(|state| {
+            openchat_bot::send_text_message(
+                format!("Your scheduled message could not be sent. Error: {error}"),
+                Vec::new(),
+                false,
+                state,
+            );
+        });
+    }
+}
+
+// Returns `Err(None)` if the message will be retried
+async fn send_scheduled_message_inner(mut message: ScheduledMessage, attempt: u32) -> Result<(), Option<String>> {
+    let message_id = message.message_id;
+    let (sender_name, sender_display_name, now_nanos) = mutate_state(|state| {
+        (
+            state.data.username.value.clone(),
+            state.data.display_name.value.clone(),
+            state.env.now_nanos(),
+        )
+    });
+    refresh_pending_transfer(&mut message.content, now_nanos);
+
+    let has_transfer = message.content.contains_crypto_transfer();
+    let retry = Retry {
+        message: message.clone(),
+        attempt,
+    };
+
+    match message.chat {
+        Chat::Direct(chat_id) => {
+            let args = send_message_v2::Args {
+                recipient: CanisterId::from(chat_id).into(),
+                thread_root_message_index: message.thread_root_message_index,
+                message_id,
+                content: message.content,
+                replies_to: None,
+                forwarding: false,
+                block_level_markdown: message.block_level_markdown,
+                message_filter_failed: None,
+                pin: None,
+                correlation_id: 0,
+            };
+            match send_message_v2_impl(args, false).await {
+                send_message_v2::Response::Success(_) | send_message_v2::Response::TransferSuccessV2(_) => Ok(()),
+                response => Err(Some(format!("{response:?}"))),
+            }
+        }
+        Chat::Group(group_id) if has_transfer => {
+            let args = send_message_with_transfer_to_group::Args {
+                group_id,
+                thread_root_message_index: message.thread_root_message_index,
+                message_id,
+                content: message.content,
+                sender_name,
+                sender_display_name,
+                replies_to: None,
+                mentioned: message.mentioned,
+                block_level_markdown: message.block_level_markdown,
+                correlation_id: 0,
+                rules_accepted: None,
+                message_filter_failed: None,
+                pin: None,
+            };
+            use send_message_with_transfer_to_group::Response;
+            match send_message_with_transfer_to_group_impl(args, true).await {
+                Response::Success(_) | Response::Retrying(..) => Ok(()),
+                response => Err(Some(format!("{response:?}"))),
+            }
+        }
+        Chat::Channel(community_id, channel_id) if has_transfer => {
+            let args = send_message_with_transfer_to_channel::Args {
+                community_id,
+                channel_id,
+                thread_root_message_index: message.thread_root_message_index,
+                message_id,
+                content: message.content,
+                sender_name,
+                sender_display_name,
+                replies_to: None,
+                mentioned: message.mentioned,
+                block_level_markdown: message.block_level_markdown,
+                community_rules_accepted: None,
+                channel_rules_accepted: None,
+                message_filter_failed: None,
+                pin: None,
+            };
+            use send_message_with_transfer_to_channel::Response;
+            match send_message_with_transfer_to_channel_impl(args, true).await {
+                Response::Success(_) | Response::Retrying(..) => Ok(()),
+                response => Err(Some(format!("{response:?}"))),
+            }
+        }
+        // These go through the same endpoints the user would call directly, so that slow mode and any restrictions on
+        // who can send messages are applied as of the time the message is sent
+        Chat::Group(group_id) => {
+            let args = group_canister::send_message_v2::Args {
+                thread_root_message_index: message.thread_root_message_index,
+                message_id,
+                content: message.content,
+                sender_name,
+                sender_display_name,
+                replies_to: None,
+                mentioned: message.mentioned,
+                forwarding: false,
+                block_level_markdown: message.block_level_markdown,
+                rules_accepted: None,
+                message_filter_failed: None,
+                correlation_id: 0,
+            };
+            match group_canister_c2c_client::send_message_v2(group_id.into(), &args).await {
+                Ok(group_canister::send_message_v2::Response::Success(_)) => Ok(()),
+                Ok(response) => Err(Some(format!("{response:?}"))),
+                Err(error) => Err(retry_or_fail(retry, format!("{error:?}"))),
+            }
+        }
+        Chat::Channel(community_id, channel_id) => {
+            let args = community_canister::send_message::Args {
+                channel_id,
+                thread_root_message_index: message.thread_root_message_index,
+                message_id,
+                content: message.content,
+                sender_name,
+                sender_display_name,
+                replies_to: None,
+                mentioned: message.mentioned,
+                forwarding: false,
+                block_level_markdown: message.block_level_markdown,
+                community_rules_accepted: None,
+                channel_rules_accepted: None,
+                message_filter_failed: None,
+            };
+            match community_canister_c2c_client::send_message(community_id.into(), &args).await {
+                Ok(community_canister::send_message::Response::Success(_)) => Ok(()),
+                Ok(response) => Err(Some(format!("{response:?}"))),
+                Err(error) => Err(retry_or_fail(retry, format!("{error:?}"))),
+            }
+        }
+    }
+}
+
+// If the call failed, the message is scheduled again shortly afterwards unless it has already been attempted too many
+// times, in which case the error is returned so that the user is notified
+fn retry_or_fail(retry: Retry, error: String) -> Option<String> {
+    if retry.attempt >= MAX_SEND_ATTEMPTS {
+        return Some(error);
+    }
+
+    mutate_state(|state| {
+        let now = state.env.now();
+        let scheduled_message_id = retry.message.id;
+        state.data.scheduled_messages.add(retry.message);
+        state.data.timer_jobs.enqueue_job(
+            TimerJob::SendScheduledMessage(SendScheduledMessageJob {
+                scheduled_message_id,
+                attempt: retry.attempt + 1,
+            }),
+            now + 10 * SECOND_IN_MS,
+            now,
+        );
+    });
+    None
+}
+
+struct Retry {
+    message: ScheduledMessage,
+    attempt: u32,
+}
+
+// Pending transfers are created when the message is scheduled, which may be too long ago for the ledger to accept them
+fn refresh_pending_transfer(content: &mut MessageContentInitial, now_nanos: TimestampNanos) {
+    let transfer = match content {
+        MessageContentInitial::Crypto(c) => &mut c.transfer,
+        MessageContentInitial::Prize(c) => &mut c.transfer,
+        _ => return,
+    };
+
+    if let CryptoTransaction::Pending(pending) = transfer {
+        pending.set_created(now_nanos);
+    }
+}
@@ -22,10 +22,15 @@ use utils::consts::{MEMO_MESSAGE, OPENCHAT_BOT_USER_ID};
 // and then update the message content to contain the completed transfer.
 #[update(guard = "caller_is_owner")]
 #[trace]
-async fn send_message_v2(mut args: Args) -> Response {
+async fn send_message_v2(args: Args) -> Response {
     run_regular_jobs();
 
-    let (my_user_id, user_type) = match mutate_state(|state| validate_request(&args, state)) {
+    send_message_v2_impl(args, true).await
+}
+
+// `verify_pin` is false when sending a scheduled message, since the PIN was verified when the message was scheduled
+pub(crate) async fn send_message_v2_impl(mut args: Args, verify_pin: bool) -> Response {
+    let (my_user_id, user_type) = match mutate_state(|state| validate_request(&args, verify_pin, state)) {
         ValidateRequestResult::Valid(u, t) => (u, t),
         ValidateRequestResult::Invalid(response) => return response,
         ValidateRequestResult::RecipientUnknown(u, local_user_index_canister_id) => {
@@ -83,6 +88,8 @@ async fn send_message_v2(mut args: Args) -> Response {
                 token0_amount: p.token0_amount,
                 token1: p.token1.clone(),
                 token1_amount: p.token1_amount,
+                additional_token0s: Vec::new(),
+                min_fill_token1_amount: None,
                 expires_at: now + p.expires_in,
                 additional_admins: Vec::new(),
                 canister_to_notify: Some(args.recipient.into()),
@@ -132,7 +139,7 @@ enum ValidateRequestResult {
     RecipientUnknown(UserId, CanisterId), // UserId, UserIndexCanisterId
 }
 
-fn validate_request(args: &Args, state: &mut RuntimeState) -> ValidateRequestResult {
+fn validate_request(args: &Args, verify_pin: bool, state: &mut RuntimeState) -> ValidateRequestResult {
     if state.data.suspended.value {
         return ValidateRequestResult::Invalid(UserSuspended);
     }
@@ -156,7 +163,7 @@ fn validate_request(args: &Args, state: &mut RuntimeState) -> ValidateRequestRes
     let now = state.env.now();
     let my_user_id: UserId = state.env.canister_id().into();
 
-    if args.content.contains_crypto_transfer() {
+    if verify_pin && args.content.contains_crypto_transfer() {
         if let Err(error) = state.data.pin_number.verify(args.pin.as_deref(), now) {
             return ValidateRequestResult::Invalid(match error {
                 VerifyPinError::PinRequired => PinRequired,
@@ -175,7 +182,7 @@ fn validate_request(args: &Args, state: &mut RuntimeState) -> ValidateRequestRes
             ContentValidationError::InvalidTypeForForwarding => {
                 InvalidRequest("Cannot forward this type of message".to_string())
             }
-            ContentValidationError::PrizeEndDateInThePast => unreachable!(),
+            ContentValidationError::PrizeEndDateInThePast | ContentValidationError::InvalidPrizeGate => unreachable!(),
             ContentValidationError::Unauthorized => {
                 InvalidRequest("User unauthorized to send messages of this type".to_string())
             }
@@ -22,10 +22,19 @@ use utils::time::{NANOS_PER_MILLISECOND, SECOND_IN_MS};
 async fn send_message_with_transfer_to_channel(
     args: send_message_with_transfer_to_channel::Args,
 ) -> send_message_with_transfer_to_channel::Response {
-    use send_message_with_transfer_to_channel::Response::*;
-
     run_regular_jobs();
 
+    send_message_with_transfer_to_channel_impl(args, false).await
+}
+
+// `is_scheduled` is true when sending a scheduled message, in which case the PIN was verified when the message was
+// scheduled and the caller is not the owner
+pub(crate) async fn send_message_with_transfer_to_channel_impl(
+    args: send_message_with_transfer_to_channel::Args,
+    is_scheduled: bool,
+) -> send_message_with_transfer_to_channel::Response {
+    use send_message_with_transfer_to_channel::Response::*;
+
     // Check that the user is a member of the community
     let (exists, now) = read_state(|state| (state.data.communities.exists(&args.community_id), state.env.now()));
     if !exists {
@@ -42,6 +51,7 @@ async fn send_message_with_transfer_to_channel(
             args.message_id,
             &args.content,
             args.pin,
+            !is_scheduled,
             now,
             state,
         )
@@ -65,11 +75,11 @@ async fn send_message_with_transfer_to_channel(
     };
 
     // Make the crypto transfer
-    let (content, completed_transaction) = match process_transaction(args.content, pending_transaction, p2p_swap_id, now).await
-    {
-        Ok((c, t)) => (c, t),
-        Err(error) => return TransferFailed(error),
-    };
+    let (content, completed_transaction) =
+        match process_transaction(args.content, pending_transaction, p2p_swap_id, !is_scheduled, now).await {
+            Ok((c, t)) => (c, t),
+            Err(error) => return TransferFailed(error),
+        };
 
     // Build the send_message args
     let c2c_args = community_canister::c2c_send_message::Args {
@@ -106,9 +116,11 @@ async fn send_message_with_transfer_to_channel(
             Response::CommunityFrozen => CommunityFrozen,
             Response::RulesNotAccepted => RulesNotAccepted,
             Response::CommunityRulesNotAccepted => CommunityRulesNotAccepted,
+            // The channel may restrict sending to members of certain user groups or be in slow mode
+            Response::NotAuthorized => NotAuthorized(completed_transaction),
+            Response::RateLimited(retry_at) => RateLimited(retry_at, completed_transaction),
             Response::MessageEmpty
             | Response::InvalidPoll(_)
-            | Response::NotAuthorized
             | Response::ThreadMessageNotFound
             | Response::InvalidRequest(_)
             | Response::TextTooLong(_) => unreachable!(),
@@ -137,10 +149,19 @@ async fn send_message_with_transfer_to_channel(
 async fn send_message_with_transfer_to_group(
     args: send_message_with_transfer_to_group::Args,
 ) -> send_message_with_transfer_to_group::Response {
-    use send_message_with_transfer_to_group::Response::*;
-
     run_regular_jobs();
 
+    send_message_with_transfer_to_group_impl(args, false).await
+}
+
+// `is_scheduled` is true when sending a scheduled message, in which case the PIN was verified when the message was
+// scheduled and the caller is not the owner
+pub(crate) async fn send_message_with_transfer_to_group_impl(
+    args: send_message_with_transfer_to_group::Args,
+    is_scheduled: bool,
+) -> send_message_with_transfer_to_group::Response {
+    use send_message_with_transfer_to_group::Response::*;
+
     // Check that the user is a member of the group
     let (exists, now) = read_state(|state| (state.data.group_chats.exists(&args.group_id), state.env.now()));
     if !exists {
@@ -157,6 +178,7 @@ async fn send_message_with_transfer_to_group(
             args.message_id,
             &args.content,
             args.pin,
+            !is_scheduled,
             now,
             state,
         )
@@ -180,11 +202,11 @@ async fn send_message_with_transfer_to_group(
     };
 
     // Make the crypto transfer
-    let (content, completed_transaction) = match process_transaction(args.content, pending_transaction, p2p_swap_id, now).await
-    {
-        Ok((c, t)) => (c, t),
-        Err(error) => return TransferFailed(error),
-    };
+    let (content, completed_transaction) =
+        match process_transaction(args.content, pending_transaction, p2p_swap_id, !is_scheduled, now).await {
+            Ok((c, t)) => (c, t),
+            Err(error) => return TransferFailed(error),
+        };
 
     // Build the send_message args
     let c2c_args = group_canister::c2c_send_message::Args {
@@ -217,9 +239,10 @@ async fn send_message_with_transfer_to_group(
             Response::UserSuspended => UserSuspended,
             Response::ChatFrozen => ChatFrozen,
             Response::RulesNotAccepted => RulesNotAccepted,
+            Response::NotAuthorized => NotAuthorized(completed_transaction),
+            Response::RateLimited(retry_at) => RateLimited(retry_at, completed_transaction),
             Response::MessageEmpty
             | Response::InvalidPoll(_)
-            | Response::NotAuthorized
             | Response::ThreadMessageNotFound
             | Response::InvalidRequest(_)
             | Response::TextTooLong(_) => unreachable!(),
@@ -263,6 +286,7 @@ fn prepare(
     message_id: MessageId,
     content: &MessageContentInitial,
     pin: Option<String>,
+    verify_pin: bool,
     now: TimestampMillis,
     state: &mut RuntimeState,
 ) -> PrepareResult {
@@ -274,12 +298,14 @@ fn prepare(
         return TextTooLong(MAX_TEXT_LENGTH);
     }
 
-    if let Err(error) = state.data.pin_number.verify(pin.as_deref(), now) {
-        return match error {
-            VerifyPinError::PinRequired => PinRequired,
-            VerifyPinError::PinIncorrect(delay) => PinIncorrect(delay),
-            VerifyPinError::TooManyFailedAttempted(delay) => TooManyFailedPinAttempts(delay),
-        };
+    if verify_pin {
+        if let Err(error) = state.data.pin_number.verify(pin.as_deref(), now) {
+            return match error {
+                VerifyPinError::PinRequired => PinRequired,
+                VerifyPinError::PinIncorrect(delay) => PinIncorrect(delay),
+                VerifyPinError::TooManyFailedAttempted(delay) => TooManyFailedPinAttempts(delay),
+            };
+        }
     }
 
     let pending_transaction = match &content {
@@ -323,6 +349,8 @@ fn prepare(
                 token0_amount: p.token0_amount,
                 token1: p.token1.clone(),
                 token1_amount: p.token1_amount,
+                additional_token0s: Vec::new(),
+                min_fill_token1_amount: None,
                 expires_at: now + p.expires_in,
                 additional_admins: vec![chat_canister_id],
                 canister_to_notify: Some(chat_canister_id),
@@ -343,9 +371,16 @@ async fn process_transaction(
     content: MessageContentInitial,
     pending_transaction: PendingCryptoTransaction,
     p2p_swap_id: Option<u32>,
+    check_caller: bool,
     now: TimestampMillis,
 ) -> Result<(MessageContentInternal, CompletedCryptoTransaction), String> {
-    match crate::crypto::process_transaction(pending_transaction).await {
+    let result = if check_caller {
+        crate::crypto::process_transaction(pending_transaction).await
+    } else {
+        crate::crypto::process_transaction_without_caller_check(pending_transaction).await
+    };
+
+    match result {
         Ok(completed) => {
             if let Some(id) = p2p_swap_id {
                 NotifyEscrowCanisterOfDepositJob::run(id);
@@ -2,13 +2,12 @@ use crate::guards::caller_is_owner;
 use crate::model::pin_number::VerifyPinError;
 use crate::model::token_swaps::TokenSwap;
 use crate::timer_job_types::{ProcessTokenSwapJob, TimerJob};
-use crate::token_swaps::swap_client::SwapClient;
+use crate::token_swaps::build_swap_client;
+use crate::token_swaps::routing::find_best_route;
 use crate::{mutate_state, read_state, run_regular_jobs, Data, RuntimeState};
 use canister_tracing_macros::trace;
 use ic_cdk::update;
-use icpswap_client::ICPSwapClient;
 use icrc_ledger_types::icrc1::transfer::TransferArg;
-use sonic_client::SonicClient;
 use tracing::{error, info};
 use types::{TimestampMillis, Timestamped};
 use user_canister::swap_tokens::{Response::*, *};
@@ -25,7 +24,7 @@ async fn swap_tokens(args: Args) -> Response {
         Err(response) => return response,
     };
 
-    process_token_swap(token_swap, 0, false).await
+    execute_token_swap(token_swap).await
 }
 
 fn prepare(args: Args, state: &mut RuntimeState) -> Result<TokenSwap, Response> {
@@ -42,13 +41,136 @@ fn prepare(args: Args, state: &mut RuntimeState) -> Result<TokenSwap, Response>
     Ok(state.data.token_swaps.push_new(args, now))
 }
 
+pub(crate) async fn execute_token_swap(token_swap: TokenSwap) -> Response {
+    if let ExchangeArgs::BestRoute(route_args) = token_swap.args.exchange_args.clone() {
+        process_best_route_swap(token_swap, route_args).await
+    } else {
+        process_token_swap(token_swap, 0, false).await
+    }
+}
+
+// Chooses the route, records it against the swap, then processes each leg of the route as its own swap
+async fn process_best_route_swap(mut token_swap: TokenSwap, route_args: BestRouteArgs) -> Response {
+    let args = token_swap.args.clone();
+    let this_canister_id = read_state(|state| state.env.canister_id());
+
+    let (_, route) = find_best_route(
+        this_canister_id,
+        &args.input_token,
+        &args.output_token,
+        args.input_amount,
+        &route_args.candidates,
+        route_args.allow_split,
+    )
+    .await;
+
+    let route = match route {
+        Some(r) if r.amount_out >= args.min_output_amount => r,
+        r => {
+            mutate_state(|state| {
+                let now = state.env.now();
+                token_swap.route = Some(Vec::new());
+                token_swap.success = Some(Timestamped::new(false, now));
+                state.data.token_swaps.upsert(token_swap);
+            });
+            return if r.is_some() { SwapFailed } else { NoRouteFound };
+        }
+    };
+
+    let legs: Vec<_> = mutate_state(|state| {
+        let now = state.env.now();
+        token_swap.route = Some(route.legs.iter().map(|l| l.summary()).collect());
+        state.data.token_swaps.upsert(token_swap.clone());
+
+        route
+            .legs
+            .iter()
+            .enumerate()
+            .map(|(index, leg)| {
+                let leg_swap = TokenSwap::new_leg(
+                    Args {
+                        input_amount: leg.input_amount,
+                        exchange_args: leg.exchange_args.clone(),
+                        min_output_amount: min_output_amount_for_leg(
+                            args.min_output_amount,
+                            leg.expected_amount_out,
+                            route.amount_out,
+                        ),
+                        pin: None,
+                        ..args.clone()
+                    },
+                    index as u32,
+                    now,
+                );
+                state.data.token_swaps.upsert(leg_swap.clone());
+                leg_swap
+            })
+            .collect()
+    });
+
+    let mut amount_out = 0;
+    let mut unspent_input_withdrawn = 0;
+    let mut any_failed = false;
+    let mut internal_error = None;
+    for leg in legs {
+        match process_token_swap(leg, 0, false).await {
+            Success(result) => {
+                amount_out += result.amount_out;
+                unspent_input_withdrawn += result.unspent_input_withdrawn;
+            }
+            // The leg will be retried in the background
+            InternalError(error) => internal_error = Some(error),
+            _ => any_failed = true,
+        }
+    }
+
+    if let Some(error) = internal_error {
+        return InternalError(error);
+    }
+
+    mutate_state(|state| {
+        let now = state.env.now();
+        token_swap.success = Some(Timestamped::new(!any_failed, now));
+        state.data.token_swaps.upsert(token_swap);
+    });
+
+    if any_failed {
+        SwapFailed
+    } else {
+        Success(SuccessResult {
+            amount_out,
+            unspent_input_withdrawn,
+        })
+    }
+}
+
+// Each leg's minimum is its share of the overall minimum, in proportion to its expected amount out
+fn min_output_amount_for_leg(min_output_amount: u128, leg_expected_amount_out: u128, total_expected_amount_out: u128) -> u128 {
+    if total_expected_amount_out == 0 {
+        return 0;
+    }
+    min_output_amount
+        .checked_mul(leg_expected_amount_out)
+        .map(|a| a / total_expected_amount_out)
+        .unwrap_or_else(|| min_output_amount / total_expected_amount_out * leg_expected_amount_out)
+}
+
 pub(crate) async fn process_token_swap(mut token_swap: TokenSwap, attempt: u32, debug: bool) -> Response {
     if debug {
         info!(swap_id = %token_swap.args.swap_id, "Swap started");
     }
 
     let args = token_swap.args.clone();
-    let swap_client = read_state(|state| build_swap_client(&args, state));
+    let Some(swap_client) = read_state(|state| {
+        build_swap_client(
+            &args.exchange_args,
+            args.input_token.clone(),
+            args.output_token.clone(),
+            state.env.canister_id(),
+        )
+    }) else {
+        return InternalError("Swap must be routed before it can be processed".to_string());
+    };
 
     let account = if let Some(a) = extract_result(&token_swap.deposit_account) {
         *a
@@ -146,7 +268,11 @@ pub(crate) async fn process_token_swap(mut token_swap: TokenSwap, attempt: u32,
             .swap(amount_to_dex.saturating_sub(args.input_token.fee), args.min_output_amount)
             .await
         {
-            Ok(a) => {
+            Ok(result) => {
+                let a = result.map(|r| {
+                    token_swap.unspent_input_withdrawn = r.unspent_input_withdrawn;
+                    r.amount_out
+                });
                 mutate_state(|state| {
                     let now = state.env.now();
                     token_swap.amount_swapped = Some(Timestamped::new(Ok(a.clone()), now));
@@ -168,6 +294,7 @@ pub(crate) async fn process_token_swap(mut token_swap: TokenSwap, attempt: u32,
         }
     };
 
+    let unspent_input_withdrawn = token_swap.unspent_input_withdrawn;
     let (successful_swap, amount_out) = if let Ok(amount_swapped) = swap_result {
         (true, amount_swapped.saturating_sub(args.output_token.fee))
     } else {
@@ -201,41 +328,15 @@ pub(crate) async fn process_token_swap(mut token_swap: TokenSwap, attempt: u32,
     }
 
     if successful_swap {
-        Success(SuccessResult { amount_out })
+        Success(SuccessResult {
+            amount_out,
+            unspent_input_withdrawn,
+        })
     } else {
         SwapFailed
     }
 }
 
-fn build_swap_client(args: &Args, state: &RuntimeState) -> Box<dyn SwapClient> {
-    let this_canister_id = state.env.canister_id();
-    let input_token = args.input_token.clone();
-    let output_token = args.output_token.clone();
-
-    match &args.exchange_args {
-        ExchangeArgs::ICPSwap(icpswap) => {
-            let (token0, token1) = if icpswap.zero_for_one { (input_token, output_token) } else { (output_token, input_token) };
-            Box::new(ICPSwapClient::new(
-                this_canister_id,
-                icpswap.swap_canister_id,
-                token0,
-                token1,
-                icpswap.zero_for_one,
-            ))
-        }
-        ExchangeArgs::Sonic(sonic) => {
-            let (token0, token1) = if sonic.zero_for_one { (input_token, output_token) } else { (output_token, input_token) };
-            Box::new(SonicClient::new(
-                this_canister_id,
-                sonic.swap_canister_id,
-                token0,
-                token1,
-                sonic.zero_for_one,
-            ))
-        }
-    }
-}
-
 fn enqueue_token_swap(token_swap: TokenSwap, attempt: u32, now: TimestampMillis, data: &mut Data) {
     if attempt < 20 {
         data.timer_jobs.enqueue_job(
@@ -255,9 +356,10 @@ fn extract_result<T>(subtask: &Option<Timestamped<Result<T, String>>>) -> Option
 }
 
 fn log_error(message: &str, error: &str, args: &Args, attempt: u32) {
+    let exchange_id = args.exchange_args.exchange_id().map(|e| e.to_string()).unwrap_or_default();
     error!(
         swap_id = %args.swap_id,
-        exchange_id = %args.exchange_args.exchange_id(),
+        exchange_id = exchange_id.as_str(),
         input_token = args.input_token.token.token_symbol(),
         output_token = args.output_token.token.token_symbol(),
         error,
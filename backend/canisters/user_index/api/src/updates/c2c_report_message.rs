@@ -1,6 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
-use types::{Chat, Message, MessageIndex, UserId};
+use types::{Chat, Message, MessageIndex, MessageVersion, UserId};
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct Args {
@@ -8,6 +8,8 @@ pub struct Args {
     pub chat_id: Chat,
     pub thread_root_message_index: Option<MessageIndex>,
     pub message: Message,
+    #[serde(default)]
+    pub edit_history: Vec<MessageVersion>,
     pub already_deleted: bool,
     pub is_public: bool,
 }
@@ -4,6 +4,7 @@ use types::{CanisterId, UserDetails};
 use user_index_canister::*;
 
 // Queries
+generate_c2c_call!(c2c_bot_commands);
 generate_c2c_call!(c2c_lookup_user);
 generate_candid_c2c_call!(platform_moderators_group);
 generate_c2c_call!(user);
@@ -15,6 +16,7 @@ generate_c2c_call!(c2c_notify_chit);
 generate_c2c_call!(c2c_notify_events);
 generate_candid_c2c_call_with_payment!(c2c_register_bot);
 generate_c2c_call!(c2c_send_openchat_bot_messages);
+generate_c2c_call!(c2c_set_bot_commands);
 generate_c2c_call!(c2c_set_avatar);
 generate_c2c_call!(c2c_suspend_users);
 
@@ -8,6 +8,14 @@ pub fn caller_is_openchat_user() -> Result<(), String> {
     }
 }
 
+pub fn caller_is_bot() -> Result<(), String> {
+    if read_state(|state| state.is_caller_bot()) {
+        Ok(())
+    } else {
+        Err("Caller is not a bot".to_string())
+    }
+}
+
 pub fn caller_is_governance_principal() -> Result<(), String> {
     if read_state(|state| state.is_caller_governance_principal()) {
         Ok(())
@@ -77,6 +77,11 @@ impl RuntimeState {
         self.data.users.get_by_user_id(&caller.into()).is_some()
     }
 
+    pub fn is_caller_bot(&self) -> bool {
+        let caller = self.env.caller();
+        self.data.users.get_by_user_id(&caller.into()).map_or(false, |u| u.is_bot)
+    }
+
     pub fn is_caller_governance_principal(&self) -> bool {
         let caller = self.env.caller();
         self.data.governance_principals.contains(&caller)
@@ -3,9 +3,9 @@ use crate::{model::account_billing::AccountBilling, TIME_UNTIL_SUSPENDED_ACCOUNT
 use candid::{CandidType, Principal};
 use serde::{Deserialize, Serialize};
 use types::{
-    is_default, is_empty_slice, CyclesTopUp, CyclesTopUpInternal, PhoneNumber, RegistrationFee, SuspensionAction,
-    SuspensionDuration, TimestampMillis, UniquePersonProof, UserId, UserSummary, UserSummaryStable, UserSummaryV2,
-    UserSummaryVolatile,
+    is_default, is_empty_slice, CyclesTopUp, CyclesTopUpInternal, PhoneNumber, RegistrationFee, SlashCommandSchema,
+    SuspensionAction, SuspensionDuration, TimestampMillis, UniquePersonProof, UserId, UserSummary, UserSummaryStable,
+    UserSummaryV2, UserSummaryVolatile,
 };
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -62,6 +62,8 @@ pub struct User {
     pub latest_chit_event: TimestampMillis,
     #[serde(rename = "uh", default, skip_serializing_if = "Option::is_none")]
     pub unique_person_proof: Option<UniquePersonProof>,
+    #[serde(rename = "bc", default, skip_serializing_if = "is_empty_slice")]
+    pub bot_commands: Vec<SlashCommandSchema>,
 }
 
 impl User {
@@ -117,6 +119,7 @@ impl User {
             streak_ends: 0,
             latest_chit_event: 0,
             unique_person_proof: None,
+            bot_commands: Vec::new(),
         }
     }
 
@@ -229,6 +232,7 @@ impl Default for User {
             chit_updated: 0,
             latest_chit_event: 0,
             unique_person_proof: None,
+            bot_commands: Vec::new(),
         }
     }
 }
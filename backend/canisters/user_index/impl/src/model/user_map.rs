@@ -3,11 +3,12 @@ use crate::model::diamond_membership_details::DiamondMembershipDetailsInternal;
 use crate::model::user::User;
 use crate::DiamondMembershipUserMetrics;
 use candid::Principal;
+use search::Query;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, HashMap};
 use std::ops::RangeFrom;
 use tracing::info;
-use types::{CyclesTopUp, Milliseconds, SuspensionDuration, TimestampMillis, UniquePersonProof, UserId};
+use types::{CyclesTopUp, Milliseconds, SlashCommandSchema, SuspensionDuration, TimestampMillis, UniquePersonProof, UserId};
 use utils::case_insensitive_hash_map::CaseInsensitiveHashMap;
 
 #[derive(Serialize, Deserialize, Default)]
@@ -188,6 +189,16 @@ impl UserMap {
         }
     }
 
+    pub fn set_bot_commands(&mut self, user_id: &UserId, commands: Vec<SlashCommandSchema>, now: TimestampMillis) -> bool {
+        if let Some(user) = self.users.get_mut(user_id).filter(|u| u.is_bot) {
+            user.bot_commands = commands;
+            user.date_updated = now;
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn set_chit(
         &mut self,
         user_id: &UserId,
@@ -258,28 +269,36 @@ impl UserMap {
         self.users.get(user_id).map(|u| u.suspension_details.is_some())
     }
 
-    pub fn search(&self, term: &str) -> impl Iterator<Item = (&User, bool)> {
-        let term = term.to_uppercase();
-
-        self.username_to_user_id.iter().filter_map(move |(username, user_id)| {
-            if let Some(user) = self.users.get(user_id) {
-                let username_match = username.find(&term).map(|s| s == 0);
-                let display_name_match = user
-                    .display_name_upper
-                    .as_ref()
-                    .and_then(|name| name.find(&term).map(|s| s == 0));
+    // Users match if each of the query's clauses is matched by their username or display name, and
+    // none of the excluded terms are. The bool returned is true if the first term matches the start
+    // of the username or display name. Message filters (eg. "has:poll") don't apply to users, so
+    // queries consisting only of filters match no one.
+    pub fn search<'a>(&'a self, query: &Query) -> impl Iterator<Item = (&'a User, bool)> {
+        let filters_only = query.clauses.is_empty() && query.has_filters();
+        let clauses: Vec<Vec<String>> = query
+            .clauses
+            .iter()
+            .map(|c| c.iter().map(|t| t.to_uppercase()).collect())
+            .collect();
+        let excluded: Vec<String> = query.excluded_terms.iter().map(|t| t.to_uppercase()).collect();
 
-                if username_match == Some(true) || display_name_match == Some(true) {
-                    Some((user, true))
-                } else if username_match.is_some() || display_name_match.is_some() {
-                    Some((user, false))
+        self.username_to_user_id
+            .iter()
+            .take_while(move |_| !filters_only)
+            .filter_map(move |(username, user_id)| {
+                let user = self.users.get(user_id)?;
+                let display_name = user.display_name_upper.as_deref().unwrap_or_default();
+                let is_match = |term: &String| username.contains(term.as_str()) || display_name.contains(term.as_str());
+
+                if clauses.iter().all(|c| c.iter().any(is_match)) && !excluded.iter().any(is_match) {
+                    let starts_with = clauses.first().and_then(|c| c.first()).map_or(true, |t| {
+                        username.starts_with(t.as_str()) || display_name.starts_with(t.as_str())
+                    });
+                    Some((user, starts_with))
                 } else {
                     None
                 }
-            } else {
-                None
-            }
-        })
+            })
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &User> {
@@ -466,6 +485,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn search_ignores_filter_only_queries() {
+        let mut user_map = UserMap::default();
+        user_map.register(
+            Principal::from_slice(&[1]),
+            Principal::from_slice(&[1, 1]).into(),
+            "julian".to_string(),
+            1,
+            None,
+            false,
+        );
+        user_map.register(
+            Principal::from_slice(&[2]),
+            Principal::from_slice(&[2, 2]).into(),
+            "matt".to_string(),
+            2,
+            None,
+            false,
+        );
+
+        assert_eq!(user_map.search(&Query::parse("jul".to_string())).count(), 1);
+        assert_eq!(user_map.search(&Query::parse("jul OR mat".to_string())).count(), 2);
+        assert_eq!(user_map.search(&Query::parse("has:poll".to_string())).count(), 0);
+        assert_eq!(user_map.search(&Query::parse("in:thread".to_string())).count(), 0);
+    }
+
     #[test]
     fn update_with_no_clashes() {
         let mut user_map = UserMap::default();
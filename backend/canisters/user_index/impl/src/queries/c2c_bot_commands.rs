@@ -0,0 +1,18 @@
+use crate::{read_state, RuntimeState};
+use canister_api_macros::query_msgpack;
+use user_index_canister::c2c_bot_commands::{Response::*, *};
+
+#[query_msgpack]
+fn c2c_bot_commands(args: Args) -> Response {
+    read_state(|state| c2c_bot_commands_impl(args, state))
+}
+
+fn c2c_bot_commands_impl(args: Args, state: &RuntimeState) -> Response {
+    match state.data.users.get_by_user_id(&args.bot_id).filter(|u| u.is_bot) {
+        Some(bot) => Success(SuccessResult {
+            username: bot.username.clone(),
+            commands: bot.bot_commands.clone(),
+        }),
+        None => BotNotFound,
+    }
+}
@@ -23,6 +23,12 @@ fn c2c_lookup_user_impl(args: Args, state: &RuntimeState) -> Response {
             is_platform_moderator,
             is_platform_operator,
             is_diamond_member,
+            date_created: user.date_created,
+            chit_balance: user.chit_balance,
+            streak: user.streak(now),
+            referred_by: user.referred_by,
+            diamond_membership_expires_at: user.diamond_membership_details.expires_at(),
+            unique_person_proof: user.unique_person_proof.clone(),
         })
     } else {
         UserNotFound
@@ -1,3 +1,4 @@
+pub mod c2c_bot_commands;
 pub mod c2c_lookup_user;
 pub mod check_username;
 pub mod chit_leaderboard;
@@ -2,6 +2,7 @@ use crate::model::user::User;
 use crate::{read_state, RuntimeState};
 use core::cmp::Ordering;
 use ic_cdk::query;
+use search::Query;
 use user_index_canister::search::{Response::*, *};
 
 const MAX_SEARCH_TERM_LENGTH: usize = 25;
@@ -19,8 +20,10 @@ fn search_impl(args: Args, state: &RuntimeState) -> Response {
     let mut search_term = args.search_term.trim().to_string();
     search_term.truncate(MAX_SEARCH_TERM_LENGTH);
 
+    let query = Query::parse(search_term.clone());
+
     // Filter
-    let mut matches: Vec<(&User, bool)> = users.search(&search_term).filter(|(u, _)| u.principal != caller).collect();
+    let mut matches: Vec<(&User, bool)> = users.search(&query).filter(|(u, _)| u.principal != caller).collect();
 
     // Sort
     matches.sort_unstable_by(|(u1, u1_starts_ci), (u2, u2_starts_ci)| {
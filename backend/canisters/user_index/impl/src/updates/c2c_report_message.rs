@@ -10,7 +10,7 @@ use canister_api_macros::update_msgpack;
 use canister_tracing_macros::trace;
 use chat_events::deep_message_links;
 use modclub_canister::submitHtmlContent::Level;
-use types::{Chat, Message, MessageContent, MessageIndex};
+use types::{Chat, Message, MessageContent, MessageIndex, MessageVersion};
 use user_index_canister::c2c_report_message::{Response::*, *};
 
 #[update_msgpack(guard = "caller_is_user_canister_or_group_index")]
@@ -49,7 +49,13 @@ fn c2c_report_message_impl(args: Args, state: &mut RuntimeState) -> Response {
     state.queue_modclub_submission(PendingModclubSubmission {
         report_index,
         title: construct_report_title(args.chat_id, args.thread_root_message_index, &args.message),
-        html_report: construct_html_report(args.chat_id, args.thread_root_message_index, &args.message, args.is_public),
+        html_report: construct_html_report(
+            args.chat_id,
+            args.thread_root_message_index,
+            &args.message,
+            &args.edit_history,
+            args.is_public,
+        ),
         level: Level::simple,
     });
 
@@ -65,6 +71,7 @@ fn construct_html_report(
     chat_id: Chat,
     thread_root_message_index: Option<MessageIndex>,
     message: &Message,
+    edit_history: &[MessageVersion],
     is_public: bool,
 ) -> String {
     let content = &message.content;
@@ -116,7 +123,15 @@ fn construct_html_report(
     // 2. Text/caption
     html.push_str(&markdown_to_html(&extract_text(content)));
 
-    // 3. Message link
+    // 3. Previous versions of the text/caption, most recent first
+    if !edit_history.is_empty() {
+        html.push_str("<h4>Previous versions</h4>\n");
+        for version in edit_history.iter().rev() {
+            html.push_str(&markdown_to_html(&extract_text(&version.content)));
+        }
+    }
+
+    // 4. Message link
     if is_public {
         let message_link = deep_message_links::build_message_link(chat_id, thread_root_message_index, message.message_index);
         html.push_str(&format!("<a href=\"{}\">link to message</a>\n", message_link));
@@ -195,7 +210,7 @@ https://github.com/open-chat-labs/open-chat/commit/e93865ea29b5bab8a9f0b01052938
             block_level_markdown: false,
         };
 
-        let report = construct_html_report(Chat::Group(chat_id), None, &message, true);
+        let report = construct_html_report(Chat::Group(chat_id), None, &message, &[], true);
 
         print!("{}", report);
     }
@@ -231,7 +246,7 @@ https://github.com/open-chat-labs/open-chat/commit/e93865ea29b5bab8a9f0b01052938
             block_level_markdown: false,
         };
 
-        let report = construct_html_report(chat, None, &message, true);
+        let report = construct_html_report(chat, None, &message, &[], true);
 
         print!("{}", report);
     }
@@ -271,7 +286,7 @@ https://github.com/open-chat-labs/open-chat/commit/e93865ea29b5bab8a9f0b01052938
             block_level_markdown: false,
         };
 
-        let report = construct_html_report(chat, None, &message, true);
+        let report = construct_html_report(chat, None, &message, &[], true);
 
         print!("{}", report);
     }
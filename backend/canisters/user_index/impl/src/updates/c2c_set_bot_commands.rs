@@ -0,0 +1,26 @@
+use crate::guards::caller_is_bot;
+use crate::{mutate_state, RuntimeState};
+use canister_api_macros::update_msgpack;
+use canister_tracing_macros::trace;
+use types::SlashCommandSchema;
+use user_index_canister::c2c_set_bot_commands::{Response::*, *};
+
+#[update_msgpack(guard = "caller_is_bot")]
+#[trace]
+fn c2c_set_bot_commands(args: Args) -> Response {
+    mutate_state(|state| c2c_set_bot_commands_impl(args, state))
+}
+
+fn c2c_set_bot_commands_impl(args: Args, state: &mut RuntimeState) -> Response {
+    if let Err(error) = SlashCommandSchema::validate_all(&args.commands) {
+        return InvalidCommands(error);
+    }
+
+    let caller = state.env.caller();
+    let now = state.env.now();
+
+    match state.data.users.set_bot_commands(&caller.into(), args.commands, now) {
+        true => Success,
+        false => BotNotFound,
+    }
+}
@@ -11,6 +11,7 @@ pub mod c2c_register_bot;
 pub mod c2c_report_message;
 pub mod c2c_send_openchat_bot_messages;
 pub mod c2c_set_avatar;
+pub mod c2c_set_bot_commands;
 pub mod c2c_suspend_users;
 pub mod create_challenge;
 pub mod delete_user;
@@ -32,6 +32,22 @@ pub enum MakeOrderResponse {
 pub struct MakeOrderSuccess {
     pub status: OrderStatus,
     pub txid: Vec<u8>,
+    pub filled: Vec<OrderFilled>,
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub struct OrderFilled {
+    #[serde(rename = "token0Value")]
+    pub token0_value: BalanceChange,
+    #[serde(rename = "token1Value")]
+    pub token1_value: BalanceChange,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum BalanceChange {
+    DebitRecord(Nat),
+    CreditRecord(Nat),
+    NoChange,
 }
 
 #[derive(CandidType, Deserialize, Debug)]
@@ -55,6 +71,10 @@ pub enum MakeOrderErrorCode {
 pub enum ICDexOrderType {
     #[serde(rename = "LMT")]
     Limit,
+    #[serde(rename = "MKT")]
+    Market,
+    #[serde(rename = "FOK")]
+    FillOrKill,
 }
 
 #[derive(CandidType, Deserialize, Debug)]
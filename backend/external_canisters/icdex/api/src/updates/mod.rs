@@ -4,3 +4,4 @@ pub mod accountConfig;
 pub mod cancelByTxid;
 pub mod deposit;
 pub mod trade;
+pub mod withdraw;
@@ -0,0 +1,5 @@
+use candid::Nat;
+
+// Token0 amount, Token1 amount, subaccount
+pub type Args = (Option<Nat>, Option<Nat>, Option<[u8; 32]>);
+pub type Response = (Nat, Nat);
@@ -0,0 +1,3 @@
+mod queries;
+
+pub use queries::*;
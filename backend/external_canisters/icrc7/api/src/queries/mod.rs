@@ -0,0 +1 @@
+pub mod icrc7_balance_of;
@@ -4,7 +4,7 @@ use candid::Principal;
 use pocket_ic::PocketIc;
 use std::ops::Deref;
 use testing::rng::random_string;
-use types::{ChatId, GroupRole};
+use types::{ChatId, GroupPermission, GroupRole};
 
 #[test]
 fn owner_can_promote_to_and_demote_from_owner() {
@@ -29,6 +29,96 @@ fn owner_can_promote_to_and_demote_from_owner() {
     assert!(matches!(summary1.role, GroupRole::Admin));
 }
 
+#[test]
+fn custom_role_grants_its_permissions_to_holders() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData { user1, user2, group_id } = init_test_data(env, canister_ids, *controller);
+
+    let message_index =
+        client::group::happy_path::send_text_message(env, &user1, group_id, None, random_string(), None).message_index;
+
+    let pin_message_args = group_canister::pin_message_v2::Args {
+        message_index,
+        correlation_id: 0,
+    };
+    let response = client::group::pin_message_v2(env, user2.principal, group_id.into(), &pin_message_args);
+    assert!(matches!(response, group_canister::pin_message_v2::Response::NotAuthorized));
+
+    let create_role_response = client::group::create_custom_role(
+        env,
+        user1.principal,
+        group_id.into(),
+        &group_canister::create_custom_role::Args {
+            name: "Helper".to_string(),
+            permissions: vec![GroupPermission::PinMessages],
+            message_permissions: Vec::new(),
+        },
+    );
+    let group_canister::create_custom_role::Response::Success(result) = create_role_response else {
+        panic!("'create_custom_role' error: {create_role_response:?}");
+    };
+
+    let assign_role_response = client::group::assign_custom_role(
+        env,
+        user1.principal,
+        group_id.into(),
+        &group_canister::assign_custom_role::Args {
+            role_id: result.role_id,
+            users_to_add: vec![user2.user_id],
+            users_to_remove: Vec::new(),
+        },
+    );
+    assert!(matches!(
+        assign_role_response,
+        group_canister::assign_custom_role::Response::Success
+    ));
+
+    let selected_initial = client::group::happy_path::selected_initial(env, &user1, group_id);
+    assert!(selected_initial.custom_roles.iter().any(|r| r.id == result.role_id));
+    assert!(selected_initial
+        .participants
+        .iter()
+        .any(|m| m.user_id == user2.user_id && m.custom_roles.contains(&result.role_id)));
+
+    let response = client::group::pin_message_v2(env, user2.principal, group_id.into(), &pin_message_args);
+    assert!(matches!(response, group_canister::pin_message_v2::Response::Success(_)));
+}
+
+#[test]
+fn custom_role_cannot_grant_change_roles() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData { user1, group_id, .. } = init_test_data(env, canister_ids, *controller);
+
+    let response = client::group::create_custom_role(
+        env,
+        user1.principal,
+        group_id.into(),
+        &group_canister::create_custom_role::Args {
+            name: "Escalator".to_string(),
+            permissions: vec![GroupPermission::ChangeRoles],
+            message_permissions: Vec::new(),
+        },
+    );
+    assert!(matches!(
+        response,
+        group_canister::create_custom_role::Response::PermissionNotGrantable
+    ));
+}
+
 fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, controller: Principal) -> TestData {
     let user1 = client::register_diamond_user(env, canister_ids, controller);
     let user2 = client::register_user(env, canister_ids);
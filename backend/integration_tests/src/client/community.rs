@@ -13,13 +13,16 @@ generate_query_call!(summary);
 generate_query_call!(summary_updates);
 
 // Updates
+generate_update_call!(add_members_to_channel);
 generate_update_call!(add_reaction);
 generate_update_call!(block_user);
 generate_update_call!(cancel_invites);
 generate_update_call!(change_role);
 generate_update_call!(claim_prize);
 generate_update_call!(create_channel);
+generate_update_call!(create_channel_category);
 generate_update_call!(create_user_group);
+generate_update_call!(delete_channel_categories);
 generate_update_call!(delete_messages);
 generate_update_call!(delete_user_groups);
 generate_update_call!(edit_message);
@@ -33,6 +36,7 @@ generate_update_call!(send_message);
 generate_update_call!(unblock_user);
 generate_update_call!(undelete_messages);
 generate_update_call!(update_channel);
+generate_update_call!(update_channel_category);
 generate_update_call!(update_community);
 generate_update_call!(update_user_group);
 
@@ -69,6 +73,7 @@ pub mod happy_path {
                 permissions_v2: None,
                 events_ttl: None,
                 gate: None,
+                category_id: None,
             },
         );
 
@@ -101,6 +106,7 @@ pub mod happy_path {
                 permissions_v2: None,
                 events_ttl: None,
                 gate: Some(gate),
+                category_id: None,
             },
         );
 
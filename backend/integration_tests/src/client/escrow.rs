@@ -34,6 +34,8 @@ pub mod happy_path {
                 token0_amount: input_amount,
                 token1: output_token.try_into().unwrap(),
                 token1_amount: output_amount,
+                additional_token0s: Vec::new(),
+                min_fill_token1_amount: None,
                 expires_at,
                 additional_admins: Vec::new(),
                 canister_to_notify: None,
@@ -56,7 +58,12 @@ pub mod happy_path {
             env,
             user_id.into(),
             escrow_canister_id,
-            &escrow_canister::notify_deposit::Args { swap_id, user_id: None },
+            &escrow_canister::notify_deposit::Args {
+                swap_id,
+                user_id: None,
+                token1_amount: None,
+                fill_id: None,
+            },
         );
 
         match response {
@@ -5,7 +5,9 @@ use group_canister::*;
 generate_query_call!(events);
 generate_query_call!(events_by_index);
 generate_query_call!(events_window);
+generate_query_call!(message_seen_by);
 generate_query_call!(public_summary);
+generate_query_call!(quiz_leaderboard);
 generate_query_call!(selected_initial);
 generate_query_call!(selected_updates_v2);
 generate_query_call!(summary);
@@ -14,16 +16,19 @@ generate_query_call!(summary_updates);
 // Updates
 generate_update_call!(accept_p2p_swap);
 generate_update_call!(add_reaction);
+generate_update_call!(assign_custom_role);
 generate_update_call!(block_user);
 generate_update_call!(cancel_p2p_swap);
 generate_update_call!(change_role);
 generate_update_call!(claim_prize);
 generate_update_call!(convert_into_community);
+generate_update_call!(create_custom_role);
 generate_update_call!(delete_messages);
 generate_update_call!(edit_message_v2);
 generate_update_call!(enable_invite_code);
 generate_update_call!(end_video_call);
 generate_update_call!(join_video_call);
+generate_update_call!(mute_member);
 generate_update_call!(pin_message_v2);
 generate_update_call!(register_poll_vote);
 generate_update_call!(remove_participant);
@@ -129,6 +134,7 @@ pub mod happy_path {
                 thread_root_message_index: None,
                 message_index,
                 poll_option,
+                ranking: None,
                 operation: VoteOperation::RegisterVote,
                 correlation_id: 0,
             },
@@ -80,6 +80,8 @@ pub mod happy_path {
                         p256dh: p256dh.into(),
                     },
                     endpoint: endpoint.into(),
+                    push_service: None,
+                    digest_mode: None,
                 },
             },
         );
@@ -0,0 +1,328 @@
+use crate::env::ENV;
+use crate::{client, CanisterIds, TestEnv, User};
+use candid::Principal;
+use pocket_ic::PocketIc;
+use std::ops::Deref;
+use testing::rng::random_string;
+use types::{AccessGate, ChannelId, CommunityId, OptionUpdate, Rules};
+
+#[test]
+fn create_channel_category_succeeds() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user1,
+        community_id,
+        channel_id,
+        ..
+    } = init_test_data(env, canister_ids, *controller);
+
+    let category_id = create_category(env, &user1, community_id, "Main", vec![channel_id], None);
+
+    let summary = client::community::happy_path::summary(env, &user1, community_id);
+    assert_eq!(summary.channel_categories.len(), 1);
+
+    let category = summary.channel_categories.first().unwrap();
+    assert_eq!(category.category_id, category_id);
+    assert_eq!(category.name, "Main");
+    assert_eq!(category.channels, vec![channel_id]);
+}
+
+#[test]
+fn non_admin_cannot_create_channel_category() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user2,
+        community_id,
+        channel_id,
+        ..
+    } = init_test_data(env, canister_ids, *controller);
+
+    let response = client::community::create_channel_category(
+        env,
+        user2.principal,
+        community_id.into(),
+        &community_canister::create_channel_category::Args {
+            name: random_string(),
+            channel_ids: vec![channel_id],
+            collapsed_by_default: false,
+            default_permissions: None,
+            default_gate: None,
+        },
+    );
+
+    assert!(matches!(
+        response,
+        community_canister::create_channel_category::Response::NotAuthorized
+    ));
+}
+
+#[test]
+fn channel_created_in_category_inherits_default_gate() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user1,
+        community_id,
+        channel_id,
+        ..
+    } = init_test_data(env, canister_ids, *controller);
+
+    let category_id = create_category(
+        env,
+        &user1,
+        community_id,
+        "Diamond",
+        vec![channel_id],
+        Some(AccessGate::DiamondMember),
+    );
+
+    let new_channel_id = create_channel_in_category(env, &user1, community_id, category_id);
+
+    let summary = client::community::happy_path::summary(env, &user1, community_id);
+    let category = summary.channel_categories.first().unwrap();
+    assert_eq!(category.channels, vec![channel_id, new_channel_id]);
+
+    let channel = summary.channels.iter().find(|c| c.channel_id == new_channel_id).unwrap();
+    assert!(matches!(channel.gate, Some(AccessGate::DiamondMember)));
+}
+
+#[test]
+fn updating_default_gate_updates_channels_still_inheriting_it() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData { user1, community_id, .. } = init_test_data(env, canister_ids, *controller);
+
+    let category_id = create_category(
+        env,
+        &user1,
+        community_id,
+        "Diamond",
+        Vec::new(),
+        Some(AccessGate::DiamondMember),
+    );
+    let inheriting_channel_id = create_channel_in_category(env, &user1, community_id, category_id);
+    let overridden_channel_id = create_channel_in_category(env, &user1, community_id, category_id);
+
+    client::community::happy_path::update_channel(
+        env,
+        user1.principal,
+        community_id,
+        &community_canister::update_channel::Args {
+            channel_id: overridden_channel_id,
+            name: None,
+            description: None,
+            rules: None,
+            avatar: OptionUpdate::NoChange,
+            permissions_v2: None,
+            events_ttl: OptionUpdate::NoChange,
+            gate: OptionUpdate::SetToNone,
+            gate_reverification: OptionUpdate::NoChange,
+            slow_mode: OptionUpdate::NoChange,
+            read_receipts_enabled: None,
+            user_group_permissions: None,
+            public: None,
+        },
+    );
+
+    let response = client::community::update_channel_category(
+        env,
+        user1.principal,
+        community_id.into(),
+        &community_canister::update_channel_category::Args {
+            category_id,
+            name: None,
+            channel_ids: None,
+            collapsed_by_default: None,
+            default_permissions: OptionUpdate::NoChange,
+            default_gate: OptionUpdate::SetToSome(AccessGate::LifetimeDiamondMember),
+            position: None,
+        },
+    );
+    assert!(matches!(
+        response,
+        community_canister::update_channel_category::Response::Success
+    ));
+
+    let summary = client::community::happy_path::summary(env, &user1, community_id);
+    let gate = |channel_id| {
+        summary
+            .channels
+            .iter()
+            .find(|c| c.channel_id == channel_id)
+            .unwrap()
+            .gate
+            .clone()
+    };
+    assert!(matches!(gate(inheriting_channel_id), Some(AccessGate::LifetimeDiamondMember)));
+    assert!(gate(overridden_channel_id).is_none());
+}
+
+#[test]
+fn update_and_delete_channel_categories_succeeds() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user1,
+        community_id,
+        channel_id,
+        ..
+    } = init_test_data(env, canister_ids, *controller);
+
+    let first = create_category(env, &user1, community_id, "First", vec![channel_id], None);
+    let second = create_category(env, &user1, community_id, "Second", Vec::new(), None);
+
+    let response = client::community::update_channel_category(
+        env,
+        user1.principal,
+        community_id.into(),
+        &community_canister::update_channel_category::Args {
+            category_id: second,
+            name: Some("Renamed".to_string()),
+            channel_ids: Some(vec![channel_id]),
+            collapsed_by_default: Some(true),
+            default_permissions: OptionUpdate::NoChange,
+            default_gate: OptionUpdate::NoChange,
+            position: Some(0),
+        },
+    );
+    assert!(matches!(
+        response,
+        community_canister::update_channel_category::Response::Success
+    ));
+
+    let summary = client::community::happy_path::summary(env, &user1, community_id);
+    let ids: Vec<_> = summary.channel_categories.iter().map(|c| c.category_id).collect();
+    assert_eq!(ids, vec![second, first]);
+    assert_eq!(summary.channel_categories[0].name, "Renamed");
+    assert!(summary.channel_categories[0].collapsed_by_default);
+    assert_eq!(summary.channel_categories[0].channels, vec![channel_id]);
+    assert!(summary.channel_categories[1].channels.is_empty());
+
+    let response = client::community::delete_channel_categories(
+        env,
+        user1.principal,
+        community_id.into(),
+        &community_canister::delete_channel_categories::Args {
+            category_ids: vec![first, second],
+        },
+    );
+    assert!(matches!(
+        response,
+        community_canister::delete_channel_categories::Response::Success
+    ));
+
+    let summary = client::community::happy_path::summary(env, &user1, community_id);
+    assert!(summary.channel_categories.is_empty());
+}
+
+fn create_category(
+    env: &mut PocketIc,
+    user: &User,
+    community_id: CommunityId,
+    name: &str,
+    channel_ids: Vec<ChannelId>,
+    default_gate: Option<AccessGate>,
+) -> u32 {
+    let response = client::community::create_channel_category(
+        env,
+        user.principal,
+        community_id.into(),
+        &community_canister::create_channel_category::Args {
+            name: name.to_string(),
+            channel_ids,
+            collapsed_by_default: false,
+            default_permissions: None,
+            default_gate,
+        },
+    );
+
+    match response {
+        community_canister::create_channel_category::Response::Success(result) => result.category_id,
+        response => panic!("'create_channel_category' error: {response:?}"),
+    }
+}
+
+fn create_channel_in_category(env: &mut PocketIc, user: &User, community_id: CommunityId, category_id: u32) -> ChannelId {
+    let name = random_string();
+    let response = client::community::create_channel(
+        env,
+        user.principal,
+        community_id.into(),
+        &community_canister::create_channel::Args {
+            is_public: false,
+            name: name.clone(),
+            description: format!("{name}_description"),
+            rules: Rules::default(),
+            subtype: None,
+            avatar: None,
+            history_visible_to_new_joiners: false,
+            permissions_v2: None,
+            events_ttl: None,
+            gate: None,
+            category_id: Some(category_id),
+        },
+    );
+
+    match response {
+        community_canister::create_channel::Response::Success(result) => result.channel_id,
+        response => panic!("'create_channel' error: {response:?}"),
+    }
+}
+
+fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, controller: Principal) -> TestData {
+    let user1 = client::register_diamond_user(env, canister_ids, controller);
+    let user2 = client::register_user(env, canister_ids);
+
+    let community_id =
+        client::user::happy_path::create_community(env, &user1, &random_string(), true, vec!["general".to_string()]);
+
+    client::local_user_index::happy_path::join_community(env, user2.principal, canister_ids.local_user_index, community_id);
+
+    let summary = client::community::happy_path::summary(env, &user1, community_id);
+
+    TestData {
+        user1,
+        user2,
+        community_id,
+        channel_id: summary.channels.first().unwrap().channel_id,
+    }
+}
+
+struct TestData {
+    user1: User,
+    user2: User,
+    community_id: CommunityId,
+    channel_id: ChannelId,
+}
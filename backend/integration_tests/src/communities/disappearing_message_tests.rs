@@ -32,6 +32,10 @@ fn disappearing_messages_in_channel() {
             permissions_v2: None,
             events_ttl: OptionUpdate::SetToSome(1000),
             gate: OptionUpdate::NoChange,
+            gate_reverification: OptionUpdate::NoChange,
+            slow_mode: OptionUpdate::NoChange,
+            read_receipts_enabled: None,
+            user_group_permissions: None,
             public: None,
         },
     );
@@ -77,6 +81,10 @@ fn disappearing_messages_in_channel() {
             permissions_v2: None,
             events_ttl: OptionUpdate::SetToNone,
             gate: OptionUpdate::NoChange,
+            gate_reverification: OptionUpdate::NoChange,
+            slow_mode: OptionUpdate::NoChange,
+            read_receipts_enabled: None,
+            user_group_permissions: None,
             public: None,
         },
     );
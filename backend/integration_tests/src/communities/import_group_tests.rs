@@ -197,6 +197,9 @@ fn pending_prizes_transferred_to_community() {
                 end_date: now_millis(env) + HOUR_IN_MS,
                 caption: None,
                 diamond_only: false,
+                quiz_message_index: None,
+                draw: false,
+                gate: None,
             }),
             sender_name: user1.username(),
             sender_display_name: None,
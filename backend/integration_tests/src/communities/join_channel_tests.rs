@@ -4,8 +4,10 @@ use crate::{client, CanisterIds, TestEnv, User};
 use candid::Principal;
 use pocket_ic::PocketIc;
 use std::ops::Deref;
+use std::time::Duration;
 use testing::rng::random_string;
-use types::{ChannelId, CommunityId, MessageContent};
+use types::{AccessGate, AccountAgeGate, ChannelId, CommunityId, GateCheckFailedReason, MessageContent, OptionUpdate};
+use utils::time::DAY_IN_MS;
 
 #[test]
 fn join_public_channel_succeeds() {
@@ -324,6 +326,74 @@ fn channel_marked_as_read_after_joining() {
     assert_eq!(user3_channel.read_by_me_up_to, Some(2.into()));
 }
 
+#[test]
+fn add_account_too_new_to_account_age_gated_channel_fails() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user1,
+        user2,
+        community_id,
+        channel_id,
+    } = init_test_data(env, canister_ids, *controller, false);
+
+    client::community::happy_path::update_channel(
+        env,
+        user1.principal,
+        community_id,
+        &community_canister::update_channel::Args {
+            channel_id,
+            name: None,
+            description: None,
+            rules: None,
+            avatar: OptionUpdate::NoChange,
+            permissions_v2: None,
+            events_ttl: OptionUpdate::NoChange,
+            gate: OptionUpdate::SetToSome(AccessGate::AccountAge(AccountAgeGate { min_age: DAY_IN_MS })),
+            gate_reverification: OptionUpdate::NoChange,
+            slow_mode: OptionUpdate::NoChange,
+            read_receipts_enabled: None,
+            user_group_permissions: None,
+            public: None,
+        },
+    );
+
+    let add_members_args = community_canister::add_members_to_channel::Args {
+        channel_id,
+        user_ids: vec![user2.user_id],
+        added_by_name: user1.username(),
+        added_by_display_name: None,
+    };
+
+    let response = client::community::add_members_to_channel(env, user1.principal, community_id.into(), &add_members_args);
+
+    if let community_canister::add_members_to_channel::Response::Failed(result) = response {
+        assert_eq!(result.users_failed_gate_check.len(), 1);
+        assert_eq!(result.users_failed_gate_check[0].user_id, user2.user_id);
+        assert!(matches!(
+            result.users_failed_gate_check[0].reason,
+            GateCheckFailedReason::AccountTooNew
+        ));
+    } else {
+        panic!("'add_members_to_channel' error: {response:?}");
+    }
+
+    env.advance_time(Duration::from_millis(DAY_IN_MS));
+
+    let response = client::community::add_members_to_channel(env, user1.principal, community_id.into(), &add_members_args);
+
+    assert!(matches!(
+        response,
+        community_canister::add_members_to_channel::Response::Success
+    ));
+}
+
 fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, controller: Principal, public: bool) -> TestData {
     let user1 = client::register_diamond_user(env, canister_ids, controller);
     let user2 = client::register_user(env, canister_ids);
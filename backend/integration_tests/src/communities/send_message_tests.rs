@@ -185,6 +185,9 @@ fn send_prize_in_channel() {
                 prizes_v2: prizes,
                 end_date: now_millis(env) + 1000,
                 diamond_only: false,
+                quiz_message_index: None,
+                draw: false,
+                gate: None,
             }),
             sender_name: user1.username(),
             sender_display_name: None,
@@ -730,6 +733,7 @@ fn set_community_rules(env: &mut PocketIc, sender: Principal, community_id: Comm
         banner: OptionUpdate::NoChange,
         permissions: None,
         gate: OptionUpdate::NoChange,
+        gate_reverification: OptionUpdate::NoChange,
         public: None,
         primary_language: None,
     };
@@ -750,6 +754,10 @@ fn set_channel_rules(env: &mut PocketIc, sender: Principal, community_id: Commun
         permissions_v2: None,
         events_ttl: OptionUpdate::NoChange,
         gate: OptionUpdate::NoChange,
+        gate_reverification: OptionUpdate::NoChange,
+        slow_mode: OptionUpdate::NoChange,
+        read_receipts_enabled: None,
+        user_group_permissions: None,
         public: None,
         channel_id,
     };
@@ -71,6 +71,10 @@ fn members_added_if_channel_made_public_or_gate_removed(make_public: bool) {
             permissions_v2: None,
             events_ttl: OptionUpdate::NoChange,
             gate: if !make_public { OptionUpdate::SetToNone } else { OptionUpdate::NoChange },
+            gate_reverification: OptionUpdate::NoChange,
+            slow_mode: OptionUpdate::NoChange,
+            read_receipts_enabled: None,
+            user_group_permissions: None,
             public: make_public.then_some(true),
         },
     );
@@ -5,8 +5,10 @@ use candid::Principal;
 use pocket_ic::PocketIc;
 use std::ops::Deref;
 use std::time::Duration;
-use testing::rng::random_string;
-use types::{ChannelId, CommunityId};
+use testing::rng::{random_message_id, random_string};
+use types::{
+    AccessGate, ChannelId, ChannelUserGroupPermissions, CommunityId, MessageContentInitial, OptionUpdate, TextContent,
+};
 
 #[test]
 fn create_user_group_succeeds() {
@@ -237,6 +239,204 @@ fn send_message_mentioning_user_group() {
     );
 }
 
+#[test]
+fn only_user_group_members_can_send_messages_when_restricted() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user1,
+        user2,
+        user3,
+        community_id,
+        channel_id,
+    } = init_test_data(env, canister_ids, *controller);
+
+    let user_group_id = client::community::happy_path::create_user_group(
+        env,
+        user1.principal,
+        community_id,
+        random_string(),
+        vec![user2.user_id],
+    );
+
+    set_user_group_permissions(
+        env,
+        user1.principal,
+        community_id,
+        channel_id,
+        ChannelUserGroupPermissions {
+            send_messages: vec![user_group_id],
+            ..Default::default()
+        },
+    );
+
+    let response = client::community::send_message(
+        env,
+        user3.principal,
+        community_id.into(),
+        &community_canister::send_message::Args {
+            channel_id,
+            thread_root_message_index: None,
+            message_id: random_message_id(),
+            content: MessageContentInitial::Text(TextContent { text: random_string() }),
+            sender_name: user3.username(),
+            sender_display_name: None,
+            replies_to: None,
+            mentioned: Vec::new(),
+            forwarding: false,
+            block_level_markdown: false,
+            community_rules_accepted: None,
+            channel_rules_accepted: None,
+            message_filter_failed: None,
+        },
+    );
+    assert!(matches!(response, community_canister::send_message::Response::NotAuthorized));
+
+    // Members of the user group and the channel owner can still send messages
+    client::community::happy_path::send_text_message(env, &user2, community_id, channel_id, None, random_string(), None);
+    client::community::happy_path::send_text_message(env, &user1, community_id, channel_id, None, random_string(), None);
+}
+
+#[test]
+fn channel_membership_synced_from_user_group() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user1,
+        user2,
+        user3,
+        community_id,
+        ..
+    } = init_test_data(env, canister_ids, *controller);
+
+    let channel_id = client::community::happy_path::create_channel(env, user1.principal, community_id, false, random_string());
+
+    let user_group_id = client::community::happy_path::create_user_group(
+        env,
+        user1.principal,
+        community_id,
+        random_string(),
+        vec![user2.user_id],
+    );
+
+    set_user_group_permissions(
+        env,
+        user1.principal,
+        community_id,
+        channel_id,
+        ChannelUserGroupPermissions {
+            sync_membership: vec![user_group_id],
+            ..Default::default()
+        },
+    );
+
+    let user2_summary = client::community::happy_path::summary(env, &user2, community_id);
+    assert!(user2_summary.channels.iter().any(|c| c.channel_id == channel_id));
+
+    let response = client::community::update_user_group(
+        env,
+        user1.principal,
+        community_id.into(),
+        &community_canister::update_user_group::Args {
+            user_group_id,
+            name: None,
+            users_to_add: vec![user3.user_id],
+            users_to_remove: Vec::new(),
+        },
+    );
+    assert!(matches!(response, community_canister::update_user_group::Response::Success));
+
+    let user3_summary = client::community::happy_path::summary(env, &user3, community_id);
+    assert!(user3_summary.channels.iter().any(|c| c.channel_id == channel_id));
+}
+
+#[test]
+fn channel_membership_not_synced_into_gated_channel() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData {
+        user1,
+        user2,
+        community_id,
+        ..
+    } = init_test_data(env, canister_ids, *controller);
+
+    let channel_id = client::community::happy_path::create_gated_channel(
+        env,
+        user1.principal,
+        community_id,
+        false,
+        random_string(),
+        AccessGate::DiamondMember,
+    );
+
+    let user_group_id = client::community::happy_path::create_user_group(
+        env,
+        user1.principal,
+        community_id,
+        random_string(),
+        vec![user2.user_id],
+    );
+
+    set_user_group_permissions(
+        env,
+        user1.principal,
+        community_id,
+        channel_id,
+        ChannelUserGroupPermissions {
+            sync_membership: vec![user_group_id],
+            ..Default::default()
+        },
+    );
+
+    let user2_summary = client::community::happy_path::summary(env, &user2, community_id);
+    assert!(!user2_summary.channels.iter().any(|c| c.channel_id == channel_id));
+}
+
+fn set_user_group_permissions(
+    env: &mut PocketIc,
+    sender: Principal,
+    community_id: CommunityId,
+    channel_id: ChannelId,
+    user_group_permissions: ChannelUserGroupPermissions,
+) {
+    let args = community_canister::update_channel::Args {
+        channel_id,
+        name: None,
+        description: None,
+        rules: None,
+        avatar: OptionUpdate::NoChange,
+        permissions_v2: None,
+        events_ttl: OptionUpdate::NoChange,
+        gate: OptionUpdate::NoChange,
+        gate_reverification: OptionUpdate::NoChange,
+        slow_mode: OptionUpdate::NoChange,
+        read_receipts_enabled: None,
+        user_group_permissions: Some(user_group_permissions),
+        public: None,
+    };
+
+    client::community::happy_path::update_channel(env, sender, community_id, &args);
+}
+
 fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, controller: Principal) -> TestData {
     let user1 = client::register_diamond_user(env, canister_ids, controller);
     let user2 = client::register_user(env, canister_ids);
@@ -78,3 +78,98 @@ fn swap_via_escrow_canister_succeeds() {
         icp_amount
     );
 }
+
+#[test]
+fn swap_filled_in_tranches_succeeds() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+    } = wrapper.env();
+
+    let user1 = client::register_user(env, canister_ids);
+    let user2 = client::register_user(env, canister_ids);
+    let user3 = client::register_user(env, canister_ids);
+    let now = now_millis(env);
+
+    let icp_amount = 100_000_000_000;
+    let chat_amount = 1_000_000_000_000;
+
+    let swap_id = match client::escrow::create_swap(
+        env,
+        user1.user_id.into(),
+        canister_ids.escrow,
+        &escrow_canister::create_swap::Args {
+            location: P2PSwapLocation::from_message(Chat::Direct(user2.user_id.into()), None, 0.into()),
+            token0: Cryptocurrency::InternetComputer.try_into().unwrap(),
+            token0_amount: icp_amount,
+            token1: Cryptocurrency::CHAT.try_into().unwrap(),
+            token1_amount: chat_amount,
+            additional_token0s: Vec::new(),
+            min_fill_token1_amount: Some(chat_amount / 4),
+            expires_at: now + DAY_IN_MS,
+            additional_admins: Vec::new(),
+            canister_to_notify: None,
+        },
+    ) {
+        escrow_canister::create_swap::Response::Success(result) => result.id,
+        response => panic!("'create_swap' error: {response:?}"),
+    };
+
+    // Enough must be deposited to cover the fee of each of the (up to 4) transfers out
+    client::ledger::happy_path::transfer(
+        env,
+        *controller,
+        canister_ids.icp_ledger,
+        Account {
+            owner: canister_ids.escrow,
+            subaccount: Some(deposit_subaccount(user1.user_id, swap_id)),
+        },
+        icp_amount + 4 * 10_000,
+    );
+    client::escrow::happy_path::notify_deposit(env, user1.user_id, canister_ids.escrow, swap_id);
+
+    for user in [&user2, &user3] {
+        client::ledger::happy_path::transfer(
+            env,
+            *controller,
+            canister_ids.chat_ledger,
+            Account {
+                owner: canister_ids.escrow,
+                subaccount: Some(deposit_subaccount(user.user_id, swap_id)),
+            },
+            chat_amount / 2 + 100_000,
+        );
+
+        let response = client::escrow::notify_deposit(
+            env,
+            user.user_id.into(),
+            canister_ids.escrow,
+            &escrow_canister::notify_deposit::Args {
+                swap_id,
+                user_id: None,
+                token1_amount: Some(chat_amount / 2),
+            },
+        );
+        assert!(matches!(
+            response,
+            escrow_canister::notify_deposit::Response::Success(escrow_canister::notify_deposit::SuccessResult {
+                complete: true
+            })
+        ));
+    }
+
+    tick_many(env, 10);
+
+    assert_eq!(
+        client::ledger::happy_path::balance_of(env, canister_ids.chat_ledger, user1.user_id),
+        chat_amount
+    );
+    for user in [&user2, &user3] {
+        assert_eq!(
+            client::ledger::happy_path::balance_of(env, canister_ids.icp_ledger, user.user_id),
+            icp_amount / 2
+        );
+    }
+}
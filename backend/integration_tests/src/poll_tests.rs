@@ -6,7 +6,10 @@ use std::collections::HashMap;
 use std::ops::Deref;
 use std::time::{Duration, SystemTime};
 use testing::rng::random_message_id;
-use types::{ChatEvent, ChatId, MessageContent, MessageContentInitial, PollConfig, PollContent, PollVotes, TotalVotes};
+use types::{
+    ChatEvent, ChatId, MessageContent, MessageContentInitial, PollConfig, PollContent, PollVotes, PollVotingSystem, TotalVotes,
+    VoteOperation,
+};
 
 #[test]
 fn allow_multiple_votes_per_user() {
@@ -21,6 +24,9 @@ fn allow_multiple_votes_per_user() {
         show_votes_before_end_date: false,
         allow_multiple_votes_per_user: true,
         allow_user_to_change_vote: true,
+        voting_system: None,
+        vote_weighting: None,
+        correct_options: None,
     };
 
     let TestData {
@@ -52,6 +58,9 @@ fn single_vote_per_user() {
         show_votes_before_end_date: false,
         allow_multiple_votes_per_user: false,
         allow_user_to_change_vote: true,
+        voting_system: None,
+        vote_weighting: None,
+        correct_options: None,
     };
 
     let TestData {
@@ -85,6 +94,9 @@ fn polls_ended_correctly() {
         show_votes_before_end_date: false,
         allow_multiple_votes_per_user: false,
         allow_user_to_change_vote: true,
+        voting_system: None,
+        vote_weighting: None,
+        correct_options: None,
     };
 
     let TestData {
@@ -102,6 +114,9 @@ fn polls_ended_correctly() {
         show_votes_before_end_date: false,
         allow_multiple_votes_per_user: false,
         allow_user_to_change_vote: true,
+        voting_system: None,
+        vote_weighting: None,
+        correct_options: None,
     };
 
     let create_poll_result2 = client::group::send_message_v2(
@@ -118,6 +133,7 @@ fn polls_ended_correctly() {
                     user: Vec::new(),
                 },
                 ended: false,
+                results: None,
             }),
             sender_name: user1.username(),
             sender_display_name: None,
@@ -218,6 +234,153 @@ fn polls_ended_correctly() {
     }
 }
 
+#[test]
+fn ranked_choice_poll_results_set_when_poll_ends() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv { env, canister_ids, .. } = wrapper.env();
+
+    let current_time = env.get_time().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() as u64;
+
+    let poll_config = PollConfig {
+        text: None,
+        options: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+        end_date: Some(current_time + 1000),
+        anonymous: false,
+        show_votes_before_end_date: true,
+        allow_multiple_votes_per_user: false,
+        allow_user_to_change_vote: true,
+        voting_system: Some(PollVotingSystem::RankedChoice),
+        vote_weighting: None,
+        correct_options: None,
+    };
+
+    let TestData {
+        user1,
+        user2,
+        group,
+        create_poll_result,
+    } = init_test_data(env, canister_ids, poll_config);
+
+    let group_canister::send_message_v2::Response::Success(r) = create_poll_result else {
+        panic!("'send_message_v2' error: {create_poll_result:?}");
+    };
+
+    for (user, ranking) in [(&user1, vec![1, 0]), (&user2, vec![1, 2])] {
+        let response = client::group::register_poll_vote(
+            env,
+            user.principal,
+            group.into(),
+            &group_canister::register_poll_vote::Args {
+                thread_root_message_index: None,
+                message_index: r.message_index,
+                poll_option: ranking[0],
+                ranking: Some(ranking.clone()),
+                operation: VoteOperation::RegisterVote,
+                correlation_id: 0,
+            },
+        );
+
+        match response {
+            group_canister::register_poll_vote::Response::Success(votes) => assert_eq!(votes.user, ranking),
+            response => panic!("'register_poll_vote' error: {response:?}"),
+        }
+    }
+
+    env.advance_time(Duration::from_millis(1000));
+    env.tick();
+
+    let event = client::group::happy_path::events_by_index(env, &user1, group, vec![r.event_index])
+        .events
+        .pop()
+        .unwrap();
+
+    if let ChatEvent::Message(m) = event.event {
+        if let MessageContent::Poll(p) = m.content {
+            assert!(p.ended);
+            let results = p.results.unwrap();
+            assert_eq!(results.winner, Some(1));
+            assert_eq!(results.tallies.get(&1), Some(&2));
+        } else {
+            unreachable!()
+        }
+    } else {
+        unreachable!()
+    }
+}
+
+#[test]
+fn quiz_answers_hidden_until_end_then_added_to_leaderboard() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv { env, canister_ids, .. } = wrapper.env();
+
+    let current_time = env.get_time().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() as u64;
+
+    let poll_config = PollConfig {
+        text: Some("2 + 2 = ?".to_string()),
+        options: vec!["3".to_string(), "4".to_string()],
+        end_date: Some(current_time + 1000),
+        anonymous: false,
+        show_votes_before_end_date: true,
+        allow_multiple_votes_per_user: false,
+        allow_user_to_change_vote: true,
+        voting_system: None,
+        vote_weighting: None,
+        correct_options: Some(vec![1]),
+    };
+
+    let TestData {
+        user1,
+        user2,
+        group,
+        create_poll_result,
+    } = init_test_data(env, canister_ids, poll_config);
+
+    let group_canister::send_message_v2::Response::Success(r) = create_poll_result else {
+        panic!("'send_message_v2' error: {create_poll_result:?}");
+    };
+
+    let votes = client::group::happy_path::register_poll_vote(env, &user1, group, r.message_index, 0);
+    assert!(matches!(votes.total, TotalVotes::Hidden(1)));
+    client::group::happy_path::register_poll_vote(env, &user2, group, r.message_index, 1);
+
+    let poll = |env: &mut PocketIc| {
+        let event = client::group::happy_path::events_by_index(env, &user1, group, vec![r.event_index])
+            .events
+            .pop()
+            .unwrap();
+
+        let ChatEvent::Message(m) = event.event else { unreachable!() };
+        let MessageContent::Poll(p) = m.content else { unreachable!() };
+        p
+    };
+
+    assert_eq!(poll(env).config.correct_options, Some(Vec::new()));
+
+    env.advance_time(Duration::from_millis(1000));
+    env.tick();
+
+    let ended = poll(env);
+    assert!(ended.ended);
+    assert_eq!(ended.config.correct_options, Some(vec![1]));
+
+    let response = client::group::quiz_leaderboard(
+        env,
+        user1.principal,
+        group.into(),
+        &group_canister::quiz_leaderboard::Args { max_results: 10 },
+    );
+
+    let group_canister::quiz_leaderboard::Response::Success(result) = response else {
+        panic!("'quiz_leaderboard' error: {response:?}");
+    };
+    assert_eq!(result.leaderboard.len(), 1);
+    assert_eq!(result.leaderboard[0].user_id, user2.user_id);
+    assert_eq!(result.leaderboard[0].correct_answers, 1);
+    let caller = result.caller.unwrap();
+    assert_eq!(caller.correct_answers, 0);
+    assert_eq!(caller.quizzes_answered, 1);
+}
+
 fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, poll_config: PollConfig) -> TestData {
     let user1 = client::register_user(env, canister_ids);
     let user2 = client::register_user(env, canister_ids);
@@ -245,6 +408,7 @@ fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, poll_config: P
                     user: Vec::new(),
                 },
                 ended: false,
+                results: None,
             }),
             sender_name: user1.username(),
             sender_display_name: None,
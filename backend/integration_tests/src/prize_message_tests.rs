@@ -60,6 +60,9 @@ fn prize_messages_can_be_claimed_successfully() {
                 end_date: now_millis(env) + HOUR_IN_MS,
                 caption: None,
                 diamond_only: false,
+                quiz_message_index: None,
+                draw: false,
+                gate: None,
             }),
             sender_name: user1.username(),
             sender_display_name: None,
@@ -150,6 +153,9 @@ fn unclaimed_prizes_get_refunded(delete_message: bool) {
                 end_date: now_millis(env) + HOUR_IN_MS,
                 caption: None,
                 diamond_only: false,
+                quiz_message_index: None,
+                draw: false,
+                gate: None,
             }),
             sender_name: user1.username(),
             sender_display_name: None,
@@ -185,6 +191,103 @@ fn unclaimed_prizes_get_refunded(delete_message: bool) {
     assert_eq!(user1_balance_after_refund, user1_balance_before_refund + 100000);
 }
 
+#[test]
+fn prize_draw_winners_paid_and_remainder_refunded() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let user1 = client::register_diamond_user(env, canister_ids, *controller);
+    let user2 = client::register_user(env, canister_ids);
+    let user3 = client::register_user(env, canister_ids);
+    let group_id = client::user::happy_path::create_group(env, &user1, random_string().as_str(), true, true);
+    client::local_user_index::happy_path::join_group(env, user2.principal, canister_ids.local_user_index, group_id);
+    client::local_user_index::happy_path::join_group(env, user3.principal, canister_ids.local_user_index, group_id);
+
+    // Send user1 some ICP
+    client::ledger::happy_path::transfer(env, *controller, canister_ids.icp_ledger, user1.user_id, 1_000_000_000);
+
+    let prizes = [100000, 100000, 100000];
+    let token = Cryptocurrency::InternetComputer;
+    let fee = token.fee().unwrap();
+    let message_id = random_message_id();
+
+    client::user::send_message_with_transfer_to_group(
+        env,
+        user1.principal,
+        user1.user_id.into(),
+        &user_canister::send_message_with_transfer_to_group::Args {
+            group_id,
+            thread_root_message_index: None,
+            message_id,
+            content: MessageContentInitial::Prize(PrizeContentInitial {
+                prizes_v2: prizes.into_iter().map(u128::from).collect(),
+                transfer: CryptoTransaction::Pending(PendingCryptoTransaction::ICRC1(icrc1::PendingCryptoTransaction {
+                    ledger: canister_ids.icp_ledger,
+                    token,
+                    amount: prizes.iter().sum::<u64>() as u128 + fee * prizes.len() as u128,
+                    to: Account::from(Principal::from(group_id)),
+                    fee,
+                    memo: None,
+                    created: now_nanos(env),
+                })),
+                end_date: now_millis(env) + HOUR_IN_MS,
+                caption: None,
+                diamond_only: false,
+                quiz_message_index: None,
+                draw: true,
+                gate: None,
+            }),
+            sender_name: user1.username(),
+            sender_display_name: None,
+            replies_to: None,
+            mentioned: Vec::new(),
+            block_level_markdown: false,
+            correlation_id: 0,
+            rules_accepted: None,
+            message_filter_failed: None,
+            pin: None,
+        },
+    );
+
+    for user in [&user2, &user3] {
+        let response = client::group::claim_prize(
+            env,
+            user.principal,
+            group_id.into(),
+            &group_canister::claim_prize::Args {
+                message_id,
+                correlation_id: 0,
+            },
+        );
+        assert!(matches!(response, group_canister::claim_prize::Response::EnteredDraw));
+    }
+
+    // Nothing is paid out until the draw takes place
+    assert_eq!(
+        client::ledger::happy_path::balance_of(env, canister_ids.icp_ledger, user2.user_id),
+        0
+    );
+
+    let user1_balance_before_draw = client::ledger::happy_path::balance_of(env, canister_ids.icp_ledger, user1.user_id);
+
+    env.advance_time(Duration::from_millis(HOUR_IN_MS));
+    tick_many(env, 10);
+
+    for user in [&user2, &user3] {
+        let balance = client::ledger::happy_path::balance_of(env, canister_ids.icp_ledger, user.user_id);
+        assert_eq!(balance, 100000);
+    }
+
+    // The prize which wasn't won is refunded to the sender
+    let user1_balance_after_draw = client::ledger::happy_path::balance_of(env, canister_ids.icp_ledger, user1.user_id);
+    assert_eq!(user1_balance_after_draw, user1_balance_before_draw + 100000);
+}
+
 #[test]
 fn old_transactions_fixed_by_updating_created_date() {
     let mut wrapper = ENV.deref().get();
@@ -230,6 +333,9 @@ fn old_transactions_fixed_by_updating_created_date() {
                 end_date: now_millis(env) + HOUR_IN_MS,
                 caption: None,
                 diamond_only: false,
+                quiz_message_index: None,
+                draw: false,
+                gate: None,
             }),
             sender_name: user.username(),
             sender_display_name: None,
@@ -4,8 +4,9 @@ use crate::{client, CanisterIds, TestEnv, User};
 use candid::Principal;
 use pocket_ic::PocketIc;
 use std::ops::Deref;
-use testing::rng::random_string;
-use types::ChatId;
+use std::time::Duration;
+use testing::rng::{random_message_id, random_string};
+use types::{ChatId, MessageContentInitial, TextContent};
 
 #[test]
 fn remove_group_member_succeeds() {
@@ -87,6 +88,64 @@ fn block_user_who_is_no_longer_group_member_succeeds() {
     ));
 }
 
+#[test]
+fn muted_member_cannot_send_messages_until_mute_expires() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData { user1, user2, group_id } = init_test_data(env, canister_ids, *controller, true);
+
+    let mute_member_response = client::group::mute_member(
+        env,
+        user1.principal,
+        group_id.into(),
+        &group_canister::mute_member::Args {
+            user_id: user2.user_id,
+            duration: 60_000,
+            reason: Some("Spamming".to_string()),
+        },
+    );
+
+    assert!(matches!(
+        mute_member_response,
+        group_canister::mute_member::Response::Success(_)
+    ));
+
+    let members = client::group::happy_path::selected_initial(env, &user1, group_id).participants;
+    assert!(members.iter().any(|m| m.user_id == user2.user_id && m.muted_until.is_some()));
+
+    let send_message_args = group_canister::send_message_v2::Args {
+        thread_root_message_index: None,
+        message_id: random_message_id(),
+        content: MessageContentInitial::Text(TextContent { text: random_string() }),
+        sender_name: user2.username(),
+        sender_display_name: None,
+        replies_to: None,
+        mentioned: Vec::new(),
+        forwarding: false,
+        block_level_markdown: false,
+        rules_accepted: None,
+        message_filter_failed: None,
+        correlation_id: 0,
+    };
+    let response = client::group::send_message_v2(env, user2.principal, group_id.into(), &send_message_args);
+    assert!(matches!(response, group_canister::send_message_v2::Response::NotAuthorized));
+
+    env.advance_time(Duration::from_secs(61));
+    tick_many(env, 3);
+
+    let members = client::group::happy_path::selected_initial(env, &user1, group_id).participants;
+    assert!(members.iter().any(|m| m.user_id == user2.user_id && m.muted_until.is_none()));
+
+    let response = client::group::send_message_v2(env, user2.principal, group_id.into(), &send_message_args);
+    assert!(matches!(response, group_canister::send_message_v2::Response::Success(_)));
+}
+
 fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, controller: Principal, public: bool) -> TestData {
     let user1 = client::register_diamond_user(env, canister_ids, controller);
     let user2 = client::register_user(env, canister_ids);
@@ -4,8 +4,10 @@ use crate::{client, CanisterIds, TestEnv, User};
 use candid::Principal;
 use pocket_ic::PocketIc;
 use std::ops::Deref;
-use testing::rng::random_string;
-use types::{ChatId, OptionUpdate::*};
+use std::time::Duration;
+use testing::rng::{random_message_id, random_string};
+use types::{ChatId, MessageContentInitial, OptionUpdate::*, SlowMode, TextContent};
+use user_canister::mark_read::ChatMessagesRead;
 
 #[test]
 fn update_group_name_succeeds() {
@@ -35,6 +37,9 @@ fn update_group_name_succeeds() {
             public: None,
             correlation_id: 0,
             gate: NoChange,
+            gate_reverification: NoChange,
+            slow_mode: NoChange,
+            read_receipts_enabled: None,
         },
     );
 
@@ -78,6 +83,9 @@ fn change_casing_of_group_name_succeeds() {
             public: None,
             correlation_id: 0,
             gate: NoChange,
+            gate_reverification: NoChange,
+            slow_mode: NoChange,
+            read_receipts_enabled: None,
         },
     );
 
@@ -124,6 +132,9 @@ fn update_group_privacy_succeeds() {
             public: Some(false),
             correlation_id: 0,
             gate: NoChange,
+            gate_reverification: NoChange,
+            slow_mode: NoChange,
+            read_receipts_enabled: None,
         },
     );
 
@@ -170,6 +181,9 @@ fn make_private_group_public_succeeds() {
             permissions_v2: None,
             events_ttl: NoChange,
             gate: NoChange,
+            gate_reverification: NoChange,
+            slow_mode: NoChange,
+            read_receipts_enabled: None,
             public: Some(true),
             correlation_id: 0,
         },
@@ -184,6 +198,123 @@ fn make_private_group_public_succeeds() {
     assert_eq!(group_summary.min_visible_message_index, 5.into());
 }
 
+#[test]
+fn slow_mode_limits_members_but_not_moderators() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData { user1, user2, group_id } = init_test_data(env, canister_ids, *controller, &random_string());
+
+    let slow_mode = SlowMode {
+        min_interval: Some(60_000),
+        max_messages_per_minute: None,
+        exempt_moderators: true,
+    };
+
+    client::group::happy_path::update_group(
+        env,
+        user1.principal,
+        group_id,
+        &group_canister::update_group_v2::Args {
+            slow_mode: SetToSome(slow_mode),
+            ..Default::default()
+        },
+    );
+
+    let summary = client::group::happy_path::summary(env, &user2, group_id);
+    assert_eq!(summary.slow_mode, Some(slow_mode));
+
+    client::group::happy_path::send_text_message(env, &user2, group_id, None, random_string(), None);
+
+    let send_message_args = group_canister::send_message_v2::Args {
+        thread_root_message_index: None,
+        message_id: random_message_id(),
+        content: MessageContentInitial::Text(TextContent { text: random_string() }),
+        sender_name: user2.username(),
+        sender_display_name: None,
+        replies_to: None,
+        mentioned: Vec::new(),
+        forwarding: false,
+        block_level_markdown: false,
+        rules_accepted: None,
+        message_filter_failed: None,
+        correlation_id: 0,
+    };
+    let response = client::group::send_message_v2(env, user2.principal, group_id.into(), &send_message_args);
+    assert!(matches!(response, group_canister::send_message_v2::Response::RateLimited(_)));
+
+    // The group owner is exempt
+    client::group::happy_path::send_text_message(env, &user1, group_id, None, random_string(), None);
+    client::group::happy_path::send_text_message(env, &user1, group_id, None, random_string(), None);
+
+    env.advance_time(Duration::from_secs(60));
+
+    let response = client::group::send_message_v2(env, user2.principal, group_id.into(), &send_message_args);
+    assert!(matches!(response, group_canister::send_message_v2::Response::Success(_)));
+}
+
+#[test]
+fn read_receipts_report_who_has_seen_a_message() {
+    let mut wrapper = ENV.deref().get();
+    let TestEnv {
+        env,
+        canister_ids,
+        controller,
+        ..
+    } = wrapper.env();
+
+    let TestData { user1, user2, group_id } = init_test_data(env, canister_ids, *controller, &random_string());
+
+    client::group::happy_path::update_group(
+        env,
+        user1.principal,
+        group_id,
+        &group_canister::update_group_v2::Args {
+            read_receipts_enabled: Some(true),
+            ..Default::default()
+        },
+    );
+
+    let summary = client::group::happy_path::summary(env, &user2, group_id);
+    assert!(summary.read_receipts_enabled);
+
+    let message_index =
+        client::group::happy_path::send_text_message(env, &user1, group_id, None, random_string(), None).message_index;
+
+    let message_seen_by_args = group_canister::message_seen_by::Args { message_index };
+    let response = client::group::message_seen_by(env, user1.principal, group_id.into(), &message_seen_by_args);
+    assert!(matches!(response, group_canister::message_seen_by::Response::Success(result) if result.seen_by_count == 0));
+
+    client::user::mark_read(
+        env,
+        user2.principal,
+        user2.user_id.into(),
+        &user_canister::mark_read::Args {
+            messages_read: vec![ChatMessagesRead {
+                chat_id: group_id,
+                read_up_to: Some(message_index),
+                threads: Vec::new(),
+                date_read_pinned: None,
+            }],
+            community_messages_read: Vec::new(),
+        },
+    );
+    tick_many(env, 3);
+
+    let response = client::group::message_seen_by(env, user1.principal, group_id.into(), &message_seen_by_args);
+    if let group_canister::message_seen_by::Response::Success(result) = response {
+        assert_eq!(result.seen_by_count, 1);
+        assert_eq!(result.seen_by, Some(vec![user2.user_id]));
+    } else {
+        panic!("'message_seen_by' error: {response:?}");
+    }
+}
+
 fn init_test_data(env: &mut PocketIc, canister_ids: &CanisterIds, controller: Principal, group_name: &str) -> TestData {
     let user1 = client::register_diamond_user(env, canister_ids, controller);
     let user2 = client::register_user(env, canister_ids);
@@ -5,13 +5,14 @@ use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
 use types::{
-    is_default, is_empty_slice, AvatarChanged, ChannelId, Chat, ChatId, ChatMetrics, CommunityId, Cryptocurrency, DeletedBy,
-    DirectChatCreated, EventIndex, EventWrapperInternal, EventsTimeToLiveUpdated, GroupCreated, GroupDescriptionChanged,
-    GroupFrozen, GroupGateUpdated, GroupInviteCodeChanged, GroupNameChanged, GroupReplyContext, GroupRulesChanged,
-    GroupUnfrozen, GroupVisibilityChanged, MemberJoined, MemberLeft, MembersAdded, MembersAddedToDefaultChannel,
-    MembersRemoved, Message, MessageContent, MessageId, MessageIndex, MessagePinned, MessageUnpinned, MultiUserChat,
-    PermissionsChanged, PushIfNotContains, Reaction, ReplyContext, RoleChanged, ThreadSummary, TimestampMillis, Timestamped,
-    Tips, UserId, UsersBlocked, UsersInvited, UsersUnblocked,
+    is_default, is_empty_slice, AvatarChanged, ChannelId, Chat, ChatId, ChatMetrics, CommunityId, Cryptocurrency,
+    CustomRoleAssigned, CustomRoleUnassigned, DeletedBy, DirectChatCreated, EventIndex, EventWrapperInternal,
+    EventsTimeToLiveUpdated, GroupCreated, GroupDescriptionChanged, GroupFrozen, GroupGateUpdated, GroupInviteCodeChanged,
+    GroupNameChanged, GroupReplyContext, GroupRulesChanged, GroupUnfrozen, GroupVisibilityChanged, MemberJoined, MemberLeft,
+    MemberMuted, MembersAdded, MembersAddedToDefaultChannel, MembersLapsed, MembersRemoved, MembersRemovedByGate, Message,
+    MessageContent, MessageId, MessageIndex, MessagePinned, MessageUnpinned, MessageVersion, MultiUserChat, PermissionsChanged,
+    PushIfNotContains, Reaction, ReplyContext, RoleChanged, ThreadSummary, TimestampMillis, Timestamped, Tips, UserId,
+    UsersBlocked, UsersInvited, UsersUnblocked,
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -66,6 +67,16 @@ pub enum ChatEventInternal {
     UsersInvited(Box<UsersInvited>),
     #[serde(rename = "adc")]
     MembersAddedToPublicChannel(Box<MembersAddedToPublicChannelInternal>),
+    #[serde(rename = "mlp")]
+    MembersLapsed(Box<MembersLapsed>),
+    #[serde(rename = "mrg")]
+    MembersRemovedByGate(Box<MembersRemovedByGate>),
+    #[serde(rename = "mmu")]
+    MemberMuted(Box<MemberMuted>),
+    #[serde(rename = "cra")]
+    CustomRoleAssigned(Box<CustomRoleAssigned>),
+    #[serde(rename = "cru")]
+    CustomRoleUnassigned(Box<CustomRoleUnassigned>),
     #[serde(rename = "e")]
     Empty,
 }
@@ -107,6 +118,11 @@ impl ChatEventInternal {
                 | ChatEventInternal::GroupGateUpdated(_)
                 | ChatEventInternal::UsersInvited(_)
                 | ChatEventInternal::MembersAddedToPublicChannel(_)
+                | ChatEventInternal::MembersLapsed(_)
+                | ChatEventInternal::MembersRemovedByGate(_)
+                | ChatEventInternal::MemberMuted(_)
+                | ChatEventInternal::CustomRoleAssigned(_)
+                | ChatEventInternal::CustomRoleUnassigned(_)
         )
     }
 
@@ -184,9 +200,56 @@ pub struct MessageInternal {
     pub forwarded: bool,
     #[serde(rename = "b", default, skip_serializing_if = "is_default")]
     pub block_level_markdown: bool,
+    #[serde(rename = "h", default, skip_serializing_if = "is_empty_slice")]
+    pub edit_history: Vec<MessageVersionInternal>,
+}
+
+// The previous versions of a message are retained up to these limits, beyond which the oldest versions are dropped
+const MAX_EDIT_HISTORY_VERSIONS: usize = 10;
+const MAX_EDIT_HISTORY_TEXT_LENGTH: u32 = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MessageVersionInternal {
+    #[serde(rename = "c")]
+    pub content: MessageContentInternal,
+    #[serde(rename = "b", default, skip_serializing_if = "is_default")]
+    pub block_level_markdown: bool,
+    #[serde(rename = "r")]
+    pub replaced_at: TimestampMillis,
+}
+
+impl MessageVersionInternal {
+    pub fn hydrate(&self, my_user_id: Option<UserId>) -> MessageVersion {
+        MessageVersion {
+            content: self.content.hydrate(my_user_id),
+            block_level_markdown: self.block_level_markdown,
+            replaced_at: self.replaced_at,
+        }
+    }
 }
 
 impl MessageInternal {
+    pub fn push_edit_history(&mut self, version: MessageVersionInternal) {
+        self.edit_history.push(version);
+
+        let mut total_text_length: u32 = self.edit_history.iter().map(|v| v.content.text_length()).sum();
+        while self.edit_history.len() > MAX_EDIT_HISTORY_VERSIONS
+            || (total_text_length > MAX_EDIT_HISTORY_TEXT_LENGTH && self.edit_history.len() > 1)
+        {
+            let removed = self.edit_history.remove(0);
+            total_text_length -= removed.content.text_length();
+        }
+    }
+
+    // Returns the previous versions of the message, oldest first, or nothing if the message has been deleted
+    pub fn edit_history(&self, my_user_id: Option<UserId>) -> Vec<MessageVersion> {
+        if self.deleted_by.is_some() {
+            Vec::new()
+        } else {
+            self.edit_history.iter().map(|v| v.hydrate(my_user_id)).collect()
+        }
+    }
+
     pub fn hydrate(&self, my_user_id: Option<UserId>) -> Message {
         Message {
             message_index: self.message_index,
@@ -599,9 +662,10 @@ impl ChatMetricsInternal {
 
 #[cfg(test)]
 mod tests {
+    use super::{MAX_EDIT_HISTORY_TEXT_LENGTH, MAX_EDIT_HISTORY_VERSIONS};
     use crate::{
-        ChatEventInternal, ChatInternal, DeletedByInternal, MessageContentInternal, MessageInternal, ReplyContextInternal,
-        TextContentInternal, ThreadSummaryInternal,
+        ChatEventInternal, ChatInternal, DeletedByInternal, MessageContentInternal, MessageInternal, MessageVersionInternal,
+        ReplyContextInternal, TextContentInternal, ThreadSummaryInternal,
     };
     use candid::Principal;
     use std::collections::{HashMap, HashSet};
@@ -622,6 +686,7 @@ mod tests {
             thread_summary: None,
             forwarded: false,
             block_level_markdown: false,
+            edit_history: Vec::new(),
         };
 
         let message_bytes_len = msgpack::serialize_then_unwrap(&message).len();
@@ -673,6 +738,7 @@ mod tests {
             }),
             forwarded: true,
             block_level_markdown: false,
+            edit_history: Vec::new(),
         };
 
         let message_bytes_len = msgpack::serialize_then_unwrap(&message).len();
@@ -693,4 +759,42 @@ mod tests {
 
         let _deserialized: EventWrapperInternal<ChatEventInternal> = msgpack::deserialize_then_unwrap(&event_bytes);
     }
+
+    #[test]
+    fn edit_history_is_bounded() {
+        let text = |len: usize| MessageContentInternal::Text(TextContentInternal { text: "a".repeat(len) });
+        let mut message = MessageInternal {
+            message_index: 1.into(),
+            message_id: 1.into(),
+            sender: Principal::from_text("4bkt6-4aaaa-aaaaf-aaaiq-cai").unwrap().into(),
+            content: text(1),
+            replies_to: None,
+            reactions: Vec::new(),
+            tips: Tips::default(),
+            last_edited: None,
+            deleted_by: None,
+            thread_summary: None,
+            forwarded: false,
+            block_level_markdown: false,
+            edit_history: Vec::new(),
+        };
+
+        for i in 0..(MAX_EDIT_HISTORY_VERSIONS as u64 + 5) {
+            message.push_edit_history(MessageVersionInternal {
+                content: text(1),
+                block_level_markdown: false,
+                replaced_at: i,
+            });
+        }
+        assert_eq!(message.edit_history.len(), MAX_EDIT_HISTORY_VERSIONS);
+        assert_eq!(message.edit_history[0].replaced_at, 5);
+
+        message.push_edit_history(MessageVersionInternal {
+            content: text(MAX_EDIT_HISTORY_TEXT_LENGTH as usize),
+            block_level_markdown: false,
+            replaced_at: 100,
+        });
+        assert_eq!(message.edit_history.len(), 1);
+        assert_eq!(message.edit_history[0].replaced_at, 100);
+    }
 }
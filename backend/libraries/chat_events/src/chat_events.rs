@@ -1,19 +1,21 @@
 use crate::expiring_events::ExpiringEvents;
 use crate::last_updated_timestamps::LastUpdatedTimestamps;
+use crate::search_index::SearchIndex;
 use crate::*;
 use candid::Principal;
 use event_store_producer::{EventBuilder, EventStoreClient, Runtime};
 use itertools::Itertools;
 use rand::rngs::StdRng;
-use rand::Rng;
-use search::{Document, Query};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use search::{Document, Query, ThreadFilter};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::cmp::{max, Reverse};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::HashMap;
 use types::{
-    AcceptP2PSwapResult, CallParticipant, CancelP2PSwapResult, CanisterId, Chat, CompleteP2PSwapResult,
+    AcceptP2PSwapResult, AccessGate, CallParticipant, CancelP2PSwapResult, CanisterId, Chat, CompleteP2PSwapResult,
     CompletedCryptoTransaction, Cryptocurrency, DirectChatCreated, EventIndex, EventWrapper, EventsTimeToLiveUpdated,
     GroupCanisterThreadDetails, GroupCreated, GroupFrozen, GroupUnfrozen, Hash, HydratedMention, Mention, Message,
     MessageContentInitial, MessageEditedEventPayload, MessageEventPayload, MessageId, MessageIndex, MessageMatch,
@@ -21,7 +23,7 @@ use types::{
     P2PSwapContent, P2PSwapStatus, PendingCryptoTransaction, PollVotes, ProposalUpdate, PushEventResult, Reaction,
     ReactionAddedEventPayload, RegisterVoteResult, ReserveP2PSwapResult, ReserveP2PSwapSuccess, TimestampMillis,
     TimestampNanos, Timestamped, Tips, UserId, VideoCall, VideoCallEndedEventPayload, VideoCallParticipants, VideoCallPresence,
-    VoteOperation,
+    VoteOperation, VoteWeighting,
 };
 
 pub const OPENCHAT_BOT_USER_ID: UserId = UserId::new(Principal::from_slice(&[228, 104, 142, 9, 133, 211, 135, 217, 129, 1]));
@@ -39,6 +41,8 @@ pub struct ChatEvents {
     last_updated_timestamps: LastUpdatedTimestamps,
     video_call_in_progress: Timestamped<Option<VideoCall>>,
     anonymized_id: String,
+    #[serde(default)]
+    search_index: SearchIndex,
 }
 
 impl ChatEvents {
@@ -70,7 +74,7 @@ impl ChatEvents {
     ) -> ChatEvents {
         let mut events = ChatEvents {
             chat: Chat::Direct(them.into()),
-            main: ChatEventsList::default(),
+            main: ChatEventsList::new(KeyPrefix::new(Chat::Direct(them.into()), None)),
             threads: HashMap::new(),
             metrics: ChatMetricsInternal::default(),
             per_user_metrics: HashMap::new(),
@@ -80,6 +84,7 @@ impl ChatEvents {
             last_updated_timestamps: LastUpdatedTimestamps::default(),
             video_call_in_progress: Timestamped::default(),
             anonymized_id: hex::encode(anonymized_id.to_be_bytes()),
+            search_index: SearchIndex::populated(),
         };
 
         events.push_event(None, ChatEventInternal::DirectChatCreated(DirectChatCreated {}), 0, now);
@@ -96,9 +101,10 @@ impl ChatEvents {
         anonymized_id: u128,
         now: TimestampMillis,
     ) -> ChatEvents {
+        let chat: Chat = chat.into();
         let mut events = ChatEvents {
-            chat: chat.into(),
-            main: ChatEventsList::default(),
+            chat,
+            main: ChatEventsList::new(KeyPrefix::new(chat, None)),
             threads: HashMap::new(),
             metrics: ChatMetricsInternal::default(),
             per_user_metrics: HashMap::new(),
@@ -108,6 +114,7 @@ impl ChatEvents {
             last_updated_timestamps: LastUpdatedTimestamps::default(),
             video_call_in_progress: Timestamped::default(),
             anonymized_id: hex::encode(anonymized_id.to_be_bytes()),
+            search_index: SearchIndex::populated(),
         };
 
         events.push_event(
@@ -128,6 +135,36 @@ impl ChatEvents {
         self.chat = chat;
     }
 
+    // Moves up to `max_events` events of a single events list from the heap into stable memory, returning true once
+    // every events list has been migrated
+    pub fn migrate_events_to_stable_memory(&mut self, max_events: usize) -> bool {
+        if !self
+            .main
+            .migrate_to_stable_memory(KeyPrefix::new(self.chat, None), max_events)
+        {
+            return false;
+        }
+
+        if let Some((root_message_index, thread)) = self.threads.iter_mut().find(|(_, t)| !t.is_fully_migrated()) {
+            thread.migrate_to_stable_memory(KeyPrefix::new(self.chat, Some(*root_message_index)), max_events);
+            return false;
+        }
+
+        true
+    }
+
+    pub fn is_fully_migrated_to_stable_memory(&self) -> bool {
+        self.main.is_fully_migrated() && self.threads.values().all(|t| t.is_fully_migrated())
+    }
+
+    // Detaches each events list from its events in stable memory, returning the key prefixes of those lists whose
+    // events are held there
+    pub(crate) fn take_stable_memory_prefixes(&mut self) -> Vec<KeyPrefix> {
+        let mut prefixes: Vec<_> = self.main.take_stable_memory_prefix().into_iter().collect();
+        prefixes.extend(self.threads.values_mut().filter_map(|t| t.take_stable_memory_prefix()));
+        prefixes
+    }
+
     pub fn iter_recently_updated_events(
         &self,
     ) -> impl Iterator<Item = (Option<MessageIndex>, EventIndex, TimestampMillis)> + '_ {
@@ -140,7 +177,9 @@ impl ChatEvents {
         mut event_store_client: Option<&mut EventStoreClient<R>>,
     ) -> EventWrapper<Message> {
         let events_list = if let Some(root_message_index) = args.thread_root_message_index {
-            self.threads.entry(root_message_index).or_default()
+            self.threads
+                .entry(root_message_index)
+                .or_insert_with(|| ChatEventsList::new(KeyPrefix::new(self.chat, Some(root_message_index))))
         } else {
             &mut self.main
         };
@@ -180,6 +219,7 @@ impl ChatEvents {
             thread_summary: None,
             forwarded: args.forwarded,
             block_level_markdown: args.block_level_markdown,
+            edit_history: Vec::new(),
         };
 
         add_to_metrics(
@@ -192,6 +232,11 @@ impl ChatEvents {
 
         let message = message_internal.hydrate(Some(message_internal.sender));
 
+        self.search_index.add(
+            (args.thread_root_message_index, message_index),
+            message_internal.content.search_terms(),
+        );
+
         let push_event_result = self.push_event(
             args.thread_root_message_index,
             ChatEventInternal::Message(Box::new(message_internal)),
@@ -258,9 +303,18 @@ impl ChatEvents {
                             || block_level_markdown_update.is_some();
 
                         let old_length = message.content.text_length();
-                        message.content = args.content.into();
+                        let search_index_key = (args.thread_root_message_index, message.message_index);
+                        let old_search_terms = message.content.search_terms();
+                        let previous_content = std::mem::replace(&mut message.content, args.content.into());
+                        let new_search_terms = message.content.search_terms();
 
                         if edited {
+                            message.push_edit_history(MessageVersionInternal {
+                                content: previous_content,
+                                block_level_markdown: message.block_level_markdown,
+                                replaced_at: args.now,
+                            });
+
                             if let Some(block_level_markdown) = block_level_markdown_update {
                                 message.block_level_markdown = block_level_markdown;
                             }
@@ -298,6 +352,8 @@ impl ChatEvents {
                             );
                         }
 
+                        self.search_index
+                            .replace(search_index_key, &old_search_terms, new_search_terms);
                         self.last_updated_timestamps
                             .mark_updated(args.thread_root_message_index, event_index, args.now);
                     }
@@ -344,10 +400,13 @@ impl ChatEvents {
                     DeleteMessageResult::NotAuthorized
                 } else {
                     let sender = message.sender;
+                    let search_index_key = (args.thread_root_message_index, message.message_index);
+                    let search_terms = message.content.search_terms();
                     message.deleted_by = Some(DeletedByInternal {
                         deleted_by: args.caller,
                         timestamp: args.now,
                     });
+                    self.search_index.remove(search_index_key, &search_terms);
                     self.last_updated_timestamps
                         .mark_updated(args.thread_root_message_index, event_index, args.now);
 
@@ -391,7 +450,10 @@ impl ChatEvents {
                         MessageContentInternal::Crypto(_) => UndeleteMessageResult::InvalidMessageType,
                         _ => {
                             let sender = message.sender;
+                            let search_index_key = (args.thread_root_message_index, message.message_index);
+                            let search_terms = message.content.search_terms();
                             message.deleted_by = None;
+                            self.search_index.add(search_index_key, search_terms);
                             self.last_updated_timestamps
                                 .mark_updated(args.thread_root_message_index, event_index, args.now);
 
@@ -437,6 +499,7 @@ impl ChatEvents {
         let deleted_by = message.deleted_by.clone()?;
 
         let content = std::mem::replace(&mut message.content, MessageContentInternal::Deleted(deleted_by));
+        message.edit_history.clear();
 
         Some((content, message.sender))
     }
@@ -448,7 +511,7 @@ impl ChatEvents {
             args.message_index.into(),
         ) {
             if let MessageContentInternal::Poll(p) = &mut message.content {
-                return match p.register_vote(args.user_id, args.option_index, args.operation) {
+                return match p.register_vote(args.user_id, args.option_index, args.ranking, args.operation) {
                     RegisterVoteResult::Success(existing_vote_removed) => {
                         let votes = p.hydrate(Some(args.user_id)).votes;
 
@@ -486,6 +549,7 @@ impl ChatEvents {
                     RegisterVoteResult::PollEnded => RegisterPollVoteResult::PollEnded,
                     RegisterVoteResult::OptionIndexOutOfRange => RegisterPollVoteResult::OptionIndexOutOfRange,
                     RegisterVoteResult::UserCannotChangeVote => RegisterPollVoteResult::UserCannotChangeVote,
+                    RegisterVoteResult::InvalidRanking => RegisterPollVoteResult::InvalidRanking,
                 };
             }
         }
@@ -507,10 +571,14 @@ impl ChatEvents {
                     EndPollResult::UnableToEndPoll
                 } else {
                     p.ended = true;
+                    // The results of weighted polls are set once the voters' weights have been looked up
+                    if p.config.vote_weighting.is_none() {
+                        p.results = Some(p.tally(None));
+                    }
                     self.last_updated_timestamps
                         .mark_updated(thread_root_message_index, event_index, now);
 
-                    EndPollResult::Success
+                    EndPollResult::Success(p.quiz_results())
                 };
             }
         }
@@ -518,6 +586,65 @@ impl ChatEvents {
         EndPollResult::PollNotFound
     }
 
+    // Returns the vote weighting, end date and the voters whose weights are still needed for a weighted poll which has
+    // ended but whose results are not yet known
+    pub fn poll_awaiting_vote_weights(
+        &self,
+        thread_root_message_index: Option<MessageIndex>,
+        message_index: MessageIndex,
+    ) -> Option<(VoteWeighting, TimestampMillis, Vec<UserId>)> {
+        let (message, _) = self.message_internal(EventIndex::default(), thread_root_message_index, message_index.into())?;
+
+        if let MessageContentInternal::Poll(p) = &message.content {
+            if p.ended && p.results.is_none() {
+                let voters = p
+                    .voters()
+                    .into_iter()
+                    .filter(|u| !p.pending_vote_weights.contains_key(u))
+                    .collect();
+
+                return p
+                    .config
+                    .vote_weighting
+                    .clone()
+                    .zip(p.config.end_date)
+                    .map(|(w, end_date)| (w, end_date, voters));
+            }
+        }
+        None
+    }
+
+    // Adds the weights to the poll's pending tally. Once every voter's weight is known, or if `finalise` is set (in
+    // which case any voters whose weights are still unknown have no weight), the results are calculated.
+    // Returns true if the results were calculated.
+    pub fn record_poll_vote_weights(
+        &mut self,
+        thread_root_message_index: Option<MessageIndex>,
+        message_index: MessageIndex,
+        weights: HashMap<UserId, u128>,
+        finalise: bool,
+        now: TimestampMillis,
+    ) -> bool {
+        if let Some((message, event_index)) =
+            self.message_internal_mut(EventIndex::default(), thread_root_message_index, message_index.into())
+        {
+            if let MessageContentInternal::Poll(p) = &mut message.content {
+                if p.ended && p.results.is_none() {
+                    p.pending_vote_weights.extend(weights);
+
+                    if finalise || p.voters().iter().all(|u| p.pending_vote_weights.contains_key(u)) {
+                        let weights = std::mem::take(&mut p.pending_vote_weights);
+                        p.results = Some(p.tally(Some(&weights)));
+                        self.last_updated_timestamps
+                            .mark_updated(thread_root_message_index, event_index, now);
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
     pub fn prize_refund(
         &self,
         thread_root_message_index: Option<MessageIndex>,
@@ -750,8 +877,19 @@ impl ChatEvents {
         user_id: UserId,
         now: TimestampMillis,
     ) -> ReservePrizeResult {
+        if let Some(quiz_message_index) = self.prize_quiz_message_index(min_visible_event_index, message_id) {
+            if !self.answered_quiz_correctly(quiz_message_index, user_id) {
+                return ReservePrizeResult::NotEligible;
+            }
+        }
+
         if let Some((message, event_index)) = self.message_internal_mut(min_visible_event_index, None, message_id.into()) {
             if let MessageContentInternal::Prize(content) = &mut message.content {
+                // The winners of prize draws are picked by `draw_prize_winners`
+                if content.draw {
+                    return ReservePrizeResult::NotEligible;
+                }
+
                 if content.end_date < now {
                     return ReservePrizeResult::PrizeEnded;
                 }
@@ -780,6 +918,110 @@ impl ChatEvents {
         ReservePrizeResult::MessageNotFound
     }
 
+    pub fn prize_requirements(&self, message_id: MessageId, min_visible_event_index: EventIndex) -> Option<PrizeRequirements> {
+        let (message, _) = self.message_internal(min_visible_event_index, None, message_id.into())?;
+
+        if let MessageContentInternal::Prize(p) = &message.content {
+            Some(PrizeRequirements {
+                draw: p.draw,
+                gate: p.gate.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn enter_prize_draw(
+        &mut self,
+        message_id: MessageId,
+        min_visible_event_index: EventIndex,
+        user_id: UserId,
+        now: TimestampMillis,
+    ) -> EnterPrizeDrawResult {
+        if let Some(quiz_message_index) = self.prize_quiz_message_index(min_visible_event_index, message_id) {
+            if !self.answered_quiz_correctly(quiz_message_index, user_id) {
+                return EnterPrizeDrawResult::NotEligible;
+            }
+        }
+
+        if let Some((message, event_index)) = self.message_internal_mut(min_visible_event_index, None, message_id.into()) {
+            if let MessageContentInternal::Prize(content) = &mut message.content {
+                return if !content.draw {
+                    EnterPrizeDrawResult::NotADraw
+                } else if content.end_date < now {
+                    EnterPrizeDrawResult::PrizeEnded
+                } else if !content.entrants.insert(user_id) {
+                    EnterPrizeDrawResult::AlreadyEntered
+                } else {
+                    self.last_updated_timestamps.mark_updated(None, event_index, now);
+                    EnterPrizeDrawResult::Success
+                };
+            }
+        }
+
+        EnterPrizeDrawResult::MessageNotFound
+    }
+
+    // Picks the winners of a prize draw at random and reserves a prize for each of them. The prizes must then be
+    // transferred, after which each one is either claimed or unreserved.
+    pub fn draw_prize_winners(
+        &mut self,
+        message_index: MessageIndex,
+        seed: [u8; 32],
+        now: TimestampMillis,
+    ) -> Option<PrizeDrawWinners> {
+        let (message, event_index) = self.message_internal_mut(EventIndex::default(), None, message_index.into())?;
+
+        let MessageContentInternal::Prize(content) = &mut message.content else {
+            return None;
+        };
+
+        if !content.draw || content.drawn {
+            return None;
+        }
+        content.drawn = true;
+
+        let mut entrants: Vec<_> = content.entrants.iter().copied().sorted().collect();
+        entrants.shuffle(&mut StdRng::from_seed(seed));
+
+        let mut winners = Vec::new();
+        for user_id in entrants {
+            let Some(amount) = content.prizes_remaining.pop() else {
+                break;
+            };
+            content.reservations.insert(user_id);
+            winners.push((user_id, amount));
+        }
+
+        let result = PrizeDrawWinners {
+            message_id: message.message_id,
+            token: content.transaction.token(),
+            ledger_canister_id: content.transaction.ledger_canister_id(),
+            fee: content.transaction.fee(),
+            winners,
+        };
+        self.last_updated_timestamps.mark_updated(None, event_index, now);
+
+        Some(result)
+    }
+
+    fn prize_quiz_message_index(&self, min_visible_event_index: EventIndex, message_id: MessageId) -> Option<MessageIndex> {
+        let (message, _) = self.message_internal(min_visible_event_index, None, message_id.into())?;
+
+        if let MessageContentInternal::Prize(p) = &message.content {
+            p.quiz_message_index
+        } else {
+            None
+        }
+    }
+
+    fn answered_quiz_correctly(&self, quiz_message_index: MessageIndex, user_id: UserId) -> bool {
+        self.message_internal(EventIndex::default(), None, quiz_message_index.into())
+            .map_or(false, |(message, _)| {
+                matches!(&message.content, MessageContentInternal::Poll(p) if p.ended && p.answered_correctly(user_id))
+            })
+    }
+
     pub fn claim_prize<R: Runtime + Send + 'static>(
         &mut self,
         message_id: MessageId,
@@ -1325,27 +1567,50 @@ impl ChatEvents {
         max_results: u8,
         my_user_id: UserId,
     ) -> Vec<MessageMatch> {
-        self.visible_main_events_reader(min_visible_event_index)
-            .iter(None, true)
-            .filter_map(|e| e.as_event())
-            .filter_map(|e| e.event.as_message().filter(|m| m.deleted_by.is_none()).map(|m| (e, m)))
-            .filter(|(_, m)| if query.users.is_empty() { true } else { query.users.contains(&m.sender) })
-            .filter_map(|(e, m)| {
-                if query.tokens.is_empty() {
-                    Some((1, m))
+        let candidates = if self.search_index.is_populated() { self.search_index.candidates(query) } else { None };
+
+        let events: Vec<_> = if let Some(keys) = candidates {
+            keys.into_iter()
+                .filter(|(root, _)| query.thread.includes(*root))
+                .filter_map(|(root, message_index)| {
+                    self.events_list(min_visible_event_index, root)
+                        .and_then(|l| l.get_event(message_index.into(), visible_from(min_visible_event_index, root)))
+                        .map(|e| (root, e))
+                })
+                .collect()
+        } else {
+            // Without any terms to look up in the index we have to scan the events
+            self.searchable_events_lists(min_visible_event_index, query.thread)
+                .flat_map(|(root, l)| {
+                    l.iter(None, false, visible_from(min_visible_event_index, root))
+                        .filter_map(|e| e.as_event())
+                        .map(move |e| (root, e))
+                })
+                .collect()
+        };
+
+        events
+            .into_iter()
+            .filter_map(|(root, e)| e.event.as_message().filter(|m| m.deleted_by.is_none()).map(|m| (root, e, m)))
+            .filter(|(_, e, m)| query.matches_filters(m.sender, &m.content.message_type(), e.timestamp))
+            .filter_map(|(root, e, m)| {
+                let mut document: Document = (&m.content).into();
+                // Unlike other searches, every word of a message search must match
+                let score = if !document.is_match(query) {
+                    0
+                } else if query.tokens.is_empty() {
+                    1
                 } else {
-                    let mut document: Document = (&m.content).into();
                     document.set_age(now - e.timestamp);
-                    match document.calculate_score(query) {
-                        0 => None,
-                        n => Some((n, m)),
-                    }
-                }
+                    document.calculate_score(query)
+                };
+                (score > 0).then_some((score, e.timestamp, root, m))
             })
-            .sorted_unstable_by_key(|(score, _)| *score)
+            .sorted_unstable_by_key(|(score, timestamp, ..)| (*score, *timestamp))
             .rev()
             .take(max_results as usize)
-            .map(|(score, message)| MessageMatch {
+            .map(|(score, _, root, message)| MessageMatch {
+                thread_root_message_index: root,
                 message_index: message.message_index,
                 sender: message.sender,
                 content: message.content.hydrate(Some(my_user_id)),
@@ -1354,6 +1619,74 @@ impl ChatEvents {
             .collect()
     }
 
+    // Adds up to `max_events` events to the search index of a chat which was created before the index existed,
+    // returning true once every events list has been indexed. Messages sent, edited or deleted in the meantime
+    // keep the index up to date themselves, so the population can be spread across many executions.
+    pub fn populate_search_index(&mut self, max_events: usize) -> bool {
+        if self.search_index.is_populated() {
+            return true;
+        }
+
+        let (root, from) = self.search_index.population_cursor();
+        let events_list = match root {
+            None => Some(&self.main),
+            Some(root) => self.threads.get(&root),
+        }
+        .filter(|l| l.latest_event_index().map_or(false, |latest| latest >= from));
+
+        let mut next_event_index = None;
+        if let Some(events_list) = events_list {
+            for (count, event) in events_list.iter(None, true, from).filter_map(|e| e.as_event()).enumerate() {
+                if count == max_events {
+                    next_event_index = Some(event.index);
+                    break;
+                }
+                if let Some(message) = event.event.as_message().filter(|m| m.deleted_by.is_none()) {
+                    self.search_index
+                        .add((root, message.message_index), message.content.search_terms());
+                }
+            }
+        }
+
+        if let Some(event_index) = next_event_index {
+            self.search_index.set_population_cursor(root, event_index);
+            return false;
+        }
+
+        // Threads are indexed in order of their root message index so that the cursor remains valid
+        let next_thread = self.threads.keys().filter(|r| root.map_or(true, |root| **r > root)).min();
+
+        if let Some(next_thread) = next_thread {
+            self.search_index
+                .set_population_cursor(Some(*next_thread), EventIndex::default());
+            false
+        } else {
+            self.search_index.mark_populated();
+            true
+        }
+    }
+
+    pub fn is_search_index_populated(&self) -> bool {
+        self.search_index.is_populated()
+    }
+
+    fn searchable_events_lists(
+        &self,
+        min_visible_event_index: EventIndex,
+        thread_filter: ThreadFilter,
+    ) -> impl Iterator<Item = (Option<MessageIndex>, &ChatEventsList)> + '_ {
+        let main = thread_filter.includes(None).then_some((None, &self.main));
+
+        let threads = self
+            .threads
+            .iter()
+            .filter(move |(root, _)| thread_filter.includes(Some(**root)))
+            .filter(move |(root, _)| self.main.is_accessible((**root).into(), min_visible_event_index))
+            .map(|(root, l)| (Some(*root), l));
+
+        main.into_iter().chain(threads)
+    }
+
     pub fn push_main_event(&mut self, event: ChatEventInternal, correlation_id: u64, now: TimestampMillis) -> PushEventResult {
         self.push_event(None, event, correlation_id, now)
     }
@@ -1365,7 +1698,10 @@ impl ChatEvents {
         correlation_id: u64,
         now: TimestampMillis,
     ) -> EventIndex {
-        let events = self.threads.entry(thread_root_message_index).or_default();
+        let events = self
+            .threads
+            .entry(thread_root_message_index)
+            .or_insert_with(|| ChatEventsList::new(KeyPrefix::new(self.chat, Some(thread_root_message_index))));
         events.push_event(event, correlation_id, None, now)
     }
 
@@ -1560,8 +1896,14 @@ impl ChatEvents {
             if let Some(event) = self.main.remove(event_index) {
                 result.events.push(event_index);
                 if let ChatEventInternal::Message(m) = event.event {
+                    if m.deleted_by.is_none() {
+                        self.search_index.remove((None, m.message_index), &m.content.search_terms());
+                    }
                     if let Some(thread) = m.thread_summary {
-                        self.threads.remove(&m.message_index);
+                        if let Some(mut thread_events) = self.threads.remove(&m.message_index) {
+                            thread_events.clear();
+                        }
+                        self.search_index.remove_thread(m.message_index);
                         result
                             .threads
                             .push((m.message_index, thread.participants_and_followers(true)));
@@ -1825,6 +2167,15 @@ impl ChatEvents {
     }
 }
 
+// Thread events are visible to anyone who can see the thread's root message
+fn visible_from(min_visible_event_index: EventIndex, thread_root_message_index: Option<MessageIndex>) -> EventIndex {
+    if thread_root_message_index.is_some() {
+        EventIndex::default()
+    } else {
+        min_visible_event_index
+    }
+}
+
 fn add_to_metrics<F: FnMut(&mut ChatMetricsInternal)>(
     metrics: &mut ChatMetricsInternal,
     per_user_metrics: &mut HashMap<UserId, ChatMetricsInternal>,
@@ -1929,6 +2280,7 @@ pub struct RegisterPollVoteArgs {
     pub thread_root_message_index: Option<MessageIndex>,
     pub message_index: MessageIndex,
     pub option_index: u32,
+    pub ranking: Vec<u32>,
     pub operation: VoteOperation,
     pub correlation_id: u64,
     pub now: TimestampMillis,
@@ -1941,10 +2293,12 @@ pub enum RegisterPollVoteResult {
     PollNotFound,
     OptionIndexOutOfRange,
     UserCannotChangeVote,
+    InvalidRanking,
 }
 
 pub enum EndPollResult {
-    Success,
+    // If the poll is a quiz, this holds who took part and who answered correctly
+    Success(Option<QuizResults>),
     PollNotFound,
     UnableToEndPoll,
 }
@@ -1989,12 +2343,36 @@ pub enum TipMessageResult {
     RecipientMismatch,
 }
 
+pub struct PrizeRequirements {
+    pub draw: bool,
+    pub gate: Option<AccessGate>,
+}
+
+pub enum EnterPrizeDrawResult {
+    Success,
+    AlreadyEntered,
+    NotEligible,
+    NotADraw,
+    PrizeEnded,
+    MessageNotFound,
+}
+
+pub struct PrizeDrawWinners {
+    pub message_id: MessageId,
+    pub token: Cryptocurrency,
+    pub ledger_canister_id: CanisterId,
+    pub fee: u128,
+    // Each winner along with the amount they have won
+    pub winners: Vec<(UserId, u128)>,
+}
+
 pub enum ReservePrizeResult {
     Success(Cryptocurrency, CanisterId, u128, u128),
     MessageNotFound,
     AlreadyClaimed,
     PrizeFullyClaimed,
     PrizeEnded,
+    NotEligible,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -1,17 +1,20 @@
 use crate::last_updated_timestamps::LastUpdatedTimestamps;
-use crate::{ChatEventInternal, ChatInternal, EventKey, EventOrExpiredRangeInternal, EventsMap, MessageInternal};
+use crate::{
+    ChatEventInternal, ChatEventsMap, ChatInternal, EventKey, EventOrExpiredRangeInternal, EventsMap, KeyPrefix,
+    MessageInternal,
+};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry::Vacant;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use types::{
     ChatEvent, EventIndex, EventOrExpiredRange, EventWrapper, EventWrapperInternal, HydratedMention, Mention, Message,
-    MessageId, MessageIndex, TimestampMillis, UserId,
+    MessageId, MessageIndex, MessageVersion, TimestampMillis, UserId,
 };
 
 #[derive(Serialize, Deserialize, Default)]
-pub struct ChatEventsList<M = BTreeMap<EventIndex, EventWrapperInternal<ChatEventInternal>>> {
+pub struct ChatEventsList<M = ChatEventsMap> {
     events_map: M,
     message_id_map: HashMap<MessageId, EventIndex>,
     message_event_indexes: Vec<EventIndex>,
@@ -19,6 +22,31 @@ pub struct ChatEventsList<M = BTreeMap<EventIndex, EventWrapperInternal<ChatEven
     latest_event_timestamp: Option<TimestampMillis>,
 }
 
+impl ChatEventsList {
+    pub fn new(prefix: KeyPrefix) -> ChatEventsList {
+        ChatEventsList {
+            events_map: ChatEventsMap::new(prefix),
+            message_id_map: HashMap::new(),
+            message_event_indexes: Vec::new(),
+            latest_event_index: None,
+            latest_event_timestamp: None,
+        }
+    }
+
+    // Returns true once there are no more events to be moved into stable memory
+    pub(crate) fn migrate_to_stable_memory(&mut self, prefix: KeyPrefix, max_events: usize) -> bool {
+        self.events_map.migrate_to_stable_memory(prefix, max_events)
+    }
+
+    pub(crate) fn is_fully_migrated(&self) -> bool {
+        self.events_map.is_fully_migrated()
+    }
+
+    pub(crate) fn take_stable_memory_prefix(&mut self) -> Option<KeyPrefix> {
+        self.events_map.take_stable_memory_prefix()
+    }
+}
+
 impl<M: EventsMap> ChatEventsList<M> {
     pub(crate) fn set_block_level_markdown(&mut self, cutoff: TimestampMillis) {
         for event_index in self.message_event_indexes.iter().rev().copied() {
@@ -178,16 +206,28 @@ impl<M: EventsMap> ChatEventsList<M> {
     }
 
     pub fn migrate_replies(&mut self, old: ChatInternal, new: ChatInternal) -> Vec<EventIndex> {
-        let mut updated = Vec::new();
-        for event in self.events_map.values_mut() {
-            if let Some(message) = event.event.as_message_mut() {
-                if let Some(r) = message.replies_to.as_mut() {
-                    if let Some((chat, _)) = r.chat_if_other.as_mut() {
-                        if *chat == old {
-                            *chat = new;
-                            updated.push(event.index);
-                        }
-                    }
+        let updated: Vec<_> = self
+            .events_map
+            .values()
+            .filter(|e| {
+                e.event
+                    .as_message()
+                    .and_then(|m| m.replies_to.as_ref())
+                    .and_then(|r| r.chat_if_other.as_ref())
+                    .map_or(false, |(chat, _)| *chat == old)
+            })
+            .map(|e| e.index)
+            .collect();
+
+        for event_index in updated.iter() {
+            if let Some(event) = self.events_map.get_mut(*event_index) {
+                if let Some((chat, _)) = event
+                    .event
+                    .as_message_mut()
+                    .and_then(|m| m.replies_to.as_mut())
+                    .and_then(|r| r.chat_if_other.as_mut())
+                {
+                    *chat = new;
                 }
             }
         }
@@ -236,7 +276,8 @@ impl<M: EventsMap> ChatEventsList<M> {
     }
 
     pub fn last_mut(&mut self) -> Option<&mut EventWrapperInternal<ChatEventInternal>> {
-        self.events_map.values_mut().next_back()
+        let event_index = self.events_map.values().next_back()?.index;
+        self.events_map.get_mut(event_index)
     }
 
     pub fn len(&self) -> usize {
@@ -275,7 +316,7 @@ impl<M: EventsMap> ChatEventsList<M> {
     }
 }
 
-pub struct ChatEventsListReader<'r, M = BTreeMap<EventIndex, EventWrapperInternal<ChatEventInternal>>> {
+pub struct ChatEventsListReader<'r, M = ChatEventsMap> {
     events_list: &'r ChatEventsList<M>,
     last_updated_timestamps: &'r LastUpdatedTimestamps,
     min_visible_event_index: EventIndex,
@@ -386,6 +427,10 @@ pub trait Reader {
         self.message_internal(event_key).map(|m| m.hydrate(my_user_id))
     }
 
+    fn message_edit_history(&self, event_key: EventKey, my_user_id: Option<UserId>) -> Option<Vec<MessageVersion>> {
+        self.message_internal(event_key).map(|m| m.edit_history(my_user_id))
+    }
+
     fn message_event_internal(&self, event_key: EventKey) -> Option<EventWrapper<&MessageInternal>> {
         self.get_event(event_key)
             .and_then(|e| e.event.as_message().map(|m| (e, m)))
@@ -450,6 +495,11 @@ pub trait Reader {
             ChatEventInternal::GroupGateUpdated(g) => ChatEvent::GroupGateUpdated(*g.clone()),
             ChatEventInternal::UsersInvited(e) => ChatEvent::UsersInvited(*e.clone()),
             ChatEventInternal::MembersAddedToPublicChannel(m) => ChatEvent::MembersAddedToDefaultChannel(m.as_ref().into()),
+            ChatEventInternal::MembersLapsed(m) => ChatEvent::MembersLapsed(*m.clone()),
+            ChatEventInternal::MembersRemovedByGate(m) => ChatEvent::MembersRemovedByGate(*m.clone()),
+            ChatEventInternal::MemberMuted(m) => ChatEvent::MemberMuted(*m.clone()),
+            ChatEventInternal::CustomRoleAssigned(r) => ChatEvent::CustomRoleAssigned(*r.clone()),
+            ChatEventInternal::CustomRoleUnassigned(r) => ChatEvent::CustomRoleUnassigned(*r.clone()),
             ChatEventInternal::Empty => ChatEvent::Empty,
         };
 
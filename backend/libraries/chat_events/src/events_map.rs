@@ -1,8 +1,27 @@
-use crate::ChatEventInternal;
+use crate::{ChatEventInternal, KeyPrefix, StableEventsMap};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
 use std::collections::BTreeMap;
+use std::fmt::Formatter;
 use std::ops::RangeBounds;
 use types::{EventIndex, EventWrapperInternal};
 
+thread_local! {
+    static EXPORTING: Cell<bool> = Cell::default();
+}
+
+// Serializes a value containing events lists so that it can be sent to another canister. Events held in stable memory
+// can only be read from within this canister, so each events list is written out as a plain map of events, with the
+// events in stable memory being streamed straight into the output rather than first being moved onto the heap.
+pub fn serialize_for_export<T: Serialize>(value: &T) -> Vec<u8> {
+    EXPORTING.set(true);
+    let bytes = msgpack::serialize_then_unwrap(value);
+    EXPORTING.set(false);
+    bytes
+}
+
 pub trait EventsMap {
     fn get_mut(&mut self, event_index: EventIndex) -> Option<&mut EventWrapperInternal<ChatEventInternal>>;
     fn insert(&mut self, event: EventWrapperInternal<ChatEventInternal>);
@@ -12,7 +31,6 @@ pub trait EventsMap {
         range: R,
     ) -> Box<dyn DoubleEndedIterator<Item = (&EventIndex, &EventWrapperInternal<ChatEventInternal>)> + '_>;
     fn values(&self) -> Box<dyn DoubleEndedIterator<Item = &EventWrapperInternal<ChatEventInternal>> + '_>;
-    fn values_mut(&mut self) -> Box<dyn DoubleEndedIterator<Item = &mut EventWrapperInternal<ChatEventInternal>> + '_>;
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool;
 }
@@ -41,10 +59,6 @@ impl EventsMap for BTreeMap<EventIndex, EventWrapperInternal<ChatEventInternal>>
         Box::new(self.values())
     }
 
-    fn values_mut(&mut self) -> Box<dyn DoubleEndedIterator<Item = &mut EventWrapperInternal<ChatEventInternal>> + '_> {
-        Box::new(self.values_mut())
-    }
-
     fn len(&self) -> usize {
         self.len()
     }
@@ -53,3 +67,144 @@ impl EventsMap for BTreeMap<EventIndex, EventWrapperInternal<ChatEventInternal>>
         self.is_empty()
     }
 }
+
+// Events are moved from the heap into stable memory in batches. New events are added to stable memory once it is in
+// use, and events are moved across starting from the latest, so every event on the heap precedes every event in
+// stable memory.
+#[derive(Default)]
+pub struct ChatEventsMap {
+    heap: BTreeMap<EventIndex, EventWrapperInternal<ChatEventInternal>>,
+    stable: Option<StableEventsMap>,
+}
+
+impl ChatEventsMap {
+    pub fn new(prefix: KeyPrefix) -> ChatEventsMap {
+        ChatEventsMap {
+            heap: BTreeMap::new(),
+            stable: crate::stable_events_map::is_stable_storage_initialized().then(|| StableEventsMap::new(prefix)),
+        }
+    }
+
+    // Returns true once there are no more events to be moved into stable memory
+    pub fn migrate_to_stable_memory(&mut self, prefix: KeyPrefix, max_events: usize) -> bool {
+        if !crate::stable_events_map::is_stable_storage_initialized() {
+            return true;
+        }
+
+        let stable = self.stable.get_or_insert_with(|| StableEventsMap::new(prefix));
+        for _ in 0..max_events {
+            let Some((_, event)) = self.heap.pop_last() else {
+                break;
+            };
+            stable.insert(event);
+        }
+        self.heap.is_empty()
+    }
+
+    pub fn is_fully_migrated(&self) -> bool {
+        self.heap.is_empty() && (self.stable.is_some() || !crate::stable_events_map::is_stable_storage_initialized())
+    }
+
+    // Detaches the events held in stable memory from this map, returning the key prefix under which they are stored
+    // so that they can be removed later on
+    pub(crate) fn take_stable_memory_prefix(&mut self) -> Option<KeyPrefix> {
+        self.stable.take().map(|s| s.into_prefix())
+    }
+}
+
+impl EventsMap for ChatEventsMap {
+    fn get_mut(&mut self, event_index: EventIndex) -> Option<&mut EventWrapperInternal<ChatEventInternal>> {
+        match self.heap.get_mut(&event_index) {
+            Some(event) => Some(event),
+            None => self.stable.as_mut().and_then(|s| s.get_mut(event_index)),
+        }
+    }
+
+    fn insert(&mut self, event: EventWrapperInternal<ChatEventInternal>) {
+        match self.stable.as_mut() {
+            Some(stable) if !self.heap.contains_key(&event.index) => stable.insert(event),
+            _ => {
+                self.heap.insert(event.index, event);
+            }
+        }
+    }
+
+    fn remove(&mut self, event_index: EventIndex) -> Option<EventWrapperInternal<ChatEventInternal>> {
+        self.heap
+            .remove(&event_index)
+            .or_else(|| self.stable.as_mut().and_then(|s| s.remove(event_index)))
+    }
+
+    fn range<R: RangeBounds<EventIndex>>(
+        &self,
+        range: R,
+    ) -> Box<dyn DoubleEndedIterator<Item = (&EventIndex, &EventWrapperInternal<ChatEventInternal>)> + '_> {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+
+        Box::new(
+            self.heap
+                .range(bounds)
+                .chain(self.stable.iter().flat_map(move |s| s.range(bounds))),
+        )
+    }
+
+    fn values(&self) -> Box<dyn DoubleEndedIterator<Item = &EventWrapperInternal<ChatEventInternal>> + '_> {
+        Box::new(self.range(..).map(|(_, e)| e))
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len() + self.stable.as_ref().map_or(0, |s| s.len())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Serialize for ChatEventsMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if EXPORTING.get() {
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for (event_index, event) in self.heap.iter() {
+                map.serialize_entry(event_index, event)?;
+            }
+            if let Some(stable) = self.stable.as_ref() {
+                stable.serialize_events(&mut map)?;
+            }
+            map.end()
+        } else {
+            (&self.heap, &self.stable).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ChatEventsMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ChatEventsMapVisitor)
+    }
+}
+
+struct ChatEventsMapVisitor;
+
+impl<'de> Visitor<'de> for ChatEventsMapVisitor {
+    type Value = ChatEventsMap;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of events or a tuple of (heap events, stable events)")
+    }
+
+    // Events lists serialized before events could be stored in stable memory are a plain map of events
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut heap = BTreeMap::new();
+        while let Some((event_index, event)) = map.next_entry()? {
+            heap.insert(event_index, event);
+        }
+        Ok(ChatEventsMap { heap, stable: None })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let heap = seq.next_element()?.unwrap_or_default();
+        let stable = seq.next_element()?.flatten();
+        Ok(ChatEventsMap { heap, stable })
+    }
+}
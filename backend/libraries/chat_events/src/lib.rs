@@ -6,12 +6,15 @@ mod events_map;
 mod expiring_events;
 mod last_updated_timestamps;
 mod message_content_internal;
+mod search_index;
+mod stable_events_map;
 
 pub use crate::chat_event_internal::*;
 pub use crate::chat_events::*;
 pub use crate::chat_events_list::*;
 pub use crate::events_map::*;
 pub use crate::message_content_internal::*;
+pub use crate::stable_events_map::*;
 
 fn incr(counter: &mut u64) {
     *counter = counter.saturating_add(1);
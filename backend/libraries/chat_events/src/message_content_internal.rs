@@ -3,19 +3,19 @@ use ic_ledger_types::Tokens;
 use ledger_utils::{create_pending_transaction, format_crypto_amount};
 use search::Document;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use types::{
-    is_default, is_empty_hashmap, is_empty_hashset, is_empty_slice, AudioContent, BlobReference, CallParticipant, CanisterId,
-    CompletedCryptoTransaction, ContentWithCaptionEventPayload, CryptoContent, CryptoContentEventPayload, CryptoTransaction,
-    CustomContent, FileContent, FileContentEventPayload, GiphyContent, GiphyImageVariant,
+    is_default, is_empty_hashmap, is_empty_hashset, is_empty_slice, AccessGate, AudioContent, BlobReference, CallParticipant,
+    CanisterId, CompletedCryptoTransaction, ContentWithCaptionEventPayload, CryptoContent, CryptoContentEventPayload,
+    CryptoTransaction, CustomContent, FileContent, FileContentEventPayload, GiphyContent, GiphyImageVariant,
     GovernanceProposalContentEventPayload, ImageContent, ImageOrVideoContentEventPayload, MessageContent,
     MessageContentEventPayload, MessageContentInitial, MessageIndex, MessageReminderContent,
     MessageReminderContentEventPayload, MessageReminderCreatedContent, MessageReport, P2PSwapContent,
-    P2PSwapContentEventPayload, PendingCryptoTransaction, PollConfig, PollContent, PollContentEventPayload, PollVotes,
-    PrizeContent, PrizeContentEventPayload, PrizeContentInitial, PrizeWinnerContent, PrizeWinnerContentEventPayload, Proposal,
-    ProposalContent, RegisterVoteResult, ReportedMessage, ReportedMessageContentEventPayload, TextContent,
-    TextContentEventPayload, ThumbnailData, TimestampMillis, TimestampNanos, TotalVotes, UserId, VideoCallContent,
-    VideoCallPresence, VideoCallType, VideoContent, VoteOperation,
+    P2PSwapContentEventPayload, PendingCryptoTransaction, PollConfig, PollContent, PollContentEventPayload, PollResults,
+    PollVotes, PollVotingSystem, PrizeContent, PrizeContentEventPayload, PrizeContentInitial, PrizeWinnerContent,
+    PrizeWinnerContentEventPayload, Proposal, ProposalContent, RegisterVoteResult, ReportedMessage,
+    ReportedMessageContentEventPayload, TextContent, TextContentEventPayload, ThumbnailData, TimestampMillis, TimestampNanos,
+    TotalVotes, UserId, VideoCallContent, VideoCallPresence, VideoCallType, VideoContent, VoteOperation,
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -125,6 +125,10 @@ impl MessageContentInternal {
         }
     }
 
+    pub fn search_terms(&self) -> BTreeSet<String> {
+        Document::from(self).terms()
+    }
+
     pub fn text_length(&self) -> u32 {
         self.text().map(|t| t.len() as u32).unwrap_or_default()
     }
@@ -569,6 +573,14 @@ pub struct PollContentInternal {
     pub votes: HashMap<u32, Vec<UserId>>,
     #[serde(rename = "e", alias = "ended")]
     pub ended: bool,
+    // Each user's full ranking of the options. Only used by ranked-choice polls
+    #[serde(rename = "r", default, skip_serializing_if = "HashMap::is_empty")]
+    pub rankings: HashMap<UserId, Vec<u32>>,
+    #[serde(rename = "w", default, skip_serializing_if = "Option::is_none")]
+    pub results: Option<PollResults>,
+    // The weights looked up so far for a weighted poll which has ended but whose results are not yet known
+    #[serde(rename = "p", default, skip_serializing_if = "HashMap::is_empty")]
+    pub pending_vote_weights: HashMap<UserId, u128>,
 }
 
 impl From<PollContent> for PollContentInternal {
@@ -577,6 +589,9 @@ impl From<PollContent> for PollContentInternal {
             config: value.config,
             votes: HashMap::new(),
             ended: false,
+            rankings: HashMap::new(),
+            results: None,
+            pending_vote_weights: HashMap::new(),
         }
     }
 }
@@ -585,7 +600,9 @@ impl MessageContentInternalSubtype for PollContentInternal {
     type ContentType = PollContent;
 
     fn hydrate(&self, my_user_id: Option<UserId>) -> Self::ContentType {
-        let user_votes = if let Some(user_id) = my_user_id {
+        let user_votes = if let Some(ranking) = my_user_id.and_then(|u| self.rankings.get(&u)) {
+            ranking.clone()
+        } else if let Some(user_id) = my_user_id {
             self.votes
                 .iter()
                 .filter(|(_, v)| v.contains(&user_id))
@@ -596,7 +613,8 @@ impl MessageContentInternalSubtype for PollContentInternal {
         };
 
         let total_votes: TotalVotes;
-        let hide_votes = self.config.end_date.is_some() && !self.ended && !self.config.show_votes_before_end_date;
+        let hide_votes =
+            self.config.end_date.is_some() && !self.ended && (!self.config.show_votes_before_end_date || self.config.is_quiz());
         if hide_votes {
             total_votes = TotalVotes::Hidden(self.votes.values().map(|v| v.len() as u32).sum());
         } else if self.config.anonymous {
@@ -605,23 +623,42 @@ impl MessageContentInternalSubtype for PollContentInternal {
             total_votes = TotalVotes::Visible(self.votes.clone());
         }
 
+        let mut config = self.config.clone();
+        if !self.ended {
+            if let Some(correct_options) = config.correct_options.as_mut() {
+                correct_options.clear();
+            }
+        }
+
         PollContent {
-            config: self.config.clone(),
+            config,
             votes: PollVotes {
                 total: total_votes,
                 user: user_votes,
             },
             ended: self.ended,
+            results: self.results.clone(),
         }
     }
 }
 
 impl PollContentInternal {
-    pub fn register_vote(&mut self, user_id: UserId, option_index: u32, operation: VoteOperation) -> RegisterVoteResult {
+    // For ranked-choice polls, `ranking` holds the user's preferences in order. If it is empty then `option_index` is
+    // treated as the user's only preference.
+    pub fn register_vote(
+        &mut self,
+        user_id: UserId,
+        option_index: u32,
+        ranking: Vec<u32>,
+        operation: VoteOperation,
+    ) -> RegisterVoteResult {
         if self.ended {
             RegisterVoteResult::PollEnded
         } else if option_index > (self.config.options.len() as u32) + 1 {
             RegisterVoteResult::OptionIndexOutOfRange
+        } else if self.config.voting_system() == PollVotingSystem::RankedChoice {
+            let ranking = if ranking.is_empty() { vec![option_index] } else { ranking };
+            self.register_ranked_vote(user_id, ranking, operation)
         } else {
             match operation {
                 VoteOperation::RegisterVote => {
@@ -631,7 +668,7 @@ impl PollContentInternal {
                     }
                     votes.push(user_id);
                     let mut existing_vote_removed = false;
-                    if !self.config.allow_multiple_votes_per_user {
+                    if !self.config.allows_multiple_votes_per_user() {
                         // If the user has already left a vote, remove it
                         for (_, votes) in self.votes.iter_mut().filter(|(&o, _)| o != option_index) {
                             if let Some((index, _)) = votes.iter().enumerate().find(|(_, &u)| u == user_id) {
@@ -660,6 +697,144 @@ impl PollContentInternal {
             }
         }
     }
+
+    fn register_ranked_vote(&mut self, user_id: UserId, ranking: Vec<u32>, operation: VoteOperation) -> RegisterVoteResult {
+        match operation {
+            VoteOperation::RegisterVote => {
+                let options_count = self.config.options.len() as u32;
+                let mut set = HashSet::new();
+                if ranking.iter().any(|o| *o >= options_count || !set.insert(*o)) {
+                    return RegisterVoteResult::InvalidRanking;
+                }
+
+                let existing_vote_removed = match self.rankings.get(&user_id) {
+                    Some(existing) if *existing == ranking => return RegisterVoteResult::SuccessNoChange,
+                    Some(_) if !self.config.allow_user_to_change_vote => return RegisterVoteResult::UserCannotChangeVote,
+                    Some(_) => true,
+                    None => false,
+                };
+
+                // Only the first preference is counted in `votes`, the full ranking is needed to calculate the results
+                self.remove_user_from_votes(user_id);
+                self.votes.entry(ranking[0]).or_default().push(user_id);
+                self.rankings.insert(user_id, ranking);
+
+                RegisterVoteResult::Success(existing_vote_removed)
+            }
+            VoteOperation::DeleteVote => {
+                if self.rankings.remove(&user_id).is_some() {
+                    self.remove_user_from_votes(user_id);
+                    RegisterVoteResult::Success(true)
+                } else {
+                    RegisterVoteResult::SuccessNoChange
+                }
+            }
+        }
+    }
+
+    fn remove_user_from_votes(&mut self, user_id: UserId) {
+        for votes in self.votes.values_mut() {
+            votes.retain(|u| *u != user_id);
+        }
+        self.votes.retain(|_, v| !v.is_empty());
+    }
+
+    pub fn answered_correctly(&self, user_id: UserId) -> bool {
+        let options_voted_for: HashSet<_> = self
+            .votes
+            .iter()
+            .filter(|(_, v)| v.contains(&user_id))
+            .map(|(o, _)| *o)
+            .collect();
+
+        self.config.is_correct_answer(&options_voted_for)
+    }
+
+    pub fn quiz_results(&self) -> Option<QuizResults> {
+        if !self.config.is_quiz() {
+            return None;
+        }
+
+        let participants = self.voters();
+        let correct = participants.iter().copied().filter(|u| self.answered_correctly(*u)).collect();
+
+        Some(QuizResults { participants, correct })
+    }
+
+    pub fn voters(&self) -> Vec<UserId> {
+        self.votes
+            .values()
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    // If `weights` is `None` then each vote has a weight of 1, otherwise users missing from `weights` have no weight
+    pub fn tally(&self, weights: Option<&HashMap<UserId, u128>>) -> PollResults {
+        let weight = |user_id: &UserId| weights.map_or(1, |w| w.get(user_id).copied().unwrap_or_default());
+
+        if self.config.voting_system() != PollVotingSystem::RankedChoice {
+            let tallies: HashMap<u32, u128> = self
+                .votes
+                .iter()
+                .map(|(option, voters)| (*option, voters.iter().map(weight).sum()))
+                .collect();
+
+            return PollResults {
+                winner: Self::outright_winner(&tallies),
+                tallies,
+                eliminated: Vec::new(),
+            };
+        }
+
+        // Instant-runoff: each round, every ranking counts towards its highest preference which hasn't yet been
+        // eliminated. The option with the lowest tally is then eliminated until one option has a majority.
+        let mut remaining: BTreeSet<u32> = (0..self.config.options.len() as u32).collect();
+        let mut eliminated = Vec::new();
+        loop {
+            let mut tallies: HashMap<u32, u128> = remaining.iter().map(|o| (*o, 0)).collect();
+            for (user_id, ranking) in self.rankings.iter() {
+                if let Some(option) = ranking.iter().find(|o| remaining.contains(*o)) {
+                    *tallies.entry(*option).or_default() += weight(user_id);
+                }
+            }
+
+            let total: u128 = tallies.values().sum();
+            let max = tallies.values().max().copied().unwrap_or_default();
+            let min = tallies.values().min().copied().unwrap_or_default();
+
+            if remaining.len() <= 1 || max.saturating_mul(2) > total || max == min {
+                return PollResults {
+                    winner: Self::outright_winner(&tallies),
+                    tallies,
+                    eliminated,
+                };
+            }
+
+            // If several options share the lowest tally, the one with the highest index is eliminated
+            let to_eliminate = *remaining.iter().rev().find(|o| tallies[*o] == min).unwrap();
+            remaining.remove(&to_eliminate);
+            eliminated.push(to_eliminate);
+        }
+    }
+
+    fn outright_winner(tallies: &HashMap<u32, u128>) -> Option<u32> {
+        let max = tallies.values().max().copied().filter(|m| *m > 0)?;
+        let mut leaders = tallies.iter().filter(|(_, t)| **t == max);
+        let (winner, _) = leaders.next()?;
+        if leaders.next().is_none() {
+            Some(*winner)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct QuizResults {
+    pub participants: Vec<UserId>,
+    pub correct: Vec<UserId>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -817,6 +992,16 @@ pub struct PrizeContentInternal {
     pub caption: Option<String>,
     #[serde(rename = "d", default, skip_serializing_if = "is_default")]
     pub diamond_only: bool,
+    #[serde(rename = "q", default, skip_serializing_if = "Option::is_none")]
+    pub quiz_message_index: Option<MessageIndex>,
+    #[serde(rename = "dw", default, skip_serializing_if = "is_default")]
+    pub draw: bool,
+    #[serde(rename = "g", default, skip_serializing_if = "Option::is_none")]
+    pub gate: Option<AccessGate>,
+    #[serde(rename = "n", default, skip_serializing_if = "is_empty_hashset")]
+    pub entrants: HashSet<UserId>,
+    #[serde(rename = "dn", default, skip_serializing_if = "is_default")]
+    pub drawn: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -837,6 +1022,16 @@ pub struct PrizeContentInternalCombined {
     pub caption: Option<String>,
     #[serde(rename = "d", default, skip_serializing_if = "is_default")]
     pub diamond_only: bool,
+    #[serde(rename = "q", default, skip_serializing_if = "Option::is_none")]
+    pub quiz_message_index: Option<MessageIndex>,
+    #[serde(rename = "dw", default, skip_serializing_if = "is_default")]
+    pub draw: bool,
+    #[serde(rename = "g", default, skip_serializing_if = "Option::is_none")]
+    pub gate: Option<AccessGate>,
+    #[serde(rename = "n", default, skip_serializing_if = "is_empty_hashset")]
+    pub entrants: HashSet<UserId>,
+    #[serde(rename = "dn", default, skip_serializing_if = "is_default")]
+    pub drawn: bool,
 }
 
 impl From<PrizeContentInternalCombined> for PrizeContentInternal {
@@ -853,6 +1048,11 @@ impl From<PrizeContentInternalCombined> for PrizeContentInternal {
             end_date: value.end_date,
             caption: value.caption,
             diamond_only: value.diamond_only,
+            quiz_message_index: value.quiz_message_index,
+            draw: value.draw,
+            gate: value.gate,
+            entrants: value.entrants,
+            drawn: value.drawn,
         }
     }
 }
@@ -867,6 +1067,11 @@ impl PrizeContentInternal {
             end_date: content.end_date,
             caption: content.caption,
             diamond_only: content.diamond_only,
+            quiz_message_index: content.quiz_message_index,
+            draw: content.draw,
+            gate: content.gate,
+            entrants: HashSet::new(),
+            drawn: false,
         }
     }
 
@@ -892,7 +1097,7 @@ impl PrizeContentInternal {
 impl MessageContentInternalSubtype for PrizeContentInternal {
     type ContentType = PrizeContent;
 
-    fn hydrate(&self, _my_user_id: Option<UserId>) -> Self::ContentType {
+    fn hydrate(&self, my_user_id: Option<UserId>) -> Self::ContentType {
         PrizeContent {
             prizes_remaining: self.prizes_remaining.len() as u32,
             prizes_pending: self.reservations.len() as u32,
@@ -901,6 +1106,11 @@ impl MessageContentInternalSubtype for PrizeContentInternal {
             end_date: self.end_date,
             caption: self.caption.clone(),
             diamond_only: self.diamond_only,
+            quiz_message_index: self.quiz_message_index,
+            draw: self.draw,
+            gate: self.gate.clone(),
+            entrants: self.entrants.len() as u32,
+            entered: my_user_id.map_or(false, |u| self.entrants.contains(&u)),
         }
     }
 }
@@ -1117,3 +1327,106 @@ impl From<MessageContentInitial> for MessageContentInternal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    #[test]
+    fn ranked_choice_eliminates_lowest_option_until_majority() {
+        let mut poll = poll(PollVotingSystem::RankedChoice, 3);
+        register(&mut poll, 1, vec![0, 2]);
+        register(&mut poll, 2, vec![0]);
+        register(&mut poll, 3, vec![1, 2]);
+        register(&mut poll, 4, vec![1]);
+        register(&mut poll, 5, vec![2, 1]);
+
+        let results = poll.tally(None);
+
+        assert_eq!(results.eliminated, vec![2]);
+        assert_eq!(results.winner, Some(1));
+        assert_eq!(results.tallies.get(&1), Some(&3));
+    }
+
+    #[test]
+    fn weighted_approval_sums_voter_weights() {
+        let mut poll = poll(PollVotingSystem::Approval, 2);
+        for option in [0, 1] {
+            poll.register_vote(user(1), option, Vec::new(), VoteOperation::RegisterVote);
+        }
+        poll.register_vote(user(2), 1, Vec::new(), VoteOperation::RegisterVote);
+
+        let weights = HashMap::from([(user(1), 10), (user(2), 5)]);
+        let results = poll.tally(Some(&weights));
+
+        assert_eq!(results.tallies.get(&0), Some(&10));
+        assert_eq!(results.tallies.get(&1), Some(&15));
+        assert_eq!(results.winner, Some(1));
+    }
+
+    #[test]
+    fn ranked_choice_rejects_invalid_rankings() {
+        let mut poll = poll(PollVotingSystem::RankedChoice, 3);
+
+        assert!(matches!(
+            poll.register_vote(user(1), 0, vec![0, 0], VoteOperation::RegisterVote),
+            RegisterVoteResult::InvalidRanking
+        ));
+        assert!(matches!(
+            poll.register_vote(user(1), 0, vec![0, 3], VoteOperation::RegisterVote),
+            RegisterVoteResult::InvalidRanking
+        ));
+    }
+
+    #[test]
+    fn quiz_answers_must_match_correct_options() {
+        let mut poll = poll(PollVotingSystem::Approval, 3);
+        poll.config.correct_options = Some(vec![0, 2]);
+        for option in [0, 2] {
+            poll.register_vote(user(1), option, Vec::new(), VoteOperation::RegisterVote);
+        }
+        for option in [0, 1, 2] {
+            poll.register_vote(user(2), option, Vec::new(), VoteOperation::RegisterVote);
+        }
+        poll.register_vote(user(3), 0, Vec::new(), VoteOperation::RegisterVote);
+
+        let results = poll.quiz_results().unwrap();
+
+        assert_eq!(results.participants.len(), 3);
+        assert_eq!(results.correct, vec![user(1)]);
+    }
+
+    fn poll(voting_system: PollVotingSystem, options: usize) -> PollContentInternal {
+        PollContentInternal {
+            config: PollConfig {
+                text: None,
+                options: (0..options).map(|o| o.to_string()).collect(),
+                end_date: Some(1),
+                anonymous: false,
+                show_votes_before_end_date: true,
+                allow_multiple_votes_per_user: false,
+                allow_user_to_change_vote: true,
+                voting_system: Some(voting_system),
+                vote_weighting: None,
+                correct_options: None,
+            },
+            votes: HashMap::new(),
+            ended: false,
+            rankings: HashMap::new(),
+            results: None,
+            pending_vote_weights: HashMap::new(),
+        }
+    }
+
+    fn register(poll: &mut PollContentInternal, user_index: u8, ranking: Vec<u32>) {
+        assert!(matches!(
+            poll.register_vote(user(user_index), ranking[0], ranking, VoteOperation::RegisterVote),
+            RegisterVoteResult::Success(_)
+        ));
+    }
+
+    fn user(index: u8) -> UserId {
+        Principal::from_slice(&[index]).into()
+    }
+}
@@ -0,0 +1,187 @@
+use search::Query;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
+use types::{EventIndex, MessageIndex};
+
+// Inverted index mapping each term to the messages containing that term.
+// Messages are keyed by their thread root message index (if in a thread) and their message index.
+#[derive(Serialize, Deserialize, Default)]
+pub struct SearchIndex {
+    populated: bool,
+    // The events list (None for the main list, otherwise the thread root message index) and the event index from
+    // which to continue populating the index of a chat which was created before the index existed
+    #[serde(default)]
+    population_cursor: Option<(Option<MessageIndex>, EventIndex)>,
+    terms: BTreeMap<String, BTreeSet<SearchIndexKey>>,
+}
+
+pub type SearchIndexKey = (Option<MessageIndex>, MessageIndex);
+
+impl SearchIndex {
+    pub fn populated() -> SearchIndex {
+        SearchIndex {
+            populated: true,
+            population_cursor: None,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_populated(&self) -> bool {
+        self.populated
+    }
+
+    pub fn mark_populated(&mut self) {
+        self.populated = true;
+        self.population_cursor = None;
+    }
+
+    pub fn population_cursor(&self) -> (Option<MessageIndex>, EventIndex) {
+        self.population_cursor.unwrap_or_default()
+    }
+
+    pub fn set_population_cursor(&mut self, thread_root_message_index: Option<MessageIndex>, event_index: EventIndex) {
+        self.population_cursor = Some((thread_root_message_index, event_index));
+    }
+
+    pub fn add(&mut self, key: SearchIndexKey, terms: BTreeSet<String>) {
+        for term in terms {
+            self.terms.entry(term).or_default().insert(key);
+        }
+    }
+
+    pub fn remove(&mut self, key: SearchIndexKey, terms: &BTreeSet<String>) {
+        for term in terms {
+            if let Some(keys) = self.terms.get_mut(term) {
+                keys.remove(&key);
+                if keys.is_empty() {
+                    self.terms.remove(term);
+                }
+            }
+        }
+    }
+
+    pub fn replace(&mut self, key: SearchIndexKey, old_terms: &BTreeSet<String>, new_terms: BTreeSet<String>) {
+        self.remove(key, old_terms);
+        self.add(key, new_terms);
+    }
+
+    pub fn remove_thread(&mut self, thread_root_message_index: MessageIndex) {
+        self.terms.retain(|_, keys| {
+            keys.retain(|(root, _)| *root != Some(thread_root_message_index));
+            !keys.is_empty()
+        });
+    }
+
+    // Returns the keys of all messages which match at least one term (either exactly or as a prefix)
+    // from each of the query's clauses and which contain every term of each phrase. Phrases and
+    // exclusions must still be verified against the message content.
+    // Returns `None` if the query has no terms, in which case every message is a candidate.
+    pub fn candidates(&self, query: &Query) -> Option<BTreeSet<SearchIndexKey>> {
+        let mut result: Option<BTreeSet<SearchIndexKey>> = None;
+
+        let phrase_clauses = query.phrases.iter().flatten().map(|t| vec![t.clone()]);
+
+        for clause in query.clauses.iter().cloned().chain(phrase_clauses) {
+            let matches: BTreeSet<_> = clause.iter().flat_map(|t| self.prefix_matches(t)).collect();
+
+            let intersection = match result {
+                Some(r) => r.intersection(&matches).copied().collect(),
+                None => matches,
+            };
+
+            if intersection.is_empty() {
+                return Some(BTreeSet::new());
+            }
+            result = Some(intersection);
+        }
+
+        result
+    }
+
+    fn prefix_matches<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = SearchIndexKey> + 'a {
+        self.terms
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(term, _)| term.starts_with(prefix))
+            .flat_map(|(_, keys)| keys.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MessageContentInternal, TextContentInternal};
+    use search::Document;
+
+    #[test]
+    fn candidates_match_all_terms_by_prefix() {
+        let mut index = SearchIndex::default();
+        index.add((None, 0.into()), text("The quick brown fox").search_terms());
+        index.add((None, 1.into()), text("A quick hello").search_terms());
+        index.add((Some(1.into()), 0.into()), text("quickly, brownies!").search_terms());
+
+        let candidates = index.candidates(&Query::parse("quick brown".to_string())).unwrap();
+
+        assert_eq!(
+            candidates.into_iter().collect::<Vec<_>>(),
+            vec![(None, 0.into()), (Some(1.into()), 0.into())]
+        );
+    }
+
+    #[test]
+    fn removed_messages_are_not_candidates() {
+        let mut index = SearchIndex::default();
+        let terms = text("hello world").search_terms();
+        index.add((None, 0.into()), terms.clone());
+        index.remove((None, 0.into()), &terms);
+
+        assert!(index.candidates(&Query::parse("hello".to_string())).unwrap().is_empty());
+        assert!(index.terms.is_empty());
+    }
+
+    #[test]
+    fn candidates_match_any_term_within_a_clause() {
+        let mut index = SearchIndex::default();
+        index.add((None, 0.into()), text("cats are great").search_terms());
+        index.add((None, 1.into()), text("dogs are great").search_terms());
+        index.add((None, 2.into()), text("dogs are loud").search_terms());
+
+        let candidates = index.candidates(&Query::parse("cat OR dog great".to_string())).unwrap();
+
+        assert_eq!(
+            candidates.into_iter().collect::<Vec<_>>(),
+            vec![(None, 0.into()), (None, 1.into())]
+        );
+    }
+
+    #[test]
+    fn candidates_agree_with_document_matching() {
+        let texts = [
+            "The quick brown fox",
+            "A quick hello",
+            "quickly, brownies!",
+            "slow brown cows",
+        ];
+
+        let mut index = SearchIndex::default();
+        for (i, t) in texts.iter().enumerate() {
+            index.add((None, (i as u32).into()), text(t).search_terms());
+        }
+
+        for query in ["quick brown", "brow", "uick", "own OR hell", "cow OR fox brown"] {
+            let candidates = index.candidates(&Query::parse(query.to_string())).unwrap();
+
+            for (i, t) in texts.iter().enumerate() {
+                assert_eq!(
+                    candidates.contains(&(None, (i as u32).into())),
+                    Document::from(&text(t)).is_match(&Query::parse(query.to_string())),
+                    "query: {query}, text: {t}"
+                );
+            }
+        }
+    }
+
+    fn text(text: &str) -> MessageContentInternal {
+        MessageContentInternal::Text(TextContentInternal { text: text.to_string() })
+    }
+}
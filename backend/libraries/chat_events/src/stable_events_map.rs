@@ -0,0 +1,620 @@
+use crate::{ChatEventInternal, ChatEvents, EventsMap};
+use candid::Principal;
+use ic_stable_structures::memory_manager::VirtualMemory;
+use ic_stable_structures::storable::Bound;
+use ic_stable_structures::{DefaultMemoryImpl, StableBTreeMap, Storable};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::ops::{Bound as RangeBound, RangeBounds};
+use types::{Chat, EventIndex, EventWrapperInternal, MessageIndex};
+
+type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+type Event = EventWrapperInternal<ChatEventInternal>;
+
+// The number of events of each events list which are kept in deserialized form on the heap
+const CACHE_CAPACITY: usize = 100;
+const MAX_KEY_SIZE: u32 = 48;
+
+thread_local! {
+    static EVENTS: RefCell<Option<StableBTreeMap<Key, Vec<u8>, Memory>>> = RefCell::default();
+}
+
+// Must be called by each canister storing chat events before any events are read or written. Until this has been
+// called, new events lists keep all of their events on the heap.
+pub fn init_stable_storage(memory: VirtualMemory<DefaultMemoryImpl>) {
+    EVENTS.set(Some(StableBTreeMap::init(memory)));
+}
+
+pub(crate) fn is_stable_storage_initialized() -> bool {
+    EVENTS.with_borrow(|e| e.is_some())
+}
+
+fn with_map<F: FnOnce(&mut StableBTreeMap<Key, Vec<u8>, Memory>) -> T, T>(f: F) -> T {
+    EVENTS.with_borrow_mut(|e| f(e.as_mut().expect("Chat events stable storage not initialized")))
+}
+
+// Events of every events list within the canister are stored in a single map. Each key is made up of a prefix which
+// identifies the events list, followed by the event index in big endian form so that keys sort in event order.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Key(Vec<u8>);
+
+impl Storable for Key {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Key(bytes.into_owned())
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: MAX_KEY_SIZE,
+        is_fixed_size: false,
+    };
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct KeyPrefix(#[serde(with = "serde_bytes")] Vec<u8>);
+
+impl KeyPrefix {
+    pub fn new(chat: Chat, thread_root_message_index: Option<MessageIndex>) -> KeyPrefix {
+        let mut bytes = Vec::new();
+        match chat {
+            Chat::Direct(chat_id) => {
+                let principal = Principal::from(chat_id);
+                let slice = principal.as_slice();
+                bytes.push(0);
+                bytes.push(slice.len() as u8);
+                bytes.extend_from_slice(slice);
+            }
+            // Each group canister only holds a single chat
+            Chat::Group(_) => bytes.push(1),
+            // Each community canister only holds the channels of a single community
+            Chat::Channel(_, channel_id) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&channel_id.to_be_bytes());
+            }
+        }
+        if let Some(root_message_index) = thread_root_message_index {
+            bytes.push(1);
+            bytes.extend_from_slice(&u32::from(root_message_index).to_be_bytes());
+        } else {
+            bytes.push(0);
+        }
+        KeyPrefix(bytes)
+    }
+
+    // Returns true if this is the prefix of one of the events lists of `chat`, either its main events list or a thread
+    fn is_for_chat(&self, chat: Chat) -> bool {
+        let main = KeyPrefix::new(chat, None);
+        self.0.starts_with(&main.0[..main.0.len() - 1])
+    }
+
+    fn key(&self, event_index: EventIndex) -> Key {
+        let mut bytes = Vec::with_capacity(self.0.len() + 4);
+        bytes.extend_from_slice(&self.0);
+        bytes.extend_from_slice(&u32::from(event_index).to_be_bytes());
+        Key(bytes)
+    }
+
+    // Sorts after the key of `event_index` and before the key of the following event index
+    fn key_upper_bound(&self, event_index: EventIndex) -> Key {
+        let mut key = self.key(event_index);
+        key.0.push(0);
+        key
+    }
+
+    fn event_index(&self, key: &Key) -> Option<EventIndex> {
+        let suffix = key.0.strip_prefix(self.0.as_slice())?;
+        let bytes: [u8; 4] = suffix.try_into().ok()?;
+        Some(u32::from_be_bytes(bytes).into())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(from = "StableEventsMapState")]
+pub struct StableEventsMap {
+    prefix: KeyPrefix,
+    len: usize,
+    // Each event is boxed so that references to it remain valid when other events are added to the cache. Events are
+    // only ever removed from the cache (or replaced) through a mutable reference to the map.
+    cache: RefCell<BTreeMap<EventIndex, Box<Event>>>,
+    // Events which have been mutated in the cache but not yet written back to stable memory
+    dirty: RefCell<BTreeSet<EventIndex>>,
+    // Events loaded while the cache is full. References to these may still be held, so they can't be dropped until the
+    // next message execution (or until the map is next mutated), but they are kept out of the cache so that reading a
+    // large range of events doesn't cause the cache to grow beyond its capacity.
+    overflow: RefCell<Vec<Box<Event>>>,
+    // Identifies the message execution in which events were last added to `overflow`
+    overflow_execution: Cell<(u64, u64)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StableEventsMapState {
+    #[serde(rename = "p")]
+    prefix: KeyPrefix,
+    #[serde(rename = "l")]
+    len: usize,
+}
+
+impl From<StableEventsMapState> for StableEventsMap {
+    fn from(value: StableEventsMapState) -> Self {
+        StableEventsMap::with_len(value.prefix, value.len)
+    }
+}
+
+impl Serialize for StableEventsMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.flush();
+
+        StableEventsMapState {
+            prefix: self.prefix.clone(),
+            len: self.len,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl StableEventsMap {
+    pub fn new(prefix: KeyPrefix) -> StableEventsMap {
+        StableEventsMap::with_len(prefix, 0)
+    }
+
+    fn with_len(prefix: KeyPrefix, len: usize) -> StableEventsMap {
+        StableEventsMap {
+            prefix,
+            len,
+            cache: RefCell::default(),
+            dirty: RefCell::default(),
+            overflow: RefCell::default(),
+            overflow_execution: Cell::default(),
+        }
+    }
+
+    pub(crate) fn into_prefix(self) -> KeyPrefix {
+        self.prefix
+    }
+
+    // Removes up to `max_events` events, starting from the earliest, returning them in ascending order
+    pub fn drain(&mut self, max_events: usize) -> Vec<Event> {
+        let event_indexes: Vec<_> = self.range(..).take(max_events).map(|(i, _)| *i).collect();
+
+        event_indexes.into_iter().filter_map(|i| self.remove(i)).collect()
+    }
+
+    // Writes each event into `map`, reading them directly from stable memory without adding them to the cache
+    pub(crate) fn serialize_events<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        self.flush();
+
+        with_map(|m| {
+            for (key, bytes) in m.range(self.prefix.key(EventIndex::default())..=self.prefix.key(u32::MAX.into())) {
+                if let Some(event_index) = self.prefix.event_index(&key) {
+                    let event: Event = msgpack::deserialize_then_unwrap(&bytes);
+                    map.serialize_entry(&event_index, &event)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    // Writes any events which have been mutated in the cache back to stable memory
+    fn flush(&self) {
+        let dirty = std::mem::take(&mut *self.dirty.borrow_mut());
+        if dirty.is_empty() {
+            return;
+        }
+
+        let cache = self.cache.borrow();
+        with_map(|m| {
+            for event_index in dirty {
+                if let Some(event) = cache.get(&event_index) {
+                    m.insert(self.prefix.key(event_index), msgpack::serialize_then_unwrap(&**event));
+                }
+            }
+        });
+    }
+
+    fn trim_cache(&mut self) {
+        self.flush();
+
+        self.overflow.get_mut().clear();
+
+        // Keep the latest events since those are the most frequently accessed
+        let cache = self.cache.get_mut();
+        while cache.len() > CACHE_CAPACITY {
+            cache.pop_first();
+        }
+    }
+
+    fn get_or_load(&self, event_index: EventIndex, bytes: Option<Vec<u8>>) -> Option<&Event> {
+        if let Some(event) = self.cache.borrow().get(&event_index) {
+            return Some(extend_lifetime(event));
+        }
+
+        let bytes = bytes.or_else(|| with_map(|m| m.get(&self.prefix.key(event_index))))?;
+        let event: Box<Event> = Box::new(msgpack::deserialize_then_unwrap(&bytes));
+
+        let mut cache = self.cache.borrow_mut();
+        if cache.len() < CACHE_CAPACITY {
+            return Some(extend_lifetime(cache.entry(event_index).or_insert(event)));
+        }
+        drop(cache);
+
+        let mut overflow = self.overflow.borrow_mut();
+        let execution = current_execution();
+        let previous = self.overflow_execution.replace(execution);
+        if execution.0 != previous.0 || execution.1 < previous.1 {
+            // No references can be held from within a previous message execution
+            overflow.clear();
+        }
+        overflow.push(event);
+        overflow.last().map(|e| extend_lifetime(e))
+    }
+
+    // Returns the first event within the inclusive range, starting from whichever end is requested
+    fn next_in_range(&self, start: EventIndex, end: EventIndex, ascending: bool) -> Option<&Event> {
+        let (key, bytes) = with_map(|m| {
+            if ascending {
+                m.range(self.prefix.key(start)..=self.prefix.key(end)).next()
+            } else {
+                m.iter_upper_bound(&self.prefix.key_upper_bound(end)).next()
+            }
+        })?;
+
+        let event_index = self.prefix.event_index(&key).filter(|i| *i >= start && *i <= end)?;
+        self.get_or_load(event_index, Some(bytes))
+    }
+}
+
+// The events lists of deleted chats whose events are yet to be removed from stable memory. A chat may have far too
+// many events to remove within a single message execution, so once the chat is deleted its events are removed in
+// batches.
+#[derive(Serialize, Deserialize, Default)]
+pub struct StableEventsPendingRemoval(VecDeque<KeyPrefix>);
+
+impl StableEventsPendingRemoval {
+    // Must be called when deleting a chat, otherwise its events would be left behind in stable memory
+    pub fn push(&mut self, events: &mut ChatEvents) {
+        self.0.extend(events.take_stable_memory_prefixes());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Removes up to `max_events` events, returning true once there are no more events to be removed
+    pub fn remove_batch(&mut self, max_events: usize) -> bool {
+        let mut remaining = max_events;
+        while let Some(prefix) = self.0.front() {
+            let removed = remove_events(prefix, remaining);
+            if removed == remaining {
+                return false;
+            }
+            self.0.pop_front();
+            remaining -= removed;
+        }
+        true
+    }
+
+    // Removes any events of `chat` which are still pending removal. Must be called before a deleted chat is recreated,
+    // since the events of the new chat are stored under the same keys.
+    pub fn remove_chat(&mut self, chat: Chat) {
+        self.0.retain(|prefix| {
+            if prefix.is_for_chat(chat) {
+                while remove_events(prefix, 1000) > 0 {}
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+// Removes up to `max_events` of the events stored under `prefix`, returning the number removed
+fn remove_events(prefix: &KeyPrefix, max_events: usize) -> usize {
+    with_map(|m| {
+        let keys: Vec<_> = m
+            .range(prefix.key(EventIndex::default())..=prefix.key(u32::MAX.into()))
+            .take(max_events)
+            .map(|(key, _)| key)
+            .collect();
+
+        for key in keys.iter() {
+            m.remove(key);
+        }
+        keys.len()
+    })
+}
+
+// Returns the time along with the instruction counter. The time is fixed for the duration of each message execution
+// and the instruction counter is reset at the start of each one, so if either the time changes or the instruction
+// counter goes down, we must be within a new message execution.
+#[cfg(target_arch = "wasm32")]
+fn current_execution() -> (u64, u64) {
+    (ic_cdk::api::time(), ic_cdk::api::instruction_counter())
+}
+
+// Outside of a canister there are no message executions, so events in the overflow are only dropped on mutation
+#[cfg(not(target_arch = "wasm32"))]
+fn current_execution() -> (u64, u64) {
+    (0, 0)
+}
+
+// SAFETY: The returned reference points to the boxed event rather than into the map itself, so it remains valid while
+// other events are added to the cache. Boxed events are only ever dropped through a mutable reference to the
+// `StableEventsMap`, which can't be obtained while the returned reference (whose lifetime is tied to a shared
+// reference to the `StableEventsMap`) is still alive, or once the message execution in which they were loaded has
+// ended, after which no references obtained during that execution remain.
+fn extend_lifetime<'a>(event: &Event) -> &'a Event {
+    unsafe { &*(event as *const Event) }
+}
+
+impl EventsMap for StableEventsMap {
+    fn get_mut(&mut self, event_index: EventIndex) -> Option<&mut Event> {
+        self.trim_cache();
+
+        if !self.cache.get_mut().contains_key(&event_index) {
+            let bytes = with_map(|m| m.get(&self.prefix.key(event_index)))?;
+            let event: Event = msgpack::deserialize_then_unwrap(&bytes);
+            self.cache.get_mut().insert(event_index, Box::new(event));
+        }
+
+        self.dirty.get_mut().insert(event_index);
+        self.cache.get_mut().get_mut(&event_index).map(|e| e.as_mut())
+    }
+
+    fn insert(&mut self, event: Event) {
+        self.trim_cache();
+
+        let event_index = event.index;
+        let bytes = msgpack::serialize_then_unwrap(&event);
+        if with_map(|m| m.insert(self.prefix.key(event_index), bytes)).is_none() {
+            self.len += 1;
+        }
+        self.dirty.get_mut().remove(&event_index);
+        self.cache.get_mut().insert(event_index, Box::new(event));
+    }
+
+    fn remove(&mut self, event_index: EventIndex) -> Option<Event> {
+        self.trim_cache();
+
+        let bytes = with_map(|m| m.remove(&self.prefix.key(event_index)))?;
+        self.len = self.len.saturating_sub(1);
+        self.dirty.get_mut().remove(&event_index);
+
+        match self.cache.get_mut().remove(&event_index) {
+            Some(event) => Some(*event),
+            None => Some(msgpack::deserialize_then_unwrap(&bytes)),
+        }
+    }
+
+    fn range<R: RangeBounds<EventIndex>>(&self, range: R) -> Box<dyn DoubleEndedIterator<Item = (&EventIndex, &Event)> + '_> {
+        let start = match range.start_bound() {
+            RangeBound::Included(i) => Some(*i),
+            RangeBound::Excluded(i) => u32::from(*i).checked_add(1).map(EventIndex::from),
+            RangeBound::Unbounded => Some(EventIndex::default()),
+        };
+        let end = match range.end_bound() {
+            RangeBound::Included(i) => Some(*i),
+            RangeBound::Excluded(i) => u32::from(*i).checked_sub(1).map(EventIndex::from),
+            RangeBound::Unbounded => Some(EventIndex::from(u32::MAX)),
+        };
+
+        let remaining = start.zip(end).filter(|(s, e)| s <= e);
+
+        Box::new(StableEventsMapRangeIter { map: self, remaining }.map(|e| (&e.index, e)))
+    }
+
+    fn values(&self) -> Box<dyn DoubleEndedIterator<Item = &Event> + '_> {
+        Box::new(self.range(..).map(|(_, e)| e))
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+struct StableEventsMapRangeIter<'a> {
+    map: &'a StableEventsMap,
+    // The inclusive bounds of the range which has yet to be iterated over
+    remaining: Option<(EventIndex, EventIndex)>,
+}
+
+impl<'a> Iterator for StableEventsMapRangeIter<'a> {
+    type Item = &'a Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = self.remaining?;
+        let next = self.map.next_in_range(start, end, true);
+
+        self.remaining = next.filter(|e| e.index < end).map(|e| (e.index.incr(), end));
+
+        next
+    }
+}
+
+impl<'a> DoubleEndedIterator for StableEventsMapRangeIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (start, end) = self.remaining?;
+        let next = self.map.next_in_range(start, end, false);
+
+        self.remaining = next.filter(|e| e.index > start).map(|e| (start, e.index.decr()));
+
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChatEventsMap;
+    use ic_stable_structures::memory_manager::{MemoryId, MemoryManager};
+    use types::{ChatId, EventsTimeToLiveUpdated, UserId};
+
+    fn setup() {
+        let memory_manager = MemoryManager::init(DefaultMemoryImpl::default());
+        init_stable_storage(memory_manager.get(MemoryId::new(0)));
+    }
+
+    fn event(index: u32) -> Event {
+        EventWrapperInternal {
+            index: index.into(),
+            timestamp: index as u64,
+            correlation_id: 0,
+            expires_at: None,
+            event: ChatEventInternal::EventsTimeToLiveUpdated(Box::new(EventsTimeToLiveUpdated {
+                updated_by: UserId::new(Principal::anonymous()),
+                new_ttl: Some(index as u64),
+            })),
+        }
+    }
+
+    fn indexes<'a>(iter: impl Iterator<Item = (&'a EventIndex, &'a Event)>) -> Vec<u32> {
+        iter.map(|(i, _)| u32::from(*i)).collect()
+    }
+
+    #[test]
+    fn range_iterates_in_both_directions() {
+        setup();
+
+        let mut map = StableEventsMap::new(KeyPrefix::new(Chat::Group(ChatId::from(Principal::anonymous())), None));
+        let mut other = StableEventsMap::new(KeyPrefix::new(
+            Chat::Group(ChatId::from(Principal::anonymous())),
+            Some(1.into()),
+        ));
+
+        for i in (0..300).filter(|i| i % 3 != 0) {
+            map.insert(event(i));
+            other.insert(event(i));
+        }
+        map.remove(10.into());
+
+        assert_eq!(map.len(), 199);
+        assert_eq!(
+            indexes(map.range(EventIndex::from(5)..EventIndex::from(12))),
+            vec![5, 7, 8, 11]
+        );
+        assert_eq!(
+            indexes(map.range(EventIndex::from(5)..=EventIndex::from(12)).rev()),
+            vec![11, 8, 7, 5]
+        );
+        assert_eq!(indexes(map.range(..).rev().take(2)), vec![299, 298]);
+        assert_eq!(indexes(map.range(..).take(2)), vec![1, 2]);
+        assert!(map.range(EventIndex::from(300)..).next().is_none());
+    }
+
+    #[test]
+    fn mutations_are_persisted_once_evicted_from_cache() {
+        setup();
+
+        let mut map = StableEventsMap::new(KeyPrefix::new(Chat::Group(ChatId::from(Principal::anonymous())), None));
+        for i in 0..(2 * CACHE_CAPACITY as u32) {
+            map.insert(event(i));
+        }
+
+        map.get_mut(0.into()).unwrap().timestamp = 1000;
+        for i in 0..(2 * CACHE_CAPACITY as u32) {
+            map.get_mut(i.into());
+        }
+
+        assert!(map.cache.get_mut().len() <= CACHE_CAPACITY + 1);
+        assert_eq!(map.values().next().unwrap().timestamp, 1000);
+    }
+
+    #[test]
+    fn reading_events_does_not_grow_cache_beyond_capacity() {
+        setup();
+
+        let mut map = StableEventsMap::new(KeyPrefix::new(Chat::Group(ChatId::from(Principal::anonymous())), None));
+        for i in 0..(3 * CACHE_CAPACITY as u32) {
+            map.insert(event(i));
+        }
+        let bytes = msgpack::serialize_then_unwrap(&map);
+        let mut map: StableEventsMap = msgpack::deserialize_then_unwrap(&bytes);
+
+        assert_eq!(map.values().count(), 3 * CACHE_CAPACITY);
+        assert_eq!(map.cache.get_mut().len(), CACHE_CAPACITY);
+
+        map.insert(event(3 * CACHE_CAPACITY as u32));
+        assert!(map.overflow.get_mut().is_empty());
+    }
+
+    #[test]
+    fn events_pending_removal_are_removed_in_batches() {
+        setup();
+
+        let chat = Chat::Direct(ChatId::from(Principal::from_slice(&[1])));
+        let other_chat = Chat::Direct(ChatId::from(Principal::from_slice(&[2])));
+        let mut main = StableEventsMap::new(KeyPrefix::new(chat, None));
+        let mut thread = StableEventsMap::new(KeyPrefix::new(chat, Some(1.into())));
+        let mut other = StableEventsMap::new(KeyPrefix::new(other_chat, None));
+        for i in 0..150 {
+            main.insert(event(i));
+            thread.insert(event(i));
+            other.insert(event(i));
+        }
+
+        let mut pending = StableEventsPendingRemoval(VecDeque::from([main.into_prefix(), thread.into_prefix()]));
+        assert!(!pending.remove_batch(100));
+        assert!(!pending.remove_batch(100));
+        assert!(pending.remove_batch(100));
+        assert!(pending.is_empty());
+
+        let main = StableEventsMap::new(KeyPrefix::new(chat, None));
+        let thread = StableEventsMap::new(KeyPrefix::new(chat, Some(1.into())));
+        assert!(main.range(..).next().is_none());
+        assert!(thread.range(..).next().is_none());
+        assert_eq!(other.range(..).count(), 150);
+
+        let mut pending = StableEventsPendingRemoval(VecDeque::from([other.into_prefix()]));
+        pending.remove_chat(chat);
+        assert!(!pending.is_empty());
+        pending.remove_chat(other_chat);
+        assert!(pending.is_empty());
+        assert!(StableEventsMap::new(KeyPrefix::new(other_chat, None))
+            .range(..)
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn migrate_legacy_events_map() {
+        setup();
+
+        let legacy: BTreeMap<EventIndex, Event> = (0..50).map(|i| (i.into(), event(i))).collect();
+        let bytes = msgpack::serialize_then_unwrap(&legacy);
+
+        let mut map: ChatEventsMap = msgpack::deserialize_then_unwrap(&bytes);
+        map.insert(event(50));
+        assert!(!map.migrate_to_stable_memory(KeyPrefix::new(Chat::Group(ChatId::from(Principal::anonymous())), None), 20));
+
+        let bytes = msgpack::serialize_then_unwrap(&map);
+        let mut map: ChatEventsMap = msgpack::deserialize_then_unwrap(&bytes);
+        assert_eq!(map.len(), 51);
+        assert_eq!(
+            indexes(map.range(EventIndex::from(28)..=EventIndex::from(32))),
+            vec![28, 29, 30, 31, 32]
+        );
+
+        // Exported events lists are plain maps of events, readable by canisters which have yet to store events in
+        // stable memory
+        let exported = crate::serialize_for_export(&map);
+        let legacy: BTreeMap<EventIndex, Event> = msgpack::deserialize_then_unwrap(&exported);
+        assert_eq!(legacy.len(), 51);
+        assert_eq!(
+            legacy.keys().rev().take(3).map(|i| u32::from(*i)).collect::<Vec<_>>(),
+            vec![50, 49, 48]
+        );
+
+        let map: ChatEventsMap = msgpack::deserialize_then_unwrap(&exported);
+        assert_eq!(map.len(), 51);
+    }
+}
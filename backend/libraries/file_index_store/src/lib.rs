@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use index_store::IndexStore;
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use types::{CanisterId, Error};
+
+// Stores the indexes in a JSON file on local disk. Each update writes the full set of indexes to a temporary file, syncs
+// it to disk, then renames it over the previous file, so a crash at any point leaves either the old or the new indexes
+// in place, never a partially written file.
+#[derive(Clone)]
+pub struct FileIndexStore {
+    path: Arc<PathBuf>,
+    indexes: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl FileIndexStore {
+    pub async fn open(path: impl Into<PathBuf>) -> Result<FileIndexStore, Error> {
+        let path = path.into();
+
+        let indexes = match fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Failed to parse index store file '{}': {e}", path.display()))?,
+            Err(error) if error.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(FileIndexStore {
+            path: Arc::new(path),
+            indexes: Arc::new(Mutex::new(indexes)),
+        })
+    }
+
+    async fn write(&self, indexes: &BTreeMap<String, u64>) -> Result<(), Error> {
+        let temp_path = temp_path(&self.path);
+        let bytes = serde_json::to_vec_pretty(indexes)?;
+
+        let mut file = fs::File::create(&temp_path).await?;
+        file.write_all(&bytes).await?;
+        file.sync_all().await?;
+        drop(file);
+
+        fs::rename(&temp_path, self.path.as_ref()).await?;
+
+        // Sync the directory so that the rename itself is durable
+        #[cfg(unix)]
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::File::open(dir).await?.sync_all().await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IndexStore for FileIndexStore {
+    async fn get(&self, canister_id: CanisterId) -> Result<Option<u64>, Error> {
+        Ok(self.indexes.lock().await.get(&key(canister_id)).copied())
+    }
+
+    async fn set(&self, canister_id: CanisterId, index: u64) -> Result<(), Error> {
+        // The lock is held until the write completes so that concurrent writes can't interleave
+        let mut indexes = self.indexes.lock().await;
+        let previous = indexes.insert(key(canister_id), index);
+
+        if let Err(error) = self.write(&indexes).await {
+            match previous {
+                Some(previous) => indexes.insert(key(canister_id), previous),
+                None => indexes.remove(&key(canister_id)),
+            };
+            return Err(error);
+        }
+
+        Ok(())
+    }
+}
+
+fn key(canister_id: CanisterId) -> String {
+    canister_id.to_text()
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("file_index_store_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("indexes.json")
+    }
+
+    #[tokio::test]
+    async fn indexes_persist_across_reopen() {
+        let path = test_path("reopen");
+        let canister1 = CanisterId::from_slice(&[1]);
+        let canister2 = CanisterId::from_slice(&[2]);
+
+        let store = FileIndexStore::open(&path).await.unwrap();
+        assert_eq!(store.get(canister1).await.unwrap(), None);
+
+        store.set(canister1, 10).await.unwrap();
+        store.set(canister2, 20).await.unwrap();
+        store.set(canister1, 11).await.unwrap();
+
+        let reopened = FileIndexStore::open(&path).await.unwrap();
+        assert_eq!(reopened.get(canister1).await.unwrap(), Some(11));
+        assert_eq!(reopened.get(canister2).await.unwrap(), Some(20));
+        assert!(!temp_path(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn leftover_temp_file_is_ignored() {
+        let path = test_path("leftover");
+        let canister = CanisterId::from_slice(&[1]);
+
+        let store = FileIndexStore::open(&path).await.unwrap();
+        store.set(canister, 5).await.unwrap();
+
+        // Simulate a crash part way through writing the temp file
+        std::fs::write(temp_path(&path), b"{\"partial").unwrap();
+
+        let reopened = FileIndexStore::open(&path).await.unwrap();
+        assert_eq!(reopened.get(canister).await.unwrap(), Some(5));
+
+        reopened.set(canister, 6).await.unwrap();
+        assert_eq!(
+            FileIndexStore::open(&path).await.unwrap().get(canister).await.unwrap(),
+            Some(6)
+        );
+    }
+
+    #[tokio::test]
+    async fn corrupt_file_is_rejected() {
+        let path = test_path("corrupt");
+        std::fs::write(&path, b"not json").unwrap();
+
+        assert!(FileIndexStore::open(&path).await.is_err());
+    }
+}
@@ -1,14 +1,19 @@
 use candid::Principal;
+use futures::future::{FutureExt, LocalBoxFuture};
+use group_index_canister::c2c_is_member::GroupOrCommunity;
 // use ic_verifiable_credentials::issuer_api::{ArgumentValue, CredentialSpec};
 // use ic_verifiable_credentials::VcFlowSigners;
 use icrc_ledger_types::icrc1::account::Account;
 use icrc_ledger_types::icrc2::transfer_from::TransferFromArgs;
 use sns_governance_canister::types::neuron::DissolveState;
 use sns_governance_canister::types::Neuron;
+use std::collections::HashMap;
 use types::{
-    AccessGate, CanisterId, CompositeGate, GateCheckFailedReason, PaymentGate, SnsNeuronGate, TimestampMillis,
-    TokenBalanceGate, UniquePersonProof, UserId, VerifiedCredentialGate,
+    AccessGate, AccountAgeGate, CanisterId, ChitGate, CompositeGate, GateCheckFailedReason, NftGate, PaymentGate,
+    ReferredByGate, SnsNeuronGate, TimestampMillis, TokenBalanceGate, UniquePersonProof, UserDetails, UserId,
+    VerifiedCredentialGate, VoteWeighting,
 };
+use user_index_canister_c2c_client::LookupUserError;
 use utils::consts::MEMO_JOINING_FEE;
 use utils::time::{DAY_IN_MS, NANOS_PER_MILLISECOND};
 
@@ -29,11 +34,35 @@ pub struct CheckGateArgs {
     pub user_id: UserId,
     pub diamond_membership_expires_at: Option<TimestampMillis>,
     pub this_canister: CanisterId,
+    pub user_index_canister: CanisterId,
+    pub group_index_canister: CanisterId,
     pub unique_person_proof: Option<UniquePersonProof>,
     pub verified_credential_args: Option<CheckVerifiedCredentialGateArgs>,
+    // If not supplied, these are looked up from the user_index when a gate needs them
+    pub user_details: Option<GateUserDetails>,
     pub now: TimestampMillis,
 }
 
+// The details of a user, held by the user_index, which are needed to check the account age, CHIT and referred-by gates
+#[derive(Clone, Debug, Default)]
+pub struct GateUserDetails {
+    pub date_created: TimestampMillis,
+    pub chit_balance: i32,
+    pub streak: u16,
+    pub referred_by: Option<UserId>,
+}
+
+impl From<&UserDetails> for GateUserDetails {
+    fn from(user: &UserDetails) -> Self {
+        GateUserDetails {
+            date_created: user.date_created,
+            chit_balance: user.chit_balance,
+            streak: user.streak,
+            referred_by: user.referred_by,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct CheckVerifiedCredentialGateArgs {
     pub user_ii_principal: Principal,
@@ -44,15 +73,158 @@ pub struct CheckVerifiedCredentialGateArgs {
 }
 
 pub async fn check_if_passes_gate(gate: AccessGate, args: CheckGateArgs) -> CheckIfPassesGateResult {
-    match gate {
-        AccessGate::Composite(g) => check_composite_gate(g, args).await,
-        g => check_non_composite_gate(g, args).await,
+    check_gate(gate, args).await
+}
+
+// Composite and not gates can be nested within each other, so the async check must be boxed to allow recursion
+fn check_gate(gate: AccessGate, args: CheckGateArgs) -> LocalBoxFuture<'static, CheckIfPassesGateResult> {
+    async move {
+        match gate {
+            AccessGate::Composite(g) => check_composite_gate(g, args).await,
+            AccessGate::Not(g) => negate(check_gate(*g, args).await),
+            g => check_non_composite_gate(g, args).await,
+        }
+    }
+    .boxed_local()
+}
+
+// Checks the gate for an existing member. The user's details are looked up once from the user_index, since they are
+// not supplied by the user, and are then used by every gate which needs them.
+pub async fn check_if_member_passes_gate(
+    gate: AccessGate,
+    user_id: UserId,
+    this_canister: CanisterId,
+    user_index_canister: CanisterId,
+    group_index_canister: CanisterId,
+    now: TimestampMillis,
+) -> CheckIfPassesGateResult {
+    let user = match lookup_user(user_id, user_index_canister).await {
+        Ok(user) => user,
+        Err(result) => return result,
+    };
+
+    check_if_passes_gate(
+        gate,
+        CheckGateArgs {
+            user_id,
+            diamond_membership_expires_at: user.diamond_membership_expires_at,
+            this_canister,
+            user_index_canister,
+            group_index_canister,
+            unique_person_proof: user.unique_person_proof.clone(),
+            verified_credential_args: None,
+            user_details: Some(GateUserDetails::from(&user)),
+            now,
+        },
+    )
+    .await
+}
+
+#[derive(Default)]
+pub struct ReverifyMembersResult {
+    pub passed: Vec<UserId>,
+    pub failed: Vec<UserId>,
+}
+
+// Re-checks the gate for each of the given users, one batch at a time. Users whose checks return an internal error are
+// included in neither list so that they are not penalised for transient errors.
+pub async fn reverify_members(
+    gate: AccessGate,
+    user_ids: Vec<UserId>,
+    this_canister: CanisterId,
+    user_index_canister: CanisterId,
+    group_index_canister: CanisterId,
+    now: TimestampMillis,
+) -> ReverifyMembersResult {
+    const BATCH_SIZE: usize = 20;
+
+    let mut result = ReverifyMembersResult::default();
+
+    for batch in user_ids.chunks(BATCH_SIZE) {
+        let futures: Vec<_> = batch
+            .iter()
+            .map(|user_id| {
+                check_if_member_passes_gate(
+                    gate.clone(),
+                    *user_id,
+                    this_canister,
+                    user_index_canister,
+                    group_index_canister,
+                    now,
+                )
+            })
+            .collect();
+
+        for (user_id, check_result) in batch.iter().zip(futures::future::join_all(futures).await) {
+            match check_result {
+                CheckIfPassesGateResult::Success => result.passed.push(*user_id),
+                CheckIfPassesGateResult::Failed(_) => result.failed.push(*user_id),
+                CheckIfPassesGateResult::InternalError(_) => {}
+            }
+        }
+    }
+
+    result
+}
+
+// Looks up the weight of each user's vote as at `as_of` (the time the poll closed), one batch at a time. Users whose
+// lookups fail are omitted from the result so that their lookups can be retried.
+pub async fn lookup_vote_weights(
+    weighting: VoteWeighting,
+    user_ids: Vec<UserId>,
+    as_of: TimestampMillis,
+) -> HashMap<UserId, u128> {
+    const BATCH_SIZE: usize = 20;
+
+    let mut weights = HashMap::new();
+
+    let sns_parameters = match &weighting {
+        VoteWeighting::SnsNeuronStake(governance_canister_id) => {
+            match sns_voting_power_parameters(*governance_canister_id).await {
+                Ok(parameters) => Some(parameters),
+                Err(_) => return weights,
+            }
+        }
+        VoteWeighting::TokenBalance(_) => None,
+    };
+
+    for batch in user_ids.chunks(BATCH_SIZE) {
+        let futures: Vec<_> = batch
+            .iter()
+            .map(|user_id| lookup_vote_weight(&weighting, sns_parameters.as_ref(), *user_id, as_of))
+            .collect();
+
+        for (user_id, weight) in batch.iter().zip(futures::future::join_all(futures).await) {
+            if let Ok(weight) = weight {
+                weights.insert(*user_id, weight);
+            }
+        }
+    }
+
+    weights
+}
+
+async fn lookup_vote_weight(
+    weighting: &VoteWeighting,
+    sns_parameters: Option<&SnsVotingPowerParameters>,
+    user_id: UserId,
+    as_of: TimestampMillis,
+) -> Result<u128, String> {
+    match weighting {
+        VoteWeighting::TokenBalance(ledger_canister_id) => token_balance(*ledger_canister_id, user_id).await,
+        VoteWeighting::SnsNeuronStake(governance_canister_id) => {
+            let parameters = sns_parameters.ok_or("SNS parameters not loaded")?;
+            let neurons = sns_neurons(*governance_canister_id, user_id).await?;
+
+            Ok(neurons.iter().map(|n| neuron_voting_power(n, parameters, as_of / 1000)).sum())
+        }
     }
 }
 
 pub fn check_if_passes_gate_synchronously(gate: AccessGate, args: CheckGateArgs) -> Option<CheckIfPassesGateResult> {
     match gate {
         AccessGate::Composite(g) => check_composite_gate_synchronously(g, args),
+        AccessGate::Not(g) => check_if_passes_gate_synchronously(*g, args).map(negate),
         g => check_non_composite_gate_synchronously(g, args),
     }
 }
@@ -66,7 +238,36 @@ async fn check_non_composite_gate(gate: AccessGate, args: CheckGateArgs) -> Chec
         AccessGate::SnsNeuron(g) => check_sns_neuron_gate(&g, args.user_id).await,
         AccessGate::Payment(g) => try_transfer_from(&g, args.user_id, args.this_canister, args.now).await,
         AccessGate::TokenBalance(g) => check_token_balance_gate(&g, args.user_id).await,
-        AccessGate::Composite(_) => unreachable!(),
+        AccessGate::AccountAge(_) | AccessGate::Chit(_) | AccessGate::ReferredBy(_) => {
+            let mut args = args;
+            if args.user_details.is_none() {
+                match lookup_user(args.user_id, args.user_index_canister).await {
+                    Ok(user) => args.user_details = Some(GateUserDetails::from(&user)),
+                    Err(result) => return result,
+                }
+            }
+            check_non_composite_gate_synchronously(gate, args).unwrap()
+        }
+        AccessGate::GroupMember(g) => {
+            check_membership_gate(
+                GroupOrCommunity::Group(g.group_id),
+                args.user_id,
+                args.group_index_canister,
+                GateCheckFailedReason::NotGroupMember,
+            )
+            .await
+        }
+        AccessGate::CommunityMember(g) => {
+            check_membership_gate(
+                GroupOrCommunity::Community(g.community_id),
+                args.user_id,
+                args.group_index_canister,
+                GateCheckFailedReason::NotCommunityMember,
+            )
+            .await
+        }
+        AccessGate::Nft(g) => check_nft_gate(&g, args.user_id).await,
+        AccessGate::Composite(_) | AccessGate::Not(_) => unreachable!(),
     }
 }
 
@@ -79,7 +280,10 @@ fn check_non_composite_gate_synchronously(gate: AccessGate, args: CheckGateArgs)
         )),
         AccessGate::UniquePerson => Some(check_unique_person_gate(args.unique_person_proof)),
         AccessGate::VerifiedCredential(g) => Some(check_verified_credential_gate(&g, args.verified_credential_args, args.now)),
-        AccessGate::Composite(_) => unreachable!(),
+        AccessGate::AccountAge(g) => args.user_details.map(|u| check_account_age_gate(&g, &u, args.now)),
+        AccessGate::Chit(g) => args.user_details.map(|u| check_chit_gate(&g, &u)),
+        AccessGate::ReferredBy(g) => args.user_details.map(|u| check_referred_by_gate(&g, &u)),
+        AccessGate::Composite(_) | AccessGate::Not(_) => unreachable!(),
         _ => None,
     }
 }
@@ -174,72 +378,110 @@ fn check_verified_credential_gate(
 }
 
 async fn check_composite_gate(gate: CompositeGate, args: CheckGateArgs) -> CheckIfPassesGateResult {
-    if let Some(result) = check_composite_gate_synchronously(gate.clone(), args.clone()) {
-        return result;
-    }
+    // An 'and' gate is decided by the first inner gate which fails, an 'or' gate by the first which passes
+    let is_decisive = |result: &CheckIfPassesGateResult| result.success() != gate.and;
+    let mut args = args;
+    let mut last_result = None;
 
-    let count = gate.inner.len();
-    for (index, inner) in gate.inner.into_iter().enumerate() {
-        if matches!(inner, AccessGate::Composite(_)) {
-            return CheckIfPassesGateResult::InternalError("Cannot have nested composite gates".to_string());
+    // Check all of the inner gates which can be checked synchronously first, so that we avoid making any c2c calls if
+    // the result can be determined without them
+    let mut pending = match check_synchronously_where_possible(gate.inner, &args, is_decisive, &mut last_result) {
+        Ok(pending) => pending,
+        Err(result) => return result,
+    };
+
+    // If any of the remaining gates need the user's details, look them up once, after which those gates can also be
+    // checked synchronously
+    if args.user_details.is_none() && pending.iter().any(requires_user_details) {
+        if let Ok(user) = lookup_user(args.user_id, args.user_index_canister).await {
+            args.user_details = Some(GateUserDetails::from(&user));
+            pending = match check_synchronously_where_possible(pending, &args, is_decisive, &mut last_result) {
+                Ok(pending) => pending,
+                Err(result) => return result,
+            };
         }
+    }
 
-        let last = index + 1 == count;
-        let result = Box::new(check_non_composite_gate(inner, args.clone()).await);
-        let success = result.success();
+    sort_payment_gates_last(&mut pending);
 
-        if (gate.and && !success) || (!gate.and && success) || last {
-            return *result;
+    for inner in pending {
+        let result = check_gate(inner, args.clone()).await;
+        if is_decisive(&result) {
+            return result;
         }
+        last_result = Some(result);
     }
 
-    CheckIfPassesGateResult::InternalError("This shouldn't be possible".to_string())
+    last_result.unwrap_or_else(|| CheckIfPassesGateResult::InternalError("Composite gate has no inner gates".to_string()))
 }
 
-fn check_composite_gate_synchronously(gate: CompositeGate, args: CheckGateArgs) -> Option<CheckIfPassesGateResult> {
-    let count = gate.inner.len();
-    let mut any_require_async = false;
-    for (index, inner) in gate.inner.into_iter().enumerate() {
-        if matches!(inner, AccessGate::Composite(_)) {
-            return Some(CheckIfPassesGateResult::InternalError(
-                "Cannot have nested composite gates".to_string(),
-            ));
+// Returns the gates which could not be checked synchronously, or the result if one of the gates decides it
+fn check_synchronously_where_possible(
+    gates: Vec<AccessGate>,
+    args: &CheckGateArgs,
+    is_decisive: impl Fn(&CheckIfPassesGateResult) -> bool,
+    last_result: &mut Option<CheckIfPassesGateResult>,
+) -> Result<Vec<AccessGate>, CheckIfPassesGateResult> {
+    let mut pending = Vec::new();
+    for gate in gates {
+        match check_if_passes_gate_synchronously(gate.clone(), args.clone()) {
+            Some(result) if is_decisive(&result) => return Err(result),
+            Some(result) => *last_result = Some(result),
+            None => pending.push(gate),
         }
+    }
+    Ok(pending)
+}
 
-        let last = index + 1 == count;
-        if let Some(result) = check_non_composite_gate_synchronously(inner, args.clone()) {
-            let success = result.success();
+// Leaves any gate containing a payment until last, so that users are only ever charged if the payment is needed in
+// order to pass the gate. The sort is stable so the other gates keep their order.
+fn sort_payment_gates_last(gates: &mut [AccessGate]) {
+    gates.sort_by_key(|g| g.contains_payment_gate());
+}
 
-            if (gate.and && !success) || (!gate.and && success) || (last && !any_require_async) {
-                return Some(result);
-            }
-        } else {
-            any_require_async = true;
-        }
+fn requires_user_details(gate: &AccessGate) -> bool {
+    match gate {
+        AccessGate::AccountAge(_) | AccessGate::Chit(_) | AccessGate::ReferredBy(_) => true,
+        AccessGate::Composite(g) => g.inner.iter().any(requires_user_details),
+        AccessGate::Not(g) => requires_user_details(g),
+        _ => false,
+    }
+}
 
-        if last && any_require_async {
-            return None;
+fn check_composite_gate_synchronously(gate: CompositeGate, args: CheckGateArgs) -> Option<CheckIfPassesGateResult> {
+    let mut any_require_async = false;
+    let mut last_result = None;
+    for inner in gate.inner {
+        match check_if_passes_gate_synchronously(inner, args.clone()) {
+            Some(result) if result.success() != gate.and => return Some(result),
+            Some(result) => last_result = Some(result),
+            None => any_require_async = true,
         }
     }
 
-    Some(CheckIfPassesGateResult::InternalError(
-        "This shouldn't be possible".to_string(),
-    ))
+    if any_require_async {
+        None
+    } else {
+        Some(
+            last_result
+                .unwrap_or_else(|| CheckIfPassesGateResult::InternalError("Composite gate has no inner gates".to_string())),
+        )
+    }
 }
 
-async fn check_sns_neuron_gate(gate: &SnsNeuronGate, user_id: UserId) -> CheckIfPassesGateResult {
-    let args = sns_governance_canister::list_neurons::Args {
-        limit: 10,
-        start_page_at: None,
-        of_principal: Some(Principal::from(user_id)),
-    };
+fn negate(result: CheckIfPassesGateResult) -> CheckIfPassesGateResult {
+    match result {
+        CheckIfPassesGateResult::Success => CheckIfPassesGateResult::Failed(GateCheckFailedReason::NegatedGatePassed),
+        CheckIfPassesGateResult::Failed(_) => CheckIfPassesGateResult::Success,
+        error @ CheckIfPassesGateResult::InternalError(_) => error,
+    }
+}
 
-    match sns_governance_canister_c2c_client::list_neurons(gate.governance_canister_id, &args).await {
-        Ok(response) if response.neurons.is_empty() => {
-            CheckIfPassesGateResult::Failed(GateCheckFailedReason::NoSnsNeuronsFound)
-        }
-        Ok(response) => {
-            let mut valid_neurons = response.neurons;
+async fn check_sns_neuron_gate(gate: &SnsNeuronGate, user_id: UserId) -> CheckIfPassesGateResult {
+    match sns_neurons(gate.governance_canister_id, user_id).await {
+        Ok(neurons) if neurons.is_empty() => CheckIfPassesGateResult::Failed(GateCheckFailedReason::NoSnsNeuronsFound),
+        Ok(neurons) => {
+            let mut valid_neurons = neurons;
             if let Some(dd) = gate.min_dissolve_delay {
                 let now = utils::time::now_millis();
                 valid_neurons.retain(|n| dissolve_delay_seconds(n, now / 1000) > (dd / 1000));
@@ -250,10 +492,7 @@ async fn check_sns_neuron_gate(gate: &SnsNeuronGate, user_id: UserId) -> CheckIf
             }
 
             if let Some(stake_required) = gate.min_stake_e8s {
-                let total_stake: u64 = valid_neurons
-                    .iter()
-                    .map(|n| n.cached_neuron_stake_e8s + n.staked_maturity_e8s_equivalent.unwrap_or_default())
-                    .sum();
+                let total_stake: u64 = valid_neurons.iter().map(neuron_stake_e8s).sum();
 
                 if total_stake < stake_required {
                     return CheckIfPassesGateResult::Failed(GateCheckFailedReason::NoSnsNeuronsWithRequiredStakeFound);
@@ -262,10 +501,121 @@ async fn check_sns_neuron_gate(gate: &SnsNeuronGate, user_id: UserId) -> CheckIf
 
             CheckIfPassesGateResult::Success
         }
-        Err(error) => CheckIfPassesGateResult::InternalError(format!("Error calling 'list_neurons': {error:?}")),
+        Err(error) => CheckIfPassesGateResult::InternalError(error),
     }
 }
 
+async fn sns_neurons(governance_canister_id: CanisterId, user_id: UserId) -> Result<Vec<Neuron>, String> {
+    const PAGE_SIZE: u32 = 100;
+
+    let mut neurons = Vec::new();
+    let mut start_page_at = None;
+
+    loop {
+        let args = sns_governance_canister::list_neurons::Args {
+            limit: PAGE_SIZE,
+            start_page_at,
+            of_principal: Some(Principal::from(user_id)),
+        };
+
+        let page = match sns_governance_canister_c2c_client::list_neurons(governance_canister_id, &args).await {
+            Ok(response) => response.neurons,
+            Err(error) => return Err(format!("Error calling 'list_neurons': {error:?}")),
+        };
+
+        let is_last_page = page.len() < PAGE_SIZE as usize;
+        start_page_at = page.last().and_then(|n| n.id.clone());
+        neurons.extend(page);
+
+        if is_last_page || start_page_at.is_none() {
+            return Ok(neurons);
+        }
+    }
+}
+
+// The parameters which an SNS uses to calculate the voting power of its neurons. Where the SNS hasn't set a parameter,
+// the SNS governance canister's own default is used.
+struct SnsVotingPowerParameters {
+    min_dissolve_delay_to_vote_seconds: u64,
+    max_dissolve_delay_seconds: u64,
+    max_neuron_age_for_age_bonus_seconds: u64,
+    max_dissolve_delay_bonus_percentage: u64,
+    max_age_bonus_percentage: u64,
+}
+
+async fn sns_voting_power_parameters(governance_canister_id: CanisterId) -> Result<SnsVotingPowerParameters, String> {
+    const ONE_YEAR_SECONDS: u64 = 365 * 24 * 60 * 60 + 6 * 60 * 60;
+
+    match sns_governance_canister_c2c_client::get_nervous_system_parameters(governance_canister_id, &()).await {
+        Ok(parameters) => Ok(SnsVotingPowerParameters {
+            min_dissolve_delay_to_vote_seconds: parameters
+                .neuron_minimum_dissolve_delay_to_vote_seconds
+                .unwrap_or(ONE_YEAR_SECONDS / 2),
+            max_dissolve_delay_seconds: parameters.max_dissolve_delay_seconds.unwrap_or(8 * ONE_YEAR_SECONDS),
+            max_neuron_age_for_age_bonus_seconds: parameters.max_neuron_age_for_age_bonus.unwrap_or(4 * ONE_YEAR_SECONDS),
+            max_dissolve_delay_bonus_percentage: parameters.max_dissolve_delay_bonus_percentage.unwrap_or(100),
+            max_age_bonus_percentage: parameters.max_age_bonus_percentage.unwrap_or(25),
+        }),
+        Err(error) => Err(format!("Error calling 'get_nervous_system_parameters': {error:?}")),
+    }
+}
+
+// Matches the SNS governance canister's own voting power calculation
+fn neuron_voting_power(neuron: &Neuron, parameters: &SnsVotingPowerParameters, now_seconds: u64) -> u128 {
+    let dissolve_delay = dissolve_delay_seconds(neuron, now_seconds);
+    // Dissolving neurons have no age
+    let age = match neuron.dissolve_state {
+        Some(DissolveState::DissolveDelaySeconds(_)) => now_seconds.saturating_sub(neuron.aging_since_timestamp_seconds),
+        _ => 0,
+    };
+    let stake = neuron_stake_e8s(neuron).saturating_sub(neuron.neuron_fees_e8s);
+
+    voting_power(
+        stake,
+        dissolve_delay,
+        age,
+        neuron.voting_power_percentage_multiplier,
+        parameters,
+    )
+}
+
+fn voting_power(
+    stake_e8s: u64,
+    dissolve_delay_seconds: u64,
+    age_seconds: u64,
+    voting_power_percentage_multiplier: u64,
+    parameters: &SnsVotingPowerParameters,
+) -> u128 {
+    if dissolve_delay_seconds < parameters.min_dissolve_delay_to_vote_seconds {
+        return 0;
+    }
+
+    let stake = stake_e8s as u128;
+
+    let dissolve_delay = dissolve_delay_seconds.min(parameters.max_dissolve_delay_seconds) as u128;
+    let dissolve_delay_bonus = if parameters.max_dissolve_delay_seconds > 0 {
+        stake * dissolve_delay * parameters.max_dissolve_delay_bonus_percentage as u128
+            / (100 * parameters.max_dissolve_delay_seconds as u128)
+    } else {
+        0
+    };
+    let stake_with_dissolve_delay_bonus = stake + dissolve_delay_bonus;
+
+    let age = age_seconds.min(parameters.max_neuron_age_for_age_bonus_seconds) as u128;
+    let age_bonus = if parameters.max_neuron_age_for_age_bonus_seconds > 0 {
+        stake_with_dissolve_delay_bonus * age * parameters.max_age_bonus_percentage as u128
+            / (100 * parameters.max_neuron_age_for_age_bonus_seconds as u128)
+    } else {
+        0
+    };
+
+    (stake_with_dissolve_delay_bonus + age_bonus) * voting_power_percentage_multiplier as u128 / 100
+}
+
+fn neuron_stake_e8s(neuron: &Neuron) -> u64 {
+    neuron.cached_neuron_stake_e8s + neuron.staked_maturity_e8s_equivalent.unwrap_or_default()
+}
+
 async fn try_transfer_from(
     gate: &PaymentGate,
     user_id: UserId,
@@ -295,12 +645,98 @@ async fn try_transfer_from(
 }
 
 async fn check_token_balance_gate(gate: &TokenBalanceGate, user_id: UserId) -> CheckIfPassesGateResult {
-    match icrc_ledger_canister_c2c_client::icrc1_balance_of(gate.ledger_canister_id, &Account::from(user_id)).await {
+    match token_balance(gate.ledger_canister_id, user_id).await {
         Ok(balance) if balance >= gate.min_balance => CheckIfPassesGateResult::Success,
-        Ok(balance) => {
-            CheckIfPassesGateResult::Failed(GateCheckFailedReason::InsufficientBalance(balance.0.try_into().unwrap()))
+        Ok(balance) => CheckIfPassesGateResult::Failed(GateCheckFailedReason::InsufficientBalance(balance)),
+        Err(error) => CheckIfPassesGateResult::InternalError(error),
+    }
+}
+
+async fn token_balance(ledger_canister_id: CanisterId, user_id: UserId) -> Result<u128, String> {
+    match icrc_ledger_canister_c2c_client::icrc1_balance_of(ledger_canister_id, &Account::from(user_id)).await {
+        Ok(balance) => Ok(balance.0.try_into().unwrap()),
+        Err(error) => Err(format!("Error calling 'icrc1_balance_of': {error:?}")),
+    }
+}
+
+fn check_account_age_gate(gate: &AccountAgeGate, user: &GateUserDetails, now: TimestampMillis) -> CheckIfPassesGateResult {
+    if now.saturating_sub(user.date_created) >= gate.min_age {
+        CheckIfPassesGateResult::Success
+    } else {
+        CheckIfPassesGateResult::Failed(GateCheckFailedReason::AccountTooNew)
+    }
+}
+
+fn check_chit_gate(gate: &ChitGate, user: &GateUserDetails) -> CheckIfPassesGateResult {
+    if gate
+        .min_balance
+        .map_or(false, |min| i64::from(user.chit_balance) < i64::from(min))
+    {
+        CheckIfPassesGateResult::Failed(GateCheckFailedReason::InsufficientChitBalance(user.chit_balance))
+    } else if gate.min_streak.map_or(false, |min| user.streak < min) {
+        CheckIfPassesGateResult::Failed(GateCheckFailedReason::InsufficientChitStreak(user.streak))
+    } else {
+        CheckIfPassesGateResult::Success
+    }
+}
+
+fn check_referred_by_gate(gate: &ReferredByGate, user: &GateUserDetails) -> CheckIfPassesGateResult {
+    if user.referred_by.map_or(false, |r| gate.user_ids.contains(&r)) {
+        CheckIfPassesGateResult::Success
+    } else {
+        CheckIfPassesGateResult::Failed(GateCheckFailedReason::NotReferredByRequiredUser)
+    }
+}
+
+// Groups and communities only reveal their members to the group_index, so the check is made via the group_index
+async fn check_membership_gate(
+    group_or_community: GroupOrCommunity,
+    user_id: UserId,
+    group_index_canister: CanisterId,
+    failed_reason: GateCheckFailedReason,
+) -> CheckIfPassesGateResult {
+    match group_index_canister_c2c_client::c2c_is_member(
+        group_index_canister,
+        &group_index_canister::c2c_is_member::Args {
+            group_or_community,
+            user_id,
+        },
+    )
+    .await
+    {
+        Ok(group_index_canister::c2c_is_member::Response::Success(true)) => CheckIfPassesGateResult::Success,
+        Ok(group_index_canister::c2c_is_member::Response::Success(false)) => CheckIfPassesGateResult::Failed(failed_reason),
+        Ok(response) => CheckIfPassesGateResult::InternalError(format!("{response:?}")),
+        Err(error) => CheckIfPassesGateResult::InternalError(format!("Error calling 'c2c_is_member': {error:?}")),
+    }
+}
+
+async fn check_nft_gate(gate: &NftGate, user_id: UserId) -> CheckIfPassesGateResult {
+    match icrc7_canister_c2c_client::icrc7_balance_of(gate.ledger_canister_id, &vec![Account::from(user_id)]).await {
+        Ok(balances) => {
+            let count: u32 = balances
+                .into_iter()
+                .next()
+                .and_then(|b| b.0.try_into().ok())
+                .unwrap_or_default();
+
+            if count >= gate.min_count {
+                CheckIfPassesGateResult::Success
+            } else {
+                CheckIfPassesGateResult::Failed(GateCheckFailedReason::InsufficientNfts(count))
+            }
         }
-        Err(error) => CheckIfPassesGateResult::InternalError(format!("Error calling 'icrc1_balance_of': {error:?}")),
+        Err(error) => CheckIfPassesGateResult::InternalError(format!("Error calling 'icrc7_balance_of': {error:?}")),
+    }
+}
+
+async fn lookup_user(user_id: UserId, user_index_canister_id: CanisterId) -> Result<UserDetails, CheckIfPassesGateResult> {
+    match user_index_canister_c2c_client::lookup_user(user_id.into(), user_index_canister_id).await {
+        Ok(user) => Ok(user),
+        Err(LookupUserError::UserNotFound) => Err(CheckIfPassesGateResult::InternalError("User not found".to_string())),
+        Err(LookupUserError::InternalError(error)) => Err(CheckIfPassesGateResult::InternalError(format!(
+            "Error calling 'c2c_lookup_user': {error}"
+        ))),
     }
 }
 
@@ -311,3 +747,300 @@ fn dissolve_delay_seconds(neuron: &Neuron, now_seconds: u64) -> u64 {
         None => 0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    const NOW: TimestampMillis = 1_000 * DAY_IN_MS;
+
+    #[test]
+    fn user_detail_gates_require_async_check_if_details_not_supplied() {
+        for gate in [
+            AccessGate::AccountAge(AccountAgeGate { min_age: DAY_IN_MS }),
+            AccessGate::Chit(ChitGate {
+                min_balance: Some(100),
+                min_streak: None,
+            }),
+            AccessGate::ReferredBy(ReferredByGate { user_ids: vec![user(1)] }),
+        ] {
+            assert!(check_if_passes_gate_synchronously(gate, args(None)).is_none());
+        }
+    }
+
+    #[test]
+    fn account_age_gate_checked_synchronously() {
+        let gate = AccessGate::AccountAge(AccountAgeGate { min_age: 30 * DAY_IN_MS });
+
+        let old_account = details(NOW - 31 * DAY_IN_MS, 0, 0, None);
+        let new_account = details(NOW - 29 * DAY_IN_MS, 0, 0, None);
+
+        assert!(check_if_passes_gate_synchronously(gate.clone(), args(Some(old_account)))
+            .unwrap()
+            .success());
+        assert!(matches!(
+            check_if_passes_gate_synchronously(gate, args(Some(new_account))),
+            Some(CheckIfPassesGateResult::Failed(GateCheckFailedReason::AccountTooNew))
+        ));
+    }
+
+    #[test]
+    fn chit_gate_checked_synchronously() {
+        let gate = AccessGate::Chit(ChitGate {
+            min_balance: Some(100),
+            min_streak: Some(5),
+        });
+
+        assert!(
+            check_if_passes_gate_synchronously(gate.clone(), args(Some(details(0, 100, 5, None))))
+                .unwrap()
+                .success()
+        );
+        assert!(matches!(
+            check_if_passes_gate_synchronously(gate.clone(), args(Some(details(0, -10, 5, None)))),
+            Some(CheckIfPassesGateResult::Failed(
+                GateCheckFailedReason::InsufficientChitBalance(-10)
+            ))
+        ));
+        assert!(matches!(
+            check_if_passes_gate_synchronously(gate, args(Some(details(0, 100, 4, None)))),
+            Some(CheckIfPassesGateResult::Failed(
+                GateCheckFailedReason::InsufficientChitStreak(4)
+            ))
+        ));
+    }
+
+    #[test]
+    fn referred_by_gate_checked_synchronously() {
+        let gate = AccessGate::ReferredBy(ReferredByGate {
+            user_ids: vec![user(1), user(2)],
+        });
+
+        assert!(
+            check_if_passes_gate_synchronously(gate.clone(), args(Some(details(0, 0, 0, Some(user(2))))))
+                .unwrap()
+                .success()
+        );
+        assert!(
+            !check_if_passes_gate_synchronously(gate.clone(), args(Some(details(0, 0, 0, Some(user(3))))))
+                .unwrap()
+                .success()
+        );
+        assert!(!check_if_passes_gate_synchronously(gate, args(Some(details(0, 0, 0, None))))
+            .unwrap()
+            .success());
+    }
+
+    #[test]
+    fn composite_gate_using_user_details_checked_synchronously() {
+        let gate = AccessGate::Composite(CompositeGate {
+            inner: vec![
+                AccessGate::DiamondMember,
+                AccessGate::AccountAge(AccountAgeGate { min_age: DAY_IN_MS }),
+            ],
+            and: false,
+        });
+
+        assert!(check_if_passes_gate_synchronously(gate.clone(), args(None)).is_none());
+        assert!(check_if_passes_gate_synchronously(gate, args(Some(details(0, 0, 0, None))))
+            .unwrap()
+            .success());
+    }
+
+    #[test]
+    fn not_gate_inverts_result() {
+        let gate = AccessGate::Not(Box::new(AccessGate::DiamondMember));
+
+        assert!(check_if_passes_gate_synchronously(gate.clone(), args(None))
+            .unwrap()
+            .success());
+
+        let mut diamond_args = args(None);
+        diamond_args.diamond_membership_expires_at = Some(NOW + DAY_IN_MS);
+        assert!(matches!(
+            check_if_passes_gate_synchronously(gate, diamond_args),
+            Some(CheckIfPassesGateResult::Failed(GateCheckFailedReason::NegatedGatePassed))
+        ));
+    }
+
+    #[test]
+    fn nested_gates_within_max_depth_are_valid() {
+        let not_3_deep = not(not(not(AccessGate::DiamondMember)));
+        let not_4_deep = not(not_3_deep.clone());
+        assert!(not_3_deep.validate());
+        assert!(!not_4_deep.validate());
+
+        let mixed_3_deep = or(vec![not(and(vec![AccessGate::DiamondMember, AccessGate::UniquePerson]))]);
+        let mixed_4_deep = or(vec![not(and(vec![not(AccessGate::DiamondMember)]))]);
+        assert!(mixed_3_deep.validate());
+        assert!(!mixed_4_deep.validate());
+    }
+
+    #[test]
+    fn total_gate_count_is_limited() {
+        let pair = || and(vec![AccessGate::DiamondMember, not(AccessGate::UniquePerson)]);
+        let triple = || {
+            and(vec![
+                AccessGate::DiamondMember,
+                AccessGate::UniquePerson,
+                AccessGate::LifetimeDiamondMember,
+            ])
+        };
+
+        // 10 x 2 = 20 gates
+        assert!(or((0..10).map(|_| pair()).collect()).validate());
+        // 7 x 3 = 21 gates
+        assert!(!or((0..7).map(|_| triple()).collect()).validate());
+        // Too many inner gates at a single level
+        assert!(!or((0..11).map(|_| AccessGate::DiamondMember).collect()).validate());
+    }
+
+    #[test]
+    fn payment_gates_cannot_be_negated_or_repeated() {
+        assert!(or(vec![AccessGate::DiamondMember, payment()]).validate());
+        assert!(!not(payment()).validate());
+        assert!(!or(vec![AccessGate::DiamondMember, not(or(vec![payment()]))]).validate());
+        assert!(!or(vec![payment(), and(vec![AccessGate::DiamondMember, payment()])]).validate());
+    }
+
+    #[test]
+    fn gates_containing_payments_are_checked_last() {
+        let mut gates = vec![
+            payment(),
+            AccessGate::DiamondMember,
+            and(vec![AccessGate::UniquePerson, payment()]),
+            AccessGate::UniquePerson,
+        ];
+
+        sort_payment_gates_last(&mut gates);
+
+        assert_eq!(gates[0], AccessGate::DiamondMember);
+        assert_eq!(gates[1], AccessGate::UniquePerson);
+        assert!(gates[2].is_payment_gate());
+        assert!(gates[3].contains_payment_gate());
+    }
+
+    // Attempting a payment would make a c2c call, which panics outside of a canister, so each of these checks
+    // returning shows that the user was not charged
+
+    #[test]
+    fn payment_not_attempted_if_synchronous_gate_fails_and_gate() {
+        let gate = and(vec![payment(), AccessGate::DiamondMember]);
+
+        let result = block_on(check_if_passes_gate(gate, args(None)));
+
+        assert!(matches!(
+            result,
+            CheckIfPassesGateResult::Failed(GateCheckFailedReason::NotDiamondMember)
+        ));
+    }
+
+    #[test]
+    fn payment_not_attempted_if_synchronous_gate_passes_or_gate() {
+        let gate = or(vec![payment(), not(AccessGate::DiamondMember)]);
+
+        assert!(block_on(check_if_passes_gate(gate, args(None))).success());
+    }
+
+    #[test]
+    fn payment_not_attempted_if_user_details_gate_passes_or_gate() {
+        let gate = or(vec![payment(), AccessGate::AccountAge(AccountAgeGate { min_age: DAY_IN_MS })]);
+
+        assert!(block_on(check_if_passes_gate(gate, args(Some(details(0, 0, 0, None))))).success());
+    }
+
+    #[test]
+    fn payment_within_or_gate_not_attempted_if_sibling_fails_and_gate() {
+        let gate = and(vec![
+            or(vec![payment(), AccessGate::UniquePerson]),
+            AccessGate::Chit(ChitGate {
+                min_balance: Some(100),
+                min_streak: None,
+            }),
+        ]);
+
+        let result = block_on(check_if_passes_gate(gate, args(Some(details(0, 50, 0, None)))));
+
+        assert!(matches!(
+            result,
+            CheckIfPassesGateResult::Failed(GateCheckFailedReason::InsufficientChitBalance(50))
+        ));
+    }
+
+    fn and(inner: Vec<AccessGate>) -> AccessGate {
+        AccessGate::Composite(CompositeGate { inner, and: true })
+    }
+
+    fn or(inner: Vec<AccessGate>) -> AccessGate {
+        AccessGate::Composite(CompositeGate { inner, and: false })
+    }
+
+    #[test]
+    fn voting_power_includes_dissolve_delay_and_age_bonuses() {
+        let parameters = sns_parameters();
+
+        assert_eq!(voting_power(1000, 0, 0, 100, &parameters), 0);
+        assert_eq!(voting_power(1000, 99, 0, 100, &parameters), 0);
+        assert_eq!(voting_power(1000, 100, 0, 100, &parameters), 1250);
+        // The bonuses stop increasing once the dissolve delay and age reach their maximums
+        assert_eq!(voting_power(1000, 400, 0, 100, &parameters), 2000);
+        assert_eq!(voting_power(1000, 800, 0, 100, &parameters), 2000);
+        assert_eq!(voting_power(1000, 400, 200, 100, &parameters), 2500);
+        assert_eq!(voting_power(1000, 400, 100, 100, &parameters), 2250);
+    }
+
+    #[test]
+    fn voting_power_applies_percentage_multiplier() {
+        assert_eq!(voting_power(1000, 400, 200, 50, &sns_parameters()), 1250);
+    }
+
+    fn sns_parameters() -> SnsVotingPowerParameters {
+        SnsVotingPowerParameters {
+            min_dissolve_delay_to_vote_seconds: 100,
+            max_dissolve_delay_seconds: 400,
+            max_neuron_age_for_age_bonus_seconds: 200,
+            max_dissolve_delay_bonus_percentage: 100,
+            max_age_bonus_percentage: 25,
+        }
+    }
+
+    fn not(gate: AccessGate) -> AccessGate {
+        AccessGate::Not(Box::new(gate))
+    }
+
+    fn payment() -> AccessGate {
+        AccessGate::Payment(PaymentGate {
+            ledger_canister_id: Principal::anonymous(),
+            amount: 100_000_000,
+            fee: 10_000,
+        })
+    }
+
+    fn args(user_details: Option<GateUserDetails>) -> CheckGateArgs {
+        CheckGateArgs {
+            user_id: user(0),
+            diamond_membership_expires_at: None,
+            this_canister: Principal::anonymous(),
+            user_index_canister: Principal::anonymous(),
+            group_index_canister: Principal::anonymous(),
+            unique_person_proof: None,
+            verified_credential_args: None,
+            user_details,
+            now: NOW,
+        }
+    }
+
+    fn details(date_created: TimestampMillis, chit_balance: i32, streak: u16, referred_by: Option<UserId>) -> GateUserDetails {
+        GateUserDetails {
+            date_created,
+            chit_balance,
+            streak,
+            referred_by,
+        }
+    }
+
+    fn user(index: u8) -> UserId {
+        Principal::from_slice(&[index]).into()
+    }
+}
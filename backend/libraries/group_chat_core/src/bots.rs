@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use types::{BotEvent, BotEventType, InstalledBotDetails, SlashCommandSchema, TimestampMillis, UserId};
+
+// If events are produced faster than they can be pushed to the bots, the oldest are dropped beyond this limit
+const MAX_PENDING_EVENTS: usize = 1_000;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct InstalledBots {
+    bots: HashMap<UserId, InstalledBot>,
+    // Events which bots have subscribed to, waiting to be collected by the canister and pushed to the bots
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    pending_events: VecDeque<PendingBotEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InstalledBot {
+    #[serde(rename = "n")]
+    pub name: String,
+    #[serde(rename = "a")]
+    pub added_by: UserId,
+    #[serde(rename = "d")]
+    pub date_added: TimestampMillis,
+    #[serde(rename = "c")]
+    pub commands: Vec<SlashCommandSchema>,
+    #[serde(rename = "s", default, skip_serializing_if = "Vec::is_empty")]
+    pub subscribed_events: Vec<BotEventType>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PendingBotEvent {
+    pub bot_id: UserId,
+    pub timestamp: TimestampMillis,
+    pub event: BotEvent,
+}
+
+impl InstalledBots {
+    pub fn add(&mut self, bot_id: UserId, bot: InstalledBot) {
+        self.bots.insert(bot_id, bot);
+    }
+
+    pub fn remove(&mut self, bot_id: &UserId) -> Option<InstalledBot> {
+        self.pending_events.retain(|e| e.bot_id != *bot_id);
+        self.bots.remove(bot_id)
+    }
+
+    pub fn get(&self, bot_id: &UserId) -> Option<&InstalledBot> {
+        self.bots.get(bot_id)
+    }
+
+    // Bots can update their name and commands at any time, so the latest values are set each time a command is executed
+    pub fn refresh(&mut self, bot_id: &UserId, name: String, commands: Vec<SlashCommandSchema>) -> bool {
+        if let Some(bot) = self.bots.get_mut(bot_id) {
+            bot.name = name;
+            bot.commands = commands;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn set_subscriptions(&mut self, bot_id: &UserId, mut event_types: Vec<BotEventType>) -> bool {
+        if let Some(bot) = self.bots.get_mut(bot_id) {
+            event_types.sort_unstable();
+            event_types.dedup();
+            bot.subscribed_events = event_types;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Queues up the event for each bot which has subscribed to events of this type, skipping the bot whose own action
+    // caused the event
+    pub fn push_event(&mut self, event: BotEvent, now: TimestampMillis) {
+        let event_type = event.event_type();
+        let initiated_by = event.initiated_by();
+
+        for (bot_id, bot) in self.bots.iter() {
+            if bot.subscribed_events.contains(&event_type) && initiated_by != Some(*bot_id) {
+                self.pending_events.push_back(PendingBotEvent {
+                    bot_id: *bot_id,
+                    timestamp: now,
+                    event: event.clone(),
+                });
+            }
+        }
+
+        while self.pending_events.len() > MAX_PENDING_EVENTS {
+            self.pending_events.pop_front();
+        }
+    }
+
+    pub fn has_pending_events(&self) -> bool {
+        !self.pending_events.is_empty()
+    }
+
+    pub fn take_pending_events(&mut self) -> VecDeque<PendingBotEvent> {
+        mem::take(&mut self.pending_events)
+    }
+
+    pub fn details(&self) -> Vec<InstalledBotDetails> {
+        self.bots
+            .iter()
+            .map(|(user_id, bot)| InstalledBotDetails {
+                user_id: *user_id,
+                added_by: bot.added_by,
+                commands: bot.commands.clone(),
+                subscribed_events: bot.subscribed_events.clone(),
+            })
+            .collect()
+    }
+}
+
+impl InstalledBot {
+    pub fn command(&self, name: &str) -> Option<&SlashCommandSchema> {
+        self.commands.iter().find(|c| c.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use types::{
+        BotMembersJoinedEvent, NumberParam, SlashCommand, SlashCommandArg, SlashCommandArgValue, SlashCommandParam,
+        SlashCommandParamType, StringParam,
+    };
+
+    #[test]
+    fn refresh_replaces_commands_captured_at_install() {
+        let mut bots = InstalledBots::default();
+        bots.add(user(1), bot(vec![schema("roll", Vec::new())]));
+
+        assert!(bots.refresh(&user(1), "dice".to_string(), vec![schema("flip", Vec::new())]));
+        assert!(!bots.refresh(&user(2), "other".to_string(), Vec::new()));
+
+        let bot = bots.get(&user(1)).unwrap();
+        assert_eq!(bot.name, "dice");
+        assert!(bot.command("roll").is_none());
+        assert!(bot.command("flip").is_some());
+    }
+
+    #[test]
+    fn events_only_pushed_to_subscribed_bots_other_than_initiator() {
+        let mut bots = InstalledBots::default();
+        bots.add(user(1), bot(Vec::new()));
+        bots.add(user(2), bot(Vec::new()));
+        bots.add(user(3), bot(Vec::new()));
+        bots.set_subscriptions(&user(1), vec![BotEventType::MembersJoined]);
+        bots.set_subscriptions(&user(2), vec![BotEventType::MembersJoined, BotEventType::MembersJoined]);
+
+        bots.push_event(
+            BotEvent::MembersJoined(BotMembersJoinedEvent {
+                user_ids: vec![user(4)],
+                added_by: Some(user(2)),
+            }),
+            10,
+        );
+
+        let pending = bots.take_pending_events();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].bot_id, user(1));
+        assert_eq!(
+            bots.get(&user(2)).unwrap().subscribed_events,
+            vec![BotEventType::MembersJoined]
+        );
+        assert!(bots.take_pending_events().is_empty());
+    }
+
+    #[test]
+    fn pending_events_are_bounded() {
+        let mut bots = InstalledBots::default();
+        bots.add(user(1), bot(Vec::new()));
+        bots.set_subscriptions(&user(1), vec![BotEventType::MembersJoined]);
+
+        for i in 0..(MAX_PENDING_EVENTS as u64 + 10) {
+            bots.push_event(
+                BotEvent::MembersJoined(BotMembersJoinedEvent {
+                    user_ids: vec![user(2)],
+                    added_by: None,
+                }),
+                i,
+            );
+        }
+
+        let pending = bots.take_pending_events();
+        assert_eq!(pending.len(), MAX_PENDING_EVENTS);
+        assert_eq!(pending.front().unwrap().timestamp, 10);
+        assert!(!bots.has_pending_events());
+    }
+
+    #[test]
+    fn validate_all_rejects_invalid_commands() {
+        assert!(SlashCommandSchema::validate_all(&[schema("roll", Vec::new()), schema("flip", Vec::new())]).is_ok());
+        assert!(SlashCommandSchema::validate_all(&[schema("roll", Vec::new()), schema("roll", Vec::new())]).is_err());
+        assert!(SlashCommandSchema::validate_all(&[schema("Roll", Vec::new())]).is_err());
+        assert!(SlashCommandSchema::validate_all(&[schema("", Vec::new())]).is_err());
+
+        let too_many: Vec<_> = (0..101).map(|i| schema(&format!("command{i}"), Vec::new())).collect();
+        assert!(SlashCommandSchema::validate_all(&too_many).is_err());
+
+        let duplicate_params = schema("roll", vec![number_param("sides", true), number_param("sides", false)]);
+        assert!(SlashCommandSchema::validate_all(&[duplicate_params]).is_err());
+
+        let invalid_range = schema(
+            "roll",
+            vec![SlashCommandParam {
+                name: "sides".to_string(),
+                description: None,
+                required: true,
+                param_type: SlashCommandParamType::NumberParam(NumberParam {
+                    min_value: Some(10.0),
+                    max_value: Some(1.0),
+                }),
+            }],
+        );
+        assert!(SlashCommandSchema::validate_all(&[invalid_range]).is_err());
+    }
+
+    #[test]
+    fn validate_command_checks_args_against_schema() {
+        let schema = schema(
+            "roll",
+            vec![
+                number_param("sides", true),
+                SlashCommandParam {
+                    name: "colour".to_string(),
+                    description: None,
+                    required: false,
+                    param_type: SlashCommandParamType::StringParam(StringParam {
+                        min_length: 1,
+                        max_length: 10,
+                        choices: vec!["red".to_string(), "blue".to_string()],
+                    }),
+                },
+            ],
+        );
+
+        assert!(schema.validate_command(&command(vec![number_arg("sides", 6.0)])).is_ok());
+        assert!(schema
+            .validate_command(&command(vec![number_arg("sides", 6.0), string_arg("colour", "red")]))
+            .is_ok());
+
+        // Missing required arg
+        assert!(schema.validate_command(&command(vec![string_arg("colour", "red")])).is_err());
+        // Unknown arg
+        assert!(schema
+            .validate_command(&command(vec![number_arg("sides", 6.0), number_arg("count", 2.0)]))
+            .is_err());
+        // Duplicate arg
+        assert!(schema
+            .validate_command(&command(vec![number_arg("sides", 6.0), number_arg("sides", 8.0)]))
+            .is_err());
+        // Out of range
+        assert!(schema.validate_command(&command(vec![number_arg("sides", 101.0)])).is_err());
+        assert!(schema
+            .validate_command(&command(vec![number_arg("sides", f64::NAN)]))
+            .is_err());
+        // Not one of the choices
+        assert!(schema
+            .validate_command(&command(vec![number_arg("sides", 6.0), string_arg("colour", "green")]))
+            .is_err());
+        // Wrong type
+        assert!(schema.validate_command(&command(vec![string_arg("sides", "six")])).is_err());
+    }
+
+    fn bot(commands: Vec<SlashCommandSchema>) -> InstalledBot {
+        InstalledBot {
+            name: "bot".to_string(),
+            added_by: user(0),
+            date_added: 0,
+            commands,
+            subscribed_events: Vec::new(),
+        }
+    }
+
+    fn schema(name: &str, params: Vec<SlashCommandParam>) -> SlashCommandSchema {
+        SlashCommandSchema {
+            name: name.to_string(),
+            description: None,
+            params,
+            permissions: Vec::new(),
+        }
+    }
+
+    fn number_param(name: &str, required: bool) -> SlashCommandParam {
+        SlashCommandParam {
+            name: name.to_string(),
+            description: None,
+            required,
+            param_type: SlashCommandParamType::NumberParam(NumberParam {
+                min_value: Some(1.0),
+                max_value: Some(100.0),
+            }),
+        }
+    }
+
+    fn command(args: Vec<SlashCommandArg>) -> SlashCommand {
+        SlashCommand {
+            name: "roll".to_string(),
+            args,
+        }
+    }
+
+    fn number_arg(name: &str, value: f64) -> SlashCommandArg {
+        SlashCommandArg {
+            name: name.to_string(),
+            value: SlashCommandArgValue::Number(value),
+        }
+    }
+
+    fn string_arg(name: &str, value: &str) -> SlashCommandArg {
+        SlashCommandArg {
+            name: name.to_string(),
+            value: SlashCommandArgValue::String(value.to_string()),
+        }
+    }
+
+    fn user(index: u8) -> UserId {
+        Principal::from_slice(&[index]).into()
+    }
+}
@@ -0,0 +1,220 @@
+use chat_events::MessageContentInternal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use types::{CustomRole, GroupPermission, MessagePermission, TimestampMillis};
+
+pub const MAX_CUSTOM_ROLES: u32 = 50;
+pub const MIN_CUSTOM_ROLE_NAME_LENGTH: u32 = 2;
+pub const MAX_CUSTOM_ROLE_NAME_LENGTH: u32 = 25;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct CustomRoles {
+    roles: BTreeMap<u32, CustomRole>,
+    next_id: u32,
+    last_updated: TimestampMillis,
+}
+
+impl CustomRoles {
+    pub fn get(&self, id: u32) -> Option<&CustomRole> {
+        self.roles.get(&id)
+    }
+
+    pub fn exists(&self, id: u32) -> bool {
+        self.roles.contains_key(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CustomRole> {
+        self.roles.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.roles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty()
+    }
+
+    pub fn last_updated(&self) -> TimestampMillis {
+        self.last_updated
+    }
+
+    pub fn is_name_taken(&self, name: &str, exclude: Option<u32>) -> bool {
+        self.roles
+            .values()
+            .any(|r| Some(r.id) != exclude && r.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn create(
+        &mut self,
+        name: String,
+        permissions: Vec<GroupPermission>,
+        message_permissions: Vec<MessagePermission>,
+        now: TimestampMillis,
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.roles.insert(
+            id,
+            CustomRole {
+                id,
+                name,
+                permissions: dedup(permissions),
+                message_permissions: dedup(message_permissions),
+            },
+        );
+        self.last_updated = now;
+        id
+    }
+
+    pub fn update(
+        &mut self,
+        id: u32,
+        name: Option<String>,
+        permissions: Option<Vec<GroupPermission>>,
+        message_permissions: Option<Vec<MessagePermission>>,
+        now: TimestampMillis,
+    ) -> bool {
+        let Some(role) = self.roles.get_mut(&id) else {
+            return false;
+        };
+
+        if let Some(name) = name {
+            role.name = name;
+        }
+        if let Some(permissions) = permissions {
+            role.permissions = dedup(permissions);
+        }
+        if let Some(message_permissions) = message_permissions {
+            role.message_permissions = dedup(message_permissions);
+        }
+        self.last_updated = now;
+        true
+    }
+
+    pub fn delete(&mut self, id: u32, now: TimestampMillis) -> bool {
+        if self.roles.remove(&id).is_some() {
+            self.last_updated = now;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// The custom roles held by a member, along with the union of the permissions they grant so that permission checks
+// don't need to look up each role. The permissions must be refreshed whenever a role definition changes.
+#[derive(Serialize, Deserialize, Clone, Default, Eq, PartialEq)]
+pub struct CustomRoleGrants {
+    #[serde(rename = "r")]
+    role_ids: BTreeSet<u32>,
+    #[serde(rename = "p", default, skip_serializing_if = "Vec::is_empty")]
+    permissions: Vec<GroupPermission>,
+    #[serde(rename = "m", default, skip_serializing_if = "Vec::is_empty")]
+    message_permissions: Vec<MessagePermission>,
+}
+
+impl CustomRoleGrants {
+    pub fn role_ids(&self) -> &BTreeSet<u32> {
+        &self.role_ids
+    }
+
+    pub fn holds(&self, role_id: u32) -> bool {
+        self.role_ids.contains(&role_id)
+    }
+
+    pub fn add(&mut self, role_id: u32, roles: &CustomRoles) -> bool {
+        if self.role_ids.insert(role_id) {
+            self.refresh(roles);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn remove(&mut self, role_id: u32, roles: &CustomRoles) -> bool {
+        if self.role_ids.remove(&role_id) {
+            self.refresh(roles);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn refresh(&mut self, roles: &CustomRoles) {
+        let held: Vec<_> = self.role_ids.iter().filter_map(|id| roles.get(*id)).collect();
+
+        self.permissions = dedup(held.iter().flat_map(|r| r.permissions.iter().copied()).collect());
+        self.message_permissions = dedup(held.iter().flat_map(|r| r.message_permissions.iter().copied()).collect());
+    }
+
+    pub fn grants(&self, permission: GroupPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    pub fn grants_message(&self, content: &MessageContentInternal) -> bool {
+        let message_permission = match content {
+            MessageContentInternal::Text(_) => MessagePermission::Text,
+            MessageContentInternal::Image(_) => MessagePermission::Image,
+            MessageContentInternal::Video(_) => MessagePermission::Video,
+            MessageContentInternal::Audio(_) => MessagePermission::Audio,
+            MessageContentInternal::File(_) => MessagePermission::File,
+            MessageContentInternal::Poll(_) => MessagePermission::Poll,
+            MessageContentInternal::Crypto(_) => MessagePermission::Crypto,
+            MessageContentInternal::Giphy(_) => MessagePermission::Giphy,
+            MessageContentInternal::Prize(_) => MessagePermission::Prize,
+            MessageContentInternal::P2PSwap(_) => MessagePermission::P2pSwap,
+            MessageContentInternal::VideoCall(_) => return self.grants(GroupPermission::StartVideoCall),
+            _ => return false,
+        };
+
+        self.message_permissions.contains(&message_permission)
+    }
+}
+
+fn dedup<T: PartialEq>(values: Vec<T>) -> Vec<T> {
+    let mut result = Vec::with_capacity(values.len());
+    for value in values {
+        if !result.contains(&value) {
+            result.push(value);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grants_reflect_role_definitions() {
+        let mut roles = CustomRoles::default();
+        let trader = roles.create("Trader".to_string(), Vec::new(), vec![MessagePermission::P2pSwap], 1);
+        let helper = roles.create("Helper".to_string(), vec![GroupPermission::PinMessages], Vec::new(), 1);
+
+        let mut grants = CustomRoleGrants::default();
+        assert!(grants.add(trader, &roles));
+        assert!(grants.add(helper, &roles));
+        assert!(grants.grants(GroupPermission::PinMessages));
+        assert!(!grants.grants(GroupPermission::DeleteMessages));
+
+        roles.update(helper, None, Some(vec![GroupPermission::DeleteMessages]), None, 2);
+        grants.refresh(&roles);
+        assert!(!grants.grants(GroupPermission::PinMessages));
+        assert!(grants.grants(GroupPermission::DeleteMessages));
+
+        assert!(grants.remove(helper, &roles));
+        assert!(!grants.grants(GroupPermission::DeleteMessages));
+        assert!(grants.holds(trader));
+    }
+
+    #[test]
+    fn role_names_are_unique_ignoring_case() {
+        let mut roles = CustomRoles::default();
+        let id = roles.create("Trader".to_string(), Vec::new(), Vec::new(), 1);
+
+        assert!(roles.is_name_taken("trader", None));
+        assert!(!roles.is_name_taken("trader", Some(id)));
+        assert!(!roles.is_name_taken("helper", None));
+    }
+}
@@ -0,0 +1,164 @@
+use types::{GateReverification, TimestampMillis, Timestamped, UserId};
+
+// The number of members whose gate checks are made by each execution of a gate reverification job
+pub const GATE_REVERIFICATION_BATCH_SIZE: usize = 100;
+
+// Implemented by both group and community members so that they can share the logic for applying the results
+pub trait GateReverificationMembers {
+    // Returns whether the member must pass periodic gate checks along with the time at which they lapsed (if they
+    // have), or `None` if the user is not a member
+    fn lapsed_mut(&mut self, user_id: &UserId) -> Option<(bool, &mut Timestamped<Option<TimestampMillis>>)>;
+}
+
+#[derive(Default, Debug, Eq, PartialEq)]
+pub struct GateReverificationOutcome {
+    pub lapsed: Vec<UserId>,
+    pub removed: Vec<UserId>,
+}
+
+// Returns up to `max` of the given users, in order, starting after the cursor
+pub fn next_members_to_reverify(user_ids: impl Iterator<Item = UserId>, after: Option<UserId>, max: usize) -> Vec<UserId> {
+    let mut user_ids: Vec<_> = user_ids.filter(|u| after.map_or(true, |a| *u > a)).collect();
+    user_ids.sort_unstable();
+    user_ids.truncate(max);
+    user_ids
+}
+
+// Clears the lapsed state of members who passed the gate. Members who failed the gate are either marked as lapsed or,
+// if there is no grace period or their grace period has passed, returned as to be removed.
+pub fn apply_gate_reverification_results<M: GateReverificationMembers>(
+    members: &mut M,
+    gate_reverification: GateReverification,
+    passed: Vec<UserId>,
+    failed: Vec<UserId>,
+    now: TimestampMillis,
+) -> GateReverificationOutcome {
+    let mut outcome = GateReverificationOutcome::default();
+
+    for user_id in passed {
+        if let Some((_, lapsed)) = members.lapsed_mut(&user_id) {
+            if lapsed.value.is_some() {
+                *lapsed = Timestamped::new(None, now);
+            }
+        }
+    }
+
+    for user_id in failed {
+        let Some((true, lapsed)) = members.lapsed_mut(&user_id) else {
+            continue;
+        };
+
+        match (gate_reverification.grace_period, lapsed.value) {
+            (Some(_), None) => {
+                *lapsed = Timestamped::new(Some(now), now);
+                outcome.lapsed.push(user_id);
+            }
+            (Some(grace_period), Some(lapsed_at)) if now < lapsed_at + grace_period => {}
+            _ => outcome.removed.push(user_id),
+        }
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use std::collections::HashMap;
+    use utils::time::DAY_IN_MS;
+
+    struct TestMembers(HashMap<UserId, (bool, Timestamped<Option<TimestampMillis>>)>);
+
+    impl GateReverificationMembers for TestMembers {
+        fn lapsed_mut(&mut self, user_id: &UserId) -> Option<(bool, &mut Timestamped<Option<TimestampMillis>>)> {
+            self.0.get_mut(user_id).map(|(r, l)| (*r, l))
+        }
+    }
+
+    #[test]
+    fn members_lapse_then_are_removed_after_grace_period() {
+        let mut members = TestMembers(HashMap::from([(user(1), (true, Timestamped::default()))]));
+        let reverification = GateReverification {
+            interval: DAY_IN_MS,
+            grace_period: Some(2 * DAY_IN_MS),
+        };
+
+        let outcome = apply_gate_reverification_results(&mut members, reverification, vec![], vec![user(1)], 10);
+        assert_eq!(outcome.lapsed, vec![user(1)]);
+        assert!(outcome.removed.is_empty());
+        assert_eq!(members.0[&user(1)].1.value, Some(10));
+
+        // Still within the grace period
+        let outcome = apply_gate_reverification_results(&mut members, reverification, vec![], vec![user(1)], 10 + DAY_IN_MS);
+        assert_eq!(outcome, GateReverificationOutcome::default());
+        assert_eq!(members.0[&user(1)].1.value, Some(10));
+
+        let outcome =
+            apply_gate_reverification_results(&mut members, reverification, vec![], vec![user(1)], 10 + 2 * DAY_IN_MS);
+        assert_eq!(outcome.removed, vec![user(1)]);
+    }
+
+    #[test]
+    fn members_removed_immediately_if_no_grace_period() {
+        let mut members = TestMembers(HashMap::from([(user(1), (true, Timestamped::default()))]));
+        let reverification = GateReverification {
+            interval: DAY_IN_MS,
+            grace_period: None,
+        };
+
+        let outcome = apply_gate_reverification_results(&mut members, reverification, vec![], vec![user(1)], 10);
+
+        assert!(outcome.lapsed.is_empty());
+        assert_eq!(outcome.removed, vec![user(1)]);
+    }
+
+    #[test]
+    fn passing_clears_lapsed_state() {
+        let mut members = TestMembers(HashMap::from([(user(1), (true, Timestamped::new(Some(10), 10)))]));
+        let reverification = GateReverification {
+            interval: DAY_IN_MS,
+            grace_period: Some(DAY_IN_MS),
+        };
+
+        let outcome = apply_gate_reverification_results(&mut members, reverification, vec![user(1)], vec![], 20);
+
+        assert_eq!(outcome, GateReverificationOutcome::default());
+        assert_eq!(members.0[&user(1)].1, Timestamped::new(None, 20));
+    }
+
+    #[test]
+    fn exempt_members_and_non_members_are_skipped() {
+        let mut members = TestMembers(HashMap::from([(user(1), (false, Timestamped::default()))]));
+        let reverification = GateReverification {
+            interval: DAY_IN_MS,
+            grace_period: None,
+        };
+
+        let outcome = apply_gate_reverification_results(&mut members, reverification, vec![], vec![user(1), user(2)], 10);
+
+        assert_eq!(outcome, GateReverificationOutcome::default());
+    }
+
+    #[test]
+    fn next_members_to_reverify_resumes_after_cursor() {
+        let user_ids = [user(4), user(1), user(3), user(2), user(5)];
+
+        assert_eq!(
+            next_members_to_reverify(user_ids.into_iter(), None, 2),
+            vec![user(1), user(2)]
+        );
+        assert_eq!(
+            next_members_to_reverify(user_ids.into_iter(), Some(user(2)), 2),
+            vec![user(3), user(4)]
+        );
+        assert_eq!(
+            next_members_to_reverify(user_ids.into_iter(), Some(user(4)), 2),
+            vec![user(5)]
+        );
+    }
+
+    fn user(index: u8) -> UserId {
+        Principal::from_slice(&[index]).into()
+    }
+}
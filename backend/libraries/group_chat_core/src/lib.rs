@@ -1,6 +1,7 @@
 use chat_events::{
     AddRemoveReactionArgs, ChatEventInternal, ChatEvents, ChatEventsListReader, DeleteMessageResult,
-    DeleteUndeleteMessagesArgs, MessageContentInternal, PushMessageArgs, Reader, TipMessageArgs, UndeleteMessageResult,
+    DeleteUndeleteMessagesArgs, EndPollResult, MessageContentInternal, PushMessageArgs, Reader, TipMessageArgs,
+    UndeleteMessageResult,
 };
 use event_store_producer::{EventStoreClient, Runtime};
 use lazy_static::lazy_static;
@@ -10,31 +11,52 @@ use serde::{Deserialize, Serialize};
 use std::cmp::{max, min};
 use std::collections::{BTreeSet, HashSet};
 use types::{
-    AccessGate, AvatarChanged, ContentValidationError, CustomPermission, Document, EventIndex, EventOrExpiredRange,
-    EventWrapper, EventsResponse, FieldTooLongResult, FieldTooShortResult, GroupDescriptionChanged, GroupGateUpdated,
-    GroupNameChanged, GroupPermissionRole, GroupPermissions, GroupReplyContext, GroupRole, GroupRulesChanged, GroupSubtype,
-    GroupVisibilityChanged, HydratedMention, InvalidPollReason, MemberLeft, MembersRemoved, Message, MessageContent,
-    MessageContentInitial, MessageId, MessageIndex, MessageMatch, MessagePermissions, MessagePinned, MessageUnpinned,
-    MessagesResponse, Milliseconds, MultiUserChat, OptionUpdate, OptionalGroupPermissions, OptionalMessagePermissions,
-    PermissionsChanged, PushEventResult, PushIfNotContains, Reaction, RoleChanged, Rules, SelectedGroupUpdates, ThreadPreview,
-    TimestampMillis, Timestamped, UpdatedRules, UserId, UsersBlocked, UsersInvited, Version, Versioned, VersionedRules,
-    VideoCall,
+    AccessGate, AvatarChanged, BotEvent, BotMembersJoinedEvent, BotMembersLeftEvent, BotMessageEvent, BotReactionEvent,
+    ContentValidationError, CustomPermission, CustomRoleAssigned, CustomRoleUnassigned, Document, EventIndex,
+    EventOrExpiredRange, EventWrapper, EventsResponse, FieldTooLongResult, FieldTooShortResult, GateReverification,
+    GroupDescriptionChanged, GroupGateUpdated, GroupNameChanged, GroupPermission, GroupPermissionRole, GroupPermissions,
+    GroupReplyContext, GroupRole, GroupRulesChanged, GroupSubtype, GroupVisibilityChanged, HydratedMention,
+    InstalledBotDetails, InvalidPollReason, MemberLeft, MemberMuted, MembersAdded, MembersLapsed, MembersRemoved,
+    MembersRemovedByGate, Message, MessageContent, MessageContentInitial, MessageId, MessageIndex, MessageMatch,
+    MessagePermission, MessagePermissions, MessagePinned, MessageUnpinned, MessageVersion, MessagesResponse, Milliseconds,
+    MultiUserChat, OptionUpdate, OptionalGroupPermissions, OptionalMessagePermissions, PermissionsChanged, PushEventResult,
+    PushIfNotContains, QuizLeaderboardEntry, Reaction, RoleChanged, Rules, SelectedGroupUpdates, SlashCommand,
+    SlashCommandSchema, SlowMode, ThreadPreview, TimestampMillis, Timestamped, UpdatedRules, UserId, UsersBlocked,
+    UsersInvited, Version, Versioned, VersionedRules, VideoCall,
 };
 use utils::document_validation::validate_avatar;
 use utils::text_validation::{
     validate_description, validate_group_name, validate_rules, NameValidationError, RulesValidationError,
 };
 
+mod bots;
+mod custom_roles;
+mod gate_reverification;
 mod invited_users;
 mod members;
 mod mentions;
+mod quiz_leaderboard;
+mod read_receipts;
 mod roles;
+mod slow_mode;
 
+pub use bots::*;
+pub use custom_roles::*;
+pub use gate_reverification::*;
 pub use invited_users::*;
 pub use members::*;
 pub use mentions::*;
+use quiz_leaderboard::QuizLeaderboard;
+use read_receipts::ReadReceipts;
+pub use read_receipts::SeenBy;
 pub use roles::*;
+use slow_mode::RecentMessages;
 use utils::consts::OPENCHAT_BOT_USER_ID;
+use utils::time::DAY_IN_MS;
+
+const MAX_MUTE_DURATION: Milliseconds = 365 * DAY_IN_MS;
+const MAX_MUTE_REASON_LENGTH: usize = 500;
+const MAX_QUIZ_LEADERBOARD_RESULTS: u32 = 100;
 
 #[derive(Serialize, Deserialize)]
 pub struct GroupChatCore {
@@ -54,8 +76,24 @@ pub struct GroupChatCore {
     pub permissions: Timestamped<GroupPermissions>,
     pub date_last_pinned: Option<TimestampMillis>,
     pub gate: Timestamped<Option<AccessGate>>,
+    #[serde(default)]
+    pub gate_reverification: Timestamped<Option<GateReverification>>,
     pub invited_users: InvitedUsers,
     pub min_visible_indexes_for_new_members: Option<(EventIndex, MessageIndex)>,
+    #[serde(default)]
+    pub bots: InstalledBots,
+    #[serde(default)]
+    pub slow_mode: Timestamped<Option<SlowMode>>,
+    #[serde(default)]
+    recent_messages: RecentMessages,
+    #[serde(default)]
+    pub custom_roles: CustomRoles,
+    #[serde(default)]
+    pub read_receipts_enabled: Timestamped<bool>,
+    #[serde(default)]
+    read_receipts: ReadReceipts,
+    #[serde(default)]
+    quiz_leaderboard: QuizLeaderboard,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -105,8 +143,16 @@ impl GroupChatCore {
             permissions: Timestamped::new(permissions, now),
             date_last_pinned: None,
             gate: Timestamped::new(gate, now),
+            gate_reverification: Timestamped::default(),
+            slow_mode: Timestamped::default(),
+            recent_messages: RecentMessages::default(),
+            custom_roles: CustomRoles::default(),
+            read_receipts_enabled: Timestamped::default(),
+            read_receipts: ReadReceipts::default(),
+            quiz_leaderboard: QuizLeaderboard::default(),
             invited_users: InvitedUsers::default(),
             min_visible_indexes_for_new_members: None,
+            bots: InstalledBots::default(),
         }
     }
 
@@ -131,10 +177,17 @@ impl GroupChatCore {
     }
 
     pub fn details_last_updated(&self) -> TimestampMillis {
-        max(
+        [
             self.events.last_updated().unwrap_or_default(),
             self.invited_users.last_updated(),
-        )
+            self.slow_mode.timestamp,
+            self.custom_roles.last_updated(),
+            self.read_receipts_enabled.timestamp,
+            self.gate_reverification.timestamp,
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
     }
 
     pub fn last_updated(&self, user_id: Option<UserId>) -> TimestampMillis {
@@ -224,6 +277,17 @@ impl GroupChatCore {
                 .if_set_after(since)
                 .cloned()
                 .map_or(OptionUpdate::NoChange, OptionUpdate::from_update),
+            slow_mode: self
+                .slow_mode
+                .if_set_after(since)
+                .copied()
+                .map_or(OptionUpdate::NoChange, OptionUpdate::from_update),
+            read_receipts_enabled: self.read_receipts_enabled.if_set_after(since).copied(),
+            gate_reverification: self
+                .gate_reverification
+                .if_set_after(since)
+                .copied()
+                .map_or(OptionUpdate::NoChange, OptionUpdate::from_update),
             rules_changed: self.rules.version_last_updated > since,
             video_call_in_progress: self
                 .events
@@ -270,6 +334,11 @@ impl GroupChatCore {
                 .map(|(_, m)| *m)
                 .collect(),
             chat_rules: self.rules.if_set_after(since).map(|r| r.clone().into()),
+            custom_roles: if self.custom_roles.last_updated() > since {
+                Some(self.custom_roles.iter().cloned().collect())
+            } else {
+                None
+            },
             ..Default::default()
         };
 
@@ -277,7 +346,7 @@ impl GroupChatCore {
         let mut users_blocked_or_unblocked = HashSet::new();
         for (user_id, update) in self.members.iter_latest_updates(since) {
             match update {
-                MemberUpdate::Added | MemberUpdate::RoleChanged => {
+                MemberUpdate::Added | MemberUpdate::RoleChanged | MemberUpdate::Muted | MemberUpdate::CustomRolesChanged => {
                     if users_added_updated_or_removed.insert(user_id) {
                         if let Some(member) = self.members.get(&user_id) {
                             result.members_added_or_updated.push(member.into());
@@ -433,6 +502,41 @@ impl GroupChatCore {
         }
     }
 
+    // Previous versions of a message are only visible to its sender and to members who can delete messages, since
+    // they may contain content which the sender chose to remove
+    pub fn message_edit_history(
+        &self,
+        user_id: UserId,
+        thread_root_message_index: Option<MessageIndex>,
+        message_id: MessageId,
+    ) -> MessageEditHistoryResult {
+        use MessageEditHistoryResult::*;
+
+        let Some(member) = self.members.get(&user_id) else {
+            return UserNotInGroup;
+        };
+
+        let Some(events_reader) = self
+            .events
+            .events_reader(member.min_visible_event_index(), thread_root_message_index)
+        else {
+            return ThreadNotFound;
+        };
+
+        let Some(message) = events_reader.message_internal(message_id.into()) else {
+            return MessageNotFound;
+        };
+
+        if message.sender != user_id && !member.can_delete_messages(&self.permissions) {
+            return NotAuthorized;
+        }
+
+        match events_reader.message_edit_history(message_id.into(), Some(user_id)) {
+            Some(edit_history) => Success(edit_history),
+            None => MessageNotFound,
+        }
+    }
+
     pub fn deleted_message(
         &self,
         user_id: UserId,
@@ -450,7 +554,7 @@ impl GroupChatCore {
                         if matches!(message.content, MessageContentInternal::Deleted(_)) {
                             MessageHardDeleted
                         } else if user_id == message.sender
-                            || (deleted_by.deleted_by != message.sender && member.role.can_delete_messages(&self.permissions))
+                            || (deleted_by.deleted_by != message.sender && member.can_delete_messages(&self.permissions))
                         {
                             Success(Box::new(message.content.hydrate(Some(user_id))))
                         } else {
@@ -497,20 +601,35 @@ impl GroupChatCore {
 
         const MIN_TERM_LENGTH: u8 = 3;
         const MAX_TERM_LENGTH: u8 = 30;
+        const MAX_QUERY_LENGTH: u8 = 200;
         const MAX_USERS: u8 = 5;
 
-        let term_length = search_term.len() as u8;
-        let users = users.unwrap_or_default();
+        if search_term.len() > MAX_QUERY_LENGTH as usize {
+            return TermTooLong(MAX_QUERY_LENGTH);
+        }
+
+        let Ok(mut query) = Query::try_parse(&search_term) else {
+            return InvalidTerm;
+        };
+
+        // Group canisters don't know usernames so these must be resolved by the client
+        if !query.usernames.is_empty() {
+            return InvalidTerm;
+        }
 
-        if users.is_empty() && term_length < MIN_TERM_LENGTH {
+        query.users.extend(users.unwrap_or_default());
+
+        let term_length = query.free_text_length();
+
+        if !query.has_filters() && term_length < MIN_TERM_LENGTH as usize {
             return TermTooShort(MIN_TERM_LENGTH);
         }
 
-        if term_length > MAX_TERM_LENGTH {
+        if term_length > MAX_TERM_LENGTH as usize {
             return TermTooLong(MAX_TERM_LENGTH);
         }
 
-        if users.len() as u8 > MAX_USERS {
+        if query.users.len() as u8 > MAX_USERS {
             return TooManyUsers(MAX_USERS);
         }
 
@@ -519,9 +638,6 @@ impl GroupChatCore {
             Some(p) => p,
         };
 
-        let mut query = Query::parse(search_term);
-        query.users = HashSet::from_iter(users);
-
         let matches = self
             .events
             .search_messages(now, member.min_visible_event_index(), &query, max_results, user_id);
@@ -533,6 +649,7 @@ impl GroupChatCore {
         &mut self,
         sender: UserId,
         sender_is_bot: bool,
+        exempt_from_slow_mode: bool,
         thread_root_message_index: Option<MessageIndex>,
         message_id: MessageId,
         content: MessageContentInitial,
@@ -560,6 +677,9 @@ impl GroupChatCore {
                     InvalidRequest("Cannot forward this type of message".to_string())
                 }
                 ContentValidationError::PrizeEndDateInThePast => InvalidRequest("Prize ended in the past".to_string()),
+                ContentValidationError::InvalidPrizeGate => {
+                    InvalidRequest("Prize gates cannot require payments or verified credentials".to_string())
+                }
                 ContentValidationError::Unauthorized => {
                     InvalidRequest("User unauthorized to send messages of this type".to_string())
                 }
@@ -568,6 +688,7 @@ impl GroupChatCore {
 
         self.send_message(
             sender,
+            exempt_from_slow_mode,
             thread_root_message_index,
             message_id,
             content.into(),
@@ -583,9 +704,11 @@ impl GroupChatCore {
         )
     }
 
+    // Slow mode is checked here, rather than by each caller, so that no route for sending messages can skip it
     pub fn send_message<R: Runtime + Send + 'static>(
         &mut self,
         sender: UserId,
+        exempt_from_slow_mode: bool,
         thread_root_message_index: Option<MessageIndex>,
         message_id: MessageId,
         content: MessageContentInternal,
@@ -601,6 +724,12 @@ impl GroupChatCore {
     ) -> SendMessageResult {
         use SendMessageResult::*;
 
+        if !exempt_from_slow_mode {
+            if let Err(retry_at) = self.check_slow_mode(sender, now) {
+                return RateLimited(retry_at);
+            }
+        }
+
         let PrepareSendMessageSuccess {
             min_visible_event_index,
             mentions_disabled,
@@ -651,6 +780,21 @@ impl GroupChatCore {
         let message_event = self.events.push_message(push_message_args, Some(event_store_client));
         let message_index = message_event.event.message_index;
 
+        if let Some(slow_mode) = &self.slow_mode.value {
+            if !sender_is_bot {
+                self.recent_messages.record(sender, slow_mode, now);
+            }
+        }
+
+        self.bots.push_event(
+            BotEvent::Message(BotMessageEvent {
+                thread_root_message_index,
+                event_index: message_event.index,
+                message: message_event.event.clone(),
+            }),
+            now,
+        );
+
         let mut mentions: HashSet<_> = mentioned.into_iter().chain(user_being_replied_to).collect();
 
         let mut users_to_notify = HashSet::new();
@@ -705,6 +849,21 @@ impl GroupChatCore {
         })
     }
 
+    // Returns the time at which the user can next send a message if they are currently rate limited by slow mode
+    fn check_slow_mode(&self, user_id: UserId, now: TimestampMillis) -> Result<(), TimestampMillis> {
+        let Some(slow_mode) = &self.slow_mode.value else {
+            return Ok(());
+        };
+
+        if let Some(member) = self.members.get(&user_id) {
+            if member.is_bot || (slow_mode.exempt_moderators && member.role.is_same_or_senior(GroupRoleInternal::Moderator)) {
+                return Ok(());
+            }
+        }
+
+        self.recent_messages.check(user_id, slow_mode, now)
+    }
+
     fn prepare_send_message(
         &mut self,
         sender: UserId,
@@ -745,17 +904,14 @@ impl GroupChatCore {
 
         let permissions = &self.permissions;
 
-        if !member
-            .role
-            .can_send_message(content, thread_root_message_index.is_some(), permissions)
-        {
+        if member.is_muted(now) || !member.can_send_message(content, thread_root_message_index.is_some(), permissions) {
             return NotAuthorized;
         }
 
         Success(PrepareSendMessageSuccess {
             min_visible_event_index: member.min_visible_event_index(),
             mentions_disabled: false,
-            everyone_mentioned: member.role.can_mention_everyone(permissions) && is_everyone_mentioned(content),
+            everyone_mentioned: member.can_mention_everyone(permissions) && is_everyone_mentioned(content),
             sender_is_bot: member.is_bot,
         })
     }
@@ -775,25 +931,40 @@ impl GroupChatCore {
             if member.suspended.value {
                 return UserSuspended;
             }
-            if !member.role.can_react_to_messages(&self.permissions) {
+            if member.is_muted(now) || !member.can_react_to_messages(&self.permissions) {
                 return NotAuthorized;
             }
 
             let min_visible_event_index = member.min_visible_event_index();
 
-            self.events
+            let result: AddRemoveReactionResult = self
+                .events
                 .add_reaction(
                     AddRemoveReactionArgs {
                         user_id,
                         min_visible_event_index,
                         thread_root_message_index,
                         message_id,
-                        reaction,
+                        reaction: reaction.clone(),
                         now,
                     },
                     Some(event_store_client),
                 )
-                .into()
+                .into();
+
+            if matches!(result, Success) {
+                self.bots.push_event(
+                    BotEvent::ReactionAdded(BotReactionEvent {
+                        thread_root_message_index,
+                        message_id,
+                        reaction,
+                        user_id,
+                    }),
+                    now,
+                );
+            }
+
+            result
         } else {
             UserNotInGroup
         }
@@ -813,22 +984,37 @@ impl GroupChatCore {
             if member.suspended.value {
                 return UserSuspended;
             }
-            if !member.role.can_react_to_messages(&self.permissions) {
+            if !member.can_react_to_messages(&self.permissions) {
                 return NotAuthorized;
             }
 
             let min_visible_event_index = member.min_visible_event_index();
 
-            self.events
+            let result: AddRemoveReactionResult = self
+                .events
                 .remove_reaction(AddRemoveReactionArgs {
                     user_id,
                     min_visible_event_index,
                     thread_root_message_index,
                     message_id,
-                    reaction,
+                    reaction: reaction.clone(),
                     now,
                 })
-                .into()
+                .into();
+
+            if matches!(result, Success) {
+                self.bots.push_event(
+                    BotEvent::ReactionRemoved(BotReactionEvent {
+                        thread_root_message_index,
+                        message_id,
+                        reaction,
+                        user_id,
+                    }),
+                    now,
+                );
+            }
+
+            result
         } else {
             UserNotInGroup
         }
@@ -845,7 +1031,7 @@ impl GroupChatCore {
             if member.suspended.value {
                 return UserSuspended;
             }
-            if !member.role.can_react_to_messages(&self.permissions) {
+            if !member.can_react_to_messages(&self.permissions) {
                 return NotAuthorized;
             }
 
@@ -874,7 +1060,7 @@ impl GroupChatCore {
                 return UserSuspended;
             }
             (
-                member.role.can_delete_messages(&self.permissions),
+                member.can_delete_messages(&self.permissions),
                 member.min_visible_event_index(),
             )
         } else if as_platform_moderator {
@@ -943,7 +1129,7 @@ impl GroupChatCore {
 
             let results = self.events.undelete_messages(DeleteUndeleteMessagesArgs {
                 caller: user_id,
-                is_admin: member.role.can_delete_messages(&self.permissions),
+                is_admin: member.can_delete_messages(&self.permissions),
                 min_visible_event_index,
                 thread_root_message_index,
                 message_ids,
@@ -999,6 +1185,8 @@ impl GroupChatCore {
                 changed_by: caller,
             };
 
+            self.bots.push_event(BotEvent::RoleChanged(event.clone()), now);
+
             self.events
                 .push_main_event(ChatEventInternal::RoleChanged(Box::new(event)), 0, now);
         };
@@ -1013,7 +1201,7 @@ impl GroupChatCore {
             if member.suspended.value {
                 return UserSuspended;
             }
-            if !member.role.can_pin_messages(&self.permissions) {
+            if !member.can_pin_messages(&self.permissions) {
                 return NotAuthorized;
             }
 
@@ -1056,7 +1244,7 @@ impl GroupChatCore {
             if member.suspended.value {
                 return UserSuspended;
             }
-            if !member.role.can_pin_messages(&self.permissions) {
+            if !member.can_pin_messages(&self.permissions) {
                 return NotAuthorized;
             }
 
@@ -1132,7 +1320,7 @@ impl GroupChatCore {
             }
 
             // The original caller must be authorized to invite other users
-            if !self.is_public.value && !member.role.can_invite_users(&self.permissions) {
+            if !self.is_public.value && !member.can_invite_users(&self.permissions) {
                 return NotAuthorized;
             }
 
@@ -1206,7 +1394,7 @@ impl GroupChatCore {
                 return UserSuspended;
             }
 
-            if !member.role.can_invite_users(&self.permissions) {
+            if !member.can_invite_users(&self.permissions) {
                 return NotAuthorized;
             }
 
@@ -1246,6 +1434,14 @@ impl GroupChatCore {
         match self.can_leave(user_id) {
             CanLeaveResult::Yes => {
                 let removed = self.members.remove(user_id, now).unwrap();
+                self.bots.remove(&user_id);
+                self.bots.push_event(
+                    BotEvent::MembersLeft(BotMembersLeftEvent {
+                        user_ids: vec![user_id],
+                        removed_by: None,
+                    }),
+                    now,
+                );
 
                 self.events
                     .push_main_event(ChatEventInternal::ParticipantLeft(Box::new(MemberLeft { user_id })), 0, now);
@@ -1282,12 +1478,18 @@ impl GroupChatCore {
                 _ => return TargetUserNotInGroup,
             };
 
-            if member
-                .role
-                .can_remove_members_with_role(target_member_role, &self.permissions)
-            {
+            if member.can_remove_members_with_role(target_member_role, &self.permissions) {
                 // Remove the user from the group
-                self.members.remove(target_user_id, now);
+                if self.members.remove(target_user_id, now).is_some() {
+                    self.bots.remove(&target_user_id);
+                    self.bots.push_event(
+                        BotEvent::MembersLeft(BotMembersLeftEvent {
+                            user_ids: vec![target_user_id],
+                            removed_by: Some(user_id),
+                        }),
+                        now,
+                    );
+                }
 
                 if block && !self.members.block(target_user_id, now) {
                     // Return Success if the user was already blocked
@@ -1320,6 +1522,446 @@ impl GroupChatCore {
         }
     }
 
+    pub fn mute_member(
+        &mut self,
+        user_id: UserId,
+        target_user_id: UserId,
+        duration: Milliseconds,
+        reason: Option<String>,
+        now: TimestampMillis,
+    ) -> MuteMemberResult {
+        use MuteMemberResult::*;
+
+        if user_id == target_user_id {
+            return CannotMuteSelf;
+        }
+
+        if duration == 0 || duration > MAX_MUTE_DURATION {
+            return DurationInvalid;
+        }
+
+        if let Some(reason) = &reason {
+            if reason.len() > MAX_MUTE_REASON_LENGTH {
+                return ReasonTooLong(MAX_MUTE_REASON_LENGTH as u32);
+            }
+        }
+
+        let Some(member) = self.members.get(&user_id) else {
+            return UserNotInGroup;
+        };
+        if member.suspended.value {
+            return UserSuspended;
+        }
+
+        let Some(target_member) = self.members.get(&target_user_id) else {
+            return TargetUserNotInGroup;
+        };
+
+        if !member.can_remove_members_with_role(target_member.role.value, &self.permissions) {
+            return NotAuthorized;
+        }
+
+        let muted_until = now + duration;
+        self.members.set_muted_until(target_user_id, Some(muted_until), now);
+
+        self.events.push_main_event(
+            ChatEventInternal::MemberMuted(Box::new(MemberMuted {
+                user_id: target_user_id,
+                muted_by: user_id,
+                muted_until,
+                reason,
+            })),
+            0,
+            now,
+        );
+
+        Success(muted_until)
+    }
+
+    // Called by the timer job which lifts each mute once it has expired
+    pub fn unmute_member_if_expired(&mut self, user_id: UserId, now: TimestampMillis) -> bool {
+        let expired = self
+            .members
+            .get(&user_id)
+            .and_then(|m| m.muted_until.value)
+            .map_or(false, |ts| ts <= now);
+
+        expired && self.members.set_muted_until(user_id, None, now)
+    }
+
+    pub fn create_custom_role(
+        &mut self,
+        user_id: UserId,
+        name: String,
+        permissions: Vec<GroupPermission>,
+        message_permissions: Vec<MessagePermission>,
+        now: TimestampMillis,
+    ) -> CustomRoleResult {
+        if let Err(error) = self.can_define_custom_role(user_id, None, Some(&name), Some(&permissions)) {
+            return error;
+        }
+
+        if self.custom_roles.len() as u32 >= MAX_CUSTOM_ROLES {
+            return CustomRoleResult::TooManyRoles(MAX_CUSTOM_ROLES);
+        }
+
+        let role_id = self.custom_roles.create(name, permissions, message_permissions, now);
+        CustomRoleResult::Success(role_id)
+    }
+
+    pub fn update_custom_role(
+        &mut self,
+        user_id: UserId,
+        role_id: u32,
+        name: Option<String>,
+        permissions: Option<Vec<GroupPermission>>,
+        message_permissions: Option<Vec<MessagePermission>>,
+        now: TimestampMillis,
+    ) -> CustomRoleResult {
+        if let Err(error) = self.can_define_custom_role(user_id, Some(role_id), name.as_deref(), permissions.as_deref()) {
+            return error;
+        }
+
+        if !self.custom_roles.update(role_id, name, permissions, message_permissions, now) {
+            return CustomRoleResult::RoleNotFound;
+        }
+
+        self.members.refresh_custom_role(role_id, &self.custom_roles);
+        CustomRoleResult::Success(role_id)
+    }
+
+    pub fn delete_custom_role(&mut self, user_id: UserId, role_id: u32, now: TimestampMillis) -> CustomRoleResult {
+        if let Err(error) = self.can_define_custom_role(user_id, Some(role_id), None, None) {
+            return error;
+        }
+
+        if !self.custom_roles.delete(role_id, now) {
+            return CustomRoleResult::RoleNotFound;
+        }
+
+        let holders: Vec<_> = self
+            .members
+            .iter()
+            .filter(|m| m.custom_roles.holds(role_id))
+            .map(|m| m.user_id)
+            .collect();
+
+        for holder in holders.iter() {
+            self.members.remove_custom_role(*holder, role_id, &self.custom_roles, now);
+        }
+
+        self.push_custom_role_unassigned_event(role_id, holders, user_id, now);
+
+        CustomRoleResult::Success(role_id)
+    }
+
+    pub fn assign_custom_role(
+        &mut self,
+        user_id: UserId,
+        role_id: u32,
+        users_to_add: Vec<UserId>,
+        users_to_remove: Vec<UserId>,
+        now: TimestampMillis,
+    ) -> CustomRoleResult {
+        use CustomRoleResult::*;
+
+        let Some(member) = self.members.get(&user_id) else {
+            return UserNotInGroup;
+        };
+        if member.suspended.value {
+            return UserSuspended;
+        }
+        // Only the member's role is considered here (rather than any custom roles they hold) so that custom roles
+        // can't be used to escalate privileges
+        if !member.role.can_change_roles(GroupRoleInternal::Member, &self.permissions) {
+            return NotAuthorized;
+        }
+        if !self.custom_roles.exists(role_id) {
+            return RoleNotFound;
+        }
+
+        let added: Vec<_> = users_to_add
+            .into_iter()
+            .filter(|u| self.members.add_custom_role(*u, role_id, &self.custom_roles, now))
+            .collect();
+        let removed: Vec<_> = users_to_remove
+            .into_iter()
+            .filter(|u| self.members.remove_custom_role(*u, role_id, &self.custom_roles, now))
+            .collect();
+
+        if !added.is_empty() {
+            self.events.push_main_event(
+                ChatEventInternal::CustomRoleAssigned(Box::new(CustomRoleAssigned {
+                    role_id,
+                    user_ids: added,
+                    assigned_by: user_id,
+                })),
+                0,
+                now,
+            );
+        }
+        self.push_custom_role_unassigned_event(role_id, removed, user_id, now);
+
+        Success(role_id)
+    }
+
+    fn push_custom_role_unassigned_event(
+        &mut self,
+        role_id: u32,
+        user_ids: Vec<UserId>,
+        unassigned_by: UserId,
+        now: TimestampMillis,
+    ) {
+        if !user_ids.is_empty() {
+            self.events.push_main_event(
+                ChatEventInternal::CustomRoleUnassigned(Box::new(CustomRoleUnassigned {
+                    role_id,
+                    user_ids,
+                    unassigned_by,
+                })),
+                0,
+                now,
+            );
+        }
+    }
+
+    fn can_define_custom_role(
+        &self,
+        user_id: UserId,
+        role_id: Option<u32>,
+        name: Option<&str>,
+        permissions: Option<&[GroupPermission]>,
+    ) -> Result<(), CustomRoleResult> {
+        use CustomRoleResult::*;
+
+        let Some(member) = self.members.get(&user_id) else {
+            return Err(UserNotInGroup);
+        };
+        if member.suspended.value {
+            return Err(UserSuspended);
+        }
+        if !member.role.can_change_permissions() {
+            return Err(NotAuthorized);
+        }
+        if let Some(role_id) = role_id {
+            if !self.custom_roles.exists(role_id) {
+                return Err(RoleNotFound);
+            }
+        }
+        if let Some(name) = name {
+            let length = name.chars().count() as u32;
+            if length < MIN_CUSTOM_ROLE_NAME_LENGTH {
+                return Err(NameTooShort(FieldTooShortResult {
+                    length_provided: length,
+                    min_length: MIN_CUSTOM_ROLE_NAME_LENGTH,
+                }));
+            }
+            if length > MAX_CUSTOM_ROLE_NAME_LENGTH {
+                return Err(NameTooLong(FieldTooLongResult {
+                    length_provided: length,
+                    max_length: MAX_CUSTOM_ROLE_NAME_LENGTH,
+                }));
+            }
+            if self.custom_roles.is_name_taken(name, role_id) {
+                return Err(NameTaken);
+            }
+        }
+        // Granting `ChangeRoles` would allow holders to assign roles, including to themselves
+        if permissions.map_or(false, |ps| ps.contains(&GroupPermission::ChangeRoles)) {
+            return Err(PermissionNotGrantable);
+        }
+        Ok(())
+    }
+
+    pub fn can_add_bot(&self, user_id: UserId) -> CanAddBotResult {
+        use CanAddBotResult::*;
+
+        if let Some(member) = self.members.get(&user_id) {
+            if member.suspended.value {
+                UserSuspended
+            } else if member.can_add_members(&self.permissions) {
+                Yes
+            } else {
+                NotAuthorized
+            }
+        } else {
+            UserNotInGroup
+        }
+    }
+
+    pub fn add_bot(
+        &mut self,
+        added_by: UserId,
+        bot_id: UserId,
+        bot_name: String,
+        commands: Vec<SlashCommandSchema>,
+        now: TimestampMillis,
+    ) -> AddBotResult {
+        use AddBotResult::*;
+
+        match self.can_add_bot(added_by) {
+            CanAddBotResult::Yes => {}
+            CanAddBotResult::UserSuspended => return UserSuspended,
+            CanAddBotResult::UserNotInGroup => return UserNotInGroup,
+            CanAddBotResult::NotAuthorized => return NotAuthorized,
+        }
+
+        match self.members.get(&bot_id) {
+            Some(member) if !member.is_bot => return NotABot,
+            Some(_) => {}
+            None => {
+                // Bots can only see the events which occur after they are added
+                let events_reader = self.events.main_events_reader();
+                let min_visible_event_index = events_reader.next_event_index();
+                let min_visible_message_index = events_reader.next_message_index();
+
+                match self
+                    .members
+                    .add(bot_id, now, min_visible_event_index, min_visible_message_index, true, true)
+                {
+                    AddResult::Success(_) => {}
+                    AddResult::AlreadyInGroup => unreachable!(),
+                    AddResult::Blocked => return Blocked,
+                    AddResult::MemberLimitReached(limit) => return MemberLimitReached(limit),
+                }
+
+                self.events.push_main_event(
+                    ChatEventInternal::ParticipantsAdded(Box::new(MembersAdded {
+                        user_ids: vec![bot_id],
+                        added_by,
+                        unblocked: Vec::new(),
+                    })),
+                    0,
+                    now,
+                );
+
+                self.bots.push_event(
+                    BotEvent::MembersJoined(BotMembersJoinedEvent {
+                        user_ids: vec![bot_id],
+                        added_by: Some(added_by),
+                    }),
+                    now,
+                );
+            }
+        }
+
+        // Retain the bot's existing subscriptions if it is being reinstalled to pick up new commands
+        let subscribed_events = self
+            .bots
+            .get(&bot_id)
+            .map(|b| b.subscribed_events.clone())
+            .unwrap_or_default();
+
+        self.bots.add(
+            bot_id,
+            InstalledBot {
+                name: bot_name,
+                added_by,
+                date_added: now,
+                commands,
+                subscribed_events,
+            },
+        );
+
+        Success
+    }
+
+    pub fn remove_bot(&mut self, removed_by: UserId, bot_id: UserId, now: TimestampMillis) -> RemoveBotResult {
+        use RemoveBotResult::*;
+
+        let Some(member) = self.members.get(&removed_by) else {
+            return UserNotInGroup;
+        };
+        if member.suspended.value {
+            return UserSuspended;
+        }
+        if !member.can_remove_members(&self.permissions) {
+            return NotAuthorized;
+        }
+        if self.bots.remove(&bot_id).is_none() {
+            return BotNotFound;
+        }
+
+        if self.members.remove(bot_id, now).is_some() {
+            self.events.push_main_event(
+                ChatEventInternal::ParticipantsRemoved(Box::new(MembersRemoved {
+                    user_ids: vec![bot_id],
+                    removed_by,
+                })),
+                0,
+                now,
+            );
+
+            self.bots.push_event(
+                BotEvent::MembersLeft(BotMembersLeftEvent {
+                    user_ids: vec![bot_id],
+                    removed_by: Some(removed_by),
+                }),
+                now,
+            );
+        }
+
+        Success
+    }
+
+    // Checks that the user can issue the command to the bot, and that both the user and the bot hold the permissions
+    // which the command requires
+    pub fn prepare_bot_command(
+        &self,
+        user_id: UserId,
+        bot_id: UserId,
+        thread_root_message_index: Option<MessageIndex>,
+        command: &SlashCommand,
+    ) -> PrepareBotCommandResult {
+        use PrepareBotCommandResult::*;
+
+        let Some(member) = self.members.get(&user_id) else {
+            return UserNotInGroup;
+        };
+        if member.suspended.value {
+            return UserSuspended;
+        }
+        if let Some(root_message_index) = thread_root_message_index {
+            if !self
+                .events
+                .is_accessible(member.min_visible_event_index(), None, root_message_index.into())
+            {
+                return ThreadMessageNotFound;
+            }
+        }
+
+        let (Some(bot), Some(bot_member)) = (self.bots.get(&bot_id), self.members.get(&bot_id)) else {
+            return BotNotFound;
+        };
+        if bot_member.suspended.value {
+            return BotNotFound;
+        }
+
+        let Some(schema) = bot.command(&command.name) else {
+            return CommandNotFound;
+        };
+
+        if let Err(error) = schema.validate_command(command) {
+            return InvalidCommand(error);
+        }
+
+        let permissions = &self.permissions;
+        for permission in schema.permissions.iter() {
+            if !member.has_permission(*permission, permissions) {
+                return NotAuthorized;
+            }
+            if !bot_member.has_permission(*permission, permissions) {
+                return BotNotAuthorized;
+            }
+        }
+
+        Success
+    }
+
+    pub fn installed_bots(&self) -> Vec<InstalledBotDetails> {
+        self.bots.details()
+    }
+
     pub fn update(
         &mut self,
         user_id: UserId,
@@ -1399,7 +2041,7 @@ impl GroupChatCore {
             }
 
             let group_permissions = &self.permissions;
-            if !member.role.can_update_group(group_permissions)
+            if !member.can_update_group(group_permissions)
                 || (permissions.is_some() && !member.role.can_change_permissions())
                 || (public.is_some() && !member.role.can_change_group_visibility())
             {
@@ -1753,7 +2395,204 @@ impl GroupChatCore {
     }
 
     pub fn has_payment_gate(&self) -> bool {
-        self.gate.value.as_ref().map(|g| g.is_payment_gate()).unwrap_or_default()
+        self.gate
+            .value
+            .as_ref()
+            .map(|g| g.contains_payment_gate())
+            .unwrap_or_default()
+    }
+
+    pub fn set_slow_mode(&mut self, update: OptionUpdate<SlowMode>, now: TimestampMillis) -> bool {
+        if let Some(slow_mode) = update.expand() {
+            if self.slow_mode.value != slow_mode {
+                self.slow_mode = Timestamped::new(slow_mode, now);
+                self.recent_messages.clear();
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn set_read_receipts_enabled(&mut self, enabled: Option<bool>, now: TimestampMillis) -> bool {
+        if let Some(enabled) = enabled {
+            if self.read_receipts_enabled.value != enabled {
+                self.read_receipts_enabled = Timestamped::new(enabled, now);
+                if !enabled {
+                    self.read_receipts = ReadReceipts::default();
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    // Replaces the permissions in full, for when they are kept in sync with defaults held outside of the chat
+    pub fn set_permissions(&mut self, permissions: GroupPermissions, changed_by: UserId, now: TimestampMillis) {
+        let old_permissions_v2 = self.permissions.value.clone();
+        self.permissions = Timestamped::new(permissions.clone(), now);
+
+        self.events.push_main_event(
+            ChatEventInternal::PermissionsChanged(Box::new(PermissionsChanged {
+                old_permissions_v2,
+                new_permissions_v2: permissions,
+                changed_by,
+            })),
+            0,
+            now,
+        );
+    }
+
+    // Called when a member's user canister reports that they have read further into the chat
+    pub fn mark_read(&mut self, user_id: UserId, previous: Option<MessageIndex>, read_up_to: MessageIndex) -> bool {
+        if !self.read_receipts_enabled.value || self.members.get(&user_id).is_none() {
+            return false;
+        }
+        let Some(latest_message_index) = self.events.main_events_reader().latest_message_index() else {
+            return false;
+        };
+
+        let events_reader = self.events.main_events_reader();
+        self.read_receipts.mark_read(
+            user_id,
+            previous,
+            read_up_to,
+            latest_message_index,
+            self.members.len(),
+            |message_index| events_reader.message_internal(message_index.into()).map(|m| m.sender),
+        );
+        true
+    }
+
+    pub fn message_seen_by(&self, user_id: UserId, message_index: MessageIndex) -> MessageSeenByResult {
+        use MessageSeenByResult::*;
+
+        let Some(member) = self.members.get(&user_id) else {
+            return UserNotInGroup;
+        };
+        if !self.read_receipts_enabled.value {
+            return ReadReceiptsDisabled;
+        }
+
+        let events_reader = self.events.visible_main_events_reader(member.min_visible_event_index());
+        let Some(sender) = events_reader.message_internal(message_index.into()).map(|m| m.sender) else {
+            return MessageNotFound;
+        };
+        let latest_message_index = events_reader.latest_message_index().unwrap_or_default();
+
+        match self
+            .read_receipts
+            .seen_by(message_index, sender, latest_message_index, |u| self.members.get(u).is_some())
+        {
+            Some(seen_by) => Success(seen_by),
+            None => MessageTooOld,
+        }
+    }
+
+    // Ends the poll and, if it is a quiz, adds the results to the quiz leaderboard
+    pub fn end_poll(
+        &mut self,
+        thread_root_message_index: Option<MessageIndex>,
+        message_index: MessageIndex,
+        now: TimestampMillis,
+    ) -> EndPollResult {
+        let result = self.events.end_poll(thread_root_message_index, message_index, now);
+        if let EndPollResult::Success(Some(quiz_results)) = &result {
+            self.quiz_leaderboard.record(quiz_results);
+        }
+        result
+    }
+
+    pub fn quiz_leaderboard(&self, user_id: UserId, max_results: u32) -> QuizLeaderboardResult {
+        if self.members.get(&user_id).is_none() {
+            return QuizLeaderboardResult::UserNotInGroup;
+        }
+
+        QuizLeaderboardResult::Success(
+            self.quiz_leaderboard
+                .top(max_results.min(MAX_QUIZ_LEADERBOARD_RESULTS) as usize, |u| {
+                    self.members.get(u).is_some()
+                }),
+            self.quiz_leaderboard.get(user_id),
+        )
+    }
+
+    pub fn set_gate_reverification(&mut self, update: OptionUpdate<GateReverification>, now: TimestampMillis) -> bool {
+        if let Some(gate_reverification) = update.expand() {
+            if self.gate_reverification.value != gate_reverification {
+                self.gate_reverification = Timestamped::new(gate_reverification, now);
+                return true;
+            }
+        }
+        false
+    }
+
+    // Returns the gate to be re-verified along with the next batch of members who must pass it, starting after the
+    // cursor, or `None` if the gate is not due to be re-verified
+    pub fn gate_to_reverify(&self, after: Option<UserId>, max: usize) -> Option<(AccessGate, Vec<UserId>)> {
+        self.gate_reverification.value?;
+
+        let gate = self.gate.value.clone().filter(|g| g.can_be_reverified())?;
+        let members = next_members_to_reverify(
+            self.members
+                .iter()
+                .filter(|m| m.requires_gate_reverification())
+                .map(|m| m.user_id),
+            after,
+            max,
+        );
+
+        Some((gate, members))
+    }
+
+    // Clears the lapsed state of members who passed the gate. Members who failed the gate are either marked as lapsed
+    // or, if there is no grace period or their grace period has passed, removed. Returns the members who were removed.
+    pub fn apply_gate_reverification_results(
+        &mut self,
+        passed: Vec<UserId>,
+        failed: Vec<UserId>,
+        now: TimestampMillis,
+    ) -> Vec<UserId> {
+        let Some(gate_reverification) = self.gate_reverification.value else {
+            return Vec::new();
+        };
+
+        let GateReverificationOutcome { lapsed, removed } =
+            apply_gate_reverification_results(&mut self.members, gate_reverification, passed, failed, now);
+
+        for user_id in removed.iter() {
+            self.members.remove(*user_id, now);
+        }
+
+        if !lapsed.is_empty() {
+            self.events.push_main_event(
+                ChatEventInternal::MembersLapsed(Box::new(MembersLapsed {
+                    user_ids: lapsed,
+                    grace_period_ends: now + gate_reverification.grace_period.unwrap_or_default(),
+                })),
+                0,
+                now,
+            );
+        }
+
+        if !removed.is_empty() {
+            self.events.push_main_event(
+                ChatEventInternal::MembersRemovedByGate(Box::new(MembersRemovedByGate {
+                    user_ids: removed.clone(),
+                })),
+                0,
+                now,
+            );
+
+            self.bots.push_event(
+                BotEvent::MembersLeft(BotMembersLeftEvent {
+                    user_ids: removed.clone(),
+                    removed_by: None,
+                }),
+                now,
+            );
+        }
+
+        removed
     }
 }
 
@@ -1781,6 +2620,7 @@ pub enum SendMessageResult {
     UserSuspended,
     RulesNotAccepted,
     InvalidRequest(String),
+    RateLimited(TimestampMillis),
 }
 
 pub struct SendMessageSuccess {
@@ -1866,6 +2706,43 @@ pub enum LeaveResult {
     UserNotInGroup,
 }
 
+pub enum CanAddBotResult {
+    Yes,
+    UserSuspended,
+    UserNotInGroup,
+    NotAuthorized,
+}
+
+pub enum AddBotResult {
+    Success,
+    UserSuspended,
+    UserNotInGroup,
+    NotAuthorized,
+    NotABot,
+    Blocked,
+    MemberLimitReached(u32),
+}
+
+pub enum RemoveBotResult {
+    Success,
+    UserSuspended,
+    UserNotInGroup,
+    NotAuthorized,
+    BotNotFound,
+}
+
+pub enum PrepareBotCommandResult {
+    Success,
+    UserSuspended,
+    UserNotInGroup,
+    ThreadMessageNotFound,
+    BotNotFound,
+    CommandNotFound,
+    InvalidCommand(String),
+    NotAuthorized,
+    BotNotAuthorized,
+}
+
 pub enum RemoveMemberResult {
     Success,
     UserSuspended,
@@ -1875,6 +2752,44 @@ pub enum RemoveMemberResult {
     CannotRemoveSelf,
 }
 
+pub enum MessageSeenByResult {
+    Success(SeenBy),
+    UserNotInGroup,
+    ReadReceiptsDisabled,
+    MessageNotFound,
+    MessageTooOld,
+}
+
+pub enum QuizLeaderboardResult {
+    // The top entries, followed by the caller's own entry
+    Success(Vec<QuizLeaderboardEntry>, Option<QuizLeaderboardEntry>),
+    UserNotInGroup,
+}
+
+pub enum MuteMemberResult {
+    Success(TimestampMillis),
+    UserSuspended,
+    UserNotInGroup,
+    TargetUserNotInGroup,
+    NotAuthorized,
+    CannotMuteSelf,
+    DurationInvalid,
+    ReasonTooLong(u32),
+}
+
+pub enum CustomRoleResult {
+    Success(u32),
+    UserNotInGroup,
+    UserSuspended,
+    NotAuthorized,
+    RoleNotFound,
+    NameTooShort(FieldTooShortResult),
+    NameTooLong(FieldTooLongResult),
+    NameTaken,
+    PermissionNotGrantable,
+    TooManyRoles(u32),
+}
+
 pub enum UpdateResult {
     Success(Box<UpdateSuccessResult>),
     UserSuspended,
@@ -1909,6 +2824,14 @@ pub enum MakePrivateResult {
     AlreadyPrivate,
 }
 
+pub enum MessageEditHistoryResult {
+    Success(Vec<MessageVersion>),
+    UserNotInGroup,
+    NotAuthorized,
+    ThreadNotFound,
+    MessageNotFound,
+}
+
 pub enum DeletedMessageResult {
     Success(Box<MessageContent>),
     UserNotInGroup,
@@ -1987,6 +2910,9 @@ pub struct SummaryUpdates {
     pub events_ttl: OptionUpdate<Milliseconds>,
     pub events_ttl_last_updated: Option<TimestampMillis>,
     pub gate: OptionUpdate<AccessGate>,
+    pub slow_mode: OptionUpdate<SlowMode>,
+    pub read_receipts_enabled: Option<bool>,
+    pub gate_reverification: OptionUpdate<GateReverification>,
     pub rules_changed: bool,
     pub video_call_in_progress: OptionUpdate<VideoCall>,
 }
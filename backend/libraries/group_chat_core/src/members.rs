@@ -1,7 +1,9 @@
+use crate::custom_roles::{CustomRoleGrants, CustomRoles};
+use crate::gate_reverification::GateReverificationMembers;
 use crate::mentions::Mentions;
 use crate::roles::GroupRoleInternal;
 use crate::AccessRulesInternal;
-use chat_events::ChatEvents;
+use chat_events::{ChatEvents, MessageContentInternal};
 use serde::de::{SeqAccess, Visitor};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -11,8 +13,8 @@ use std::collections::hash_map::Entry::Vacant;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Formatter;
 use types::{
-    is_default, is_empty_btreemap, is_empty_hashset, is_empty_slice, EventIndex, GroupMember, GroupPermissions,
-    HydratedMention, MessageIndex, TimestampMillis, Timestamped, UserId, Version, MAX_RETURNED_MENTIONS,
+    is_default, is_empty_btreemap, is_empty_hashset, is_empty_slice, EventIndex, GroupMember, GroupPermission,
+    GroupPermissions, HydratedMention, MessageIndex, TimestampMillis, Timestamped, UserId, Version, MAX_RETURNED_MENTIONS,
 };
 
 const MAX_MEMBERS_PER_GROUP: u32 = 100_000;
@@ -36,6 +38,8 @@ pub enum MemberUpdate {
     RoleChanged = 3,
     Blocked = 4,
     Unblocked = 5,
+    Muted = 6,
+    CustomRolesChanged = 7,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -55,6 +59,9 @@ impl GroupMembers {
             suspended: Timestamped::default(),
             rules_accepted: Some(Timestamped::new(Version::zero(), now)),
             is_bot,
+            lapsed: Timestamped::default(),
+            muted_until: Timestamped::default(),
+            custom_roles: CustomRoleGrants::default(),
         };
 
         GroupMembers {
@@ -97,6 +104,9 @@ impl GroupMembers {
                         suspended: Timestamped::default(),
                         rules_accepted: None,
                         is_bot,
+                        lapsed: Timestamped::default(),
+                        muted_until: Timestamped::default(),
+                        custom_roles: CustomRoleGrants::default(),
                     };
                     e.insert(member.clone());
                     self.updates.insert((now, user_id, MemberUpdate::Added));
@@ -258,6 +268,44 @@ impl GroupMembers {
         ChangeRoleResult::Success(ChangeRoleSuccess { prev_role })
     }
 
+    pub fn set_muted_until(&mut self, user_id: UserId, muted_until: Option<TimestampMillis>, now: TimestampMillis) -> bool {
+        if let Some(member) = self.members.get_mut(&user_id) {
+            if member.muted_until.value != muted_until {
+                member.muted_until = Timestamped::new(muted_until, now);
+                self.updates.insert((now, user_id, MemberUpdate::Muted));
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn add_custom_role(&mut self, user_id: UserId, role_id: u32, roles: &CustomRoles, now: TimestampMillis) -> bool {
+        if let Some(member) = self.members.get_mut(&user_id) {
+            if member.custom_roles.add(role_id, roles) {
+                self.updates.insert((now, user_id, MemberUpdate::CustomRolesChanged));
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn remove_custom_role(&mut self, user_id: UserId, role_id: u32, roles: &CustomRoles, now: TimestampMillis) -> bool {
+        if let Some(member) = self.members.get_mut(&user_id) {
+            if member.custom_roles.remove(role_id, roles) {
+                self.updates.insert((now, user_id, MemberUpdate::CustomRolesChanged));
+                return true;
+            }
+        }
+        false
+    }
+
+    // Recalculates the permissions granted to each holder of the role after its definition has changed
+    pub fn refresh_custom_role(&mut self, role_id: u32, roles: &CustomRoles) {
+        for member in self.members.values_mut().filter(|m| m.custom_roles.holds(role_id)) {
+            member.custom_roles.refresh(roles);
+        }
+    }
+
     pub fn owner_count(&self) -> u32 {
         self.owner_count
     }
@@ -290,6 +338,14 @@ impl GroupMembers {
     }
 }
 
+impl GateReverificationMembers for GroupMembers {
+    fn lapsed_mut(&mut self, user_id: &UserId) -> Option<(bool, &mut Timestamped<Option<TimestampMillis>>)> {
+        self.members
+            .get_mut(user_id)
+            .map(|m| (m.requires_gate_reverification(), &mut m.lapsed))
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 pub enum AddResult {
     Success(GroupMemberInternal),
@@ -336,6 +392,12 @@ pub struct GroupMemberInternal {
     pub rules_accepted: Option<Timestamped<Version>>,
     #[serde(rename = "b", default, skip_serializing_if = "is_default")]
     pub is_bot: bool,
+    #[serde(rename = "la", default, skip_serializing_if = "is_default")]
+    pub lapsed: Timestamped<Option<TimestampMillis>>,
+    #[serde(rename = "mu", default, skip_serializing_if = "is_default")]
+    pub muted_until: Timestamped<Option<TimestampMillis>>,
+    #[serde(rename = "cr", default, skip_serializing_if = "is_default")]
+    pub custom_roles: CustomRoleGrants,
 
     #[serde(rename = "me", default, skip_serializing_if = "is_default")]
     min_visible_event_index: EventIndex,
@@ -351,12 +413,77 @@ impl GroupMemberInternal {
             self.notifications_muted.timestamp,
             self.suspended.timestamp,
             self.rules_accepted.as_ref().map(|r| r.timestamp).unwrap_or_default(),
+            self.muted_until.timestamp,
+            self.lapsed.timestamp,
         ]
         .into_iter()
         .max()
         .unwrap()
     }
 
+    // Owners, admins and bots are exempt from periodic gate checks
+    pub fn requires_gate_reverification(&self) -> bool {
+        !self.is_bot && !self.role.is_owner() && !self.role.is_admin()
+    }
+
+    pub fn is_muted(&self, now: TimestampMillis) -> bool {
+        self.muted_until.value.map_or(false, |ts| ts > now)
+    }
+
+    // The following permission checks honour both the member's role and any custom roles they hold
+
+    pub fn has_permission(&self, permission: GroupPermission, permissions: &GroupPermissions) -> bool {
+        self.role.has_permission(permission, permissions) || self.custom_roles.grants(permission)
+    }
+
+    pub fn can_add_members(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::AddMembers, permissions)
+    }
+
+    pub fn can_remove_members(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::RemoveMembers, permissions)
+    }
+
+    pub fn can_remove_members_with_role(&self, member_role: GroupRoleInternal, permissions: &GroupPermissions) -> bool {
+        self.role.is_same_or_senior(member_role) && self.can_remove_members(permissions)
+    }
+
+    pub fn can_unblock_users(&self, permissions: &GroupPermissions) -> bool {
+        self.can_remove_members(permissions)
+    }
+
+    pub fn can_delete_messages(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::DeleteMessages, permissions)
+    }
+
+    pub fn can_update_group(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::UpdateGroup, permissions)
+    }
+
+    pub fn can_pin_messages(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::PinMessages, permissions)
+    }
+
+    pub fn can_react_to_messages(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::ReactToMessages, permissions)
+    }
+
+    pub fn can_invite_users(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::InviteUsers, permissions)
+    }
+
+    pub fn can_mention_everyone(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::MentionAllMembers, permissions)
+    }
+
+    pub fn can_start_video_call(&self, permissions: &GroupPermissions) -> bool {
+        self.has_permission(GroupPermission::StartVideoCall, permissions)
+    }
+
+    pub fn can_send_message(&self, message: &MessageContentInternal, is_thread: bool, permissions: &GroupPermissions) -> bool {
+        self.role.can_send_message(message, is_thread, permissions) || self.custom_roles.grants_message(message)
+    }
+
     pub fn min_visible_event_index(&self) -> EventIndex {
         if self.role.can_view_full_message_history() {
             EventIndex::default()
@@ -409,6 +536,8 @@ impl From<&GroupMemberInternal> for GroupMember {
             user_id: p.user_id,
             date_added: p.date_added,
             role: p.role.value.into(),
+            muted_until: p.muted_until.value,
+            custom_roles: p.custom_roles.role_ids().iter().copied().collect(),
         }
     }
 }
@@ -452,6 +581,7 @@ impl<'de> Visitor<'de> for GroupMembersMapVisitor {
 
 #[cfg(test)]
 mod tests {
+    use crate::custom_roles::{CustomRoleGrants, CustomRoles};
     use crate::roles::GroupRoleInternal;
     use crate::{GroupMemberInternal, Mentions};
     use candid::Principal;
@@ -474,6 +604,9 @@ mod tests {
             min_visible_message_index: 0.into(),
             rules_accepted: Some(Timestamped::new(Version::zero(), 1)),
             is_bot: false,
+            lapsed: Timestamped::default(),
+            muted_until: Timestamped::default(),
+            custom_roles: CustomRoleGrants::default(),
         };
 
         let member_bytes = msgpack::serialize_then_unwrap(&member);
@@ -489,6 +622,9 @@ mod tests {
         let mut mentions = Mentions::default();
         mentions.add(Some(1.into()), 1.into(), 1);
 
+        let mut custom_roles = CustomRoleGrants::default();
+        custom_roles.add(1, &CustomRoles::default());
+
         let member = GroupMemberInternal {
             user_id: Principal::from_text("4bkt6-4aaaa-aaaaf-aaaiq-cai").unwrap().into(),
             date_added: 1,
@@ -503,12 +639,15 @@ mod tests {
             min_visible_message_index: 1.into(),
             rules_accepted: Some(Timestamped::new(Version::zero(), 1)),
             is_bot: true,
+            lapsed: Timestamped::new(Some(1), 1),
+            muted_until: Timestamped::new(Some(1), 1),
+            custom_roles,
         };
 
         let member_bytes = msgpack::serialize_then_unwrap(&member);
         let member_bytes_len = member_bytes.len();
 
-        assert_eq!(member_bytes_len, 120);
+        assert_eq!(member_bytes_len, 150);
 
         let _deserialized: GroupMemberInternal = msgpack::deserialize_then_unwrap(&member_bytes);
     }
@@ -0,0 +1,89 @@
+use chat_events::QuizResults;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use types::{QuizLeaderboardEntry, UserId};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct QuizLeaderboard {
+    #[serde(rename = "s", default, skip_serializing_if = "HashMap::is_empty")]
+    scores: HashMap<UserId, QuizScore>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+struct QuizScore {
+    #[serde(rename = "c", default)]
+    correct_answers: u32,
+    #[serde(rename = "a", default)]
+    quizzes_answered: u32,
+}
+
+impl QuizLeaderboard {
+    pub fn record(&mut self, results: &QuizResults) {
+        for user_id in results.participants.iter() {
+            self.scores.entry(*user_id).or_default().quizzes_answered += 1;
+        }
+        for user_id in results.correct.iter() {
+            self.scores.entry(*user_id).or_default().correct_answers += 1;
+        }
+    }
+
+    // Ranked by correct answers, with ties going to whoever has answered fewer quizzes
+    pub fn top<F: Fn(&UserId) -> bool>(&self, max_results: usize, is_member: F) -> Vec<QuizLeaderboardEntry> {
+        let mut entries: Vec<_> = self
+            .scores
+            .iter()
+            .filter(|(u, s)| s.correct_answers > 0 && is_member(u))
+            .map(|(u, s)| Self::entry(*u, s))
+            .collect();
+
+        entries.sort_unstable_by_key(|e| (Reverse(e.correct_answers), e.quizzes_answered, e.user_id));
+        entries.truncate(max_results);
+        entries
+    }
+
+    pub fn get(&self, user_id: UserId) -> Option<QuizLeaderboardEntry> {
+        self.scores.get(&user_id).map(|s| Self::entry(user_id, s))
+    }
+
+    fn entry(user_id: UserId, score: &QuizScore) -> QuizLeaderboardEntry {
+        QuizLeaderboardEntry {
+            user_id,
+            correct_answers: score.correct_answers,
+            quizzes_answered: score.quizzes_answered,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    #[test]
+    fn leaderboard_ranked_by_correct_answers() {
+        let mut leaderboard = QuizLeaderboard::default();
+
+        leaderboard.record(&QuizResults {
+            participants: vec![user(1), user(2), user(3)],
+            correct: vec![user(1), user(2)],
+        });
+        leaderboard.record(&QuizResults {
+            participants: vec![user(2), user(3)],
+            correct: vec![user(2), user(3)],
+        });
+
+        let top = leaderboard.top(10, |_| true);
+
+        assert_eq!(
+            top.iter().map(|e| e.user_id).collect::<Vec<_>>(),
+            vec![user(2), user(1), user(3)]
+        );
+        assert_eq!(top[0].correct_answers, 2);
+        assert_eq!(leaderboard.get(user(3)).unwrap().quizzes_answered, 2);
+    }
+
+    fn user(index: u8) -> UserId {
+        Principal::from_slice(&[index]).into()
+    }
+}
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use types::{MessageIndex, UserId};
+
+// Once a group has more members (or readers) than this, we stop tracking who has read each message and instead
+// only track how many members have read each of the latest messages
+const MAX_MEMBERS_FOR_FULL_TRACKING: u32 = 1000;
+// When only tracking counts, this is the number of latest messages for which counts are retained
+const MAX_MESSAGES_TRACKED: u32 = 1000;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ReadReceipts {
+    #[serde(rename = "u", default, skip_serializing_if = "HashMap::is_empty")]
+    read_up_to: HashMap<UserId, MessageIndex>,
+    #[serde(rename = "c", default, skip_serializing_if = "BTreeMap::is_empty")]
+    counts: BTreeMap<MessageIndex, u32>,
+    #[serde(rename = "o", default)]
+    counts_only: bool,
+}
+
+pub struct SeenBy {
+    pub count: u32,
+    // Only populated for groups which are small enough to track who has read each message
+    pub users: Option<Vec<UserId>>,
+}
+
+impl ReadReceipts {
+    // `previous` is the position previously reported by the user's canister, which is what allows us to keep counts
+    // accurate without storing each member's position. `sender_of` returns the sender of each message so that members
+    // reading their own messages aren't counted.
+    pub fn mark_read<F: Fn(MessageIndex) -> Option<UserId>>(
+        &mut self,
+        user_id: UserId,
+        previous: Option<MessageIndex>,
+        read_up_to: MessageIndex,
+        latest_message_index: MessageIndex,
+        member_count: u32,
+        sender_of: F,
+    ) {
+        if !self.counts_only
+            && (member_count > MAX_MEMBERS_FOR_FULL_TRACKING || self.read_up_to.len() as u32 > MAX_MEMBERS_FOR_FULL_TRACKING)
+        {
+            self.switch_to_counts_only(latest_message_index, &sender_of);
+        }
+
+        let read_up_to = read_up_to.min(latest_message_index);
+
+        if self.counts_only {
+            let window_start = Self::window_start(latest_message_index);
+            let from = previous.map_or(0, |p| u32::from(p).saturating_add(1)).max(window_start);
+
+            for message_index in (from..=u32::from(read_up_to)).map(MessageIndex::from) {
+                if sender_of(message_index) != Some(user_id) {
+                    *self.counts.entry(message_index).or_default() += 1;
+                }
+            }
+            self.prune(window_start);
+        } else {
+            let current = self.read_up_to.entry(user_id).or_insert(read_up_to);
+            if *current < read_up_to {
+                *current = read_up_to;
+            }
+        }
+    }
+
+    pub fn seen_by<F: Fn(&UserId) -> bool>(
+        &self,
+        message_index: MessageIndex,
+        sender: UserId,
+        latest_message_index: MessageIndex,
+        is_member: F,
+    ) -> Option<SeenBy> {
+        if self.counts_only {
+            if u32::from(message_index) < Self::window_start(latest_message_index) {
+                None
+            } else {
+                Some(SeenBy {
+                    count: self.counts.get(&message_index).copied().unwrap_or_default(),
+                    users: None,
+                })
+            }
+        } else {
+            let users: Vec<_> = self
+                .read_up_to
+                .iter()
+                .filter(|(u, r)| **r >= message_index && **u != sender && is_member(u))
+                .map(|(u, _)| *u)
+                .collect();
+
+            Some(SeenBy {
+                count: users.len() as u32,
+                users: Some(users),
+            })
+        }
+    }
+
+    fn switch_to_counts_only<F: Fn(MessageIndex) -> Option<UserId>>(
+        &mut self,
+        latest_message_index: MessageIndex,
+        sender_of: &F,
+    ) {
+        let window_start = Self::window_start(latest_message_index);
+        let senders: HashMap<MessageIndex, UserId> = (window_start..=u32::from(latest_message_index))
+            .map(MessageIndex::from)
+            .filter_map(|i| sender_of(i).map(|s| (i, s)))
+            .collect();
+
+        for (user_id, read_up_to) in self.read_up_to.iter() {
+            for message_index in (window_start..=u32::from(*read_up_to)).map(MessageIndex::from) {
+                if senders.get(&message_index) != Some(user_id) {
+                    *self.counts.entry(message_index).or_default() += 1;
+                }
+            }
+        }
+        self.read_up_to = HashMap::new();
+        self.counts_only = true;
+    }
+
+    fn prune(&mut self, window_start: u32) {
+        while let Some((&first, _)) = self.counts.first_key_value() {
+            if u32::from(first) >= window_start {
+                break;
+            }
+            self.counts.pop_first();
+        }
+    }
+
+    fn window_start(latest_message_index: MessageIndex) -> u32 {
+        u32::from(latest_message_index).saturating_sub(MAX_MESSAGES_TRACKED - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    #[test]
+    fn small_groups_track_who_has_read_each_message() {
+        let mut read_receipts = ReadReceipts::default();
+        let sender = user(1);
+
+        read_receipts.mark_read(user(2), None, 5.into(), 10.into(), 3, |_| Some(sender));
+        read_receipts.mark_read(user(3), None, 8.into(), 10.into(), 3, |_| Some(sender));
+
+        let seen_by = read_receipts.seen_by(6.into(), sender, 10.into(), |_| true).unwrap();
+        assert_eq!(seen_by.count, 1);
+        assert_eq!(seen_by.users, Some(vec![user(3)]));
+
+        let seen_by = read_receipts.seen_by(5.into(), sender, 10.into(), |_| true).unwrap();
+        assert_eq!(seen_by.count, 2);
+    }
+
+    #[test]
+    fn large_groups_only_track_counts() {
+        let mut read_receipts = ReadReceipts::default();
+        let sender = user(1);
+        let member_count = MAX_MEMBERS_FOR_FULL_TRACKING + 1;
+
+        read_receipts.mark_read(user(2), None, 5.into(), 10.into(), member_count, |_| Some(sender));
+        read_receipts.mark_read(user(2), Some(5.into()), 8.into(), 10.into(), member_count, |_| Some(sender));
+        read_receipts.mark_read(user(3), None, 6.into(), 10.into(), member_count, |_| Some(sender));
+
+        let seen_by = read_receipts.seen_by(6.into(), sender, 10.into(), |_| true).unwrap();
+        assert_eq!(seen_by.count, 2);
+        assert!(seen_by.users.is_none());
+
+        let seen_by = read_receipts.seen_by(7.into(), sender, 10.into(), |_| true).unwrap();
+        assert_eq!(seen_by.count, 1);
+    }
+
+    #[test]
+    fn counts_exclude_members_reading_their_own_messages() {
+        let mut read_receipts = ReadReceipts::default();
+        let member_count = MAX_MEMBERS_FOR_FULL_TRACKING + 1;
+        // Messages 0 to 4 were sent by user 1, the rest by user 2
+        let sender_of = |i: MessageIndex| Some(if u32::from(i) < 5 { user(1) } else { user(2) });
+
+        read_receipts.mark_read(user(1), None, 8.into(), 10.into(), member_count, sender_of);
+        read_receipts.mark_read(user(2), None, 8.into(), 10.into(), member_count, sender_of);
+
+        let seen_by = read_receipts.seen_by(3.into(), user(1), 10.into(), |_| true).unwrap();
+        assert_eq!(seen_by.count, 1);
+
+        let seen_by = read_receipts.seen_by(6.into(), user(2), 10.into(), |_| true).unwrap();
+        assert_eq!(seen_by.count, 1);
+    }
+
+    #[test]
+    fn switching_to_counts_only_excludes_members_reading_their_own_messages() {
+        let mut read_receipts = ReadReceipts::default();
+        let sender_of = |_| Some(user(1));
+
+        read_receipts.mark_read(user(1), None, 5.into(), 10.into(), 3, sender_of);
+        read_receipts.mark_read(user(2), None, 5.into(), 10.into(), 3, sender_of);
+        read_receipts.mark_read(
+            user(3),
+            None,
+            5.into(),
+            10.into(),
+            MAX_MEMBERS_FOR_FULL_TRACKING + 1,
+            sender_of,
+        );
+
+        let seen_by = read_receipts.seen_by(5.into(), user(1), 10.into(), |_| true).unwrap();
+        assert_eq!(seen_by.count, 2);
+        assert!(seen_by.users.is_none());
+    }
+
+    fn user(index: u8) -> UserId {
+        Principal::from_slice(&[index]).into()
+    }
+}
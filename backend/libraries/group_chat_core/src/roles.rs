@@ -1,6 +1,6 @@
 use chat_events::MessageContentInternal;
 use serde::{Deserialize, Serialize};
-use types::{GroupPermissionRole, GroupPermissions, GroupRole};
+use types::{GroupPermission, GroupPermissionRole, GroupPermissions, GroupRole};
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum GroupRoleInternal {
@@ -154,6 +154,10 @@ impl GroupRoleInternal {
         self.is_permitted(permissions.mention_all_members)
     }
 
+    pub fn has_permission(&self, permission: GroupPermission, permissions: &GroupPermissions) -> bool {
+        self.is_permitted(permissions.role_for(permission))
+    }
+
     pub fn is_permitted(&self, permission_role: GroupPermissionRole) -> bool {
         match permission_role {
             GroupPermissionRole::None => false,
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use types::{Milliseconds, SlowMode, TimestampMillis, UserId};
+use utils::time::MINUTE_IN_MS;
+
+// Tracks the messages recently sent by each member so that slow mode can be enforced
+#[derive(Serialize, Deserialize, Default)]
+pub struct RecentMessages {
+    #[serde(rename = "u")]
+    by_user: HashMap<UserId, VecDeque<TimestampMillis>>,
+    #[serde(rename = "p")]
+    last_pruned: TimestampMillis,
+}
+
+impl RecentMessages {
+    // Returns the time at which the user can next send a message if they are currently rate limited
+    pub fn check(&self, user_id: UserId, slow_mode: &SlowMode, now: TimestampMillis) -> Result<(), TimestampMillis> {
+        let Some(timestamps) = self.by_user.get(&user_id) else {
+            return Ok(());
+        };
+
+        let mut next_allowed = now;
+
+        if let (Some(min_interval), Some(latest)) = (slow_mode.min_interval, timestamps.back()) {
+            next_allowed = next_allowed.max(latest + min_interval);
+        }
+
+        if let Some(max_messages) = slow_mode.max_messages_per_minute.map(|m| m as usize) {
+            let in_last_minute: Vec<_> = timestamps.iter().filter(|&&ts| ts + MINUTE_IN_MS > now).collect();
+            if in_last_minute.len() >= max_messages {
+                // The user can send again once the oldest message which would take them over the limit is a minute old
+                let oldest_over_limit = *in_last_minute[in_last_minute.len() - max_messages];
+                next_allowed = next_allowed.max(oldest_over_limit + MINUTE_IN_MS);
+            }
+        }
+
+        if next_allowed > now {
+            Err(next_allowed)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn record(&mut self, user_id: UserId, slow_mode: &SlowMode, now: TimestampMillis) {
+        let window = retention_window(slow_mode);
+
+        let timestamps = self.by_user.entry(user_id).or_default();
+        timestamps.push_back(now);
+        while timestamps.front().map_or(false, |ts| ts + window <= now) {
+            timestamps.pop_front();
+        }
+
+        if self.last_pruned + window < now {
+            self.prune(window, now);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.by_user.clear();
+    }
+
+    fn prune(&mut self, window: Milliseconds, now: TimestampMillis) {
+        self.by_user
+            .retain(|_, timestamps| timestamps.back().map_or(false, |ts| ts + window > now));
+        self.last_pruned = now;
+    }
+}
+
+fn retention_window(slow_mode: &SlowMode) -> Milliseconds {
+    slow_mode.min_interval.unwrap_or_default().max(MINUTE_IN_MS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+
+    #[test]
+    fn min_interval_enforced() {
+        let slow_mode = SlowMode {
+            min_interval: Some(10_000),
+            max_messages_per_minute: None,
+            exempt_moderators: false,
+        };
+        let user_id: UserId = Principal::from_slice(&[1]).into();
+        let mut recent_messages = RecentMessages::default();
+
+        assert!(recent_messages.check(user_id, &slow_mode, 1_000).is_ok());
+        recent_messages.record(user_id, &slow_mode, 1_000);
+
+        assert_eq!(recent_messages.check(user_id, &slow_mode, 5_000), Err(11_000));
+        assert!(recent_messages.check(user_id, &slow_mode, 11_000).is_ok());
+    }
+
+    #[test]
+    fn max_messages_per_minute_enforced() {
+        let slow_mode = SlowMode {
+            min_interval: None,
+            max_messages_per_minute: Some(3),
+            exempt_moderators: false,
+        };
+        let user_id: UserId = Principal::from_slice(&[1]).into();
+        let mut recent_messages = RecentMessages::default();
+
+        for now in [1_000, 2_000, 3_000] {
+            assert!(recent_messages.check(user_id, &slow_mode, now).is_ok());
+            recent_messages.record(user_id, &slow_mode, now);
+        }
+
+        assert_eq!(recent_messages.check(user_id, &slow_mode, 4_000), Err(61_000));
+        assert!(recent_messages.check(user_id, &slow_mode, 61_000).is_ok());
+    }
+}
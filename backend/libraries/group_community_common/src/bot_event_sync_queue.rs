@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::collections::HashMap;
+use types::{BotChatEvent, CanisterId, Milliseconds, TimestampMillis};
+use utils::canister_event_sync_queue::CanisterEventSyncQueue;
+
+const MAX_ATTEMPTS: u32 = 10;
+const INITIAL_RETRY_DELAY: Milliseconds = 5 * 1000; // 5 seconds
+const MAX_RETRY_DELAY: Milliseconds = 60 * 60 * 1000; // 1 hour
+const MAX_EVENTS_PENDING_RETRY: usize = 10_000;
+
+// Queues up the events which bots have subscribed to so that they can be pushed to the bots in batches. If pushing to a
+// bot fails then its events are held back and retried with exponential backoff, and any new events for that bot are
+// held back along with them.
+#[derive(Serialize, Deserialize, Default)]
+pub struct BotEventSyncQueue {
+    queue: CanisterEventSyncQueue<BotChatEvent>,
+    retrying: HashMap<CanisterId, PendingRetry>,
+    failed_attempts: HashMap<CanisterId, u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PendingRetry {
+    due: TimestampMillis,
+    events: Vec<BotChatEvent>,
+}
+
+impl BotEventSyncQueue {
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty() && self.retrying.is_empty()
+    }
+
+    pub fn push(&mut self, bot_id: CanisterId, event: BotChatEvent) {
+        if let Some(retry) = self.retrying.get_mut(&bot_id) {
+            if retry.events.len() >= MAX_EVENTS_PENDING_RETRY {
+                retry.events.remove(0);
+            }
+            retry.events.push(event);
+        } else {
+            self.queue.push(bot_id, event);
+        }
+    }
+
+    pub fn try_start_batch(&mut self, now: TimestampMillis) -> Option<Vec<(CanisterId, Vec<BotChatEvent>)>> {
+        if self.queue.sync_in_progress() {
+            return None;
+        }
+
+        let due: Vec<_> = self.retrying.iter().filter(|(_, r)| r.due <= now).map(|(c, _)| *c).collect();
+        for bot_id in due {
+            let retry = self.retrying.remove(&bot_id).unwrap();
+            self.queue.requeue_failed_events(bot_id, retry.events);
+        }
+
+        self.queue.try_start_batch()
+    }
+
+    pub fn mark_batch_completed(&mut self) {
+        self.queue.mark_batch_completed();
+    }
+
+    pub fn clear_failed_attempts(&mut self, bot_id: &CanisterId) {
+        self.failed_attempts.remove(bot_id);
+    }
+
+    // Returns false if the bot has reached the maximum number of attempts, in which case the events are dropped
+    pub fn mark_failed(&mut self, bot_id: CanisterId, events: Vec<BotChatEvent>, now: TimestampMillis) -> bool {
+        let attempts = self.failed_attempts.get(&bot_id).copied().unwrap_or_default() + 1;
+
+        if attempts >= MAX_ATTEMPTS {
+            self.failed_attempts.remove(&bot_id);
+            return false;
+        }
+
+        self.failed_attempts.insert(bot_id, attempts);
+
+        let delay = min(INITIAL_RETRY_DELAY << (attempts - 1), MAX_RETRY_DELAY);
+        let retry = self.retrying.entry(bot_id).or_insert(PendingRetry {
+            due: now + delay,
+            events: Vec::new(),
+        });
+
+        // Any events pushed while the batch was in progress go after the events which failed
+        let newer_events = std::mem::replace(&mut retry.events, events);
+        retry.events.extend(newer_events);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Principal;
+    use types::{BotEvent, BotMembersLeftEvent, Chat};
+
+    fn event(timestamp: TimestampMillis) -> BotChatEvent {
+        BotChatEvent {
+            chat: Chat::Group(Principal::anonymous().into()),
+            timestamp,
+            event: BotEvent::MembersLeft(BotMembersLeftEvent {
+                user_ids: Vec::new(),
+                removed_by: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn failed_events_are_retried_with_backoff() {
+        let mut queue = BotEventSyncQueue::default();
+        let bot_id = CanisterId::from_slice(&[1]);
+
+        queue.push(bot_id, event(1));
+        let batch = queue.try_start_batch(0).unwrap();
+        queue.mark_batch_completed();
+
+        let (_, events) = batch.into_iter().next().unwrap();
+        assert!(queue.mark_failed(bot_id, events, 0));
+
+        // New events are held back along with the failed events
+        queue.push(bot_id, event(2));
+        assert!(queue.try_start_batch(INITIAL_RETRY_DELAY - 1).is_none());
+
+        let batch = queue.try_start_batch(INITIAL_RETRY_DELAY).unwrap();
+        let timestamps: Vec<_> = batch[0].1.iter().map(|e| e.timestamp).collect();
+        assert_eq!(timestamps, vec![1, 2]);
+        queue.mark_batch_completed();
+
+        assert!(queue.mark_failed(bot_id, batch.into_iter().next().unwrap().1, INITIAL_RETRY_DELAY));
+        assert_eq!(queue.retrying[&bot_id].due, 3 * INITIAL_RETRY_DELAY);
+    }
+
+    #[test]
+    fn events_dropped_after_max_attempts() {
+        let mut queue = BotEventSyncQueue::default();
+        let bot_id = CanisterId::from_slice(&[1]);
+
+        for attempt in 1..MAX_ATTEMPTS {
+            assert!(queue.mark_failed(bot_id, vec![event(1)], 0), "{attempt}");
+            queue.retrying.clear();
+        }
+        assert!(!queue.mark_failed(bot_id, vec![event(1)], 0));
+        assert!(queue.is_empty());
+    }
+}
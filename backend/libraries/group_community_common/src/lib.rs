@@ -1,5 +1,7 @@
+mod bot_event_sync_queue;
 mod payment_receipts;
 mod pending_payments_queue;
 
+pub use bot_event_sync_queue::*;
 pub use payment_receipts::*;
 pub use pending_payments_queue::*;
@@ -3,6 +3,10 @@ use ic_cdk::api::call::{CallResult, RejectionCode};
 use icdex_canister::{ICDexOrderType, MakeOrderResponse, OrderPrice, OrderQuantity, TradingOrder};
 use types::{AggregatedOrders, CancelOrderRequest, CanisterId, MakeOrderRequest, Order, OrderType, TokenInfo};
 
+mod swap_client;
+
+pub use swap_client::{ICDexSwapClient, ICDexSwapResult};
+
 pub struct ICDexClient<M: Fn(MakeOrderRequest), C: Fn(CancelOrderRequest)> {
     this_canister_id: CanisterId,
     dex_canister_id: CanisterId,
@@ -0,0 +1,388 @@
+use candid::Nat;
+use ic_cdk::api::call::CallResult;
+use icdex_canister::deposit::Token0OrToken1;
+use icdex_canister::{BalanceChange, ICDexOrderType, MakeOrderResponse, OrderFilled, OrderPrice, OrderQuantity, Orderbook};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+use types::{CanisterId, TokenInfo};
+
+// ICDex charges takers 0.5% of the amount received
+const TAKER_FEE_PPM: u128 = 5_000;
+
+pub struct ICDexSwapResult {
+    // The amount received after taker fees
+    pub amount_out: u128,
+    // Any of the input which the order didn't use, which is withdrawn straight after the swap
+    pub unspent_input_withdrawn: u128,
+}
+
+// Swaps tokens by placing fill-or-kill limit orders against an ICDex orderbook, so that each swap either completes
+// at an acceptable price or doesn't happen at all. Token0 is the pair's base token and token1 is its quote token, so
+// `zero_for_one` means selling the base token.
+#[derive(Serialize, Deserialize)]
+pub struct ICDexSwapClient {
+    this_canister_id: CanisterId,
+    dex_canister_id: CanisterId,
+    token0: TokenInfo,
+    token1: TokenInfo,
+    zero_for_one: bool,
+}
+
+impl ICDexSwapClient {
+    pub fn new(
+        this_canister_id: CanisterId,
+        dex_canister_id: CanisterId,
+        token0: TokenInfo,
+        token1: TokenInfo,
+        zero_for_one: bool,
+    ) -> Self {
+        ICDexSwapClient {
+            this_canister_id,
+            dex_canister_id,
+            token0,
+            token1,
+            zero_for_one,
+        }
+    }
+
+    pub async fn deposit_account(&self) -> CallResult<Account> {
+        let (account, ..) =
+            icdex_canister_c2c_client::getTxAccount(self.dex_canister_id, (self.this_canister_id.to_string(),)).await?;
+
+        Ok(account)
+    }
+
+    pub async fn deposit(&self, amount: u128) -> CallResult<()> {
+        let token = if self.zero_for_one { Token0OrToken1::Token0 } else { Token0OrToken1::Token1 };
+        let args = (token, amount.saturating_sub(self.input_token().fee).into(), None);
+
+        icdex_canister_c2c_client::deposit(self.dex_canister_id, args).await
+    }
+
+    pub async fn quote(&self, amount: u128) -> CallResult<u128> {
+        let (unit_size, orderbook) = icdex_canister_c2c_client::level10(self.dex_canister_id, ()).await?;
+
+        Ok(calculate_amount_out(
+            amount,
+            self.zero_for_one,
+            nat_to_u128(unit_size),
+            &orderbook,
+        ))
+    }
+
+    pub async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<ICDexSwapResult, String>> {
+        let (unit_size, orderbook) = icdex_canister_c2c_client::level10(self.dex_canister_id, ()).await?;
+        let unit_size = nat_to_u128(unit_size);
+
+        let order = if self.zero_for_one {
+            sell_order(amount, min_amount_out, unit_size)
+        } else {
+            buy_order(amount, min_amount_out, unit_size, &orderbook)
+        };
+        let Some(order) = order else {
+            return Ok(Err(format!(
+                "Unable to place an order for {amount} which returns at least {min_amount_out}"
+            )));
+        };
+
+        let args = (order, ICDexOrderType::FillOrKill, None, None, None, None);
+
+        match icdex_canister_c2c_client::trade(self.dex_canister_id, args).await?.0 {
+            MakeOrderResponse::Ok(result) => match amount_received(&result.filled, self.zero_for_one) {
+                0 => Ok(Err("Order was not filled".to_string())),
+                amount_out => {
+                    let unspent_input = amount.saturating_sub(amount_spent(&result.filled, self.zero_for_one));
+                    let unspent_input_withdrawn = self.withdraw_unspent_input(unspent_input).await;
+                    Ok(Ok(ICDexSwapResult {
+                        amount_out,
+                        unspent_input_withdrawn,
+                    }))
+                }
+            },
+            MakeOrderResponse::Err(error) => Ok(Err(format!("{error:?}"))),
+        }
+    }
+
+    pub async fn withdraw(&self, successful_swap: bool, amount: u128) -> CallResult<u128> {
+        let withdraw_token0 = successful_swap != self.zero_for_one;
+        let amount = if successful_swap { amount + self.output_token().fee } else { amount };
+        let args = if withdraw_token0 {
+            (Some(Nat::from(amount)), None, None)
+        } else {
+            (None, Some(Nat::from(amount)), None)
+        };
+
+        let (token0_amount, token1_amount) = icdex_canister_c2c_client::withdraw(self.dex_canister_id, args).await?;
+
+        Ok(nat_to_u128(if withdraw_token0 { token0_amount } else { token1_amount }))
+    }
+
+    // Orders are made in whole units and buys may be filled below the limit price, so some of the input is usually
+    // left over. The swap has already been made at this point, so if this fails the remainder is left in the trading
+    // account rather than the swap being retried. Returns the amount withdrawn after the ledger fee.
+    async fn withdraw_unspent_input(&self, amount: u128) -> u128 {
+        let fee = self.input_token().fee;
+        if amount <= fee {
+            return 0;
+        }
+
+        let args = if self.zero_for_one {
+            (Some(Nat::from(amount)), None, None)
+        } else {
+            (None, Some(Nat::from(amount)), None)
+        };
+
+        match icdex_canister_c2c_client::withdraw(self.dex_canister_id, args).await {
+            Ok(_) => amount - fee,
+            Err(_) => 0,
+        }
+    }
+
+    fn input_token(&self) -> &TokenInfo {
+        if self.zero_for_one {
+            &self.token0
+        } else {
+            &self.token1
+        }
+    }
+
+    fn output_token(&self) -> &TokenInfo {
+        if self.zero_for_one {
+            &self.token1
+        } else {
+            &self.token0
+        }
+    }
+}
+
+// Walks the orderbook to calculate the amount received by a market order of `amount`, after taker fees.
+// Prices are quoted in token1 per `unit_size` of token0.
+fn calculate_amount_out(amount: u128, sell_token0: bool, unit_size: u128, orderbook: &Orderbook) -> u128 {
+    if unit_size == 0 {
+        return 0;
+    }
+
+    let mut remaining = amount;
+    let mut amount_out = 0;
+
+    if sell_token0 {
+        remaining -= remaining % unit_size;
+        for level in orderbook.bid.iter() {
+            let (price, quantity) = (nat_to_u128(level.price.clone()), nat_to_u128(level.quantity.clone()));
+            let filled = remaining.min(quantity);
+            amount_out += filled * price / unit_size;
+            remaining -= filled;
+            if remaining == 0 {
+                break;
+            }
+        }
+    } else {
+        for level in orderbook.ask.iter() {
+            let (price, quantity) = (nat_to_u128(level.price.clone()), nat_to_u128(level.quantity.clone()));
+            if price == 0 {
+                continue;
+            }
+            let cost = quantity * price / unit_size;
+            if remaining >= cost {
+                amount_out += quantity;
+                remaining -= cost;
+            } else {
+                let filled = remaining * unit_size / price;
+                amount_out += filled - filled % unit_size;
+                break;
+            }
+        }
+    }
+
+    after_taker_fee(amount_out)
+}
+
+// Sells the whole units of token0 within `amount` with the limit set at the minimum price which still results in
+// `min_amount_out` after fees, so the order can only be filled at that price or better
+fn sell_order(amount: u128, min_amount_out: u128, unit_size: u128) -> Option<OrderPrice> {
+    if unit_size == 0 {
+        return None;
+    }
+
+    let quantity = amount - amount % unit_size;
+    if quantity == 0 {
+        return None;
+    }
+
+    let price = before_taker_fee(min_amount_out).saturating_mul(unit_size).div_ceil(quantity);
+
+    Some(OrderPrice {
+        price: price.max(1).into(),
+        quantity: OrderQuantity::Sell(quantity.into()),
+    })
+}
+
+// Buys as much token0 as `amount` covers when walking the orderbook's asks. Limit orders are paid for at the limit
+// price, so the limit is the highest price reached, and the quantity is reduced until it can be paid for at that
+// price. The order is only placed if the quantity results in at least `min_amount_out` after fees.
+fn buy_order(amount: u128, min_amount_out: u128, unit_size: u128, orderbook: &Orderbook) -> Option<OrderPrice> {
+    if unit_size == 0 {
+        return None;
+    }
+
+    let mut quantity = 0;
+    let mut price = 0;
+
+    for level in orderbook.ask.iter() {
+        let (level_price, level_quantity) = (nat_to_u128(level.price.clone()), nat_to_u128(level.quantity.clone()));
+        if level_price == 0 {
+            continue;
+        }
+        let affordable = amount.saturating_mul(unit_size) / level_price;
+        if affordable <= quantity {
+            break;
+        }
+        price = level_price;
+        quantity = affordable.min(quantity + level_quantity);
+        if quantity == affordable {
+            break;
+        }
+    }
+
+    quantity -= quantity % unit_size;
+    if quantity == 0 || after_taker_fee(quantity) < min_amount_out {
+        return None;
+    }
+
+    Some(OrderPrice {
+        price: price.into(),
+        quantity: OrderQuantity::Buy(quantity.into(), 0u32.into()),
+    })
+}
+
+// The amount of the output token credited by the order's fills, after taker fees
+fn amount_received(filled: &[OrderFilled], sell_token0: bool) -> u128 {
+    let credited: u128 = filled
+        .iter()
+        .map(|f| if sell_token0 { &f.token1_value } else { &f.token0_value })
+        .map(|change| match change {
+            BalanceChange::CreditRecord(amount) => nat_to_u128(amount.clone()),
+            BalanceChange::DebitRecord(_) | BalanceChange::NoChange => 0,
+        })
+        .sum();
+
+    after_taker_fee(credited)
+}
+
+// The amount of the input token debited by the order's fills
+fn amount_spent(filled: &[OrderFilled], sell_token0: bool) -> u128 {
+    filled
+        .iter()
+        .map(|f| if sell_token0 { &f.token0_value } else { &f.token1_value })
+        .map(|change| match change {
+            BalanceChange::DebitRecord(amount) => nat_to_u128(amount.clone()),
+            BalanceChange::CreditRecord(_) | BalanceChange::NoChange => 0,
+        })
+        .sum()
+}
+
+fn after_taker_fee(amount: u128) -> u128 {
+    amount - (amount * TAKER_FEE_PPM / 1_000_000)
+}
+
+// The smallest amount which is at least `amount` once taker fees have been deducted
+fn before_taker_fee(amount: u128) -> u128 {
+    amount.saturating_mul(1_000_000).div_ceil(1_000_000 - TAKER_FEE_PPM)
+}
+
+fn nat_to_u128(value: Nat) -> u128 {
+    value.0.try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use icdex_canister::PriceAndQuantity;
+
+    const UNIT_SIZE: u128 = 100;
+
+    fn orderbook() -> Orderbook {
+        let level = |price: u128, quantity: u128| PriceAndQuantity {
+            price: price.into(),
+            quantity: quantity.into(),
+        };
+        Orderbook {
+            ask: vec![level(20, 1_000), level(30, 1_000)],
+            bid: vec![level(10, 1_000), level(5, 1_000)],
+        }
+    }
+
+    fn price(order: &OrderPrice) -> u128 {
+        nat_to_u128(order.price.clone())
+    }
+
+    #[test]
+    fn amount_out_walks_bids_when_selling() {
+        // 1000 at 10 per 100 = 100, then 500 at 5 per 100 = 25, with the remainder of 50 being dropped
+        assert_eq!(
+            calculate_amount_out(1_550, true, UNIT_SIZE, &orderbook()),
+            after_taker_fee(125)
+        );
+    }
+
+    #[test]
+    fn amount_out_walks_asks_when_buying() {
+        // 200 buys 1000 at 20 per 100, then 50 buys 166 at 30 per 100, rounded down to whole units
+        assert_eq!(
+            calculate_amount_out(250, false, UNIT_SIZE, &orderbook()),
+            after_taker_fee(1_100)
+        );
+    }
+
+    #[test]
+    fn sell_order_limit_is_minimum_acceptable_price() {
+        let order = sell_order(1_550, 100, UNIT_SIZE).unwrap();
+        assert!(matches!(&order.quantity, OrderQuantity::Sell(q) if nat_to_u128(q.clone()) == 1_500));
+
+        // Filling the order at the limit price returns the minimum amount out after fees
+        let amount_out = after_taker_fee(1_500 * price(&order) / UNIT_SIZE);
+        assert!(amount_out >= 100);
+        assert!(after_taker_fee(1_500 * (price(&order) - 1) / UNIT_SIZE) < 100);
+
+        assert!(sell_order(50, 0, UNIT_SIZE).is_none());
+    }
+
+    #[test]
+    fn buy_order_can_be_paid_for_at_limit_price() {
+        // 500 would buy 1666 at 30 per 100, so the order reaches the second level, rounded down to whole units
+        let order = buy_order(500, 1_000, UNIT_SIZE, &orderbook()).unwrap();
+        let OrderQuantity::Buy(quantity, _) = &order.quantity else {
+            panic!();
+        };
+        let quantity = nat_to_u128(quantity.clone());
+
+        assert_eq!(price(&order), 30);
+        assert_eq!(quantity, 1_600);
+        assert!(quantity * price(&order) / UNIT_SIZE <= 500);
+
+        // 250 would only buy 833 at 30 per 100, so it is better to stop at the first level
+        let order = buy_order(250, 900, UNIT_SIZE, &orderbook()).unwrap();
+        assert_eq!(price(&order), 20);
+
+        assert!(buy_order(250, 1_000_000, UNIT_SIZE, &orderbook()).is_none());
+    }
+
+    #[test]
+    fn amount_received_and_spent_sum_fills_of_each_token() {
+        let filled = vec![
+            OrderFilled {
+                token0_value: BalanceChange::DebitRecord(1_000u32.into()),
+                token1_value: BalanceChange::CreditRecord(100u32.into()),
+            },
+            OrderFilled {
+                token0_value: BalanceChange::DebitRecord(500u32.into()),
+                token1_value: BalanceChange::CreditRecord(25u32.into()),
+            },
+        ];
+
+        assert_eq!(amount_received(&filled, true), after_taker_fee(125));
+        assert_eq!(amount_received(&filled, false), 0);
+        assert_eq!(amount_spent(&filled, true), 1_500);
+        assert_eq!(amount_spent(&filled, false), 0);
+    }
+}
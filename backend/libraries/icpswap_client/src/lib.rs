@@ -52,6 +52,19 @@ impl ICPSwapClient {
         }
     }
 
+    pub async fn quote(&self, amount: u128) -> CallResult<u128> {
+        let args = icpswap_swap_pool_canister::quote::Args {
+            operator: self.this_canister_id,
+            amount_in: amount.to_string(),
+            zero_for_one: self.zero_for_one,
+            amount_out_minimum: "0".to_string(),
+        };
+        match icpswap_swap_pool_canister_c2c_client::quote(self.swap_canister_id, &args).await? {
+            ICPSwapResult::Ok(amount_out) => Ok(nat_to_u128(amount_out)),
+            ICPSwapResult::Err(error) => Err(convert_error(error)),
+        }
+    }
+
     pub async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<u128, String>> {
         let args = icpswap_swap_pool_canister::swap::Args {
             operator: self.this_canister_id,
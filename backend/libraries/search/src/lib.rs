@@ -1,10 +1,10 @@
-use std::{cmp::max_by, collections::HashSet};
-use types::{Milliseconds, UserId};
+use std::cmp::max_by;
+use std::collections::BTreeSet;
+use types::Milliseconds;
 
-pub struct Query {
-    pub tokens: Vec<Token>,
-    pub users: HashSet<UserId>,
-}
+mod query;
+
+pub use query::*;
 
 pub struct Token {
     pub value: String,
@@ -21,15 +21,6 @@ impl Token {
     }
 }
 
-impl Query {
-    pub fn parse(free_text: String) -> Query {
-        Query {
-            tokens: parse_tokens(free_text),
-            users: HashSet::new(),
-        }
-    }
-}
-
 pub struct Field {
     tokens: Vec<Token>,
     weight: f32,
@@ -61,6 +52,46 @@ impl Document {
         self
     }
 
+    // The set of normalised terms under which this document should be indexed
+    pub fn terms(&self) -> BTreeSet<String> {
+        self.fields
+            .iter()
+            .flat_map(|f| f.tokens.iter())
+            .flat_map(|t| index_terms(&t.value_lower))
+            .collect()
+    }
+
+    // Every clause must be matched by at least one term (either exactly or as a prefix), every
+    // phrase must be present, and none of the excluded terms or phrases may be present
+    pub fn is_match(&self, query: &Query) -> bool {
+        let terms = self.terms();
+
+        query
+            .clauses
+            .iter()
+            .all(|clause| clause.iter().any(|c| terms.iter().any(|t| t.starts_with(c.as_str()))))
+            && query.phrases.iter().all(|p| self.contains_phrase(p))
+            && !query
+                .excluded_terms
+                .iter()
+                .any(|e| terms.iter().any(|t| t.starts_with(e.as_str())))
+            && !query
+                .excluded_phrases
+                .iter()
+                .any(|p| !p.is_empty() && self.contains_phrase(p))
+    }
+
+    pub fn contains_phrase(&self, phrase: &[String]) -> bool {
+        if phrase.is_empty() {
+            return true;
+        }
+
+        self.fields.iter().any(|f| {
+            let terms: Vec<_> = f.tokens.iter().flat_map(|t| index_terms(&t.value_lower)).collect();
+            terms.windows(phrase.len()).any(|w| w == phrase)
+        })
+    }
+
     // The search term is split into words and each word is matched against each field
     // There is a match if at least one word matches at least one field case insensitive
     // Extra weight is given:
@@ -153,6 +184,13 @@ fn parse_tokens(text: String) -> Vec<Token> {
     text.split_whitespace().map(|word| Token::new(word.to_string())).collect()
 }
 
+// Splits text into lowercase alphanumeric terms, these are the keys used by search indexes
+pub fn index_terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,4 +207,43 @@ mod tests {
 
         assert!(doc1.calculate_score(&query) > doc2.calculate_score(&query));
     }
+
+    #[test]
+    fn test_excluded_terms_prevent_match() {
+        let mut doc = Document::default();
+        doc.add_field("The quick brown fox jumps over the lazy dog.".to_string(), 1.0, false);
+
+        assert!(doc.is_match(&Query::parse("fox".to_string())));
+        assert!(!doc.is_match(&Query::parse("fox -dog".to_string())));
+        assert!(doc.is_match(&Query::parse("cat OR fox".to_string())));
+        assert!(!doc.is_match(&Query::parse("cat fox".to_string())));
+    }
+
+    #[test]
+    fn test_terms_match_by_prefix() {
+        let mut doc = Document::default();
+        doc.add_field("The quick brown fox jumps over the lazy dog.".to_string(), 1.0, false);
+
+        assert!(doc.is_match(&Query::parse("qui bro".to_string())));
+        assert!(!doc.is_match(&Query::parse("uick".to_string())));
+        assert!(!doc.is_match(&Query::parse("own OR azy".to_string())));
+    }
+
+    #[test]
+    fn test_score_is_positive_if_any_word_matches() {
+        let mut doc = Document::default();
+        doc.add_field("The quick brown fox jumps over the lazy dog.".to_string(), 1.0, false);
+
+        assert!(doc.calculate_score(&Query::parse("cat fox".to_string())) > 0);
+        assert_eq!(doc.calculate_score(&Query::parse("cat mouse".to_string())), 0);
+    }
+
+    #[test]
+    fn test_contains_phrase() {
+        let mut doc = Document::default();
+        doc.add_field("The quick brown fox jumps over the lazy dog.".to_string(), 1.0, false);
+
+        assert!(doc.contains_phrase(&["lazy".to_string(), "dog".to_string()]));
+        assert!(!doc.contains_phrase(&["quick".to_string(), "fox".to_string()]));
+    }
 }
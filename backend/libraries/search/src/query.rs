@@ -0,0 +1,369 @@
+use crate::{index_terms, Token};
+use candid::Principal;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use types::{MessageIndex, Milliseconds, TimestampMillis, UserId};
+
+const DAY_IN_MS: Milliseconds = 24 * 60 * 60 * 1000;
+
+// The supported syntax is -
+// * free text, every word must match (as a case-insensitive prefix of a word)
+// * "quoted phrases", the words must appear consecutively
+// * `a OR b`, either word can match
+// * `-word` or `-"some phrase"`, excludes documents containing the word or phrase
+// * `from:@UserId(<principal>)`, `from:<principal>` or `from:@username`
+// * `has:image|video|audio|file|gif|poll|crypto|prize|swap`
+// * `before:YYYY-MM-DD` and `after:YYYY-MM-DD`
+// * `in:thread`, `in:thread:<root message index>`, `in:main` or `in:any`
+// Filters which aren't relevant to the type of document being searched are ignored.
+#[derive(Default)]
+pub struct Query {
+    // All of the positive words and phrases, used for scoring
+    pub tokens: Vec<Token>,
+    // Each clause must be matched by at least one of its terms
+    pub clauses: Vec<Vec<String>>,
+    pub phrases: Vec<Vec<String>>,
+    pub excluded_terms: Vec<String>,
+    pub excluded_phrases: Vec<Vec<String>>,
+    pub users: HashSet<UserId>,
+    // Usernames which the caller must resolve into `users`
+    pub usernames: Vec<String>,
+    pub message_types: HashSet<String>,
+    pub since: Option<TimestampMillis>,
+    pub until: Option<TimestampMillis>,
+    pub thread: ThreadFilter,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThreadFilter {
+    #[default]
+    MainOnly,
+    ThreadsOnly,
+    Thread(MessageIndex),
+    Any,
+}
+
+impl ThreadFilter {
+    pub fn includes(&self, thread_root_message_index: Option<MessageIndex>) -> bool {
+        match self {
+            ThreadFilter::MainOnly => thread_root_message_index.is_none(),
+            ThreadFilter::ThreadsOnly => thread_root_message_index.is_some(),
+            ThreadFilter::Thread(root) => thread_root_message_index == Some(*root),
+            ThreadFilter::Any => true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnknownMessageType(String),
+    InvalidDate(String),
+    InvalidThreadFilter(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnknownMessageType(t) => write!(f, "Unknown message type: {t}"),
+            ParseError::InvalidDate(d) => write!(f, "Invalid date: {d}"),
+            ParseError::InvalidThreadFilter(t) => write!(f, "Invalid thread filter: {t}"),
+        }
+    }
+}
+
+enum Lexeme {
+    Word(String),
+    Phrase(String),
+}
+
+impl Query {
+    // Parses the text leniently, if the text contains any invalid filters then the whole text is
+    // treated as free text
+    pub fn parse(free_text: String) -> Query {
+        Query::try_parse(&free_text).unwrap_or_else(|_| Query::free_text(&free_text))
+    }
+
+    pub fn try_parse(text: &str) -> Result<Query, ParseError> {
+        let mut query = Query::default();
+        let mut pending_or = false;
+
+        for (negated, lexeme) in lex(text) {
+            match lexeme {
+                Lexeme::Word(word) if word == "OR" && !negated => {
+                    pending_or = !query.clauses.is_empty();
+                    continue;
+                }
+                Lexeme::Word(word) if negated => query.excluded_terms.extend(index_terms(&word)),
+                Lexeme::Phrase(phrase) if negated => query.excluded_phrases.push(index_terms(&phrase).collect()),
+                Lexeme::Phrase(phrase) => {
+                    let terms: Vec<_> = index_terms(&phrase).collect();
+                    if !terms.is_empty() {
+                        query.tokens.push(Token::new(phrase));
+                        query.phrases.push(terms);
+                    }
+                }
+                Lexeme::Word(word) => {
+                    if let Some((key, value)) = word.split_once(':') {
+                        if query.try_apply_filter(key, value)? {
+                            pending_or = false;
+                            continue;
+                        }
+                    }
+                    query.add_term(word, pending_or);
+                }
+            }
+            pending_or = false;
+        }
+
+        Ok(query)
+    }
+
+    pub fn has_filters(&self) -> bool {
+        !self.users.is_empty()
+            || !self.usernames.is_empty()
+            || !self.message_types.is_empty()
+            || self.since.is_some()
+            || self.until.is_some()
+            || self.thread != ThreadFilter::MainOnly
+    }
+
+    pub fn matches_filters(&self, sender: UserId, message_type: &str, timestamp: TimestampMillis) -> bool {
+        (self.users.is_empty() || self.users.contains(&sender))
+            && (self.message_types.is_empty() || self.message_types.contains(message_type))
+            && self.since.map_or(true, |since| timestamp >= since)
+            && self.until.map_or(true, |until| timestamp < until)
+    }
+
+    // The combined length of the words and phrases being searched for, excluding any filters
+    pub fn free_text_length(&self) -> usize {
+        self.tokens.iter().map(|t| t.value.len()).sum()
+    }
+
+    fn free_text(text: &str) -> Query {
+        let mut query = Query::default();
+        for word in text.split_whitespace() {
+            query.add_term(word.to_string(), false);
+        }
+        query
+    }
+
+    fn add_term(&mut self, word: String, or: bool) {
+        let terms: Vec<_> = index_terms(&word).collect();
+        if terms.is_empty() {
+            return;
+        }
+
+        self.tokens.push(Token::new(word));
+
+        match self.clauses.last_mut() {
+            Some(clause) if or => clause.extend(terms),
+            _ => self.clauses.extend(terms.into_iter().map(|t| vec![t])),
+        }
+    }
+
+    // Returns Ok(false) if the key is not a recognised filter, in which case the word is treated
+    // as free text
+    fn try_apply_filter(&mut self, key: &str, value: &str) -> Result<bool, ParseError> {
+        match key {
+            "from" => {
+                for user in value.split('|').filter(|v| !v.is_empty()) {
+                    let user = user.trim_start_matches('@');
+                    let principal_text = user.strip_prefix("UserId(").and_then(|u| u.strip_suffix(')')).unwrap_or(user);
+
+                    if let Ok(principal) = Principal::from_text(principal_text) {
+                        self.users.insert(principal.into());
+                    } else {
+                        self.usernames.push(user.to_string());
+                    }
+                }
+            }
+            "has" => {
+                for message_type in value.split('|') {
+                    self.message_types.insert(parse_message_type(message_type)?.to_string());
+                }
+            }
+            "before" => self.until = Some(parse_date(value)?),
+            "after" => self.since = Some(parse_date(value)? + DAY_IN_MS),
+            "in" => {
+                self.thread = match value {
+                    "thread" | "threads" => ThreadFilter::ThreadsOnly,
+                    "main" => ThreadFilter::MainOnly,
+                    "any" => ThreadFilter::Any,
+                    _ => value
+                        .strip_prefix("thread:")
+                        .and_then(|root| root.parse::<u32>().ok())
+                        .map(|root| ThreadFilter::Thread(root.into()))
+                        .ok_or_else(|| ParseError::InvalidThreadFilter(value.to_string()))?,
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+fn lex(text: &str) -> Vec<(bool, Lexeme)> {
+    let mut lexemes = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut negated = false;
+        let mut first = c;
+        if c == '-' && chars.peek().map_or(false, |n| !n.is_whitespace()) {
+            negated = true;
+            first = chars.next().unwrap();
+        }
+
+        if first == '"' {
+            let phrase: String = chars.by_ref().take_while(|c| *c != '"').collect();
+            lexemes.push((negated, Lexeme::Phrase(phrase)));
+        } else {
+            let mut word = first.to_string();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                word.push(c);
+            }
+            lexemes.push((negated, Lexeme::Word(word)));
+        }
+    }
+
+    lexemes
+}
+
+// Maps onto the values returned by `MessageContent::message_type`
+fn parse_message_type(value: &str) -> Result<&'static str, ParseError> {
+    match value.to_lowercase().as_str() {
+        "text" => Ok("Text"),
+        "image" => Ok("Image"),
+        "video" => Ok("Video"),
+        "audio" => Ok("Audio"),
+        "file" => Ok("File"),
+        "gif" | "giphy" => Ok("Giphy"),
+        "poll" => Ok("Poll"),
+        "crypto" => Ok("Crypto"),
+        "prize" => Ok("Prize"),
+        "swap" => Ok("P2PSwap"),
+        "proposal" => Ok("GovernanceProposal"),
+        _ => Err(ParseError::UnknownMessageType(value.to_string())),
+    }
+}
+
+// Parses dates in the format YYYY-MM-DD, returning the timestamp of the start of that day (UTC)
+fn parse_date(value: &str) -> Result<TimestampMillis, ParseError> {
+    let invalid = || ParseError::InvalidDate(value.to_string());
+
+    let mut parts = value.splitn(3, '-');
+    let mut next = || parts.next().and_then(|p| p.parse::<u32>().ok()).ok_or_else(invalid);
+    let (year, month, day) = (next()?, next()?, next()?);
+
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(invalid());
+    }
+
+    Ok(days_since_epoch(year, month, day) * DAY_IN_MS)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_since_epoch(year: u32, month: u32, day: u32) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year } as u64;
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month = month as u64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as u64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_filters() {
+        let query = Query::try_parse(
+            "from:@UserId(3skqk-iqaaa-aaaaf-aaa3q-cai) has:image|poll before:2024-03-01 after:2024-02-28 in:thread",
+        )
+        .unwrap();
+
+        assert!(query.tokens.is_empty());
+        assert_eq!(query.users.len(), 1);
+        assert_eq!(
+            query.message_types,
+            HashSet::from_iter(["Image".to_string(), "Poll".to_string()])
+        );
+        assert_eq!(query.until, Some(1709251200000));
+        assert_eq!(query.since, Some(1709164800000));
+        assert_eq!(query.thread, ThreadFilter::ThreadsOnly);
+    }
+
+    #[test]
+    fn parse_terms_phrases_and_negations() {
+        let query = Query::try_parse("cat OR dog food \"hot dog\" -fish -\"cold food\" from:@julian").unwrap();
+
+        assert_eq!(
+            query.clauses,
+            vec![vec!["cat".to_string(), "dog".to_string()], vec!["food".to_string()]]
+        );
+        assert_eq!(query.phrases, vec![vec!["hot".to_string(), "dog".to_string()]]);
+        assert_eq!(query.excluded_terms, vec!["fish".to_string()]);
+        assert_eq!(query.excluded_phrases, vec![vec!["cold".to_string(), "food".to_string()]]);
+        assert_eq!(query.usernames, vec!["julian".to_string()]);
+        assert_eq!(query.tokens.len(), 4);
+    }
+
+    #[test]
+    fn invalid_filters_are_rejected() {
+        assert!(matches!(
+            Query::try_parse("has:banana"),
+            Err(ParseError::UnknownMessageType(_))
+        ));
+        assert!(matches!(
+            Query::try_parse("before:2024-02-30"),
+            Err(ParseError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            Query::try_parse("in:outer-space"),
+            Err(ParseError::InvalidThreadFilter(_))
+        ));
+    }
+
+    #[test]
+    fn parse_specific_thread() {
+        assert_eq!(
+            Query::try_parse("hello in:thread:12").unwrap().thread,
+            ThreadFilter::Thread(12.into())
+        );
+        assert!(matches!(
+            Query::try_parse("in:thread:abc"),
+            Err(ParseError::InvalidThreadFilter(_))
+        ));
+    }
+
+    #[test]
+    fn lenient_parse_falls_back_to_free_text() {
+        let query = Query::parse("has:banana split".to_string());
+
+        assert_eq!(query.tokens.len(), 2);
+        assert!(query.message_types.is_empty());
+    }
+
+    #[test]
+    fn unknown_keys_are_treated_as_free_text() {
+        let query = Query::try_parse("http://example.com").unwrap();
+
+        assert_eq!(query.tokens.len(), 1);
+        assert_eq!(query.clauses.len(), 3);
+    }
+}
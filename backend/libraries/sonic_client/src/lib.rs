@@ -52,6 +52,27 @@ impl SonicClient {
         }
     }
 
+    // Calculated from the pair's reserves using the constant product formula, after Sonic's 0.3% fee
+    pub async fn quote(&self, amount: u128) -> CallResult<u128> {
+        let input_token = self.input_token().ledger;
+        let output_token = self.output_token().ledger;
+
+        let Some(pair) = sonic_canister_c2c_client::get_pair(self.sonic_canister_id, (input_token, output_token))
+            .await?
+            .0
+        else {
+            return Err(convert_error("Pair not found".to_string()));
+        };
+
+        let (reserve_in, reserve_out) = if pair.token0 == input_token.to_string() {
+            (pair.reserve0, pair.reserve1)
+        } else {
+            (pair.reserve1, pair.reserve0)
+        };
+
+        Ok(calculate_amount_out(amount, reserve_in, reserve_out))
+    }
+
     pub async fn swap(&self, amount: u128, min_amount_out: u128) -> CallResult<Result<u128, String>> {
         let args = (
             Nat::from(amount),
@@ -111,6 +132,17 @@ async fn retrieve_subaccount(sonic_canister_id: CanisterId) -> CallResult<[u8; 3
     }
 }
 
+// The constant product formula, after Sonic's 0.3% fee is deducted from the amount in
+fn calculate_amount_out(amount: u128, reserve_in: Nat, reserve_out: Nat) -> u128 {
+    let amount_in_with_fee = Nat::from(amount) * Nat::from(997u32);
+    let denominator = reserve_in * Nat::from(1000u32) + amount_in_with_fee.clone();
+    if denominator == Nat::from(0u32) {
+        0
+    } else {
+        nat_to_u128(amount_in_with_fee * reserve_out / denominator)
+    }
+}
+
 fn nat_to_u128(value: Nat) -> u128 {
     value.0.try_into().unwrap()
 }
@@ -118,3 +150,29 @@ fn nat_to_u128(value: Nat) -> u128 {
 fn convert_error(error: String) -> (RejectionCode, String) {
     (RejectionCode::Unknown, error)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_out_follows_constant_product_after_fee() {
+        // 997_000 * 2_000_000 / (1_000_000_000 + 997_000) = 1992.01...
+        assert_eq!(
+            calculate_amount_out(1_000, Nat::from(1_000_000u32), Nat::from(2_000_000u32)),
+            1_992
+        );
+
+        // Large trades relative to the reserves are subject to high slippage
+        assert_eq!(
+            calculate_amount_out(1_000_000, Nat::from(1_000_000u32), Nat::from(2_000_000u32)),
+            998_497
+        );
+    }
+
+    #[test]
+    fn amount_out_is_zero_without_liquidity() {
+        assert_eq!(calculate_amount_out(0, Nat::from(0u32), Nat::from(0u32)), 0);
+        assert_eq!(calculate_amount_out(1_000, Nat::from(1_000_000u32), Nat::from(0u32)), 0);
+    }
+}
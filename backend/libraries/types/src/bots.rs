@@ -1,6 +1,10 @@
-use crate::{MessageContentInitial, MessageId};
+use crate::{
+    Chat, EventIndex, GroupPermission, Message, MessageContentInitial, MessageId, MessageIndex, Reaction, RoleChanged,
+    TimestampMillis, UserId,
+};
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
 pub struct BotMessage {
@@ -9,3 +13,279 @@ pub struct BotMessage {
     pub message_id: Option<MessageId>,
     pub block_level_markdown: Option<bool>,
 }
+
+// A message returned by a bot in response to a command which could not be sent
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BotMessageFailure {
+    pub message_id: MessageId,
+    pub reason: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SlashCommandSchema {
+    pub name: String,
+    pub description: Option<String>,
+    pub params: Vec<SlashCommandParam>,
+    // Both the user issuing the command and the bot must hold each of these permissions within the group / channel
+    pub permissions: Vec<GroupPermission>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SlashCommandParam {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+    pub param_type: SlashCommandParamType,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SlashCommandParamType {
+    UserParam,
+    BooleanParam,
+    StringParam(StringParam),
+    NumberParam(NumberParam),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StringParam {
+    pub min_length: u16,
+    pub max_length: u16,
+    pub choices: Vec<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NumberParam {
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SlashCommand {
+    pub name: String,
+    pub args: Vec<SlashCommandArg>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct SlashCommandArg {
+    pub name: String,
+    pub value: SlashCommandArgValue,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum SlashCommandArgValue {
+    User(UserId),
+    Boolean(bool),
+    String(String),
+    Number(f64),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct InstalledBotDetails {
+    pub user_id: UserId,
+    pub added_by: UserId,
+    pub commands: Vec<SlashCommandSchema>,
+    pub subscribed_events: Vec<BotEventType>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum BotEventType {
+    Message,
+    MembersJoined,
+    MembersLeft,
+    Reaction,
+    RoleChanged,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BotChatEvent {
+    pub chat: Chat,
+    pub timestamp: TimestampMillis,
+    pub event: BotEvent,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub enum BotEvent {
+    Message(BotMessageEvent),
+    MembersJoined(BotMembersJoinedEvent),
+    MembersLeft(BotMembersLeftEvent),
+    ReactionAdded(BotReactionEvent),
+    ReactionRemoved(BotReactionEvent),
+    RoleChanged(RoleChanged),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BotMessageEvent {
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub event_index: EventIndex,
+    pub message: Message,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BotMembersJoinedEvent {
+    pub user_ids: Vec<UserId>,
+    pub added_by: Option<UserId>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BotMembersLeftEvent {
+    pub user_ids: Vec<UserId>,
+    pub removed_by: Option<UserId>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct BotReactionEvent {
+    pub thread_root_message_index: Option<MessageIndex>,
+    pub message_id: MessageId,
+    pub reaction: Reaction,
+    pub user_id: UserId,
+}
+
+impl BotEvent {
+    pub fn event_type(&self) -> BotEventType {
+        match self {
+            BotEvent::Message(_) => BotEventType::Message,
+            BotEvent::MembersJoined(_) => BotEventType::MembersJoined,
+            BotEvent::MembersLeft(_) => BotEventType::MembersLeft,
+            BotEvent::ReactionAdded(_) | BotEvent::ReactionRemoved(_) => BotEventType::Reaction,
+            BotEvent::RoleChanged(_) => BotEventType::RoleChanged,
+        }
+    }
+
+    // The user whose action caused this event, if any
+    pub fn initiated_by(&self) -> Option<UserId> {
+        match self {
+            BotEvent::Message(e) => Some(e.message.sender),
+            BotEvent::MembersJoined(e) => e.added_by,
+            BotEvent::MembersLeft(e) => e.removed_by,
+            BotEvent::ReactionAdded(e) | BotEvent::ReactionRemoved(e) => Some(e.user_id),
+            BotEvent::RoleChanged(e) => Some(e.changed_by),
+        }
+    }
+}
+
+impl SlashCommandSchema {
+    const MAX_COMMANDS: usize = 100;
+    const MAX_NAME_LENGTH: usize = 32;
+    const MAX_DESCRIPTION_LENGTH: usize = 200;
+    const MAX_PARAMS: usize = 10;
+    const MAX_CHOICES: usize = 25;
+
+    pub fn validate_all(commands: &[SlashCommandSchema]) -> Result<(), String> {
+        if commands.len() > Self::MAX_COMMANDS {
+            return Err(format!("Bots can register at most {} commands", Self::MAX_COMMANDS));
+        }
+
+        let mut names = HashSet::new();
+        for command in commands {
+            command.validate()?;
+
+            if !names.insert(command.name.as_str()) {
+                return Err(format!("Duplicate command: {}", command.name));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name(&self.name)?;
+        validate_description(&self.description)?;
+
+        if self.params.len() > Self::MAX_PARAMS {
+            return Err(format!("Commands can have at most {} parameters", Self::MAX_PARAMS));
+        }
+
+        let mut names = HashSet::new();
+        for param in self.params.iter() {
+            validate_name(&param.name)?;
+            validate_description(&param.description)?;
+
+            if !names.insert(param.name.as_str()) {
+                return Err(format!("Duplicate parameter: {}", param.name));
+            }
+
+            match &param.param_type {
+                SlashCommandParamType::StringParam(p) => {
+                    if p.min_length > p.max_length {
+                        return Err(format!("Invalid length range for parameter: {}", param.name));
+                    }
+                    if p.choices.len() > Self::MAX_CHOICES {
+                        return Err(format!("Too many choices for parameter: {}", param.name));
+                    }
+                }
+                SlashCommandParamType::NumberParam(p) => {
+                    if matches!((p.min_value, p.max_value), (Some(min), Some(max)) if min > max) {
+                        return Err(format!("Invalid value range for parameter: {}", param.name));
+                    }
+                }
+                SlashCommandParamType::UserParam | SlashCommandParamType::BooleanParam => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // Checks that the command's args match the parameters defined by this schema
+    pub fn validate_command(&self, command: &SlashCommand) -> Result<(), String> {
+        let mut provided = HashSet::new();
+
+        for arg in command.args.iter() {
+            let Some(param) = self.params.iter().find(|p| p.name == arg.name) else {
+                return Err(format!("Unknown parameter: {}", arg.name));
+            };
+
+            if !provided.insert(arg.name.as_str()) {
+                return Err(format!("Duplicate parameter: {}", arg.name));
+            }
+
+            let valid = match (&param.param_type, &arg.value) {
+                (SlashCommandParamType::UserParam, SlashCommandArgValue::User(_)) => true,
+                (SlashCommandParamType::BooleanParam, SlashCommandArgValue::Boolean(_)) => true,
+                (SlashCommandParamType::StringParam(p), SlashCommandArgValue::String(s)) => {
+                    let length = s.chars().count();
+                    length >= p.min_length as usize
+                        && length <= p.max_length as usize
+                        && (p.choices.is_empty() || p.choices.contains(s))
+                }
+                (SlashCommandParamType::NumberParam(p), SlashCommandArgValue::Number(n)) => {
+                    n.is_finite() && p.min_value.map_or(true, |min| *n >= min) && p.max_value.map_or(true, |max| *n <= max)
+                }
+                _ => false,
+            };
+
+            if !valid {
+                return Err(format!("Invalid value for parameter: {}", arg.name));
+            }
+        }
+
+        if let Some(missing) = self.params.iter().find(|p| p.required && !provided.contains(p.name.as_str())) {
+            return Err(format!("Missing required parameter: {}", missing.name));
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.len() > SlashCommandSchema::MAX_NAME_LENGTH
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        Err(format!("Invalid name: {name}"))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_description(description: &Option<String>) -> Result<(), String> {
+    if description
+        .as_ref()
+        .map_or(false, |d| d.chars().count() > SlashCommandSchema::MAX_DESCRIPTION_LENGTH)
+    {
+        Err("Description too long".to_string())
+    } else {
+        Ok(())
+    }
+}
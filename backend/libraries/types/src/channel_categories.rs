@@ -0,0 +1,13 @@
+use crate::{AccessGate, ChannelId, GroupPermissions};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct ChannelCategory {
+    pub category_id: u32,
+    pub name: String,
+    pub channels: Vec<ChannelId>,
+    pub collapsed_by_default: bool,
+    pub default_permissions: Option<GroupPermissions>,
+    pub default_gate: Option<AccessGate>,
+}
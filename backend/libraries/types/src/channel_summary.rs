@@ -1,6 +1,7 @@
 use crate::{
-    AccessGate, ChannelId, ChatMetrics, EventIndex, EventWrapper, GroupMembership, GroupMembershipUpdates, GroupPermissions,
-    GroupSubtype, Message, MessageIndex, Milliseconds, OptionUpdate, TimestampMillis, VideoCall,
+    AccessGate, ChannelId, ChannelUserGroupPermissions, ChatMetrics, EventIndex, EventWrapper, GateReverification,
+    GroupMembership, GroupMembershipUpdates, GroupPermissions, GroupSubtype, Message, MessageIndex, Milliseconds, OptionUpdate,
+    SlowMode, TimestampMillis, VideoCall,
 };
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
@@ -28,6 +29,14 @@ pub struct CommunityCanisterChannelSummary {
     pub events_ttl: Option<Milliseconds>,
     pub events_ttl_last_updated: TimestampMillis,
     pub gate: Option<AccessGate>,
+    #[serde(default)]
+    pub slow_mode: Option<SlowMode>,
+    #[serde(default)]
+    pub read_receipts_enabled: bool,
+    #[serde(default)]
+    pub gate_reverification: Option<GateReverification>,
+    #[serde(default)]
+    pub user_group_permissions: ChannelUserGroupPermissions,
     pub membership: Option<GroupMembership>,
     pub video_call_in_progress: Option<VideoCall>,
 }
@@ -53,6 +62,14 @@ pub struct CommunityCanisterChannelSummaryUpdates {
     pub events_ttl: OptionUpdate<Milliseconds>,
     pub events_ttl_last_updated: Option<TimestampMillis>,
     pub gate: OptionUpdate<AccessGate>,
+    #[serde(default)]
+    pub slow_mode: OptionUpdate<SlowMode>,
+    #[serde(default)]
+    pub read_receipts_enabled: Option<bool>,
+    #[serde(default)]
+    pub gate_reverification: OptionUpdate<GateReverification>,
+    #[serde(default)]
+    pub user_group_permissions: Option<ChannelUserGroupPermissions>,
     pub membership: Option<GroupMembershipUpdates>,
     pub video_call_in_progress: OptionUpdate<VideoCall>,
 }
@@ -1,7 +1,7 @@
 use crate::{
-    AccessGate, BuildVersion, CanisterId, ChatId, EventIndex, EventWrapper, FrozenGroupInfo, GroupMember, GroupPermissions,
-    GroupRole, HydratedMention, Message, MessageIndex, Milliseconds, OptionUpdate, TimestampMillis, UserId, Version,
-    MAX_RETURNED_MENTIONS,
+    AccessGate, BuildVersion, CanisterId, ChatId, CustomRole, EventIndex, EventWrapper, FrozenGroupInfo, GateReverification,
+    GroupMember, GroupPermissions, GroupRole, HydratedMention, Message, MessageIndex, Milliseconds, OptionUpdate, SlowMode,
+    TimestampMillis, UserId, Version, MAX_RETURNED_MENTIONS,
 };
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
@@ -147,6 +147,12 @@ pub struct GroupCanisterGroupChatSummary {
     pub events_ttl: Option<Milliseconds>,
     pub events_ttl_last_updated: TimestampMillis,
     pub gate: Option<AccessGate>,
+    #[serde(default)]
+    pub slow_mode: Option<SlowMode>,
+    #[serde(default)]
+    pub read_receipts_enabled: bool,
+    #[serde(default)]
+    pub gate_reverification: Option<GateReverification>,
     pub rules_accepted: bool,
     pub membership: Option<GroupMembership>,
     pub video_call_in_progress: Option<VideoCall>,
@@ -189,6 +195,11 @@ impl GroupCanisterGroupChatSummary {
             my_metrics: updates.my_metrics.unwrap_or(self.my_metrics),
             latest_threads,
             rules_accepted: updates.rules_accepted.unwrap_or(self.rules_accepted),
+            lapsed: updates
+                .membership
+                .as_ref()
+                .map_or(OptionUpdate::NoChange, |m| m.lapsed.clone())
+                .apply_to(self.membership.as_ref().and_then(|m| m.lapsed)),
         };
 
         GroupCanisterGroupChatSummary {
@@ -221,6 +232,9 @@ impl GroupCanisterGroupChatSummary {
             events_ttl: updates.events_ttl.apply_to(self.events_ttl),
             events_ttl_last_updated: updates.events_ttl_last_updated.unwrap_or(self.events_ttl_last_updated),
             gate: updates.gate.apply_to(self.gate),
+            slow_mode: updates.slow_mode.apply_to(self.slow_mode),
+            read_receipts_enabled: updates.read_receipts_enabled.unwrap_or(self.read_receipts_enabled),
+            gate_reverification: updates.gate_reverification.apply_to(self.gate_reverification),
             rules_accepted: membership.rules_accepted,
             membership: Some(membership),
             video_call_in_progress: updates.video_call_in_progress.apply_to(self.video_call_in_progress),
@@ -256,6 +270,12 @@ pub struct GroupCanisterGroupChatSummaryUpdates {
     pub events_ttl: OptionUpdate<Milliseconds>,
     pub events_ttl_last_updated: Option<TimestampMillis>,
     pub gate: OptionUpdate<AccessGate>,
+    #[serde(default)]
+    pub slow_mode: OptionUpdate<SlowMode>,
+    #[serde(default)]
+    pub read_receipts_enabled: Option<bool>,
+    #[serde(default)]
+    pub gate_reverification: OptionUpdate<GateReverification>,
     pub rules_accepted: Option<bool>,
     pub membership: Option<GroupMembershipUpdates>,
     pub video_call_in_progress: OptionUpdate<VideoCall>,
@@ -270,6 +290,8 @@ pub struct GroupMembership {
     pub my_metrics: ChatMetrics,
     pub latest_threads: Vec<GroupCanisterThreadDetails>,
     pub rules_accepted: bool,
+    #[serde(default)]
+    pub lapsed: Option<TimestampMillis>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -281,6 +303,8 @@ pub struct GroupMembershipUpdates {
     pub latest_threads: Vec<GroupCanisterThreadDetails>,
     pub unfollowed_threads: Vec<MessageIndex>,
     pub rules_accepted: Option<bool>,
+    #[serde(default)]
+    pub lapsed: OptionUpdate<TimestampMillis>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug, Default)]
@@ -296,6 +320,8 @@ pub struct SelectedGroupUpdates {
     pub pinned_messages_added: Vec<MessageIndex>,
     pub pinned_messages_removed: Vec<MessageIndex>,
     pub chat_rules: Option<VersionedRules>,
+    #[serde(default)]
+    pub custom_roles: Option<Vec<CustomRole>>,
 }
 
 impl SelectedGroupUpdates {
@@ -308,6 +334,7 @@ impl SelectedGroupUpdates {
             || !self.pinned_messages_added.is_empty()
             || !self.pinned_messages_removed.is_empty()
             || self.chat_rules.is_some()
+            || self.custom_roles.is_some()
     }
 }
 
@@ -10,4 +10,5 @@ pub struct CommunityMember {
     pub date_added: TimestampMillis,
     pub role: CommunityRole,
     pub display_name: Option<String>,
+    pub custom_roles: Vec<u32>,
 }
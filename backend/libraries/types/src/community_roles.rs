@@ -44,6 +44,41 @@ impl Default for CommunityPermissions {
     }
 }
 
+// Identifies a single permission within `CommunityPermissions`
+#[derive(CandidType, Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CommunityPermission {
+    ChangeRoles,
+    UpdateDetails,
+    InviteUsers,
+    RemoveMembers,
+    CreatePublicChannel,
+    CreatePrivateChannel,
+    ManageUserGroups,
+}
+
+// A named role defined within a community. Members holding the role are granted its permissions in addition to those
+// granted by their `CommunityRole`
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CommunityCustomRole {
+    pub id: u32,
+    pub name: String,
+    pub permissions: Vec<CommunityPermission>,
+}
+
+impl CommunityPermissions {
+    pub fn role_for(&self, permission: CommunityPermission) -> CommunityPermissionRole {
+        match permission {
+            CommunityPermission::ChangeRoles => self.change_roles,
+            CommunityPermission::UpdateDetails => self.update_details,
+            CommunityPermission::InviteUsers => self.invite_users,
+            CommunityPermission::RemoveMembers => self.remove_members,
+            CommunityPermission::CreatePublicChannel => self.create_public_channel,
+            CommunityPermission::CreatePrivateChannel => self.create_private_channel,
+            CommunityPermission::ManageUserGroups => self.manage_user_groups,
+        }
+    }
+}
+
 #[derive(CandidType, Serialize, Deserialize, Copy, Clone, Debug)]
 pub enum CommunityPermissionRole {
     Owners,
@@ -108,6 +143,10 @@ impl CommunityRole {
         self.has_owner_rights()
     }
 
+    pub fn has_permission(&self, permission: CommunityPermission, permissions: &CommunityPermissions) -> bool {
+        self.is_permitted(permissions.role_for(permission))
+    }
+
     pub fn is_permitted(&self, permission_role: CommunityPermissionRole) -> bool {
         match permission_role {
             CommunityPermissionRole::Owners => self.has_owner_rights(),
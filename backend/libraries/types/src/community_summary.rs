@@ -1,7 +1,8 @@
 use crate::user_groups::UserGroupSummary;
 use crate::{
-    AccessGate, CanisterId, ChannelId, ChatMetrics, CommunityCanisterChannelSummary, CommunityCanisterChannelSummaryUpdates,
-    CommunityId, CommunityPermissions, CommunityRole, EventIndex, FrozenGroupInfo, OptionUpdate, TimestampMillis,
+    AccessGate, CanisterId, ChannelCategory, ChannelId, ChatMetrics, CommunityCanisterChannelSummary,
+    CommunityCanisterChannelSummaryUpdates, CommunityId, CommunityPermissions, CommunityRole, EventIndex, FrozenGroupInfo,
+    GateReverification, OptionUpdate, TimestampMillis,
 };
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
@@ -20,11 +21,15 @@ pub struct CommunityCanisterCommunitySummary {
     pub permissions: CommunityPermissions,
     pub frozen: Option<FrozenGroupInfo>,
     pub gate: Option<AccessGate>,
+    #[serde(default)]
+    pub gate_reverification: Option<GateReverification>,
     pub primary_language: String,
     pub latest_event_index: EventIndex,
     pub channels: Vec<CommunityCanisterChannelSummary>,
     pub membership: Option<CommunityMembership>,
     pub user_groups: Vec<UserGroupSummary>,
+    #[serde(default)]
+    pub channel_categories: Vec<ChannelCategory>,
     pub metrics: ChatMetrics,
 }
 
@@ -34,6 +39,8 @@ pub struct CommunityMembership {
     pub role: CommunityRole,
     pub rules_accepted: bool,
     pub display_name: Option<String>,
+    #[serde(default)]
+    pub lapsed: Option<TimestampMillis>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -49,6 +56,8 @@ pub struct CommunityCanisterCommunitySummaryUpdates {
     pub permissions: Option<CommunityPermissions>,
     pub frozen: OptionUpdate<FrozenGroupInfo>,
     pub gate: OptionUpdate<AccessGate>,
+    #[serde(default)]
+    pub gate_reverification: OptionUpdate<GateReverification>,
     pub primary_language: Option<String>,
     pub latest_event_index: Option<EventIndex>,
     pub channels_added: Vec<CommunityCanisterChannelSummary>,
@@ -57,6 +66,8 @@ pub struct CommunityCanisterCommunitySummaryUpdates {
     pub membership: Option<CommunityMembershipUpdates>,
     pub user_groups: Vec<UserGroupSummary>,
     pub user_groups_deleted: Vec<u32>,
+    #[serde(default)]
+    pub channel_categories: Option<Vec<ChannelCategory>>,
     pub metrics: Option<ChatMetrics>,
 }
 
@@ -65,4 +76,6 @@ pub struct CommunityMembershipUpdates {
     pub role: Option<CommunityRole>,
     pub rules_accepted: Option<bool>,
     pub display_name: OptionUpdate<String>,
+    #[serde(default)]
+    pub lapsed: OptionUpdate<TimestampMillis>,
 }
@@ -33,6 +33,11 @@ pub enum ChatEvent {
     GroupGateUpdated(GroupGateUpdated),
     UsersInvited(UsersInvited),
     MembersAddedToDefaultChannel(MembersAddedToDefaultChannel),
+    MembersLapsed(MembersLapsed),
+    MembersRemovedByGate(MembersRemovedByGate),
+    MemberMuted(MemberMuted),
+    CustomRoleAssigned(CustomRoleAssigned),
+    CustomRoleUnassigned(CustomRoleUnassigned),
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug)]
@@ -140,6 +145,39 @@ pub struct MembersRemoved {
     pub removed_by: UserId,
 }
 
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct MembersLapsed {
+    pub user_ids: Vec<UserId>,
+    pub grace_period_ends: TimestampMillis,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct MembersRemovedByGate {
+    pub user_ids: Vec<UserId>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct MemberMuted {
+    pub user_id: UserId,
+    pub muted_by: UserId,
+    pub muted_until: TimestampMillis,
+    pub reason: Option<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CustomRoleAssigned {
+    pub role_id: u32,
+    pub user_ids: Vec<UserId>,
+    pub assigned_by: UserId,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct CustomRoleUnassigned {
+    pub role_id: u32,
+    pub user_ids: Vec<UserId>,
+    pub unassigned_by: UserId,
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct UsersBlocked {
     pub user_ids: Vec<UserId>,
@@ -1,4 +1,4 @@
-use crate::{CanisterId, Milliseconds};
+use crate::{CanisterId, ChatId, CommunityId, Milliseconds, UserId};
 use candid::{CandidType, Principal};
 use icrc_ledger_types::icrc2::transfer_from::TransferFromError;
 use serde::{Deserialize, Serialize};
@@ -16,25 +16,96 @@ pub enum AccessGate {
     Payment(PaymentGate),
     TokenBalance(TokenBalanceGate),
     Composite(CompositeGate),
+    AccountAge(AccountAgeGate),
+    Chit(ChitGate),
+    ReferredBy(ReferredByGate),
+    GroupMember(GroupMemberGate),
+    CommunityMember(CommunityMemberGate),
+    Nft(NftGate),
+    Not(Box<AccessGate>),
 }
 
 impl AccessGate {
+    // The maximum number of levels of composite / not gates which can be nested within each other
+    const MAX_DEPTH: usize = 3;
+    const MAX_INNER_GATES: usize = 10;
+    const MAX_TOTAL_GATES: usize = 20;
+
     pub fn validate(&self) -> bool {
-        if let AccessGate::Composite(g) = self {
-            if g.inner.is_empty() || g.inner.len() > 10 {
-                return false;
-            }
-            if g.inner.iter().any(|i| matches!(i, AccessGate::Composite(_))) {
-                return false;
+        self.validate_inner(0)
+            && self.leaf_count() <= Self::MAX_TOTAL_GATES
+            // Users can only be charged once, and only when the payment is needed in order to pass the gate
+            && self.payment_gate_count() <= 1
+    }
+
+    fn validate_inner(&self, depth: usize) -> bool {
+        match self {
+            AccessGate::Composite(g) => {
+                depth < Self::MAX_DEPTH
+                    && !g.inner.is_empty()
+                    && g.inner.len() <= Self::MAX_INNER_GATES
+                    && g.inner.iter().all(|i| i.validate_inner(depth + 1))
             }
+            AccessGate::Not(g) => depth < Self::MAX_DEPTH && !g.contains_payment_gate() && g.validate_inner(depth + 1),
+            AccessGate::Chit(g) => g.min_balance.is_some() || g.min_streak.is_some(),
+            AccessGate::ReferredBy(g) => !g.user_ids.is_empty() && g.user_ids.len() <= 100,
+            AccessGate::Nft(g) => g.min_count > 0,
+            _ => true,
+        }
+    }
+
+    fn leaf_count(&self) -> usize {
+        match self {
+            AccessGate::Composite(g) => g.inner.iter().map(|i| i.leaf_count()).sum(),
+            AccessGate::Not(g) => g.leaf_count(),
+            _ => 1,
+        }
+    }
+
+    fn payment_gate_count(&self) -> usize {
+        match self {
+            AccessGate::Composite(g) => g.inner.iter().map(|i| i.payment_gate_count()).sum(),
+            AccessGate::Not(g) => g.payment_gate_count(),
+            AccessGate::Payment(_) => 1,
+            _ => 0,
+        }
+    }
+
+    // Gates which can be checked without any input from the user, meaning members can be periodically re-verified
+    pub fn can_be_reverified(&self) -> bool {
+        match self {
+            AccessGate::SnsNeuron(_)
+            | AccessGate::TokenBalance(_)
+            | AccessGate::AccountAge(_)
+            | AccessGate::Chit(_)
+            | AccessGate::ReferredBy(_)
+            | AccessGate::GroupMember(_)
+            | AccessGate::CommunityMember(_)
+            | AccessGate::Nft(_) => true,
+            AccessGate::Composite(g) => g.inner.iter().all(|i| i.can_be_reverified()),
+            AccessGate::Not(g) => g.can_be_reverified(),
+            _ => false,
+        }
+    }
+
+    // Payment and verified credential gates can only be passed with input from the user
+    pub fn requires_user_input(&self) -> bool {
+        match self {
+            AccessGate::Payment(_) | AccessGate::VerifiedCredential(_) => true,
+            AccessGate::Composite(g) => g.inner.iter().any(|i| i.requires_user_input()),
+            AccessGate::Not(g) => g.requires_user_input(),
+            _ => false,
         }
-        true
     }
 
     pub fn is_payment_gate(&self) -> bool {
         matches!(self, AccessGate::Payment(_))
     }
 
+    pub fn contains_payment_gate(&self) -> bool {
+        self.payment_gate_count() > 0
+    }
+
     pub fn gate_type(&self) -> &'static str {
         match self {
             AccessGate::DiamondMember => "diamond",
@@ -45,10 +116,33 @@ impl AccessGate {
             AccessGate::Payment(_) => "payment",
             AccessGate::TokenBalance(_) => "token_balance",
             AccessGate::Composite(_) => "composite",
+            AccessGate::AccountAge(_) => "account_age",
+            AccessGate::Chit(_) => "chit",
+            AccessGate::ReferredBy(_) => "referred_by",
+            AccessGate::GroupMember(_) => "group_member",
+            AccessGate::CommunityMember(_) => "community_member",
+            AccessGate::Nft(_) => "nft",
+            AccessGate::Not(_) => "not",
         }
     }
 }
 
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GateReverification {
+    pub interval: Milliseconds,
+    // If set, members who fail the gate are marked as lapsed and are only removed if they still fail the gate once
+    // the grace period has passed. If not set, members who fail the gate are removed immediately.
+    pub grace_period: Option<Milliseconds>,
+}
+
+impl GateReverification {
+    const MIN_INTERVAL: Milliseconds = 24 * 60 * 60 * 1000; // 1 day
+
+    pub fn validate(&self) -> bool {
+        self.interval >= Self::MIN_INTERVAL
+    }
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct VerifiedCredentialGate {
     pub issuer_canister_id: CanisterId,
@@ -90,6 +184,38 @@ pub struct CompositeGate {
     pub and: bool,
 }
 
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct AccountAgeGate {
+    pub min_age: Milliseconds,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ChitGate {
+    pub min_balance: Option<u32>,
+    pub min_streak: Option<u16>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ReferredByGate {
+    pub user_ids: Vec<UserId>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct GroupMemberGate {
+    pub group_id: ChatId,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CommunityMemberGate {
+    pub community_id: CommunityId,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct NftGate {
+    pub ledger_canister_id: CanisterId,
+    pub min_count: u32,
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub enum GateCheckFailedReason {
     NotDiamondMember,
@@ -101,6 +227,14 @@ pub enum GateCheckFailedReason {
     PaymentFailed(TransferFromError),
     InsufficientBalance(u128),
     FailedVerifiedCredentialCheck(String),
+    AccountTooNew,
+    InsufficientChitBalance(i32),
+    InsufficientChitStreak(u16),
+    NotReferredByRequiredUser,
+    NotGroupMember,
+    NotCommunityMember,
+    InsufficientNfts(u32),
+    NegatedGatePassed,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
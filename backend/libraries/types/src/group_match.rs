@@ -37,4 +37,6 @@ pub struct ChannelMatch {
     pub member_count: u32,
     pub gate: Option<AccessGate>,
     pub subtype: Option<GroupSubtype>,
+    #[serde(default)]
+    pub category_id: Option<u32>,
 }
@@ -9,4 +9,8 @@ pub struct GroupMember {
     pub user_id: UserId,
     pub date_added: TimestampMillis,
     pub role: GroupRole,
+    #[serde(default)]
+    pub muted_until: Option<TimestampMillis>,
+    #[serde(default)]
+    pub custom_roles: Vec<u32>,
 }
@@ -151,3 +151,61 @@ impl GroupPermissionRole {
         *self as usize
     }
 }
+
+// Identifies a single permission within `GroupPermissions`, allowing other types (eg. bot commands) to refer to the
+// permissions they require
+#[derive(CandidType, Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GroupPermission {
+    ChangeRoles,
+    UpdateGroup,
+    AddMembers,
+    InviteUsers,
+    RemoveMembers,
+    DeleteMessages,
+    PinMessages,
+    ReactToMessages,
+    MentionAllMembers,
+    StartVideoCall,
+}
+
+// Identifies a single type of message within `MessagePermissions`
+#[derive(CandidType, Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MessagePermission {
+    Text,
+    Image,
+    Video,
+    Audio,
+    File,
+    Poll,
+    Crypto,
+    Giphy,
+    Prize,
+    P2pSwap,
+}
+
+// A named role defined within a group or channel. Members holding the role are granted its permissions in addition to
+// those granted by their `GroupRole`
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CustomRole {
+    pub id: u32,
+    pub name: String,
+    pub permissions: Vec<GroupPermission>,
+    pub message_permissions: Vec<MessagePermission>,
+}
+
+impl GroupPermissions {
+    pub fn role_for(&self, permission: GroupPermission) -> GroupPermissionRole {
+        match permission {
+            GroupPermission::ChangeRoles => self.change_roles,
+            GroupPermission::UpdateGroup => self.update_group,
+            GroupPermission::AddMembers => self.add_members,
+            GroupPermission::InviteUsers => self.invite_users,
+            GroupPermission::RemoveMembers => self.remove_members,
+            GroupPermission::DeleteMessages => self.delete_messages,
+            GroupPermission::PinMessages => self.pin_messages,
+            GroupPermission::ReactToMessages => self.react_to_messages,
+            GroupPermission::MentionAllMembers => self.mention_all_members,
+            GroupPermission::StartVideoCall => self.start_video_call,
+        }
+    }
+}
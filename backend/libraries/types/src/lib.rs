@@ -10,6 +10,7 @@ mod bots;
 mod build_version;
 mod canister_upgrade_status;
 mod canister_wasm;
+mod channel_categories;
 mod channel_summary;
 mod chat;
 mod chat_id;
@@ -60,6 +61,7 @@ mod reactions;
 mod referral_codes;
 mod registration_fee;
 mod relayed_args;
+mod slow_mode;
 mod source_group;
 mod subscription;
 mod suspension;
@@ -81,6 +83,7 @@ pub use bots::*;
 pub use build_version::*;
 pub use canister_upgrade_status::*;
 pub use canister_wasm::*;
+pub use channel_categories::*;
 pub use channel_summary::*;
 pub use chat::*;
 pub use chat_id::*;
@@ -131,6 +134,7 @@ pub use reactions::*;
 pub use referral_codes::*;
 pub use registration_fee::*;
 pub use relayed_args::*;
+pub use slow_mode::*;
 pub use source_group::*;
 pub use subscription::*;
 pub use suspension::*;
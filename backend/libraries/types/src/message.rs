@@ -1,4 +1,6 @@
-use crate::{CanisterId, Chat, EventIndex, MessageContent, MessageId, MessageIndex, Reaction, ThreadSummary, UserId};
+use crate::{
+    CanisterId, Chat, EventIndex, MessageContent, MessageId, MessageIndex, Reaction, ThreadSummary, TimestampMillis, UserId,
+};
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
@@ -19,6 +21,14 @@ pub struct Message {
     pub block_level_markdown: bool,
 }
 
+// A previous version of a message which has since been edited
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct MessageVersion {
+    pub content: MessageContent,
+    pub block_level_markdown: bool,
+    pub replaced_at: TimestampMillis,
+}
+
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct ReplyContext {
     pub chat_if_other: Option<(Chat, Option<MessageIndex>)>,
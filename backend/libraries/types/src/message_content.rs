@@ -1,6 +1,6 @@
-use crate::polls::{InvalidPollReason, PollConfig, PollVotes};
+use crate::polls::{InvalidPollReason, PollConfig, PollResults, PollVotes};
 use crate::{
-    CanisterId, CompletedCryptoTransaction, CryptoTransaction, CryptoTransferDetails, Cryptocurrency, MessageIndex,
+    AccessGate, CanisterId, CompletedCryptoTransaction, CryptoTransaction, CryptoTransferDetails, Cryptocurrency, MessageIndex,
     Milliseconds, P2PSwapAccepted, P2PSwapCancelled, P2PSwapCompleted, P2PSwapExpired, P2PSwapReserved, P2PSwapStatus,
     ProposalContent, TimestampMillis, TokenInfo, TotalVotes, User, UserId, VideoCallType,
 };
@@ -61,6 +61,7 @@ pub enum ContentValidationError {
     TransferCannotBeZero,
     InvalidTypeForForwarding,
     PrizeEndDateInThePast,
+    InvalidPrizeGate,
     Unauthorized,
 }
 
@@ -249,6 +250,9 @@ impl MessageContentInitial {
                 if p.end_date <= now {
                     return Err(ContentValidationError::PrizeEndDateInThePast);
                 }
+                if p.gate.as_ref().map_or(false, |g| !g.validate() || g.requires_user_input()) {
+                    return Err(ContentValidationError::InvalidPrizeGate);
+                }
             }
             MessageContentInitial::P2PSwap(_) => {
                 if sender_is_bot {
@@ -369,6 +373,11 @@ impl From<MessageContentInitial> for MessageContent {
                 caption: c.caption,
                 prizes_pending: 0,
                 diamond_only: c.diamond_only,
+                quiz_message_index: c.quiz_message_index,
+                draw: c.draw,
+                gate: c.gate,
+                entrants: 0,
+                entered: false,
             }),
             MessageContentInitial::MessageReminderCreated(r) => MessageContent::MessageReminderCreated(r),
             MessageContentInitial::MessageReminder(r) => MessageContent::MessageReminder(r),
@@ -447,12 +456,14 @@ pub struct PollContent {
     pub config: PollConfig,
     pub votes: PollVotes,
     pub ended: bool,
+    #[serde(default)]
+    pub results: Option<PollResults>,
 }
 
 impl PollContent {
     pub fn initialize_votes(&mut self) {
         let total_votes: TotalVotes;
-        if self.config.end_date.is_some() && !self.config.show_votes_before_end_date {
+        if self.config.end_date.is_some() && (!self.config.show_votes_before_end_date || self.config.is_quiz()) {
             total_votes = TotalVotes::Hidden(0);
         } else if self.config.anonymous {
             total_votes = TotalVotes::Anonymous(HashMap::new());
@@ -473,6 +484,7 @@ pub enum RegisterVoteResult {
     PollEnded,
     UserCannotChangeVote,
     OptionIndexOutOfRange,
+    InvalidRanking,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -489,6 +501,15 @@ pub struct PrizeContentInitial {
     pub end_date: TimestampMillis,
     pub caption: Option<String>,
     pub diamond_only: bool,
+    // If set, only users who correctly answered the quiz in this message can claim a prize
+    #[serde(default)]
+    pub quiz_message_index: Option<MessageIndex>,
+    // If true, users enter a draw until the end date, at which point the winners are picked at random
+    #[serde(default)]
+    pub draw: bool,
+    // If set, users must pass this gate in order to claim a prize or enter the draw
+    #[serde(default)]
+    pub gate: Option<AccessGate>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -500,6 +521,18 @@ pub struct PrizeContent {
     pub end_date: TimestampMillis,
     pub caption: Option<String>,
     pub diamond_only: bool,
+    #[serde(default)]
+    pub quiz_message_index: Option<MessageIndex>,
+    #[serde(default)]
+    pub draw: bool,
+    #[serde(default)]
+    pub gate: Option<AccessGate>,
+    // The number of users who have entered the draw
+    #[serde(default)]
+    pub entrants: u32,
+    // Whether the current user has entered the draw
+    #[serde(default)]
+    pub entered: bool,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
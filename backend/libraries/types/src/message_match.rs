@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
 pub struct MessageMatch {
+    pub thread_root_message_index: Option<MessageIndex>,
     pub sender: UserId,
     pub message_index: MessageIndex,
     pub content: MessageContent,
@@ -22,7 +22,7 @@ impl NotificationEnvelope {
     }
 }
 
-#[derive(CandidType, Serialize)]
+#[derive(CandidType, Serialize, Deserialize, Clone)]
 pub enum Notification {
     AddedToChannel(AddedToChannelNotification),
     DirectMessage(DirectMessageNotification),
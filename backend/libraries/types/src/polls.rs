@@ -1,4 +1,4 @@
-use crate::{TimestampMillis, UserId};
+use crate::{CanisterId, TimestampMillis, UserId};
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -12,6 +12,42 @@ pub struct PollConfig {
     pub show_votes_before_end_date: bool,
     pub allow_multiple_votes_per_user: bool,
     pub allow_user_to_change_vote: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voting_system: Option<PollVotingSystem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vote_weighting: Option<VoteWeighting>,
+    // If set, the poll is a quiz. The correct options are hidden (left empty) until the poll has ended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct_options: Option<Vec<u32>>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum PollVotingSystem {
+    // Each vote counts towards a single option (or several if `allow_multiple_votes_per_user` is set)
+    #[default]
+    Plurality,
+    // Users can approve any number of options
+    Approval,
+    // Users rank the options and the winner is determined by instant-runoff
+    RankedChoice,
+}
+
+// If set, each vote is weighted by the voter's holdings at the time the poll closes
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum VoteWeighting {
+    TokenBalance(CanisterId),
+    SnsNeuronStake(CanisterId),
+}
+
+// The outcome of a poll, calculated once it ends
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PollResults {
+    // The (weighted) tally for each option. For ranked-choice polls this is the tally of the final round
+    pub tallies: HashMap<u32, u128>,
+    // For ranked-choice polls, the options in the order in which they were eliminated
+    pub eliminated: Vec<u32>,
+    // `None` if there were no votes or the top options were tied
+    pub winner: Option<u32>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
@@ -35,11 +71,56 @@ impl PollConfig {
             Err(InvalidPollReason::DuplicateOptions)
         } else if self.end_date.unwrap_or(u64::MAX) < now {
             Err(InvalidPollReason::EndDateInThePast)
+        } else if self.end_date.is_none()
+            && (self.voting_system() == PollVotingSystem::RankedChoice || self.vote_weighting.is_some() || self.is_quiz())
+        {
+            Err(InvalidPollReason::EndDateRequired)
+        } else if !self.correct_options_valid() {
+            Err(InvalidPollReason::InvalidCorrectOptions)
         } else {
             Ok(())
         }
     }
 
+    pub fn is_quiz(&self) -> bool {
+        self.correct_options.is_some()
+    }
+
+    // A user answers a quiz correctly if they vote for exactly the correct options or, if they can only vote for a
+    // single option, if they vote for any one of the correct options
+    pub fn is_correct_answer(&self, options_voted_for: &HashSet<u32>) -> bool {
+        let Some(correct_options) = self.correct_options.as_ref() else {
+            return false;
+        };
+
+        if self.allows_multiple_votes_per_user() {
+            options_voted_for.len() == correct_options.len() && correct_options.iter().all(|o| options_voted_for.contains(o))
+        } else {
+            options_voted_for.len() == 1 && options_voted_for.iter().all(|o| correct_options.contains(o))
+        }
+    }
+
+    fn correct_options_valid(&self) -> bool {
+        let Some(correct_options) = self.correct_options.as_ref() else {
+            return true;
+        };
+
+        let mut set = HashSet::new();
+        self.voting_system() != PollVotingSystem::RankedChoice
+            && !correct_options.is_empty()
+            && correct_options
+                .iter()
+                .all(|o| (*o as usize) < self.options.len() && set.insert(*o))
+    }
+
+    pub fn allows_multiple_votes_per_user(&self) -> bool {
+        self.allow_multiple_votes_per_user || self.voting_system() == PollVotingSystem::Approval
+    }
+
+    pub fn voting_system(&self) -> PollVotingSystem {
+        self.voting_system.unwrap_or_default()
+    }
+
     fn contains_duplicate_options(&self) -> bool {
         let mut set = HashSet::new();
         self.options.iter().any(|o| !set.insert(o))
@@ -70,5 +151,14 @@ pub enum InvalidPollReason {
     OptionTooLong(u32),
     DuplicateOptions,
     EndDateInThePast,
+    EndDateRequired,
+    InvalidCorrectOptions,
     PollsNotValidForDirectChats,
 }
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct QuizLeaderboardEntry {
+    pub user_id: UserId,
+    pub correct_answers: u32,
+    pub quizzes_answered: u32,
+}
@@ -0,0 +1,26 @@
+use crate::Milliseconds;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SlowMode {
+    // The minimum interval between messages sent by each member
+    pub min_interval: Option<Milliseconds>,
+    // The maximum number of messages each member can send within any one minute period
+    pub max_messages_per_minute: Option<u32>,
+    // If true, moderators, admins and owners are not subject to slow mode
+    pub exempt_moderators: bool,
+}
+
+impl SlowMode {
+    const MAX_INTERVAL: Milliseconds = 24 * 60 * 60 * 1000; // 1 day
+
+    pub fn validate(&self) -> bool {
+        if self.min_interval.is_none() && self.max_messages_per_minute.is_none() {
+            return false;
+        }
+
+        self.min_interval.map_or(true, |i| i > 0 && i <= Self::MAX_INTERVAL)
+            && self.max_messages_per_minute.map_or(true, |m| m > 0)
+    }
+}
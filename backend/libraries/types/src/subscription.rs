@@ -5,6 +5,19 @@ use serde::{Deserialize, Serialize};
 pub struct SubscriptionInfo {
     pub endpoint: String,
     pub keys: SubscriptionKeys,
+    // If not set then defaults to Web Push. For native push services the endpoint holds the device's registration token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_service: Option<PushService>,
+    // If true, message notifications are held back and sent periodically as a digest, one per chat
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_mode: Option<bool>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PushService {
+    #[default]
+    WebPush,
+    Fcm,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -14,6 +27,23 @@ pub struct SubscriptionKeys {
 }
 
 impl SubscriptionInfo {
+    pub fn push_service(&self) -> PushService {
+        self.push_service.unwrap_or_default()
+    }
+
+    // Identifies the subscription within the user's subscriptions. Web Push subscriptions are identified by their p256dh
+    // key, but native push subscriptions don't have one, so they are identified by their registration token instead.
+    pub fn key(&self) -> &str {
+        match self.push_service() {
+            PushService::WebPush => &self.keys.p256dh,
+            PushService::Fcm => &self.endpoint,
+        }
+    }
+
+    pub fn digest_mode(&self) -> bool {
+        self.digest_mode.unwrap_or_default()
+    }
+
     pub fn approx_size(&self) -> usize {
         self.endpoint.len() + self.keys.approx_size() + 24
     }
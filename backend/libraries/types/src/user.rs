@@ -1,4 +1,4 @@
-use crate::CanisterId;
+use crate::{CanisterId, TimestampMillis, UniquePersonProof};
 use candid::{CandidType, Principal};
 use icrc_ledger_types::icrc1::account::Account;
 use serde::{Deserialize, Serialize};
@@ -61,4 +61,16 @@ pub struct UserDetails {
     pub is_platform_moderator: bool,
     pub is_platform_operator: bool,
     pub is_diamond_member: bool,
+    #[serde(default)]
+    pub date_created: TimestampMillis,
+    #[serde(default)]
+    pub chit_balance: i32,
+    #[serde(default)]
+    pub streak: u16,
+    #[serde(default)]
+    pub referred_by: Option<UserId>,
+    #[serde(default)]
+    pub diamond_membership_expires_at: Option<TimestampMillis>,
+    #[serde(default)]
+    pub unique_person_proof: Option<UniquePersonProof>,
 }
@@ -15,3 +15,43 @@ pub struct UserGroupDetails {
     pub name: String,
     pub members: Vec<UserId>,
 }
+
+// Allows community user groups to be used as permission subjects within a channel. Each field is a list of
+// user group ids, and an empty list means that the rule is not in use.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ChannelUserGroupPermissions {
+    // If not empty, only members of these user groups (plus channel moderators and above) can send messages
+    pub send_messages: Vec<u32>,
+    // Members of these user groups can see and join the channel even if it is private
+    pub view: Vec<u32>,
+    // Members of these user groups are not subject to the channel's slow mode
+    pub exempt_from_slow_mode: Vec<u32>,
+    // Members of these user groups are automatically added to the channel
+    pub sync_membership: Vec<u32>,
+}
+
+impl ChannelUserGroupPermissions {
+    pub fn user_group_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.send_messages
+            .iter()
+            .chain(self.view.iter())
+            .chain(self.exempt_from_slow_mode.iter())
+            .chain(self.sync_membership.iter())
+            .copied()
+    }
+
+    pub fn remove_user_group(&mut self, user_group_id: u32) -> bool {
+        let mut removed = false;
+        for ids in [
+            &mut self.send_messages,
+            &mut self.view,
+            &mut self.exempt_from_slow_mode,
+            &mut self.sync_membership,
+        ] {
+            let original_len = ids.len();
+            ids.retain(|id| *id != user_group_id);
+            removed |= ids.len() != original_len;
+        }
+        removed
+    }
+}
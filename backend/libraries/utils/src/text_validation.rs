@@ -10,6 +10,8 @@ const MAX_GROUP_DESCRIPTION_LENGTH: u32 = 1024;
 const MAX_GROUP_RULES_LENGTH: u32 = 1024;
 const MIN_USER_GROUP_NAME_LENGTH: u32 = 3;
 const MAX_USER_GROUP_NAME_LENGTH: u32 = 25;
+const MIN_CHANNEL_CATEGORY_NAME_LENGTH: u32 = 1;
+const MAX_CHANNEL_CATEGORY_NAME_LENGTH: u32 = 40;
 
 const RESERVED_GROUP_NAMES: [&str; 8] = [
     "channel",
@@ -123,6 +125,14 @@ pub fn validate_user_group_name(name: &str) -> Result<(), UsernameValidationErro
     }
 }
 
+pub fn validate_channel_category_name(name: &str) -> Result<(), StringLengthValidationError> {
+    validate_string_length(
+        name.trim(),
+        MIN_CHANNEL_CATEGORY_NAME_LENGTH,
+        MAX_CHANNEL_CATEGORY_NAME_LENGTH,
+    )
+}
+
 pub fn validate_description(description: &str) -> Result<(), FieldTooLongResult> {
     validate_string_length(description, 0, MAX_GROUP_DESCRIPTION_LENGTH).map_err(|e| match e {
         StringLengthValidationError::TooLong(f) => f,
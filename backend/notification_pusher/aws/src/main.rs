@@ -1,6 +1,7 @@
 use aws_config::BehaviorVersion;
 use candid::Principal;
 use dynamodb_index_store::DynamoDbIndexStore;
+use notification_pusher_core::backends::FcmConfig;
 use notification_pusher_core::ic_agent::IcAgent;
 use notification_pusher_core::run_notifications_pusher;
 use std::str::FromStr;
@@ -15,6 +16,11 @@ async fn main() -> Result<(), Error> {
     info!("Starting...");
 
     let vapid_private_pem = dotenv::var("VAPID_PRIVATE_PEM")?;
+    // The JSON contents of the Firebase service account key, used to mint the access tokens needed to push to FCM
+    let fcm_config = match dotenv::var("FCM_SERVICE_ACCOUNT_KEY") {
+        Ok(key) => Some(FcmConfig::from_service_account_key(&key)?),
+        Err(_) => None,
+    };
     let index_canister_id = Principal::from_text(dotenv::var("NOTIFICATIONS_INDEX_CANISTER_ID")?).unwrap();
     let notifications_canister_ids_string = dotenv::var("NOTIFICATIONS_CANISTER_IDS")?;
     let ic_url = dotenv::var("IC_URL")?;
@@ -41,6 +47,7 @@ async fn main() -> Result<(), Error> {
         notifications_canister_ids,
         dynamodb_index_store,
         vapid_private_pem,
+        fcm_config,
         10,
     )
     .await;
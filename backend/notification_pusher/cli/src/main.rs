@@ -1,11 +1,13 @@
 use candid::Principal;
-use index_store::DummyStore;
+use file_index_store::FileIndexStore;
+use index_store::{DummyStore, IndexStore};
+use notification_pusher_core::backends::FcmConfig;
 use notification_pusher_core::ic_agent::IcAgent;
 use notification_pusher_core::run_notifications_pusher;
 use std::collections::HashMap;
 use std::str::FromStr;
 use tracing::info;
-use types::Error;
+use types::{CanisterId, Error};
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -17,15 +19,62 @@ async fn main() -> Result<(), Error> {
     let args: Vec<String> = std::env::args().collect();
     let index = args.get(1).map(|a| a.parse::<u64>().unwrap()).unwrap_or_default();
     let vapid_private_pem = dotenv::var("VAPID_PRIVATE_PEM")?;
+    // The JSON contents of the Firebase service account key, used to mint the access tokens needed to push to FCM
+    let fcm_config = match dotenv::var("FCM_SERVICE_ACCOUNT_KEY") {
+        Ok(key) => Some(FcmConfig::from_service_account_key(&key)?),
+        Err(_) => None,
+    };
     let index_canister_id = Principal::from_text(dotenv::var("NOTIFICATIONS_INDEX_CANISTER_ID")?)?;
     let notifications_canister_id = Principal::from_text(dotenv::var("NOTIFICATIONS_CANISTER_ID")?)?;
-    let index_store = DummyStore::new(HashMap::from([(notifications_canister_id, index)]));
     let ic_url = dotenv::var("IC_URL")?;
     let ic_identity_pem = dotenv::var("IC_IDENTITY_PEM")?;
     let is_production = bool::from_str(&dotenv::var("IS_PRODUCTION")?).unwrap();
 
     let ic_agent = IcAgent::build(&ic_url, &ic_identity_pem, !is_production).await?;
 
+    // If a path is provided, the indexes processed up to are persisted to that file so that the pusher resumes from
+    // where it left off after a restart, otherwise they are only held in memory
+    match dotenv::var("INDEX_STORE_PATH") {
+        Ok(path) => {
+            let index_store = FileIndexStore::open(path).await?;
+            if index_store.get(notifications_canister_id).await?.is_none() && index > 0 {
+                index_store.set(notifications_canister_id, index).await?;
+            }
+            run(
+                ic_agent,
+                index_canister_id,
+                notifications_canister_id,
+                index_store,
+                vapid_private_pem,
+                fcm_config,
+            )
+            .await;
+        }
+        Err(_) => {
+            let index_store = DummyStore::new(HashMap::from([(notifications_canister_id, index)]));
+            run(
+                ic_agent,
+                index_canister_id,
+                notifications_canister_id,
+                index_store,
+                vapid_private_pem,
+                fcm_config,
+            )
+            .await;
+        }
+    }
+
+    Ok(())
+}
+
+async fn run<I: IndexStore + 'static>(
+    ic_agent: IcAgent,
+    index_canister_id: CanisterId,
+    notifications_canister_id: CanisterId,
+    index_store: I,
+    vapid_private_pem: String,
+    fcm_config: Option<FcmConfig>,
+) {
     info!("Initialization complete");
 
     run_notifications_pusher(
@@ -34,9 +83,8 @@ async fn main() -> Result<(), Error> {
         vec![notifications_canister_id],
         index_store,
         vapid_private_pem,
+        fcm_config,
         1,
     )
     .await;
-
-    Ok(())
 }
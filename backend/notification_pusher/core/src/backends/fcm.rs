@@ -0,0 +1,417 @@
+use crate::backends::{PushBackend, PushError};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Private};
+use openssl::sign::Signer;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use types::{Error, SubscriptionInfo};
+
+pub(super) const MAX_PAYLOAD_LENGTH_BYTES: usize = 4 * 1000; // Just under 4KB
+
+const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+// Google issues access tokens which are valid for an hour, each one is replaced this long before it expires
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+pub struct FcmConfig {
+    // The 'messages:send' endpoint of the FCM HTTP v1 API
+    url: String,
+    service_account: ServiceAccount,
+    access_token: Mutex<Option<AccessToken>>,
+}
+
+// The fields we need from a Google service account key file
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+struct ServiceAccount {
+    client_email: String,
+    private_key: PKey<Private>,
+    token_uri: String,
+}
+
+struct AccessToken {
+    token: String,
+    expires_at: SystemTime,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl FcmConfig {
+    // Builds the config from the JSON contents of a service account key file, which is used to mint the OAuth access
+    // tokens needed to call FCM
+    pub fn from_service_account_key(key_json: &str) -> Result<FcmConfig, Error> {
+        let key: ServiceAccountKey = serde_json::from_str(key_json)?;
+        let url = format!("https://fcm.googleapis.com/v1/projects/{}/messages:send", key.project_id);
+
+        FcmConfig::new(url, key)
+    }
+
+    fn new(url: String, key: ServiceAccountKey) -> Result<FcmConfig, Error> {
+        Ok(FcmConfig {
+            url,
+            service_account: ServiceAccount {
+                client_email: key.client_email,
+                private_key: PKey::private_key_from_pem(key.private_key.as_bytes())?,
+                token_uri: key.token_uri,
+            },
+            access_token: Mutex::default(),
+        })
+    }
+
+    // Returns the current access token, minting a new one if there is none or it is about to expire. The lock is held
+    // while refreshing so that concurrent pushes wait for a single refresh rather than each making their own.
+    async fn access_token(&self, client: &Client) -> Result<String, Error> {
+        let mut access_token = self.access_token.lock().await;
+        let now = SystemTime::now();
+
+        if let Some(token) = access_token.as_ref().filter(|t| now + TOKEN_REFRESH_MARGIN < t.expires_at) {
+            return Ok(token.token.clone());
+        }
+
+        let new_token = self.mint_access_token(client, now).await?;
+        let token = new_token.token.clone();
+        *access_token = Some(new_token);
+        Ok(token)
+    }
+
+    // Exchanges a JWT signed with the service account's private key for an access token
+    async fn mint_access_token(&self, client: &Client, now: SystemTime) -> Result<AccessToken, Error> {
+        let assertion = self.service_account.signed_jwt(now)?;
+
+        let response = client
+            .post(&self.service_account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", &assertion),
+            ])
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(format!("Failed to get FCM access token. Status: {status}. Response: {text}").into());
+        }
+
+        let response: TokenResponse = serde_json::from_str(&text)?;
+        Ok(AccessToken {
+            token: response.access_token,
+            expires_at: now + Duration::from_secs(response.expires_in),
+        })
+    }
+}
+
+impl ServiceAccount {
+    fn signed_jwt(&self, now: SystemTime) -> Result<String, Error> {
+        let issued_at = now.duration_since(UNIX_EPOCH)?.as_secs();
+
+        let header = json!({ "alg": "RS256", "typ": "JWT" });
+        let claims = json!({
+            "iss": self.client_email,
+            "scope": FCM_SCOPE,
+            "aud": self.token_uri,
+            "iat": issued_at,
+            "exp": issued_at + 3600,
+        });
+
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.private_key)?;
+        signer.update(message.as_bytes())?;
+        let signature = signer.sign_to_vec()?;
+
+        Ok(format!("{message}.{}", URL_SAFE_NO_PAD.encode(signature)))
+    }
+}
+
+// Pushes notifications to native mobile apps via the FCM HTTP v1 API. The subscription's endpoint holds the device's
+// registration token.
+pub struct FcmBackend {
+    client: Client,
+    config: FcmConfig,
+}
+
+impl FcmBackend {
+    pub fn new(config: FcmConfig) -> FcmBackend {
+        FcmBackend {
+            client: Client::new(),
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl PushBackend for FcmBackend {
+    async fn push(&self, payload: &[u8], subscription: &SubscriptionInfo) -> Result<(), PushError> {
+        if payload.len() > MAX_PAYLOAD_LENGTH_BYTES {
+            return Err(PushError::MaxLengthExceeded(payload.len()));
+        }
+
+        let body = json!({
+            "message": {
+                "token": subscription.endpoint,
+                "data": {
+                    "payload": String::from_utf8_lossy(payload),
+                },
+                "android": {
+                    "priority": "high",
+                    "ttl": "3600s",
+                },
+                "apns": {
+                    "headers": {
+                        "apns-priority": "10",
+                    },
+                },
+            }
+        });
+
+        let access_token = self.config.access_token(&self.client).await.map_err(PushError::Other)?;
+
+        let response = self
+            .client
+            .post(&self.config.url)
+            .bearer_auth(access_token)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| PushError::Other(e.into()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let text = response.text().await.unwrap_or_default();
+        let error_status = serde_json::from_str::<ErrorResponse>(&text)
+            .map(|r| r.error.status)
+            .unwrap_or_default();
+
+        if is_invalid_subscription(status, &error_status) {
+            Err(PushError::InvalidSubscription(error_status))
+        } else {
+            Err(PushError::Other(
+                format!("FCM request failed. Status: {status}. Response: {text}").into(),
+            ))
+        }
+    }
+}
+
+// FCM returns 'UNREGISTERED' if the app has been uninstalled or the token has expired, and 'INVALID_ARGUMENT' if the
+// token is malformed
+fn is_invalid_subscription(status: StatusCode, error_status: &str) -> bool {
+    status == StatusCode::NOT_FOUND
+        || (status == StatusCode::BAD_REQUEST && matches!(error_status, "UNREGISTERED" | "INVALID_ARGUMENT"))
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetails,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetails {
+    #[serde(default)]
+    status: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::rsa::Rsa;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+    use types::{PushService, SubscriptionKeys};
+
+    // Serves `responses.len()` requests, responding to each in turn with the given status and body, and returns the
+    // requests received, each as (headers, body)
+    async fn run_stub(path: &str, responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<(String, String)>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}{path}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, response_body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 4096];
+                loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(headers_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..headers_end]
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().to_string())
+                            })
+                            .map_or(0, |v| v.parse::<usize>().unwrap());
+
+                        if request.len() >= headers_end + 4 + content_length {
+                            break;
+                        }
+                    }
+                }
+
+                let response = format!(
+                    "HTTP/1.1 {status} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response_body}",
+                    response_body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+
+                let text = String::from_utf8(request).unwrap();
+                let (headers, body) = text.split_once("\r\n\r\n").unwrap();
+                requests.push((headers.to_string(), body.to_string()));
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+
+    const TOKEN_RESPONSE: &str = r#"{"access_token":"minted_token","expires_in":3600,"token_type":"Bearer"}"#;
+
+    async fn backend(fcm_url: String) -> (FcmBackend, JoinHandle<Vec<(String, String)>>) {
+        let (token_uri, token_handle) = run_stub("/token", vec![(200, TOKEN_RESPONSE)]).await;
+        (backend_with_token_uri(fcm_url, token_uri), token_handle)
+    }
+
+    fn backend_with_token_uri(fcm_url: String, token_uri: String) -> FcmBackend {
+        let private_key = Rsa::generate(2048).unwrap().private_key_to_pem().unwrap();
+        let key = ServiceAccountKey {
+            project_id: "test".to_string(),
+            client_email: "pusher@test.iam.gserviceaccount.com".to_string(),
+            private_key: String::from_utf8(private_key).unwrap(),
+            token_uri,
+        };
+
+        FcmBackend::new(FcmConfig::new(fcm_url, key).unwrap())
+    }
+
+    fn subscription() -> SubscriptionInfo {
+        SubscriptionInfo {
+            endpoint: "registration_token".to_string(),
+            keys: SubscriptionKeys {
+                p256dh: "p256dh".to_string(),
+                auth: "auth".to_string(),
+            },
+            push_service: Some(PushService::Fcm),
+            digest_mode: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn push_succeeds() {
+        let (url, handle) = run_stub(
+            "/v1/projects/test/messages:send",
+            vec![(200, r#"{"name":"projects/test/messages/1"}"#)],
+        )
+        .await;
+        let (backend, token_handle) = backend(url).await;
+
+        let result = backend.push(b"hello", &subscription()).await;
+        assert!(result.is_ok());
+
+        let (headers, body) = handle.await.unwrap().pop().unwrap();
+        assert!(headers.to_ascii_lowercase().contains("authorization: bearer minted_token"));
+        let request: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(request["message"]["token"], "registration_token");
+        assert_eq!(request["message"]["data"]["payload"], "hello");
+
+        let (_, token_request) = token_handle.await.unwrap().pop().unwrap();
+        assert!(token_request.contains("grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer"));
+        let assertion = token_request.split("assertion=").nth(1).unwrap();
+        assert_eq!(assertion.split('.').count(), 3);
+    }
+
+    #[tokio::test]
+    async fn access_token_reused_until_near_expiry() {
+        let ok = r#"{"name":"projects/test/messages/1"}"#;
+        let (url, handle) = run_stub("/v1/projects/test/messages:send", vec![(200, ok), (200, ok)]).await;
+        // The token endpoint only serves a single request, so a second refresh would fail
+        let (backend, _) = backend(url).await;
+
+        assert!(backend.push(b"hello", &subscription()).await.is_ok());
+        assert!(backend.push(b"hello", &subscription()).await.is_ok());
+        assert_eq!(handle.await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn failure_to_get_access_token_is_not_invalid_subscription() {
+        let (token_uri, _) = run_stub("/token", vec![(400, r#"{"error":"invalid_grant"}"#)]).await;
+        let backend = backend_with_token_uri("http://127.0.0.1:1".to_string(), token_uri);
+
+        let result = backend.push(b"hello", &subscription()).await;
+        assert!(matches!(result, Err(PushError::Other(_))));
+    }
+
+    #[tokio::test]
+    async fn unregistered_token_is_invalid() {
+        let (url, _) = run_stub(
+            "/v1/projects/test/messages:send",
+            vec![(404, r#"{"error":{"code":404,"status":"NOT_FOUND"}}"#)],
+        )
+        .await;
+        let (backend, _) = backend(url).await;
+
+        let result = backend.push(b"hello", &subscription()).await;
+        assert!(matches!(result, Err(PushError::InvalidSubscription(_))));
+    }
+
+    #[tokio::test]
+    async fn malformed_token_is_invalid() {
+        let (url, _) = run_stub(
+            "/v1/projects/test/messages:send",
+            vec![(400, r#"{"error":{"code":400,"status":"INVALID_ARGUMENT"}}"#)],
+        )
+        .await;
+        let (backend, _) = backend(url).await;
+
+        let result = backend.push(b"hello", &subscription()).await;
+        assert!(matches!(result, Err(PushError::InvalidSubscription(_))));
+    }
+
+    #[tokio::test]
+    async fn quota_exceeded_is_not_invalid() {
+        let (url, _) = run_stub(
+            "/v1/projects/test/messages:send",
+            vec![(429, r#"{"error":{"code":429,"status":"RESOURCE_EXHAUSTED"}}"#)],
+        )
+        .await;
+        let (backend, _) = backend(url).await;
+
+        let result = backend.push(b"hello", &subscription()).await;
+        assert!(matches!(result, Err(PushError::Other(_))));
+    }
+
+    #[tokio::test]
+    async fn payload_too_large() {
+        let backend = backend_with_token_uri("http://127.0.0.1:1".to_string(), "http://127.0.0.1:1".to_string());
+
+        let result = backend.push(&[b'a'; MAX_PAYLOAD_LENGTH_BYTES + 1], &subscription()).await;
+
+        assert!(matches!(result, Err(PushError::MaxLengthExceeded(_))));
+    }
+}
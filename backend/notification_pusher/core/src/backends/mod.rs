@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use types::{Error, PushService, SubscriptionInfo};
+
+mod fcm;
+mod web_push;
+
+pub use self::fcm::{FcmBackend, FcmConfig};
+pub use self::web_push::WebPushBackend;
+
+#[async_trait]
+pub trait PushBackend: Send + Sync {
+    async fn push(&self, payload: &[u8], subscription: &SubscriptionInfo) -> Result<(), PushError>;
+}
+
+#[derive(Debug)]
+pub enum PushError {
+    // The subscription is no longer valid so should be removed
+    InvalidSubscription(String),
+    MaxLengthExceeded(usize),
+    // Any other failure, after which the subscription is throttled for a short while
+    Other(Error),
+}
+
+pub fn max_payload_length_bytes(push_service: PushService) -> usize {
+    match push_service {
+        PushService::WebPush => self::web_push::MAX_PAYLOAD_LENGTH_BYTES,
+        PushService::Fcm => self::fcm::MAX_PAYLOAD_LENGTH_BYTES,
+    }
+}
+
+pub struct PushBackends {
+    web_push: WebPushBackend,
+    fcm: Option<FcmBackend>,
+}
+
+impl PushBackends {
+    pub fn new(vapid_private_pem: &str, fcm_config: Option<FcmConfig>) -> PushBackends {
+        PushBackends {
+            web_push: WebPushBackend::new(vapid_private_pem),
+            fcm: fcm_config.map(FcmBackend::new),
+        }
+    }
+
+    pub fn get(&self, push_service: PushService) -> Option<&dyn PushBackend> {
+        match push_service {
+            PushService::WebPush => Some(&self.web_push),
+            PushService::Fcm => self.fcm.as_ref().map(|b| b as &dyn PushBackend),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::backends::{PushBackend, PushError};
+use async_trait::async_trait;
+use web_push::{
+    ContentEncoding, HyperWebPushClient, PartialVapidSignatureBuilder, SubscriptionInfo, SubscriptionKeys, Urgency,
+    VapidSignature, VapidSignatureBuilder, WebPushClient, WebPushError, WebPushMessage, WebPushMessageBuilder,
+};
+
+pub(super) const MAX_PAYLOAD_LENGTH_BYTES: usize = 3 * 1000; // Just under 3KB
+
+pub struct WebPushBackend {
+    web_push_client: HyperWebPushClient,
+    sig_builder: PartialVapidSignatureBuilder,
+}
+
+impl WebPushBackend {
+    pub fn new(vapid_private_pem: &str) -> WebPushBackend {
+        WebPushBackend {
+            web_push_client: HyperWebPushClient::new(),
+            sig_builder: VapidSignatureBuilder::from_pem_no_sub(vapid_private_pem.as_bytes()).unwrap(),
+        }
+    }
+
+    fn build_vapid_signature(&self, subscription: &SubscriptionInfo) -> Result<VapidSignature, WebPushError> {
+        let mut sig_builder = self.sig_builder.clone().add_sub_info(subscription);
+        sig_builder.add_claim("sub", "https://oc.app");
+        sig_builder.build()
+    }
+}
+
+#[async_trait]
+impl PushBackend for WebPushBackend {
+    async fn push(&self, payload: &[u8], subscription: &types::SubscriptionInfo) -> Result<(), PushError> {
+        let subscription = convert_subscription(subscription);
+        let vapid_signature = self
+            .build_vapid_signature(&subscription)
+            .map_err(|e| PushError::Other(e.into()))?;
+
+        let message =
+            build_web_push_message(payload, &subscription, vapid_signature).map_err(|e| PushError::Other(e.into()))?;
+        let length = message.payload.as_ref().map_or(0, |p| p.content.len());
+        if length > MAX_PAYLOAD_LENGTH_BYTES {
+            return Err(PushError::MaxLengthExceeded(length));
+        }
+
+        match self.web_push_client.send(message).await {
+            Ok(_) => Ok(()),
+            Err(error @ (WebPushError::EndpointNotValid | WebPushError::InvalidUri | WebPushError::EndpointNotFound)) => {
+                Err(PushError::InvalidSubscription(format!("{error:?}")))
+            }
+            Err(error) => Err(PushError::Other(error.into())),
+        }
+    }
+}
+
+fn build_web_push_message(
+    payload: &[u8],
+    subscription: &SubscriptionInfo,
+    vapid_signature: VapidSignature,
+) -> Result<WebPushMessage, WebPushError> {
+    let mut message_builder = WebPushMessageBuilder::new(subscription);
+    message_builder.set_payload(ContentEncoding::Aes128Gcm, payload);
+    message_builder.set_vapid_signature(vapid_signature);
+    message_builder.set_ttl(3600); // 1 hour
+    message_builder.set_urgency(Urgency::High);
+    message_builder.build()
+}
+
+fn convert_subscription(value: &types::SubscriptionInfo) -> SubscriptionInfo {
+    SubscriptionInfo {
+        endpoint: value.endpoint.clone(),
+        keys: SubscriptionKeys {
+            p256dh: value.keys.p256dh.clone(),
+            auth: value.keys.auth.clone(),
+        },
+    }
+}
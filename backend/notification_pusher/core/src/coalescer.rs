@@ -0,0 +1,489 @@
+use crate::backends::max_payload_length_bytes;
+use crate::reader::build_payload;
+use crate::Notification;
+use async_channel::{Receiver, Sender};
+use std::collections::btree_map::Entry::Occupied;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time;
+use tracing::{error, info};
+use types::{CanisterId, ChannelId, ChatId, CommunityId, SubscriptionInfo, TimestampMillis, UserId};
+
+// After a message notification is pushed, any further messages from the same chat within this window are collapsed
+// into a single notification which is pushed once the window closes
+const COALESCE_WINDOW: Duration = Duration::from_secs(30);
+// Subscriptions in digest mode receive at most one notification per chat within this interval
+const DIGEST_INTERVAL: Duration = Duration::from_secs(15 * 60);
+// Once this many batches are pending, new notifications are passed straight through rather than held back
+const MAX_PENDING_BATCHES: usize = 100_000;
+
+pub struct NotificationToCoalesce {
+    pub notification: Notification,
+    // Only set for message notifications, since these are the only ones which are coalesced
+    pub message: Option<Arc<MessageNotification>>,
+}
+
+pub struct MessageNotification {
+    notifications_canister_id: CanisterId,
+    notification_index: u64,
+    chat: ChatKey,
+    timestamp: TimestampMillis,
+    notification: types::Notification,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+enum ChatKey {
+    Direct(UserId),
+    Group(ChatId),
+    Channel(CommunityId, ChannelId),
+}
+
+impl MessageNotification {
+    pub fn try_decode(
+        notifications_canister_id: CanisterId,
+        notification_index: u64,
+        notification_bytes: &[u8],
+        timestamp: TimestampMillis,
+    ) -> Option<MessageNotification> {
+        let notification: types::Notification = candid::decode_one(notification_bytes).ok()?;
+
+        let chat = match &notification {
+            types::Notification::DirectMessage(n) => ChatKey::Direct(n.sender),
+            types::Notification::GroupMessage(n) => ChatKey::Group(n.chat_id),
+            types::Notification::ChannelMessage(n) => ChatKey::Channel(n.community_id, n.channel_id),
+            _ => return None,
+        };
+
+        Some(MessageNotification {
+            notifications_canister_id,
+            notification_index,
+            chat,
+            timestamp,
+            notification,
+        })
+    }
+
+    pub fn notifications_canister_id(&self) -> CanisterId {
+        self.notifications_canister_id
+    }
+
+    pub fn notification_index(&self) -> u64 {
+        self.notification_index
+    }
+}
+
+// Tracks the message notifications which have been read but may not have been pushed yet, either because they are
+// queued for the coalescer or are held back in a batch. Readers don't persist an index beyond these, so if the pusher
+// restarts they are read again rather than lost, at the cost of some notifications possibly being pushed twice.
+#[derive(Default)]
+pub struct HeldNotifications {
+    held: Mutex<HashMap<CanisterId, BTreeMap<u64, usize>>>,
+}
+
+impl HeldNotifications {
+    pub fn hold(&self, notifications_canister_id: CanisterId, notification_index: u64) {
+        if let Ok(mut held) = self.held.lock() {
+            *held
+                .entry(notifications_canister_id)
+                .or_default()
+                .entry(notification_index)
+                .or_default() += 1;
+        }
+    }
+
+    pub fn release(&self, notifications_canister_id: CanisterId, notification_index: u64) {
+        if let Ok(mut held) = self.held.lock() {
+            if let Some(indexes) = held.get_mut(&notifications_canister_id) {
+                if let Occupied(mut e) = indexes.entry(notification_index) {
+                    *e.get_mut() -= 1;
+                    if *e.get() == 0 {
+                        e.remove();
+                    }
+                }
+            }
+        }
+    }
+
+    // Returns the index up to which the notifications read from the given canister have all been pushed
+    pub fn processed_up_to(&self, notifications_canister_id: CanisterId, read_up_to: u64) -> u64 {
+        let lowest_held = self
+            .held
+            .lock()
+            .ok()
+            .and_then(|held| held.get(&notifications_canister_id)?.keys().next().copied());
+
+        lowest_held.map_or(read_up_to, |index| read_up_to.min(index.saturating_sub(1)))
+    }
+}
+
+pub struct Coalescer {
+    receiver: Receiver<NotificationToCoalesce>,
+    sender: Sender<Notification>,
+    batches: Batches,
+}
+
+impl Coalescer {
+    pub fn new(
+        receiver: Receiver<NotificationToCoalesce>,
+        sender: Sender<Notification>,
+        held_notifications: Arc<HeldNotifications>,
+    ) -> Self {
+        Self {
+            receiver,
+            sender,
+            batches: Batches::new(held_notifications),
+        }
+    }
+
+    pub async fn run(mut self) {
+        info!("Notifications coalescer started");
+
+        let mut interval = time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                next = self.receiver.recv() => {
+                    let Ok(notification) = next else { break };
+                    if let Some(notification) = self.batches.process(notification, Instant::now()) {
+                        self.send(notification).await;
+                    }
+                }
+                _ = interval.tick() => {
+                    for notification in self.batches.take_due(Instant::now()) {
+                        self.send(notification).await;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn send(&self, notification: Notification) {
+        if self.sender.send(notification).await.is_err() {
+            error!("Failed to forward notification to pushers");
+        }
+    }
+}
+
+struct Batches {
+    batches: HashMap<(String, ChatKey), Batch>,
+    held_notifications: Arc<HeldNotifications>,
+}
+
+struct Batch {
+    recipient: UserId,
+    subscription_info: SubscriptionInfo,
+    // The latest message held back, along with its payload
+    latest: Option<(Arc<MessageNotification>, Arc<Vec<u8>>)>,
+    // The number of messages held back
+    count: usize,
+    // The earliest message held back from each notifications canister, which stop the readers from advancing their
+    // persisted index until the batch is flushed
+    held: Vec<(CanisterId, u64)>,
+    flush_at: Instant,
+}
+
+impl Batch {
+    // Only the earliest held message from each canister needs to stay held, since that alone stops the reader's
+    // persisted index from passing any of the later ones
+    fn hold(&mut self, message: &MessageNotification, held_notifications: &HeldNotifications) {
+        let canister_id = message.notifications_canister_id;
+        if self.held.iter().any(|(c, _)| *c == canister_id) {
+            held_notifications.release(canister_id, message.notification_index);
+        } else {
+            self.held.push((canister_id, message.notification_index));
+        }
+    }
+
+    fn release(&mut self, held_notifications: &HeldNotifications) {
+        for (canister_id, notification_index) in self.held.drain(..) {
+            held_notifications.release(canister_id, notification_index);
+        }
+    }
+}
+
+impl Batches {
+    fn new(held_notifications: Arc<HeldNotifications>) -> Batches {
+        Batches {
+            batches: HashMap::new(),
+            held_notifications,
+        }
+    }
+
+    // Returns the notification if it should be pushed immediately
+    fn process(&mut self, notification: NotificationToCoalesce, now: Instant) -> Option<Notification> {
+        let NotificationToCoalesce { notification, message } = notification;
+        let Some(message) = message else {
+            return Some(notification);
+        };
+
+        let digest_mode = notification.subscription_info.digest_mode();
+        let key = (notification.subscription_info.endpoint.clone(), message.chat);
+
+        if let Some(batch) = self.batches.get_mut(&key) {
+            batch.hold(&message, &self.held_notifications);
+            batch.latest = Some((message, notification.payload));
+            batch.count += 1;
+            return None;
+        }
+
+        if self.batches.len() >= MAX_PENDING_BATCHES {
+            self.held_notifications
+                .release(message.notifications_canister_id, message.notification_index);
+            return Some(notification);
+        }
+
+        if digest_mode {
+            self.batches.insert(
+                key,
+                Batch {
+                    recipient: notification.recipient,
+                    subscription_info: notification.subscription_info,
+                    held: vec![(message.notifications_canister_id, message.notification_index)],
+                    latest: Some((message, notification.payload)),
+                    count: 1,
+                    flush_at: now + DIGEST_INTERVAL,
+                },
+            );
+            None
+        } else {
+            self.held_notifications
+                .release(message.notifications_canister_id, message.notification_index);
+            self.batches.insert(
+                key,
+                Batch {
+                    recipient: notification.recipient,
+                    subscription_info: notification.subscription_info.clone(),
+                    latest: None,
+                    count: 0,
+                    held: Vec::new(),
+                    flush_at: now + COALESCE_WINDOW,
+                },
+            );
+            Some(notification)
+        }
+    }
+
+    // Returns the notifications for each batch whose window has closed. Batches which are not in digest mode are kept
+    // open for another window if any messages were held back, so that a steady stream of messages results in one
+    // notification per window.
+    fn take_due(&mut self, now: Instant) -> Vec<Notification> {
+        let mut notifications = Vec::new();
+
+        self.batches.retain(|_, batch| {
+            if batch.flush_at > now {
+                return true;
+            }
+
+            // Once the notification is passed on to the pushers it no longer needs to be held
+            batch.release(&self.held_notifications);
+
+            let Some((latest, latest_payload)) = batch.latest.take() else {
+                return false;
+            };
+
+            let payload = if batch.count == 1 {
+                latest_payload
+            } else {
+                let max_length = max_payload_length_bytes(batch.subscription_info.push_service());
+                Arc::new(build_summary_payload(&latest, batch.count, max_length))
+            };
+
+            notifications.push(Notification {
+                recipient: batch.recipient,
+                payload,
+                subscription_info: batch.subscription_info.clone(),
+            });
+
+            batch.count = 0;
+            batch.flush_at = now + COALESCE_WINDOW;
+            !batch.subscription_info.digest_mode()
+        });
+
+        notifications
+    }
+}
+
+fn build_summary_payload(latest: &MessageNotification, count: usize, max_length: usize) -> Vec<u8> {
+    let payload = build_payload(&summarise(&latest.notification, count, true), latest.timestamp);
+    if payload.len() <= max_length {
+        payload
+    } else {
+        // Drop the chat name from the text, leaving the payload no larger than that of the latest message
+        build_payload(&summarise(&latest.notification, count, false), latest.timestamp)
+    }
+}
+
+// Converts the latest message notification into one saying how many new messages there are in the chat
+fn summarise(notification: &types::Notification, count: usize, include_chat_name: bool) -> Vec<u8> {
+    let text = |chat_name: &str| {
+        if include_chat_name {
+            format!("{count} new messages in {chat_name}")
+        } else {
+            format!("{count} new messages")
+        }
+    };
+
+    let mut summary = notification.clone();
+    match &mut summary {
+        types::Notification::DirectMessage(n) => {
+            n.message_text = Some(format!("{count} new messages"));
+            n.message_type = "Text".to_string();
+            n.image_url = None;
+            n.crypto_transfer = None;
+        }
+        types::Notification::GroupMessage(n) => {
+            n.message_text = Some(text(&n.group_name));
+            n.message_type = "Text".to_string();
+            n.image_url = None;
+            n.crypto_transfer = None;
+        }
+        types::Notification::ChannelMessage(n) => {
+            n.message_text = Some(text(&n.channel_name));
+            n.message_type = "Text".to_string();
+            n.image_url = None;
+            n.crypto_transfer = None;
+        }
+        _ => {}
+    }
+
+    candid::encode_one(summary).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use candid::Principal;
+    use types::{GroupMessageNotification, SubscriptionKeys, Timestamped};
+
+    fn subscription(digest_mode: bool) -> SubscriptionInfo {
+        SubscriptionInfo {
+            endpoint: "endpoint".to_string(),
+            keys: SubscriptionKeys {
+                p256dh: "p256dh".to_string(),
+                auth: "auth".to_string(),
+            },
+            push_service: None,
+            digest_mode: Some(digest_mode),
+        }
+    }
+
+    fn group_message(message_index: u32, group_name: String) -> types::Notification {
+        types::Notification::GroupMessage(GroupMessageNotification {
+            chat_id: Principal::from_slice(&[1]).into(),
+            thread_root_message_index: None,
+            message_index: message_index.into(),
+            event_index: message_index.into(),
+            group_name,
+            sender: Principal::from_slice(&[2]).into(),
+            sender_name: "sender".to_string(),
+            sender_display_name: None,
+            message_type: "Image".to_string(),
+            message_text: Some(format!("message {message_index}")),
+            image_url: Some("image".to_string()),
+            group_avatar_id: None,
+            crypto_transfer: None,
+        })
+    }
+
+    fn canister_id() -> CanisterId {
+        Principal::from_slice(&[4])
+    }
+
+    // Mirrors the reader, which holds each message notification before passing it to the coalescer
+    fn message(message_index: u32, digest_mode: bool, held_notifications: &HeldNotifications) -> NotificationToCoalesce {
+        let bytes = candid::encode_one(group_message(message_index, "Group".to_string())).unwrap();
+        let timestamp = message_index as TimestampMillis;
+        held_notifications.hold(canister_id(), message_index as u64);
+
+        NotificationToCoalesce {
+            notification: Notification {
+                recipient: Principal::from_slice(&[3]).into(),
+                payload: Arc::new(build_payload(&bytes, timestamp)),
+                subscription_info: subscription(digest_mode),
+            },
+            message: MessageNotification::try_decode(canister_id(), message_index as u64, &bytes, timestamp).map(Arc::new),
+        }
+    }
+
+    fn decode(notification: &Notification) -> GroupMessageNotification {
+        let timestamped: Timestamped<String> = serde_json::from_slice(&notification.payload).unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD_NO_PAD
+            .decode(timestamped.value)
+            .unwrap();
+
+        match candid::decode_one(&bytes).unwrap() {
+            types::Notification::GroupMessage(n) => n,
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn messages_within_window_are_coalesced() {
+        let held_notifications = Arc::new(HeldNotifications::default());
+        let mut batches = Batches::new(held_notifications.clone());
+        let now = Instant::now();
+
+        assert!(batches.process(message(1, false, &held_notifications), now).is_some());
+        for i in 2..=4 {
+            assert!(batches.process(message(i, false, &held_notifications), now).is_none());
+        }
+        assert!(batches.take_due(now + COALESCE_WINDOW - Duration::from_secs(1)).is_empty());
+        assert_eq!(held_notifications.processed_up_to(canister_id(), 4), 1);
+
+        let notifications = batches.take_due(now + COALESCE_WINDOW);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(held_notifications.processed_up_to(canister_id(), 4), 4);
+
+        let summary = decode(&notifications[0]);
+        assert_eq!(u32::from(summary.message_index), 4);
+        assert_eq!(summary.message_text.as_deref(), Some("3 new messages in Group"));
+        assert!(summary.image_url.is_none());
+
+        // The batch stays open for another window, then closes once no more messages arrive
+        assert!(batches
+            .process(message(5, false, &held_notifications), now + COALESCE_WINDOW)
+            .is_none());
+        let notifications = batches.take_due(now + 2 * COALESCE_WINDOW);
+        assert_eq!(decode(&notifications[0]).message_text.as_deref(), Some("message 5"));
+
+        assert!(batches.take_due(now + 3 * COALESCE_WINDOW).is_empty());
+        assert!(batches.batches.is_empty());
+        assert!(batches
+            .process(message(6, false, &held_notifications), now + 3 * COALESCE_WINDOW)
+            .is_some());
+    }
+
+    #[test]
+    fn digest_mode_holds_back_all_messages() {
+        let held_notifications = Arc::new(HeldNotifications::default());
+        let mut batches = Batches::new(held_notifications.clone());
+        let now = Instant::now();
+
+        for i in 1..=3 {
+            assert!(batches.process(message(i, true, &held_notifications), now).is_none());
+        }
+        assert!(batches.take_due(now + COALESCE_WINDOW).is_empty());
+
+        // The reader can't persist an index beyond the held messages until the digest has been sent
+        assert_eq!(held_notifications.processed_up_to(canister_id(), 3), 0);
+
+        let notifications = batches.take_due(now + DIGEST_INTERVAL);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            decode(&notifications[0]).message_text.as_deref(),
+            Some("3 new messages in Group")
+        );
+        assert!(batches.batches.is_empty());
+        assert_eq!(held_notifications.processed_up_to(canister_id(), 3), 3);
+    }
+
+    #[test]
+    fn summary_fits_within_max_payload_length() {
+        let bytes = candid::encode_one(group_message(1, "a".repeat(1500))).unwrap();
+        let latest = MessageNotification::try_decode(canister_id(), 1, &bytes, 1).unwrap();
+
+        let payload = build_summary_payload(&latest, 2, 3000);
+        assert!(payload.len() <= 3000);
+    }
+}
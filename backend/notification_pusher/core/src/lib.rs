@@ -1,3 +1,5 @@
+use crate::backends::{FcmConfig, PushBackends};
+use crate::coalescer::{Coalescer, HeldNotifications, NotificationToCoalesce};
 use crate::ic_agent::IcAgent;
 use crate::pusher::Pusher;
 use crate::reader::Reader;
@@ -5,9 +7,10 @@ use crate::subscription_remover::SubscriptionRemover;
 use index_store::IndexStore;
 use std::sync::{Arc, RwLock};
 use tracing::info;
-use types::{CanisterId, UserId};
-use web_push::SubscriptionInfo;
+use types::{CanisterId, SubscriptionInfo, UserId};
 
+pub mod backends;
+mod coalescer;
 pub mod ic_agent;
 mod pusher;
 mod reader;
@@ -19,29 +22,37 @@ pub async fn run_notifications_pusher<I: IndexStore + 'static>(
     notifications_canister_ids: Vec<CanisterId>,
     index_store: I,
     vapid_private_pem: String,
+    fcm_config: Option<FcmConfig>,
     pusher_count: usize,
 ) {
     info!("Notifications pusher starting");
 
+    let (to_coalesce_sender, to_coalesce_receiver) = async_channel::bounded::<NotificationToCoalesce>(50_000);
     let (sender, receiver) = async_channel::bounded::<Notification>(50_000);
     let (subscriptions_to_remove_sender, subscriptions_to_remove_receiver) = async_channel::bounded(10_000);
+    let held_notifications = Arc::new(HeldNotifications::default());
 
     for notification_canister_id in notifications_canister_ids {
         let reader = Reader::new(
             ic_agent.clone(),
             notification_canister_id,
             index_store.clone(),
-            sender.clone(),
+            to_coalesce_sender.clone(),
+            held_notifications.clone(),
         );
         tokio::spawn(reader.run());
     }
 
+    let coalescer = Coalescer::new(to_coalesce_receiver, sender, held_notifications);
+    tokio::spawn(coalescer.run());
+
     let invalid_subscriptions = Arc::new(RwLock::default());
     let throttled_subscriptions = Arc::new(RwLock::default());
+    let backends = Arc::new(PushBackends::new(&vapid_private_pem, fcm_config));
     for _ in 0..pusher_count {
         let pusher = Pusher::new(
             receiver.clone(),
-            &vapid_private_pem,
+            backends.clone(),
             subscriptions_to_remove_sender.clone(),
             invalid_subscriptions.clone(),
             throttled_subscriptions.clone(),
@@ -1,3 +1,4 @@
+use crate::backends::{PushBackends, PushError};
 use crate::Notification;
 use async_channel::{Receiver, Sender};
 use std::collections::{BinaryHeap, HashMap};
@@ -5,18 +6,12 @@ use std::sync::{Arc, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{error, info};
 use types::{Error, Milliseconds, TimestampMillis, UserId};
-use web_push::{
-    ContentEncoding, HyperWebPushClient, PartialVapidSignatureBuilder, SubscriptionInfo, Urgency, VapidSignature,
-    VapidSignatureBuilder, WebPushClient, WebPushError, WebPushMessage, WebPushMessageBuilder,
-};
 
-const MAX_PAYLOAD_LENGTH_BYTES: usize = 3 * 1000; // Just under 3KB
 const ONE_MINUTE: Milliseconds = 60 * 1000;
 
 pub struct Pusher {
     receiver: Receiver<Notification>,
-    web_push_client: HyperWebPushClient,
-    sig_builder: PartialVapidSignatureBuilder,
+    backends: Arc<PushBackends>,
     subscriptions_to_remove_sender: Sender<(UserId, String)>,
     invalid_subscriptions: Arc<RwLock<HashMap<String, TimestampMillis>>>,
     throttled_subscriptions: Arc<RwLock<HashMap<String, TimestampMillis>>>,
@@ -25,15 +20,14 @@ pub struct Pusher {
 impl Pusher {
     pub fn new(
         receiver: Receiver<Notification>,
-        vapid_private_pem: &str,
+        backends: Arc<PushBackends>,
         subscriptions_to_remove_sender: Sender<(UserId, String)>,
         invalid_subscriptions: Arc<RwLock<HashMap<String, TimestampMillis>>>,
         throttled_subscriptions: Arc<RwLock<HashMap<String, TimestampMillis>>>,
     ) -> Self {
         Self {
             receiver,
-            web_push_client: HyperWebPushClient::new(),
-            sig_builder: VapidSignatureBuilder::from_pem_no_sub(vapid_private_pem.as_bytes()).unwrap(),
+            backends,
             subscriptions_to_remove_sender,
             invalid_subscriptions,
             throttled_subscriptions,
@@ -67,71 +61,46 @@ impl Pusher {
     }
 
     pub async fn push_notification(&self, notification: &Notification) -> Result<(), Error> {
-        let payload_bytes = notification.payload.as_ref();
         let subscription = &notification.subscription_info;
-        let vapid_signature = self.build_vapid_signature(subscription)?;
-
-        let message = build_web_push_message(payload_bytes, subscription, vapid_signature.clone())?;
-        let length = message.payload.as_ref().map_or(0, |p| p.content.len());
-        if length <= MAX_PAYLOAD_LENGTH_BYTES {
-            if let Err(error) = self.web_push_client.send(message).await {
-                match error {
-                    WebPushError::EndpointNotValid | WebPushError::InvalidUri | WebPushError::EndpointNotFound => {
-                        let _ = self
-                            .subscriptions_to_remove_sender
-                            .try_send((notification.recipient, subscription.keys.p256dh.clone()));
-
-                        if let Ok(mut map) = self.invalid_subscriptions.write() {
-                            if map.len() > 10000 {
-                                prune_invalid_subscriptions(&mut map);
-                            }
-                            map.insert(subscription.endpoint.clone(), timestamp());
-                        }
-
-                        info!(
-                            ?error,
-                            subscription.endpoint, "Failed to push notification, subscription queued to be removed"
-                        );
-                        Ok(())
-                    }
-                    _ => {
-                        if let Ok(mut map) = self.throttled_subscriptions.write() {
-                            if map.len() > 100 {
-                                let timestamp = timestamp();
-                                map.retain(|_, ts| *ts > timestamp);
-                            }
-                            info!(subscription.endpoint, "Subscription throttled for 1 minute");
-                            map.insert(subscription.endpoint.clone(), timestamp() + ONE_MINUTE);
-                        }
-                        Err(error.into())
+        let push_service = subscription.push_service();
+        let Some(backend) = self.backends.get(push_service) else {
+            return Err(format!("Push service not configured: {push_service:?}").into());
+        };
+
+        match backend.push(notification.payload.as_ref(), subscription).await {
+            Ok(()) => Ok(()),
+            Err(PushError::InvalidSubscription(error)) => {
+                let _ = self
+                    .subscriptions_to_remove_sender
+                    .try_send((notification.recipient, subscription.key().to_string()));
+
+                if let Ok(mut map) = self.invalid_subscriptions.write() {
+                    if map.len() > 10000 {
+                        prune_invalid_subscriptions(&mut map);
                     }
+                    map.insert(subscription.endpoint.clone(), timestamp());
                 }
-            } else {
+
+                info!(
+                    ?error,
+                    subscription.endpoint, "Failed to push notification, subscription queued to be removed"
+                );
                 Ok(())
             }
-        } else {
-            Err(format!("Max length exceeded. Length: {length}").into())
+            Err(PushError::MaxLengthExceeded(length)) => Err(format!("Max length exceeded. Length: {length}").into()),
+            Err(PushError::Other(error)) => {
+                if let Ok(mut map) = self.throttled_subscriptions.write() {
+                    if map.len() > 100 {
+                        let timestamp = timestamp();
+                        map.retain(|_, ts| *ts > timestamp);
+                    }
+                    info!(subscription.endpoint, "Subscription throttled for 1 minute");
+                    map.insert(subscription.endpoint.clone(), timestamp() + ONE_MINUTE);
+                }
+                Err(error)
+            }
         }
     }
-
-    fn build_vapid_signature(&self, subscription: &SubscriptionInfo) -> Result<VapidSignature, WebPushError> {
-        let mut sig_builder = self.sig_builder.clone().add_sub_info(subscription);
-        sig_builder.add_claim("sub", "https://oc.app");
-        sig_builder.build()
-    }
-}
-
-fn build_web_push_message(
-    payload: &[u8],
-    subscription: &SubscriptionInfo,
-    vapid_signature: VapidSignature,
-) -> Result<WebPushMessage, WebPushError> {
-    let mut message_builder = WebPushMessageBuilder::new(subscription);
-    message_builder.set_payload(ContentEncoding::Aes128Gcm, payload);
-    message_builder.set_vapid_signature(vapid_signature);
-    message_builder.set_ttl(3600); // 1 hour
-    message_builder.set_urgency(Urgency::High);
-    message_builder.build()
 }
 
 // Prunes the oldest 1000 subscriptions
@@ -161,24 +130,6 @@ fn timestamp() -> TimestampMillis {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
-struct SubscriptionInfoDebug<'a> {
-    endpoint: &'a str,
-    p256dh_len: usize,
-    auth_len: usize,
-}
-
-impl<'a> From<&'a SubscriptionInfo> for SubscriptionInfoDebug<'a> {
-    fn from(s: &'a SubscriptionInfo) -> Self {
-        SubscriptionInfoDebug {
-            endpoint: &s.endpoint,
-            p256dh_len: s.keys.p256dh.len(),
-            auth_len: s.keys.auth.len(),
-        }
-    }
-}
-
 #[test]
 fn oldest_subscriptions_are_pruned() {
     let mut map = HashMap::new();
@@ -1,33 +1,46 @@
+use crate::coalescer::{HeldNotifications, MessageNotification, NotificationToCoalesce};
 use crate::ic_agent::IcAgent;
 use crate::Notification;
 use async_channel::Sender;
 use base64::Engine;
 use index_store::IndexStore;
-use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time;
 use tracing::{error, info};
-use types::{CanisterId, Error, Timestamped, UserId};
-use web_push::{SubscriptionInfo, SubscriptionKeys};
+use types::{CanisterId, Error, TimestampMillis, Timestamped};
 
 pub struct Reader<I: IndexStore> {
     ic_agent: IcAgent,
     notifications_canister_id: CanisterId,
     index_store: I,
-    sender: Sender<Notification>,
+    sender: Sender<NotificationToCoalesce>,
+    held_notifications: Arc<HeldNotifications>,
+    // The index of the latest notification read, which may be ahead of the persisted index if notifications are being
+    // held back by the coalescer
+    read_up_to: Option<u64>,
+    processed_up_to: Option<u64>,
 }
 
 impl<I: IndexStore> Reader<I> {
-    pub fn new(ic_agent: IcAgent, notifications_canister_id: CanisterId, index_store: I, sender: Sender<Notification>) -> Self {
+    pub fn new(
+        ic_agent: IcAgent,
+        notifications_canister_id: CanisterId,
+        index_store: I,
+        sender: Sender<NotificationToCoalesce>,
+        held_notifications: Arc<HeldNotifications>,
+    ) -> Self {
         Self {
             ic_agent,
             notifications_canister_id,
             index_store,
             sender,
+            held_notifications,
+            read_up_to: None,
+            processed_up_to: None,
         }
     }
 
-    pub async fn run(self) {
+    pub async fn run(mut self) {
         info!(%self.notifications_canister_id, "Notifications reader started");
 
         let mut interval = time::interval(time::Duration::from_secs(1));
@@ -51,37 +64,52 @@ impl<I: IndexStore> Reader<I> {
         }
     }
 
-    async fn read_notifications(&self) -> Result<(), Error> {
-        let from_notification_index = self.index_processed_up_to().await? + 1;
+    async fn read_notifications(&mut self) -> Result<(), Error> {
+        let read_up_to = match self.read_up_to {
+            Some(index) => index,
+            None => self.index_processed_up_to().await?,
+        };
         let ic_response = self
             .ic_agent
-            .notifications(&self.notifications_canister_id, from_notification_index)
+            .notifications(&self.notifications_canister_id, read_up_to + 1)
             .await?;
 
         if let Some(latest_notification_index) = ic_response.notifications.last().map(|e| e.index) {
-            let subscriptions_map: HashMap<UserId, Vec<SubscriptionInfo>> = ic_response
-                .subscriptions
-                .into_iter()
-                .map(|(k, v)| (k, v.into_iter().map(convert_subscription).collect()))
-                .collect();
-
-            for notification in ic_response.notifications.into_iter().map(|n| n.value) {
-                let base64 = base64::engine::general_purpose::STANDARD_NO_PAD.encode(notification.notification_bytes);
-
-                let payload = Arc::new(serde_json::to_vec(&Timestamped::new(base64, notification.timestamp)).unwrap());
+            let subscriptions_map = ic_response.subscriptions;
+
+            for (index, notification) in ic_response.notifications.into_iter().map(|n| (n.index, n.value)) {
+                let payload = Arc::new(build_payload(&notification.notification_bytes, notification.timestamp));
+                let message = MessageNotification::try_decode(
+                    self.notifications_canister_id,
+                    index,
+                    &notification.notification_bytes,
+                    notification.timestamp,
+                )
+                .map(Arc::new);
 
                 for user_id in notification.recipients {
                     if let Some(subscriptions) = subscriptions_map.get(&user_id) {
                         for subscription_info in subscriptions.iter().cloned() {
+                            // Held until the coalescer either passes the notification on or flushes the batch it
+                            // was added to
+                            if message.is_some() {
+                                self.held_notifications.hold(self.notifications_canister_id, index);
+                            }
                             if self
                                 .sender
-                                .try_send(Notification {
-                                    recipient: user_id,
-                                    payload: payload.clone(),
-                                    subscription_info,
+                                .try_send(NotificationToCoalesce {
+                                    notification: Notification {
+                                        recipient: user_id,
+                                        payload: payload.clone(),
+                                        subscription_info,
+                                    },
+                                    message: message.clone(),
                                 })
                                 .is_err()
                             {
+                                if message.is_some() {
+                                    self.held_notifications.release(self.notifications_canister_id, index);
+                                }
                                 return Err("Notifications queue is full".into());
                             }
                         }
@@ -89,14 +117,28 @@ impl<I: IndexStore> Reader<I> {
                 }
             }
 
-            self.set_index_processed_up_to(latest_notification_index).await?;
+            self.read_up_to = Some(latest_notification_index);
+        }
+
+        // The persisted index only advances once any notifications being held back have been pushed, so that they
+        // aren't lost if the pusher restarts
+        if let Some(read_up_to) = self.read_up_to {
+            let processed_up_to = self
+                .held_notifications
+                .processed_up_to(self.notifications_canister_id, read_up_to);
+
+            if self.processed_up_to != Some(processed_up_to) {
+                self.set_index_processed_up_to(processed_up_to).await?;
+                self.processed_up_to = Some(processed_up_to);
+            }
         }
 
         Ok(())
     }
 
-    async fn index_processed_up_to(&self) -> Result<u64, Error> {
+    async fn index_processed_up_to(&mut self) -> Result<u64, Error> {
         if let Some(index) = self.index_store.get(self.notifications_canister_id).await? {
+            self.processed_up_to = Some(index);
             Ok(index)
         } else {
             let index = self
@@ -105,6 +147,7 @@ impl<I: IndexStore> Reader<I> {
                 .await?;
 
             self.set_index_processed_up_to(index).await?;
+            self.processed_up_to = Some(index);
 
             Ok(index)
         }
@@ -127,12 +170,8 @@ impl<I: IndexStore> Reader<I> {
     }
 }
 
-fn convert_subscription(value: types::SubscriptionInfo) -> SubscriptionInfo {
-    SubscriptionInfo {
-        endpoint: value.endpoint,
-        keys: SubscriptionKeys {
-            p256dh: value.keys.p256dh,
-            auth: value.keys.auth,
-        },
-    }
+pub fn build_payload(notification_bytes: &[u8], timestamp: TimestampMillis) -> Vec<u8> {
+    let base64 = base64::engine::general_purpose::STANDARD_NO_PAD.encode(notification_bytes);
+
+    serde_json::to_vec(&Timestamped::new(base64, timestamp)).unwrap()
 }